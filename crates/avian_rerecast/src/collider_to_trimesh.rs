@@ -121,22 +121,16 @@ fn compound_trimesh(
     rot: Rotation,
     subdivisions: u32,
 ) -> TriMesh {
-    compound.shapes().iter().fold(
-        TriMesh::default(),
-        |mut compound_trimesh, (sub_pos, shape)| {
+    compound
+        .shapes()
+        .iter()
+        .filter_map(|(sub_pos, shape)| {
             let pos = Position(pos.0 + rot * Vec3::from(sub_pos.translation));
             let rot = Rotation((rot.mul_quat(sub_pos.rotation.into())).normalize());
-            let Some(trimesh) =
-                // No need to track recursive compounds because parry panics on nested compounds anyways lol
-                shape_to_trimesh(&shape.as_typed_shape(), pos, rot,  subdivisions)
-            else {
-                return compound_trimesh;
-            };
-
-            compound_trimesh.extend(trimesh);
-            compound_trimesh
-        },
-    )
+            // No need to track recursive compounds because parry panics on nested compounds anyways lol
+            shape_to_trimesh(&shape.as_typed_shape(), pos, rot, subdivisions)
+        })
+        .collect()
 }
 
 #[cfg(test)]
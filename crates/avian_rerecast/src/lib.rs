@@ -3,51 +3,88 @@
 use avian3d::prelude::*;
 use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
+use bevy_math::{bounding::Aabb3d, prelude::*};
 use bevy_rerecast_core::{NavmeshApp as _, NavmeshSettings, rerecast::TriMesh};
 
 mod collider_to_trimesh;
+mod terrain;
 pub use crate::collider_to_trimesh::ColliderToTriMesh;
+pub use crate::terrain::{ColliderHeightSampler, NavmeshTerrain};
 
 /// Everything you need to get started with the Navmesh plugin.
 pub mod prelude {
-    pub use crate::AvianBackendPlugin;
+    pub use crate::{AvianBackendPlugin, NavmeshTerrain};
 }
 
 /// The plugin of the crate. Will make all entities with [`Collider`] a collider belonging to a static [`RigidBody`] available for navmesh generation.
+/// Colliders marked as [`NavmeshTerrain`] are sampled by height instead of being converted into a trimesh as a whole.
 #[non_exhaustive]
 #[derive(Debug, Default)]
 pub struct AvianBackendPlugin;
 
 impl Plugin for AvianBackendPlugin {
     fn build(&self, app: &mut App) {
-        app.set_navmesh_backend(collider_backend);
+        app.add_navmesh_backend(collider_backend);
+        app.add_navmesh_affector_bounds(collider_affector_bounds);
     }
 }
 
 fn collider_backend(
     input: In<NavmeshSettings>,
-    colliders: Query<(Entity, &Collider, &Position, &Rotation, &ColliderOf)>,
+    colliders: Query<(
+        Entity,
+        &Collider,
+        &Position,
+        &Rotation,
+        &ColliderOf,
+        Option<&NavmeshTerrain>,
+    )>,
     bodies: Query<&RigidBody>,
 ) -> TriMesh {
-    colliders
+    let mut colliders = colliders
         .iter()
-        .filter_map(|(entity, collider, pos, rot, collider_of)| {
-            if input
-                .filter
-                .as_ref()
-                .is_some_and(|entities| !entities.contains(&entity))
-            {
-                return None;
-            }
+        .filter(|(entity, ..)| input.includes(*entity))
+        .collect::<Vec<_>>();
+    // Merge in a stable order so that identical scenes always produce identical navmeshes.
+    colliders.sort_by_key(|(entity, ..)| *entity);
+    colliders
+        .into_iter()
+        .filter_map(|(_entity, collider, pos, rot, collider_of, terrain)| {
             let body = bodies.get(collider_of.body).ok()?;
             if !body.is_static() {
                 return None;
             }
+            if let (Some(terrain), Some(aabb)) = (terrain, input.aabb)
+                && input.up == Vec3::Y
+            {
+                return Some(terrain::sample_terrain(collider, *pos, *rot, terrain, aabb));
+            }
             let subdivisions = 10;
             collider.to_trimesh(*pos, *rot, subdivisions)
         })
-        .fold(TriMesh::default(), |mut acc, t| {
-            acc.extend(t);
-            acc
+        .collect()
+}
+
+fn collider_affector_bounds(
+    input: In<NavmeshSettings>,
+    colliders: Query<(Entity, &Collider, &Position, &Rotation, &ColliderOf)>,
+    bodies: Query<&RigidBody>,
+) -> Vec<(Entity, Aabb3d)> {
+    colliders
+        .iter()
+        .filter(|(entity, ..)| input.includes(*entity))
+        .filter(|(.., collider_of)| {
+            bodies
+                .get(collider_of.body)
+                .is_ok_and(|body| body.is_static())
+        })
+        .map(|(entity, collider, pos, rot, _collider_of)| {
+            let aabb = collider.aabb(pos.0, *rot);
+            let aabb = Aabb3d {
+                min: aabb.min.into(),
+                max: aabb.max.into(),
+            };
+            (entity, aabb)
         })
+        .collect()
 }
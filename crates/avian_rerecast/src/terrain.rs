@@ -0,0 +1,122 @@
+//! Support for sampling terrain colliders instead of converting them into trimeshes, see [`NavmeshTerrain`].
+
+use avian3d::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_math::{bounding::Aabb3d, prelude::*};
+use bevy_rerecast_core::rerecast::{Aabb2d, HeightSampler, TriMesh};
+
+/// Marks a [`Collider`] as terrain, e.g. a [`Collider::heightfield`].
+///
+/// Instead of converting the whole collider into a trimesh, the [`AvianBackendPlugin`](crate::AvianBackendPlugin)
+/// samples its height on a regular grid inside of [`NavmeshSettings::aabb`](bevy_rerecast_core::NavmeshSettings::aabb).
+/// This is much cheaper for large terrains of which only a small part is needed for the navmesh.
+///
+/// Falls back to converting the whole collider if no AABB is set or if [`NavmeshSettings::up`](bevy_rerecast_core::NavmeshSettings::up)
+/// is not [`Vec3::Y`].
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct NavmeshTerrain {
+    /// The distance between two height samples in world units.
+    /// Should be at most the cell size of the navmesh to not lose detail.
+    pub spacing: f32,
+}
+
+impl Default for NavmeshTerrain {
+    fn default() -> Self {
+        Self { spacing: 0.25 }
+    }
+}
+
+/// Samples the height of a [`Collider`] by casting rays straight down onto it.
+#[derive(Debug, Clone, Copy)]
+pub struct ColliderHeightSampler<'a> {
+    /// The collider to sample.
+    pub collider: &'a Collider,
+    /// The position of the collider.
+    pub position: Position,
+    /// The rotation of the collider.
+    pub rotation: Rotation,
+    /// The height from which the rays are cast.
+    pub top: f32,
+    /// The height below which no terrain is detected.
+    pub bottom: f32,
+}
+
+impl HeightSampler for ColliderHeightSampler<'_> {
+    fn sample_height(&self, x: f32, z: f32) -> Option<f32> {
+        let (distance, _normal) = self.collider.cast_ray(
+            self.position.0,
+            self.rotation,
+            Vec3::new(x, self.top, z),
+            Vec3::NEG_Y,
+            self.top - self.bottom,
+            true,
+        )?;
+        Some(self.top - distance)
+    }
+}
+
+pub(crate) fn sample_terrain(
+    collider: &Collider,
+    position: Position,
+    rotation: Rotation,
+    terrain: &NavmeshTerrain,
+    aabb: Aabb3d,
+) -> TriMesh {
+    let sampler = ColliderHeightSampler {
+        collider,
+        position,
+        rotation,
+        top: aabb.max.y,
+        bottom: aabb.min.y,
+    };
+    let bounds = Aabb2d {
+        min: aabb.min.xz(),
+        max: aabb.max.xz(),
+    };
+    TriMesh::from_height_sampler(&sampler, bounds, terrain.spacing)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_math::ops;
+
+    use super::*;
+
+    #[test]
+    fn samples_heightfield_within_aabb() {
+        let heights = vec![vec![2.0; 5]; 5];
+        let collider = Collider::heightfield(heights, Vec3::new(8.0, 1.0, 8.0));
+        let position = Position::from_xyz(0.0, 1.0, 0.0);
+        let aabb = Aabb3d::new(Vec3::ZERO, Vec3::new(2.0, 5.0, 2.0));
+
+        let trimesh = sample_terrain(
+            &collider,
+            position,
+            Rotation::default(),
+            &NavmeshTerrain { spacing: 0.5 },
+            aabb,
+        );
+        // 9x9 samples and 8x8 quads of two triangles each
+        assert_eq!(trimesh.vertices.len(), 81);
+        assert_eq!(trimesh.indices.len(), 128);
+        for vertex in &trimesh.vertices {
+            assert!(ops::abs(vertex.y - 3.0) < 1e-4);
+            assert!(ops::abs(vertex.x) <= 2.0 && ops::abs(vertex.z) <= 2.0);
+        }
+    }
+
+    #[test]
+    fn skips_samples_outside_of_terrain() {
+        let heights = vec![vec![0.0; 3]; 3];
+        let collider = Collider::heightfield(heights, Vec3::new(2.0, 1.0, 2.0));
+        let sampler = ColliderHeightSampler {
+            collider: &collider,
+            position: Position::default(),
+            rotation: Rotation::default(),
+            top: 5.0,
+            bottom: -5.0,
+        };
+        assert!(sampler.sample_height(0.0, 0.0).is_some());
+        assert_eq!(sampler.sample_height(3.0, 0.0), None);
+    }
+}
@@ -0,0 +1,270 @@
+//! Benchmarks of the navmesh generation for the `models/dungeon.glb` scene used by the regression tests.
+//!
+//! Run with `cargo bench -p bevy_rerecast --bench generation`.
+
+#![allow(missing_docs)]
+
+use core::time::Duration;
+use std::time::Instant;
+
+use bevy::{
+    asset::AssetPlugin,
+    camera::{primitives::Aabb, visibility::VisibilityPlugin},
+    ecs::system::RunSystemOnce,
+    gltf::GltfPlugin,
+    mesh::MeshPlugin,
+    prelude::*,
+    scene::{SceneInstanceReady, ScenePlugin},
+};
+use bevy_rerecast::{
+    Mesh3dBackendPlugin, RerecastPlugin,
+    debug::NavmeshDebugPlugin,
+    generator::NavmeshInputCaptured,
+    prelude::*,
+    rerecast::{
+        AreaType, CompactHeightfield, Config, ConfigBuilder, ContourSet, DetailNavmesh,
+        Heightfield, HeightfieldBuilder, PolygonNavmesh, TriMesh,
+    },
+};
+use bevy_rerecast_editor_integration::NavmeshEditorIntegrationPlugin;
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+
+/// The values for [`NavmeshSettings::cell_size_fraction`] to benchmark. Higher values mean smaller cells.
+const CELL_SIZE_FRACTIONS: [f32; 2] = [2.0, 4.0];
+
+fn generation(c: &mut Criterion) {
+    let mut app = dungeon_app();
+    let trimesh = capture_input(&mut app);
+
+    let mut group = c.benchmark_group("generate_navmesh");
+    group.sample_size(10);
+    for fraction in CELL_SIZE_FRACTIONS {
+        let settings = NavmeshSettings {
+            cell_size_fraction: fraction,
+            ..default()
+        };
+        group.bench_with_input(
+            BenchmarkId::from_parameter(fraction),
+            &settings,
+            |b, settings| {
+                b.iter(|| {
+                    let handle = app
+                        .world_mut()
+                        .run_system_once({
+                            let settings = settings.clone();
+                            move |mut generator: NavmeshGenerator| {
+                                generator.generate(settings.clone())
+                            }
+                        })
+                        .unwrap();
+                    // Synchronous builds finish within the update.
+                    app.update();
+                    assert!(app.world().resource::<Assets<Navmesh>>().contains(&handle));
+                });
+            },
+        );
+    }
+    group.finish();
+
+    for fraction in CELL_SIZE_FRACTIONS {
+        bench_stages(c, &trimesh, fraction);
+    }
+}
+
+/// Benchmarks each stage of the pipeline on its own, starting from the output of the previous stage.
+fn bench_stages(c: &mut Criterion, trimesh: &TriMesh, cell_size_fraction: f32) {
+    let settings = NavmeshSettings::default();
+    let config = ConfigBuilder {
+        cell_size_fraction,
+        cell_height_fraction: settings.cell_height_fraction,
+        agent_radius: settings.agent_radius,
+        agent_height: settings.agent_height,
+        walkable_climb: settings.walkable_climb,
+        aabb: trimesh.compute_aabb().unwrap(),
+        ..default()
+    }
+    .build();
+    let mut trimesh = trimesh.clone();
+    trimesh.mark_walkable_triangles_as(config.walkable_slope_angle, AreaType::DEFAULT_WALKABLE);
+
+    let heightfield = rasterize(&trimesh, &config);
+    let eroded = erode(heightfield.clone(), &config);
+    let regions = partition(eroded.clone(), &config);
+    let contours = build_contours(&regions, &config);
+    let polygon = contours
+        .clone()
+        .into_polygon_mesh(config.max_vertices_per_polygon)
+        .unwrap();
+
+    let mut group = c.benchmark_group(format!("stages/cell_size_fraction_{cell_size_fraction}"));
+    group.sample_size(10);
+    group.bench_function("rasterize", |b| b.iter(|| rasterize(&trimesh, &config)));
+    group.bench_function("erode", |b| {
+        b.iter_batched(
+            || heightfield.clone(),
+            |heightfield| erode(heightfield, &config),
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("regions", |b| {
+        b.iter_batched(
+            || eroded.clone(),
+            |eroded| partition(eroded, &config),
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("contours", |b| b.iter(|| build_contours(&regions, &config)));
+    group.bench_function("polymesh", |b| {
+        b.iter_batched(
+            || contours.clone(),
+            |contours| {
+                contours
+                    .into_polygon_mesh(config.max_vertices_per_polygon)
+                    .unwrap()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("detail", |b| b.iter(|| detail(&polygon, &regions, &config)));
+    group.finish();
+}
+
+fn rasterize(trimesh: &TriMesh, config: &Config) -> Heightfield {
+    let mut heightfield = HeightfieldBuilder {
+        aabb: config.aabb,
+        cell_size: config.cell_size,
+        cell_height: config.cell_height,
+    }
+    .build()
+    .unwrap();
+    heightfield
+        .rasterize_triangles(trimesh, config.walkable_climb)
+        .unwrap();
+    heightfield.filter_low_hanging_walkable_obstacles(config.walkable_climb);
+    heightfield.filter_ledge_spans(config.walkable_height, config.walkable_climb);
+    heightfield.filter_walkable_low_height_spans(config.walkable_height);
+    heightfield
+}
+
+fn erode(heightfield: Heightfield, config: &Config) -> CompactHeightfield {
+    let mut compact_heightfield = heightfield
+        .into_compact(config.walkable_height, config.walkable_climb)
+        .unwrap();
+    compact_heightfield.erode_walkable_area(config.walkable_radius);
+    compact_heightfield
+}
+
+fn partition(mut compact_heightfield: CompactHeightfield, config: &Config) -> CompactHeightfield {
+    compact_heightfield.build_distance_field();
+    compact_heightfield
+        .build_regions(
+            config.border_size,
+            config.min_region_area,
+            config.merge_region_area,
+        )
+        .unwrap();
+    compact_heightfield
+}
+
+fn build_contours(compact_heightfield: &CompactHeightfield, config: &Config) -> ContourSet {
+    compact_heightfield.build_contours(
+        config.max_simplification_error,
+        config.max_edge_len,
+        config.contour_flags,
+    )
+}
+
+fn detail(
+    polygon: &PolygonNavmesh,
+    compact_heightfield: &CompactHeightfield,
+    config: &Config,
+) -> DetailNavmesh {
+    DetailNavmesh::new(
+        polygon,
+        compact_heightfield,
+        config.detail_sample_dist,
+        config.detail_sample_max_error,
+    )
+    .unwrap()
+}
+
+/// Generates a navmesh once to capture the obstacles the backend collects from the dungeon.
+fn capture_input(app: &mut App) -> TriMesh {
+    app.add_observer(
+        |captured: On<NavmeshInputCaptured>, mut commands: Commands| {
+            commands.insert_resource(CapturedInput(captured.event().trimesh.clone()));
+        },
+    );
+    // Generation is skipped for dropped handles, so keep it alive until the update ran.
+    let _handle = app
+        .world_mut()
+        .run_system_once(|mut generator: NavmeshGenerator| {
+            generator.generate(NavmeshSettings {
+                capture_input: true,
+                ..default()
+            })
+        })
+        .unwrap();
+    app.update();
+    app.world_mut()
+        .remove_resource::<CapturedInput>()
+        .expect("Failed to capture the dungeon obstacles")
+        .0
+}
+
+#[derive(Resource)]
+struct CapturedInput(TriMesh);
+
+#[derive(Resource)]
+struct GltfLoaded;
+
+/// An app with the dungeon spawned and synchronous builds enabled, set up like the regression tests.
+fn dungeon_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin {
+            file_path: "../../assets".to_string(),
+            ..default()
+        },
+        ScenePlugin,
+        MeshPlugin,
+        TransformPlugin,
+        VisibilityPlugin,
+        GltfPlugin::default(),
+    ))
+    .init_asset::<StandardMaterial>()
+    .register_type::<Aabb>()
+    .register_type::<MeshMaterial3d<StandardMaterial>>();
+    app.add_plugins((
+        NavmeshPlugins::default()
+            .build()
+            .set(RerecastPlugin::default().with_synchronous_builds(true))
+            .disable::<NavmeshDebugPlugin>()
+            .disable::<NavmeshEditorIntegrationPlugin>(),
+        Mesh3dBackendPlugin::default(),
+    ));
+    app.finish();
+    app.cleanup();
+
+    let scene = app.world().load_asset("models/dungeon.glb#Scene0");
+    app.world_mut().spawn(SceneRoot(scene)).observe(
+        |_: On<SceneInstanceReady>, mut commands: Commands| {
+            commands.insert_resource(GltfLoaded);
+        },
+    );
+    let now = Instant::now();
+    while app.world().get_resource::<GltfLoaded>().is_none() {
+        app.update();
+        assert!(
+            now.elapsed() < Duration::from_secs(5),
+            "Timeout waiting for glTF to load"
+        );
+    }
+    // Let the transforms of the spawned scene propagate before generating.
+    app.update();
+    app
+}
+
+criterion_group!(benches, generation);
+criterion_main!(benches);
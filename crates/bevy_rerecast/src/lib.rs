@@ -94,6 +94,10 @@
 //!
 //! If you need to regenerate a navmesh because the environment has changed, use [`NavmeshGenerator::regenerate`]. Once the navmesh was regenerated, you can observe a [`NavmeshReady`] trigger.
 //!
+//! If your game streams in several levels or rooms, each of them can own its navmesh. Insert the handle as a [`NavmeshRef`] component
+//! on the entity of the region, e.g. after generating it with [`NavmeshSettings::aabb`] set to the bounds of the region,
+//! and pass the component to the [`NavmeshQueries`] of the agents in that region.
+//!
 //! Take a look at the [`examples`](https://github.com/janhohenheim/rerecast/tree/main/examples/examples) directory to see all of this in action!
 //!
 //! ### Editor
@@ -180,8 +184,8 @@
 //! - [ ] Generate tiles
 //! - Partitioning
 //!   - [x] Watershed
-//!   - [ ] Monotone
-//!   - [ ] Layer
+//!   - [x] Monotone
+//!   - [x] Layer
 //! - [x] `no_std` support
 //! - [x] cross-platform determinism (use `libm` feature)
 //!
@@ -209,9 +213,10 @@
 //! [`NavmeshReady`]: crate::prelude::NavmeshReady
 //! [`NavmeshGenerator`]: crate::prelude::NavmeshGenerator
 //! [`NavmeshGenerator::regenerate`]: crate::prelude::NavmeshGenerator::regenerate
+//! [`NavmeshQueries`]: crate::prelude::NavmeshQueries
 //! [`Mesh3d`]: https://docs.rs/bevy/latest/bevy/prelude/struct.Mesh3d.html
 
-use bevy_app::plugin_group;
+use bevy_app::{PluginGroup, PluginGroupBuilder};
 pub use bevy_rerecast_core::*;
 #[cfg(feature = "editor_integration")]
 pub use bevy_rerecast_editor_integration as editor_integration;
@@ -222,31 +227,70 @@ pub mod prelude {
     pub use bevy_rerecast_core::prelude::*;
 }
 
-plugin_group! {
-    /// The plugin group of the crate. Contains the following plugins:
-    /// - [`RerecastPlugin`]: The main plugin. Adds functionality for creating and managing navmeshes.
-    /// - [`RerecastEditorIntegrationPlugin`](editor_integration::RerecastEditorIntegrationPlugin): Allows communication with the editor.
-    ///   Requires the `editor_integration` feature.
-    /// - [`NavmeshDebugPlugin`]: Adds visual debugging functionality for navmeshes.
-    ///   Requires the `debug_plugin` feature.
-    ///
-    /// Note that rerecast does not do anything until you also add a navmesh backend.
-    ///
-    /// A backend's job is to provide the [`TriMesh`](rerecast::TriMesh)es that will be used to create the navmesh. Said navmesh
-    /// contains all non-walkable geometry, which is collectively called the *obstacles*.
-    /// For example, if you enable the `bevy_mesh` feature, you can add the [`Mesh3dNavmeshPlugin`] to your app to
-    /// set a backend that generates navmeshes from entities with a `Mesh3d` component.
-    ///
-    /// To set your own backend, use [`NavmeshApp::set_navmesh_backend`].
-    /// Only one backend can be set at a time. Setting a new backend will replace the previous one.
-    /// By default, no backend is set.
-    #[derive(Debug, Default)]
-    #[non_exhaustive]
-    pub struct NavmeshPlugins {
-        :RerecastPlugin,
+/// The plugin group of the crate. Contains the following plugins:
+/// - [`RerecastPlugin`]: The main plugin. Adds functionality for creating and managing navmeshes.
+/// - [`NavmeshEditorIntegrationPlugin`](editor_integration::NavmeshEditorIntegrationPlugin): Allows communication with the editor.
+///   Requires the `editor_integration` feature.
+/// - [`NavmeshDebugPlugin`](debug::NavmeshDebugPlugin): Adds visual debugging functionality for navmeshes.
+///   Requires the `debug_plugin` feature.
+/// - [`Mesh3dBackendPlugin`]: A navmesh backend that uses all entities with a `Mesh3d` as obstacles.
+///   Requires the `bevy_mesh` feature. This member is disabled by default.
+/// - [`HeightmapBackendPlugin`]: A navmesh backend that uses all entities with a [`HeightmapTerrain`] as obstacles.
+///   Requires the `heightmap` feature. This member is disabled by default.
+///
+/// Each member can be toggled individually through [`PluginGroup::build`]:
+///
+/// ```no_run
+/// use bevy_app::prelude::*;
+/// use bevy_rerecast::{Mesh3dBackendPlugin, debug::NavmeshDebugPlugin, prelude::*};
+///
+/// App::new().add_plugins(
+///     NavmeshPlugins::default()
+///         .build()
+///         .disable::<NavmeshDebugPlugin>()
+///         .enable::<Mesh3dBackendPlugin>(),
+/// );
+/// ```
+///
+/// Note that rerecast does not do anything until you also add a navmesh backend.
+///
+/// A backend's job is to provide the [`TriMesh`](rerecast::TriMesh)es that will be used to create the navmesh. Said navmesh
+/// contains all non-walkable geometry, which is collectively called the *obstacles*.
+/// For example, if you enable the `bevy_mesh` feature, you can enable the [`Mesh3dBackendPlugin`] to
+/// add a backend that generates navmeshes from entities with a `Mesh3d` component.
+///
+/// To set your own backend, use [`NavmeshApp::set_navmesh_backend`], which replaces all previous backends.
+/// To combine it with other backends instead, e.g. for worlds with both render meshes and colliders,
+/// use [`NavmeshApp::add_navmesh_backend`]. The obstacles of all backends are then merged.
+/// By default, no backend is set.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct NavmeshPlugins;
+
+impl PluginGroup for NavmeshPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        let mut group = PluginGroupBuilder::start::<Self>().add(RerecastPlugin::default());
         #[cfg(feature = "editor_integration")]
-        editor_integration:::NavmeshEditorIntegrationPlugin,
+        {
+            group = group.add(editor_integration::NavmeshEditorIntegrationPlugin::default());
+        }
         #[cfg(feature = "debug_plugin")]
-        debug:::NavmeshDebugPlugin,
+        {
+            group = group.add(debug::NavmeshDebugPlugin::default());
+        }
+        #[cfg(feature = "bevy_mesh")]
+        {
+            // Most apps use the backend of their physics engine instead, so this is opt-in.
+            group = group
+                .add(Mesh3dBackendPlugin::default())
+                .disable::<Mesh3dBackendPlugin>();
+        }
+        #[cfg(feature = "heightmap")]
+        {
+            group = group
+                .add(HeightmapBackendPlugin::default())
+                .disable::<HeightmapBackendPlugin>();
+        }
+        group
     }
 }
@@ -1,100 +1,2148 @@
 #![allow(missing_docs)]
 
+extern crate alloc;
+
+use alloc::sync::Arc;
+use core::{any::TypeId, time::Duration};
 use std::time::Instant;
 
-use bevy::{
-    asset::AssetPlugin,
-    camera::{primitives::Aabb, visibility::VisibilityPlugin},
-    ecs::system::RunSystemOnce,
-    gltf::GltfPlugin,
-    log::LogPlugin,
-    math::bounding::Aabb3d,
-    mesh::MeshPlugin,
-    prelude::*,
-    scene::{SceneInstanceReady, ScenePlugin},
-};
-use bevy_rerecast::{Mesh3dBackendPlugin, debug::NavmeshDebugPlugin, prelude::*};
-use bevy_rerecast_editor_integration::NavmeshEditorIntegrationPlugin;
+use bevy::{
+    app::{PluginGroupBuilder, Plugins},
+    asset::{AssetPlugin, RenderAssetUsages},
+    camera::{primitives::Aabb, visibility::VisibilityPlugin},
+    ecs::system::RunSystemOnce,
+    gizmos::GizmoAsset,
+    gltf::GltfPlugin,
+    log::LogPlugin,
+    math::{bounding::Aabb3d, ops},
+    mesh::{Indices, MeshPlugin, PrimitiveTopology},
+    platform::collections::HashSet,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    scene::{SceneInstanceReady, ScenePlugin},
+};
+use bevy_rerecast::rerecast::{
+    AreaType, BuildContoursFlags, Config, ConfigBuilder, ConvexVolume, DetailNavmesh,
+    PolygonNavmesh, TriMesh,
+};
+use bevy_rerecast::{
+    CombineTriMeshes as _, CoordinateRemap, Handedness, HeightmapBackendPlugin, HeightmapTerrain,
+    Mesh2dBackendPlugin, Mesh3dBackendPlugin, NavmeshBackends, NavmeshExcluded, NavmeshMetadata,
+    NavmeshSettingsError, PartitionMethod, RerecastPlugin, TriMeshFromBevyMesh as _,
+    asset_loader::NavmeshLoaderSettings,
+    debug::{DetailNavmeshGizmo, NavmeshDebugPlugin, NavmeshGizmoConfig, PolygonNavmeshGizmo},
+    generator::{LastBuildStats, NavmeshGenerationError},
+    prelude::*,
+};
+use bevy_rerecast_editor_integration::NavmeshEditorIntegrationPlugin;
+
+/// How far detail vertices may drift from the reference navmeshes, to tolerate float noise across platforms.
+const TOLERANCE: f32 = 1.0e-4;
+
+#[test]
+fn gltf_generation() {
+    let mut app = App::new_test();
+    let gltf_handle = app.world().load_asset("models/dungeon.glb#Scene0");
+    app.world_mut().spawn(SceneRoot(gltf_handle)).observe(
+        |_: On<SceneInstanceReady>, mut commands: Commands| {
+            commands.insert_resource(GltfLoaded);
+        },
+    );
+
+    let now = Instant::now();
+    while app.world().get_resource::<GltfLoaded>().is_none() {
+        app.update();
+        if now.elapsed().as_secs() > 5 {
+            panic!("Timeout waiting for glTF to load");
+        }
+    }
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings::default());
+    let navmesh = app.get_navmesh(&navmesh_handle);
+    let expected_navmesh = app.read_navmesh("test/dungeon/navmesh.nav");
+
+    assert!(
+        expected_navmesh.approx_eq(&navmesh, TOLERANCE),
+        "Generated navmesh does not match reference"
+    );
+}
+
+#[test]
+fn primitive_2d_regeneration() {
+    let mut app = App::new_test();
+    let ground_handle = app
+        .world_mut()
+        .resource_mut::<Assets<Mesh>>()
+        .add(Cuboid::new(1000.0, 1000.0, 1.0));
+    let cube_handle = app
+        .world_mut()
+        .resource_mut::<Assets<Mesh>>()
+        .add(Cuboid::new(10.0, 10.0, 10.0));
+    app.world_mut().spawn(Mesh3d(ground_handle));
+    let cube_entity = app.world_mut().spawn(Mesh3d(cube_handle)).id();
+
+    let settings = NavmeshSettings {
+        aabb: Some(Aabb3d::new(Vec3::ZERO, Vec3::new(100.0, 100.0, 5.0))),
+        ..NavmeshSettings::from_agent_2d(5.0, 2.0)
+    };
+    let navmesh_handle = app.generate_navmesh(settings.clone());
+    let navmesh = app.get_navmesh(&navmesh_handle);
+    let expected_navmesh = app.read_navmesh("test/primitives/navmesh_1.nav");
+
+    assert!(
+        expected_navmesh.approx_eq(&navmesh, TOLERANCE),
+        "Initial generated navmesh does not match reference"
+    );
+
+    app.world_mut().despawn(cube_entity);
+    app.regenerate_navmesh(&navmesh_handle, settings);
+    app.wait_for_navmesh_ready(&navmesh_handle);
+    let navmesh = app.get_navmesh(&navmesh_handle);
+    let expected_navmesh = app.read_navmesh("test/primitives/navmesh_2.nav");
+
+    assert!(
+        expected_navmesh.approx_eq(&navmesh, TOLERANCE),
+        "Regenerated navmesh does not match reference"
+    );
+}
+
+#[test]
+fn empty_generation() {
+    let mut app = App::new_test();
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings::default());
+    let navmesh = app.get_navmesh(&navmesh_handle);
+
+    assert!(
+        navmesh.polygon.vertices.is_empty(),
+        "Navmesh generated without affectors should be empty"
+    );
+    assert_eq!(navmesh.polygon.polygon_count(), 0);
+    assert!(navmesh.detail.meshes.is_empty());
+    assert!(navmesh.is_empty());
+    assert_eq!(navmesh.vertex_count(), 0);
+    assert_eq!(navmesh.detail_triangle_count(), 0);
+}
+
+#[test]
+fn too_coarse_cells_diagnostic() {
+    let mut app = App::new_test();
+    app.spawn_mesh(Cuboid::new(20.0, 1.0, 20.0), Transform::default());
+
+    let settings = NavmeshSettings {
+        cell_height_fraction: 0.2,
+        ..NavmeshSettings::default()
+    };
+    let navmesh_handle = app.generate_navmesh(settings);
+    let navmesh = app.get_navmesh(&navmesh_handle);
+
+    assert!(
+        navmesh
+            .diagnostics
+            .iter()
+            .any(|diagnostic| matches!(diagnostic, NavmeshDiagnostic::TooCoarseCells { .. })),
+        "Expected a too-coarse cells diagnostic, got {:?}",
+        navmesh.diagnostics
+    );
+}
+
+#[test]
+fn drop_handle_during_regeneration() {
+    let mut app = App::new_test();
+    app.spawn_mesh(Cuboid::new(20.0, 1.0, 20.0), Transform::default());
+
+    let settings = NavmeshSettings::default();
+    let navmesh_handle = app.generate_navmesh(settings.clone());
+    app.get_navmesh(&navmesh_handle);
+
+    assert!(app.regenerate_navmesh(&navmesh_handle, settings));
+    drop(navmesh_handle);
+    for _ in 0..10 {
+        app.update();
+    }
+}
+
+#[test]
+fn pending_generation_status() {
+    let mut app = App::new_test();
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings::default());
+    let id = navmesh_handle.id();
+    let (pending, count) = app
+        .world_mut()
+        .run_system_once(move |generator: NavmeshGenerator| {
+            (generator.is_pending(id), generator.pending_count())
+        })
+        .unwrap();
+    assert!(pending);
+    assert_eq!(count, 1);
+
+    app.get_navmesh(&navmesh_handle);
+    let (pending, count) = app
+        .world_mut()
+        .run_system_once(move |generator: NavmeshGenerator| {
+            (generator.is_pending(id), generator.pending_count())
+        })
+        .unwrap();
+    assert!(!pending);
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn thin_platform_diagnostic() {
+    let mut app = App::new_test();
+    app.spawn_mesh(Cuboid::new(20.0, 0.05, 20.0), Transform::default());
+
+    let settings = NavmeshSettings {
+        cell_height_fraction: 2.0,
+        ..NavmeshSettings::default()
+    };
+    let navmesh_handle = app.generate_navmesh(settings);
+    let navmesh = app.get_navmesh(&navmesh_handle);
+
+    assert!(
+        navmesh
+            .diagnostics
+            .iter()
+            .any(|diagnostic| matches!(diagnostic, NavmeshDiagnostic::ThinFeatures { .. })),
+        "Expected a thin features diagnostic, got {:?}",
+        navmesh.diagnostics
+    );
+}
+
+#[test]
+fn batch_generation_runs_backend_once() {
+    let mut app = App::new_test();
+    app.init_resource::<BackendRuns>();
+    app.set_navmesh_backend(|_: In<NavmeshSettings>, mut runs: ResMut<BackendRuns>| {
+        runs.0 += 1;
+        TriMesh::default()
+    });
+
+    let handles = app
+        .world_mut()
+        .run_system_once(|mut generator: NavmeshGenerator| {
+            generator.generate_batch([
+                NavmeshSettings::from_agent_3d(0.3, 1.0),
+                NavmeshSettings::from_agent_3d(0.6, 2.0),
+                NavmeshSettings::from_agent_3d(1.2, 4.0),
+            ])
+        })
+        .unwrap();
+    assert_eq!(handles.len(), 3);
+    for handle in &handles {
+        app.get_navmesh(handle);
+    }
+    assert_eq!(app.world().resource::<BackendRuns>().0, 1);
+}
+
+#[derive(Resource, Default)]
+struct BackendRuns(usize);
+
+#[test]
+fn skip_detail_generation() {
+    let mut app = App::new_test();
+    app.spawn_mesh(Cuboid::new(20.0, 1.0, 20.0), Transform::default());
+
+    let with_detail = app.generate_navmesh(NavmeshSettings::default());
+    let with_detail = app.get_navmesh(&with_detail);
+    let without_detail = app.generate_navmesh(NavmeshSettings {
+        generate_detail: false,
+        ..default()
+    });
+    let without_detail = app.get_navmesh(&without_detail);
+
+    assert!(!with_detail.detail.meshes.is_empty());
+    assert_eq!(without_detail.detail, default());
+    assert_eq!(with_detail.polygon, without_detail.polygon);
+
+    let size = |navmesh: &Navmesh| {
+        bincode::serde::encode_to_vec(navmesh, bincode::config::standard())
+            .unwrap()
+            .len()
+    };
+    assert!(size(&without_detail) < size(&with_detail));
+}
+
+#[test]
+fn partition_methods() {
+    let mut app = App::new_test();
+    app.spawn_mesh(Cuboid::new(20.0, 1.0, 20.0), Transform::default());
+    app.spawn_mesh(
+        Cuboid::new(2.0, 5.0, 2.0),
+        Transform::from_xyz(3.0, 2.0, -1.0),
+    );
+
+    for partition in [
+        PartitionMethod::Watershed,
+        PartitionMethod::Monotone,
+        PartitionMethod::Layers,
+    ] {
+        let navmesh_handle = app.generate_navmesh(NavmeshSettings {
+            partition,
+            ..default()
+        });
+        let navmesh = app.get_navmesh(&navmesh_handle);
+        assert!(
+            navmesh.polygon.polygon_count() > 0,
+            "{partition:?} partitioning generated an empty navmesh"
+        );
+        assert!(!navmesh.detail.meshes.is_empty());
+        assert_eq!(navmesh.settings.partition, partition);
+    }
+}
+
+#[test]
+fn null_area_volume_carves_hole() {
+    let mut app = App::new_test();
+    app.spawn_mesh(Cuboid::new(20.0, 1.0, 20.0), Transform::default());
+
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings {
+        area_volumes: vec![ConvexVolume {
+            vertices: vec![
+                Vec2::new(-2.0, -2.0),
+                Vec2::new(2.0, -2.0),
+                Vec2::new(2.0, 2.0),
+                Vec2::new(-2.0, 2.0),
+            ],
+            min_y: -1.0,
+            max_y: 2.0,
+            area: AreaType::NOT_WALKABLE,
+        }],
+        ..default()
+    });
+    let navmesh = app.get_navmesh(&navmesh_handle);
+
+    // Leave a margin of roughly one cell plus the contour simplification error around the box footprint.
+    for inside in [
+        Vec2::ZERO,
+        Vec2::new(1.0, 1.0),
+        Vec2::new(-1.0, 1.0),
+        Vec2::new(1.0, -1.0),
+        Vec2::new(-1.0, -1.0),
+    ] {
+        assert!(
+            !covers(&navmesh.polygon, inside),
+            "Point {inside} inside the null area volume should not be walkable"
+        );
+    }
+    for outside in [
+        Vec2::new(3.0, 0.0),
+        Vec2::new(-3.0, 0.0),
+        Vec2::new(0.0, 3.0),
+        Vec2::new(0.0, -3.0),
+    ] {
+        assert!(
+            covers(&navmesh.polygon, outside),
+            "Point {outside} around the null area volume should be walkable"
+        );
+    }
+}
+
+/// Returns whether any polygon of the mesh contains the point on the XZ plane.
+fn covers(mesh: &PolygonNavmesh, point: Vec2) -> bool {
+    let cell = Vec2::splat(mesh.cell_size);
+    mesh.polygons().any(|polygon| {
+        let vertices = polygon
+            .map(|i| mesh.aabb.min.xz() + mesh.vertices[i as usize].xz().as_vec2() * cell)
+            .collect::<Vec<_>>();
+        let mut inside = false;
+        let mut j = vertices.len() - 1;
+        for i in 0..vertices.len() {
+            let (a, b) = (vertices[i], vertices[j]);
+            if (a.y > point.y) != (b.y > point.y)
+                && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+            {
+                inside = !inside;
+            }
+            j = i;
+        }
+        inside
+    })
+}
+
+#[test]
+fn generated_navmesh_stores_contours() {
+    let mut app = App::new_test();
+    app.spawn_mesh(Cuboid::new(20.0, 1.0, 20.0), Transform::default());
+
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings::default());
+    let navmesh = app.get_navmesh(&navmesh_handle);
+    let contours = navmesh
+        .contours
+        .expect("Generated navmeshes should store their contours");
+    assert!(!contours.contours.is_empty());
+    assert_eq!(contours.aabb, navmesh.polygon.aabb);
+    for contour in &contours.contours {
+        assert!(contour.vertices.len() <= contour.raw_vertices.len());
+    }
+
+    let loaded = app.read_navmesh("test/primitives/navmesh_1.nav");
+    assert_eq!(loaded.contours, None);
+}
+
+#[test]
+fn custom_default_walkable_area() {
+    let mut app = App::new_test();
+    app.spawn_mesh(Cuboid::new(20.0, 1.0, 20.0), Transform::default());
+
+    let floor = AreaType(1);
+    let mud = AreaType(2);
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings {
+        default_walkable_area: floor,
+        area_volumes: vec![ConvexVolume {
+            vertices: vec![
+                Vec2::new(-2.0, -2.0),
+                Vec2::new(2.0, -2.0),
+                Vec2::new(2.0, 2.0),
+                Vec2::new(-2.0, 2.0),
+            ],
+            min_y: -1.0,
+            max_y: 2.0,
+            area: mud,
+        }],
+        ..default()
+    });
+    let navmesh = app.get_navmesh(&navmesh_handle);
+
+    assert!(navmesh.polygon.areas.contains(&floor));
+    assert!(navmesh.polygon.areas.contains(&mud));
+    assert!(
+        navmesh
+            .polygon
+            .areas
+            .iter()
+            .all(|area| *area == floor || *area == mud),
+        "Unexpected area types: {:?}",
+        navmesh.polygon.areas
+    );
+}
+
+#[test]
+fn gizmos_follow_their_config() {
+    let mut app = App::new();
+    app.add_plugins(headless_plugins)
+        .init_asset::<GizmoAsset>()
+        .add_plugins((
+            NavmeshPlugins::default()
+                .build()
+                .disable::<NavmeshEditorIntegrationPlugin>(),
+            Mesh3dBackendPlugin::default(),
+        ));
+    app.spawn_mesh(Cuboid::new(10.0, 1.0, 10.0), Transform::default());
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings::default());
+    app.get_navmesh(&navmesh_handle);
+
+    let polygon = app
+        .world_mut()
+        .spawn(PolygonNavmeshGizmo::new(&navmesh_handle))
+        .id();
+    let detail = app
+        .world_mut()
+        .spawn(DetailNavmeshGizmo::new(&navmesh_handle))
+        .id();
+    app.update();
+
+    let drawn = |app: &App, entity: Entity| {
+        let entity = app.world().entity(entity);
+        let visible = entity.get::<Visibility>() == Some(&Visibility::Inherited);
+        (visible, entity.contains::<Mesh3d>())
+    };
+    // Polygon gizmos are disabled by default.
+    assert_eq!(drawn(&app, polygon), (false, false));
+    assert_eq!(drawn(&app, detail), (true, true));
+
+    let mut config = app.world_mut().resource_mut::<NavmeshGizmoConfig>();
+    config.polygon_navmesh.enabled = true;
+    config.detail_navmesh.enabled = false;
+    app.update();
+    assert_eq!(drawn(&app, polygon), (true, true));
+    assert!(!drawn(&app, detail).0);
+
+    app.world_mut()
+        .resource_mut::<Assets<Navmesh>>()
+        .remove(&navmesh_handle);
+    app.update();
+    app.update();
+    assert!(app.world().get_entity(polygon).is_err());
+    assert!(app.world().get_entity(detail).is_err());
+}
+
+#[test]
+fn backend_receives_configured_aabb() {
+    let mut app = App::new_test();
+    app.init_resource::<BackendAabbs>();
+    app.set_navmesh_backend(
+        |input: In<NavmeshSettings>, mut aabbs: ResMut<BackendAabbs>| {
+            aabbs.0.push(input.aabb);
+            TriMesh::default()
+        },
+    );
+
+    let aabb = Aabb3d::new(Vec3::ZERO, Vec3::new(10.0, 5.0, 10.0));
+    let handle = app.generate_navmesh(NavmeshSettings {
+        aabb: Some(aabb),
+        ..default()
+    });
+    app.get_navmesh(&handle);
+    let handle = app.generate_navmesh(NavmeshSettings::default());
+    app.get_navmesh(&handle);
+
+    let other_aabb = Aabb3d::new(Vec3::new(20.0, 0.0, 0.0), Vec3::new(5.0, 5.0, 5.0));
+    let handles = app
+        .world_mut()
+        .run_system_once(move |mut generator: NavmeshGenerator| {
+            generator.generate_batch([
+                NavmeshSettings {
+                    aabb: Some(aabb),
+                    ..default()
+                },
+                NavmeshSettings {
+                    aabb: Some(other_aabb),
+                    ..default()
+                },
+            ])
+        })
+        .unwrap();
+    for handle in &handles {
+        app.get_navmesh(handle);
+    }
+
+    let received = &app.world().resource::<BackendAabbs>().0;
+    assert_eq!(
+        received,
+        &[
+            Some(aabb),
+            None,
+            Some(Aabb3d::new(
+                Vec3::new(7.5, 0.0, 0.0),
+                Vec3::new(17.5, 5.0, 10.0)
+            )),
+        ]
+    );
+}
+
+#[derive(Resource, Default)]
+struct BackendAabbs(Vec<Option<Aabb3d>>);
+
+#[test]
+fn async_path_matches_sync_path() {
+    let mut app = App::new_test();
+    app.spawn_mesh(Cuboid::new(100.0, 1.0, 100.0), Transform::default());
+    let pillar = app
+        .world_mut()
+        .resource_mut::<Assets<Mesh>>()
+        .add(Cuboid::new(3.0, 10.0, 3.0));
+    // A grid of pillars breaks the floor up into many polygons.
+    for x in -4..=4 {
+        for z in -4..=4 {
+            let translation = Vec3::new(x as f32 * 10.0, 5.0, z as f32 * 10.0);
+            app.world_mut().spawn((
+                Mesh3d(pillar.clone()),
+                Transform::from_translation(translation),
+            ));
+        }
+    }
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings::default());
+    let mesh = app.get_navmesh(&navmesh_handle).polygon;
+    assert!(mesh.polygon_count() > 100);
+
+    // Path between the polygons in opposite corners of the floor.
+    let nvp = usize::from(mesh.max_vertices_per_polygon);
+    let corner = |poly: &u32| {
+        let vertex = mesh.vertices[usize::from(mesh.polygons[*poly as usize * nvp])];
+        u32::from(vertex.x) + u32::from(vertex.z)
+    };
+    let polygons = 0..mesh.polygon_count() as u32;
+    let start = polygons.clone().min_by_key(corner).unwrap();
+    let end = polygons.max_by_key(corner).unwrap();
+    let expected = mesh
+        .find_path(start, end, &HashSet::new())
+        .expect("The corners of the floor should be connected");
+    assert!(expected.len() > 10);
+
+    let id = navmesh_handle.id();
+    let task = app
+        .world_mut()
+        .run_system_once(move |queries: NavmeshQueries| {
+            queries.find_path_async(id, start, end, 0.0)
+        })
+        .unwrap();
+    assert_eq!(bevy::tasks::block_on(task), Some(expected));
+
+    let task = app
+        .world_mut()
+        .run_system_once(move |queries: NavmeshQueries| {
+            queries.find_path_async(AssetId::default(), start, end, 0.0)
+        })
+        .unwrap();
+    assert_eq!(bevy::tasks::block_on(task), None);
+}
+
+#[test]
+fn keep_heightfield() {
+    let mut app = App::new_test();
+    app.spawn_mesh(Cuboid::new(20.0, 1.0, 20.0), Transform::default());
+
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings::default());
+    let navmesh = app.get_navmesh(&navmesh_handle);
+    assert_eq!(navmesh.heightfield, None);
+
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings {
+        keep_heightfield: true,
+        ..default()
+    });
+    let navmesh = app.get_navmesh(&navmesh_handle);
+    let heightfield = navmesh
+        .heightfield
+        .expect("Navmeshes generated with `keep_heightfield` should store their heightfield");
+    assert!(!heightfield.centers.is_empty());
+    // All walkable spans lie on top of the ground.
+    for center in &heightfield.centers {
+        assert!(ops::abs(center.y - 0.5) <= heightfield.span_size.y * 2.0);
+        assert!(ops::abs(center.x) <= 10.0 && ops::abs(center.z) <= 10.0);
+    }
+}
+
+#[test]
+fn keep_distance_field() {
+    let mut app = App::new_test();
+    app.spawn_mesh(Cuboid::new(20.0, 1.0, 20.0), Transform::default());
+
+    for partition in [PartitionMethod::Watershed, PartitionMethod::Monotone] {
+        let navmesh_handle = app.generate_navmesh(NavmeshSettings {
+            keep_distance_field: true,
+            partition,
+            ..default()
+        });
+        let navmesh = app.get_navmesh(&navmesh_handle);
+        let distance_field = navmesh.distance_field.expect(
+            "Navmeshes generated with `keep_distance_field` should store their distance field",
+        );
+        assert!(distance_field.max_distance > 0, "{partition:?}");
+
+        // The distance to the border grows towards the center of the ground.
+        let distance_at = |point: Vec3| {
+            distance_field
+                .spans
+                .iter()
+                .min_by(|(a, _), (b, _)| a.distance(point).total_cmp(&b.distance(point)))
+                .map(|(_, distance)| *distance)
+                .unwrap()
+        };
+        assert!(
+            distance_at(Vec3::new(0.0, 0.5, 0.0)) > distance_at(Vec3::new(9.0, 0.5, 0.0)),
+            "{partition:?}"
+        );
+    }
+}
+
+#[test]
+fn cancel_and_regenerate_on_aabb_change() {
+    let mut app = App::new_test();
+    app.spawn_mesh(Cuboid::new(20.0, 1.0, 20.0), Transform::default());
+
+    let old_aabb = Aabb3d::new(Vec3::ZERO, Vec3::new(10.0, 5.0, 10.0));
+    let new_aabb = Aabb3d::new(Vec3::new(5.0, 0.0, 5.0), Vec3::new(4.0, 5.0, 4.0));
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings {
+        aabb: Some(old_aabb),
+        ..default()
+    });
+    // Start the build with the old bounds.
+    app.update();
+
+    let handle = navmesh_handle.clone();
+    let pending = app
+        .world_mut()
+        .run_system_once(move |mut generator: NavmeshGenerator| {
+            generator.cancel(&handle);
+            let requeued = generator.regenerate(
+                &handle,
+                NavmeshSettings {
+                    aabb: Some(new_aabb),
+                    ..default()
+                },
+            );
+            assert!(
+                requeued,
+                "Cancelled navmeshes should be able to be regenerated"
+            );
+            generator.is_pending(&handle)
+        })
+        .unwrap();
+    assert!(pending);
+    // The old build might have finished in the frame it started, so ignore its event.
+    app.world_mut().remove_resource::<NavmeshReadyResource>();
+    app.wait_for_navmesh_ready(&navmesh_handle);
+
+    let navmesh = app
+        .world()
+        .resource::<Assets<Navmesh>>()
+        .get(&navmesh_handle)
+        .unwrap();
+    assert_eq!(navmesh.settings.aabb, Some(new_aabb));
+    assert!(!navmesh.polygon.vertices.is_empty());
+
+    // Cancelling a queued navmesh means it is never generated.
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings::default());
+    let handle = navmesh_handle.clone();
+    let cancelled = app
+        .world_mut()
+        .run_system_once(move |mut generator: NavmeshGenerator| {
+            (generator.cancel(&handle), generator.cancel(&handle))
+        })
+        .unwrap();
+    assert_eq!(cancelled, (true, false));
+    for _ in 0..10 {
+        app.update();
+    }
+    assert!(
+        app.world()
+            .resource::<Assets<Navmesh>>()
+            .get(&navmesh_handle)
+            .is_none()
+    );
+}
+
+#[test]
+fn settings_presets() {
+    let mut app = App::new_test();
+    let ground_3d = app.spawn_mesh(Cuboid::new(20.0, 1.0, 20.0), Transform::default());
+
+    for settings in [
+        NavmeshSettings::indoor_human(),
+        NavmeshSettings::outdoor_large_world(),
+    ] {
+        let navmesh_handle = app.generate_navmesh(settings);
+        let navmesh = app.get_navmesh(&navmesh_handle);
+        assert!(navmesh.polygon.polygon_count() > 0);
+    }
+
+    app.world_mut().despawn(ground_3d);
+    app.spawn_mesh(Cuboid::new(20.0, 20.0, 1.0), Transform::default());
+
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings::top_down_2d());
+    let navmesh = app.get_navmesh(&navmesh_handle);
+    assert!(navmesh.polygon.polygon_count() > 0);
+    assert_eq!(navmesh.detail, default());
+}
+
+#[test]
+fn navmesh_plugins_members() {
+    let app_with = |plugins: PluginGroupBuilder| {
+        let mut app = App::new();
+        app.add_plugins((
+            headless_plugins,
+            plugins.disable::<NavmeshEditorIntegrationPlugin>(),
+        ));
+        app
+    };
+
+    let app = app_with(NavmeshPlugins::default().build());
+    assert!(app.is_plugin_added::<RerecastPlugin>());
+    assert!(app.is_plugin_added::<NavmeshDebugPlugin>());
+    assert!(app.world().contains_resource::<NavmeshGizmoConfig>());
+    assert!(!app.is_plugin_added::<Mesh3dBackendPlugin>());
+    assert!(!app.world().contains_resource::<NavmeshBackends>());
+
+    let app = app_with(
+        NavmeshPlugins::default()
+            .build()
+            .disable::<NavmeshDebugPlugin>()
+            .enable::<Mesh3dBackendPlugin>(),
+    );
+    assert!(!app.is_plugin_added::<NavmeshDebugPlugin>());
+    assert!(!app.world().contains_resource::<NavmeshGizmoConfig>());
+    assert!(app.is_plugin_added::<Mesh3dBackendPlugin>());
+    assert!(app.world().contains_resource::<NavmeshBackends>());
+
+    let app = app_with(
+        NavmeshPlugins::default()
+            .build()
+            .disable::<RerecastPlugin>(),
+    );
+    assert!(!app.is_plugin_added::<RerecastPlugin>());
+    assert!(!app.world().contains_resource::<Assets<Navmesh>>());
+}
+
+#[test]
+fn path_between_entities_on_dungeon() {
+    let mut app = App::new_test();
+    let navmesh = app.read_navmesh("test/dungeon/navmesh.nav");
+    let mesh = &navmesh.polygon;
+    let polygon_count = mesh.polygon_count() as u32;
+    let (start_poly, end_poly) = (0..polygon_count)
+        .flat_map(|start| (0..polygon_count).map(move |end| (start, end)))
+        .find(|&(start, end)| {
+            mesh.find_path(start, end, &HashSet::new())
+                .is_some_and(|corridor| corridor.len() > 3)
+        })
+        .expect("The dungeon should contain polygons that are connected through others");
+    let center = |poly: u32| {
+        let triangle = mesh.poly_triangles(poly).next().unwrap();
+        triangle.into_iter().sum::<Vec3>() / 3.0
+    };
+    let (start, end) = (center(start_poly), center(end_poly));
+
+    let id = app
+        .world_mut()
+        .resource_mut::<Assets<Navmesh>>()
+        .add(navmesh)
+        .id();
+    // Place the agents' origins above the ground so that they need to be snapped to the navmesh.
+    let start_agent = app
+        .world_mut()
+        .spawn(GlobalTransform::from_translation(start + Vec3::Y))
+        .id();
+    let end_agent = app
+        .world_mut()
+        .spawn(GlobalTransform::from_translation(end + Vec3::Y))
+        .id();
+    let path = app
+        .world_mut()
+        .run_system_once(move |queries: NavmeshQueries| {
+            queries.find_path_between(id, start_agent, end_agent, 0.0)
+        })
+        .unwrap()
+        .expect("The agents should be connected");
+
+    assert!(path.len() >= 2);
+    let (first, last) = (path[0], path[path.len() - 1]);
+    assert!(first.distance(start) < 1e-3, "{first} != {start}");
+    assert!(last.distance(end) < 1e-3, "{last} != {end}");
+}
+
+#[test]
+fn left_handed_z_up_is_not_mirrored() {
+    let mut app = App::new_test();
+    // A single-sided 20x10 ground in the XY plane that faces +Z in a left-handed coordinate system
+    let ground = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        vec![
+            [0.0, 0.0, 0.0],
+            [20.0, 0.0, 0.0],
+            [20.0, 10.0, 0.0],
+            [0.0, 10.0, 0.0],
+        ],
+    )
+    .with_inserted_indices(Indices::U32(vec![0, 2, 1, 0, 3, 2]));
+    app.spawn_mesh(ground, Transform::default());
+
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings {
+        up: Vec3::Z,
+        handedness: Handedness::LeftHanded,
+        ..NavmeshSettings::from_agent_3d(0.5, 2.0)
+    });
+    let navmesh = app.get_navmesh(&navmesh_handle);
+    assert!(navmesh.polygon.polygon_count() > 0);
+    for vertex in &navmesh.detail.vertices {
+        assert!(
+            (-0.01..=20.01).contains(&vertex.x)
+                && (-0.01..=10.01).contains(&vertex.y)
+                && ops::abs(vertex.z) < 1.0,
+            "Detail vertex {vertex} lies outside of the ground"
+        );
+    }
+
+    // Queries take world-space positions and convert the polygons into Recast's coordinate system once per change.
+    app.update();
+    let (start, end) = (Vec3::new(2.0, 5.0, 0.0), Vec3::new(18.0, 5.0, 0.0));
+    let half_extents = NavmeshQueries::DEFAULT_HALF_EXTENTS;
+    let id = navmesh_handle.id();
+    let path = app
+        .world_mut()
+        .run_system_once(move |queries: NavmeshQueries| {
+            queries.find_path(id, start, end, half_extents, 0.0)
+        })
+        .unwrap()
+        .expect("The ground should connect both points");
+    let remap = CoordinateRemap::from(&navmesh.settings);
+    let mut polygon = navmesh.polygon.clone();
+    remap.polygon_to_recast(&mut polygon);
+    let expected = polygon
+        .find_nearest_smooth_path(
+            remap.to_recast(start),
+            remap.to_recast(end),
+            remap.to_recast(half_extents).abs(),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|point| remap.from_recast(point))
+        .collect::<Vec<_>>();
+    assert_eq!(path, expected);
+    // The endpoints are snapped onto the polygons, which lie slightly above the ground along z.
+    assert_eq!(path.first().unwrap().truncate(), start.truncate());
+    assert_eq!(path.last().unwrap().truncate(), end.truncate());
+
+    let height = app
+        .world_mut()
+        .run_system_once(move |queries: NavmeshQueries| {
+            queries.height_at(id, Vec3::new(10.0, 5.0, 1.0))
+        })
+        .unwrap()
+        .expect("The ground should be covered by the navmesh");
+    assert!(ops::abs(height) < 0.5, "{height} != 0");
+}
+
+#[test]
+fn captured_input_matches_obstacles() {
+    let mut app = App::new_test();
+    app.capture(NavmeshInputCaptured::clone);
+    let ground = Cuboid::new(20.0, 1.0, 20.0);
+    let cube = Cuboid::from_length(2.0);
+    let transforms = [Transform::default(), Transform::from_xyz(3.0, 1.5, -4.0)];
+    for (primitive, transform) in [ground, cube].into_iter().zip(transforms) {
+        app.spawn_mesh(primitive, transform);
+    }
+
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings::default());
+    app.get_navmesh(&navmesh_handle);
+    assert!(
+        app.captured::<NavmeshInputCaptured>().is_none(),
+        "Input must only be captured when enabled"
+    );
+
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings {
+        capture_input: true,
+        ..default()
+    });
+    app.get_navmesh(&navmesh_handle);
+    let captured = app.captured::<NavmeshInputCaptured>().unwrap();
+    assert_eq!(captured.id, navmesh_handle.id());
+
+    let expected = TriMesh::combine([ground, cube].into_iter().zip(transforms).map(
+        |(primitive, transform)| {
+            let trimesh = TriMesh::from_mesh(&primitive.into()).unwrap();
+            (GlobalTransform::from(transform), trimesh)
+        },
+    ));
+    assert_same_triangles(&captured.trimesh, &expected);
+}
+
+/// Asserts that two trimeshes contain the same triangles, regardless of their order.
+/// The backends collect obstacles sorted by entity, which is not necessarily the spawn order.
+fn assert_same_triangles(actual: &TriMesh, expected: &TriMesh) {
+    let triangles = |trimesh: &TriMesh| {
+        trimesh
+            .indices
+            .iter()
+            .zip(&trimesh.area_types)
+            .map(|(indices, area)| {
+                let triangle = indices.to_array().map(|i| trimesh.vertices[i as usize]);
+                (triangle, *area)
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut expected = triangles(expected);
+    let actual = triangles(actual);
+    assert_eq!(actual.len(), expected.len());
+    for (triangle, area) in actual {
+        let index = expected
+            .iter()
+            .position(|(other, other_area)| {
+                area == *other_area
+                    && triangle
+                        .iter()
+                        .zip(other)
+                        .all(|(a, b)| a.abs_diff_eq(*b, TOLERANCE))
+            })
+            .unwrap_or_else(|| panic!("Unexpected triangle {triangle:?}"));
+        expected.swap_remove(index);
+    }
+}
+
+#[test]
+fn low_ceiling_excludes_tall_agents() {
+    let mut app = App::new_test();
+    // An L-shaped floor whose arm along z ends below a ceiling 2.5 units above the floor.
+    for (cuboid, translation) in [
+        (Cuboid::new(20.0, 1.0, 4.0), Vec3::new(10.0, -0.5, 2.0)),
+        (Cuboid::new(4.0, 1.0, 20.0), Vec3::new(2.0, -0.5, 10.0)),
+        (Cuboid::new(8.0, 0.5, 8.0), Vec3::new(2.0, 2.75, 18.0)),
+    ] {
+        app.spawn_mesh(cuboid, Transform::from_translation(translation));
+    }
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings::from_agent_3d(0.5, 2.0));
+    let navmesh = app.get_navmesh(&navmesh_handle);
+    assert_eq!(navmesh.clearances.len(), navmesh.polygon.polygon_count());
+
+    let id = navmesh_handle.id();
+    let mut find_path = |start: Vec3, end: Vec3, min_clearance: f32| {
+        app.world_mut()
+            .run_system_once(move |queries: NavmeshQueries| {
+                let half_extents = NavmeshQueries::DEFAULT_HALF_EXTENTS;
+                queries.find_path(id, start, end, half_extents, min_clearance)
+            })
+            .unwrap()
+    };
+    let open_end = Vec3::new(18.0, 0.0, 2.0);
+    let open_middle = Vec3::new(14.0, 0.0, 2.0);
+    let covered_end = Vec3::new(2.0, 0.0, 18.0);
+    assert!(
+        find_path(open_end, covered_end, 2.0).is_some(),
+        "A short agent fits below the ceiling"
+    );
+    assert!(
+        find_path(open_end, covered_end, 3.0).is_none(),
+        "A tall agent does not fit below the ceiling"
+    );
+    assert!(
+        find_path(open_end, open_middle, 3.0).is_some(),
+        "A tall agent can still walk where there is no ceiling"
+    );
+}
+
+#[test]
+fn failed_generation_triggers_event() {
+    let mut app = App::new_test();
+    app.capture(|failed: &NavmeshGenerationFailed| failed.id);
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings {
+        agent_radius: -0.5,
+        ..default()
+    });
+    assert_eq!(
+        *app.wait_for_capture::<AssetId<Navmesh>>(),
+        navmesh_handle.id()
+    );
+    let navmeshes = app.world().resource::<Assets<Navmesh>>();
+    assert!(navmeshes.get(&navmesh_handle).is_none());
+}
+
+#[test]
+fn time_budget_coarsens_cells() {
+    let mut app = App::new_test();
+    app.spawn_mesh(Cuboid::new(100.0, 1.0, 100.0), Transform::default());
+
+    let unbounded = NavmeshSettings::default();
+    let navmesh_handle = app.generate_navmesh(unbounded.clone());
+    let unbounded_navmesh = app.get_navmesh(&navmesh_handle);
+
+    let budget = Duration::from_micros(1);
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings {
+        time_budget: Some(budget),
+        ..unbounded.clone()
+    });
+    let navmesh = app.get_navmesh(&navmesh_handle);
+
+    assert!(navmesh.polygon.polygon_count() > 0);
+    assert!(navmesh.polygon.cell_size > unbounded_navmesh.polygon.cell_size);
+    assert!(navmesh.settings.cell_size_fraction < unbounded.cell_size_fraction);
+    let coarsening = |navmesh: &Navmesh| {
+        navmesh
+            .diagnostics
+            .iter()
+            .find_map(|diagnostic| match diagnostic {
+                NavmeshDiagnostic::CoarsenedForTimeBudget {
+                    budget,
+                    factor,
+                    skipped_detail,
+                    ..
+                } => Some((*budget, *factor, *skipped_detail)),
+                _ => None,
+            })
+    };
+    assert_eq!(coarsening(&unbounded_navmesh), None);
+    let (reported_budget, factor, skipped_detail) =
+        coarsening(&navmesh).expect("Expected a coarsened-for-time-budget diagnostic");
+    assert_eq!(reported_budget, budget);
+    assert_eq!(factor, NavmeshSettings::MAX_TIME_BUDGET_COARSENING);
+    assert_eq!(
+        navmesh.settings.detail_sample_dist,
+        unbounded.detail_sample_dist * factor
+    );
+    // Not even the coarsest cells fit into a microsecond.
+    assert!(skipped_detail);
+    assert!(!navmesh.settings.generate_detail);
+    assert!(navmesh.detail.meshes.is_empty());
+}
+
+#[test]
+fn navmesh_types_are_registered() {
+    let app = App::new_test();
+    let registry = app.world().resource::<AppTypeRegistry>().read();
+    let registered = |type_id: TypeId| registry.get(type_id).is_some();
+
+    assert!(registered(TypeId::of::<Navmesh>()));
+    assert!(registered(TypeId::of::<NavmeshSettings>()));
+    assert!(registered(TypeId::of::<PolygonNavmesh>()));
+    assert!(registered(TypeId::of::<DetailNavmesh>()));
+    assert!(registered(TypeId::of::<ConvexVolume>()));
+    assert!(registered(TypeId::of::<BuildContoursFlags>()));
+    assert!(registered(TypeId::of::<AreaType>()));
+    assert!(registered(TypeId::of::<PartitionMethod>()));
+    assert!(registered(TypeId::of::<Handedness>()));
+    assert!(registered(TypeId::of::<NavmeshMetadata>()));
+    assert!(registered(TypeId::of::<NavmeshDiagnostic>()));
+    assert!(registered(TypeId::of::<NavmeshRef>()));
+    assert!(registered(TypeId::of::<NavmeshExcluded>()));
+    assert!(registered(TypeId::of::<Config>()));
+    assert!(registered(TypeId::of::<ConfigBuilder>()));
+    // Nested types are registered together with the types containing them.
+    assert!(registered(TypeId::of::<Vec<ConvexVolume>>()));
+    #[cfg(feature = "ron")]
+    assert!(registered(TypeId::of::<
+        bevy_rerecast::settings_file::NavmeshSettingsAsset,
+    >()));
+}
+
+#[test]
+fn staged_load_provides_polygons_before_detail() {
+    let expected = App::new_test().read_navmesh("test/primitives/navmesh_1.nav");
+    assert!(!expected.detail.meshes.is_empty());
+
+    let mut app = App::new_test();
+    app.capture(|ready: &NavmeshDetailReady| ready.0);
+    let handle: Handle<Navmesh> = app.world().resource::<AssetServer>().load_with_settings(
+        "test/primitives/navmesh_1_split.nav",
+        |settings: &mut NavmeshLoaderSettings| {
+            settings.staged = true;
+            settings.settings_sidecar = true;
+        },
+    );
+
+    let coarse = app.get_navmesh(&handle);
+    assert_eq!(coarse.polygon, expected.polygon);
+    assert_eq!(coarse.settings, expected.settings);
+    assert_eq!(coarse.clearances, expected.clearances);
+    assert_eq!(coarse.metadata, expected.metadata);
+    assert!(coarse.detail.meshes.is_empty());
+    assert!(coarse.pending_detail.is_some());
+
+    assert_eq!(*app.wait_for_capture::<AssetId<Navmesh>>(), handle.id());
+    let navmesh = app.get_navmesh(&handle);
+    assert_eq!(navmesh.detail, expected.detail);
+    assert!(navmesh.pending_detail.is_none());
+
+    // Legacy files store the detail mesh in front of the settings, so they are loaded at once.
+    let mut app = App::new_test();
+    let handle: Handle<Navmesh> = app.world().resource::<AssetServer>().load_with_settings(
+        "test/primitives/navmesh_1.nav",
+        |settings: &mut NavmeshLoaderSettings| settings.staged = true,
+    );
+    let navmesh = app.get_navmesh(&handle);
+    assert_eq!(navmesh.detail, expected.detail);
+    assert!(navmesh.pending_detail.is_none());
+}
+
+#[test]
+fn prune_unreachable_islands() {
+    let mut app = App::new_test();
+    // An L-shaped floor and a small platform far above it.
+    for (cuboid, translation) in [
+        (Cuboid::new(20.0, 1.0, 4.0), Vec3::new(10.0, -0.5, 2.0)),
+        (Cuboid::new(4.0, 1.0, 20.0), Vec3::new(2.0, -0.5, 10.0)),
+        (Cuboid::new(4.0, 1.0, 4.0), Vec3::new(15.0, 10.0, 15.0)),
+    ] {
+        app.spawn_mesh(cuboid, Transform::from_translation(translation));
+    }
+    let settings = NavmeshSettings::from_agent_3d(0.5, 2.0);
+    let handle = app.generate_navmesh(settings.clone());
+    let unpruned = app.get_navmesh(&handle);
+    let islands = unpruned.polygon.connected_components();
+    assert_eq!(islands.len(), 2);
+    assert!(islands.iter().any(|island| island.len() == 1));
+
+    let handle = app.generate_navmesh(NavmeshSettings {
+        prune_unreachable_below: Some(2),
+        ..settings
+    });
+    let navmesh = app.get_navmesh(&handle);
+    assert_eq!(navmesh.polygon.connected_components().len(), 1);
+    assert_eq!(
+        navmesh.polygon.polygon_count(),
+        unpruned.polygon.polygon_count() - 1
+    );
+    assert_eq!(navmesh.detail.meshes.len(), navmesh.polygon.polygon_count());
+    assert_eq!(navmesh.clearances.len(), navmesh.polygon.polygon_count());
+    assert!(
+        navmesh
+            .diagnostics
+            .contains(&NavmeshDiagnostic::PrunedIslands {
+                islands: 1,
+                polygons: 1,
+            })
+    );
+}
+
+#[test]
+fn synchronous_builds_are_ready_in_the_same_update() {
+    let mut app = App::new_synchronous_test();
+    app.spawn_mesh(
+        Cuboid::new(10.0, 1.0, 10.0),
+        Transform::from_xyz(0.0, -0.5, 0.0),
+    );
+    // Let the transforms propagate before the generation is queued.
+    app.update();
+
+    let handle = app.generate_navmesh(NavmeshSettings::from_agent_3d(0.5, 2.0));
+    app.update();
+    assert_eq!(
+        app.world().get_resource::<NavmeshReadyResource>(),
+        Some(&NavmeshReadyResource(handle.id()))
+    );
+    let navmesh = app
+        .world()
+        .resource::<Assets<Navmesh>>()
+        .get(&handle)
+        .unwrap();
+    // The flat floor is a single quad.
+    assert_eq!(navmesh.polygon_count(), 1);
+    assert_eq!(navmesh.vertex_count(), 4);
+    assert_eq!(navmesh.detail_triangle_count(), 2);
+}
+
+#[test]
+fn navmesh_aabb_matches_settings_aabb() {
+    let mut app = App::new_test();
+    app.spawn_mesh(
+        Cuboid::new(10.0, 1.0, 10.0),
+        Transform::from_xyz(0.0, -0.5, 0.0),
+    );
+    let aabb = Aabb3d::new(Vec3::new(1.0, 0.0, -1.0), Vec3::new(4.0, 2.0, 3.0));
+    let handle = app.generate_navmesh(NavmeshSettings {
+        aabb: Some(aabb),
+        ..NavmeshSettings::from_agent_3d(0.5, 2.0)
+    });
+    let navmesh = app.get_navmesh(&handle);
+    assert!(!navmesh.is_empty());
+    // The border is `walkable_radius + 3` cells of 0.25 units,
+    // and the top is raised by the agent height.
+    let border = 5.0 * 0.25;
+    assert_eq!(
+        navmesh.aabb(),
+        Aabb3d {
+            min: aabb.min + Vec3A::new(border, 0.0, border),
+            max: aabb.max + Vec3A::new(-border, 2.0, -border),
+        }
+    );
+}
+
+#[test]
+fn tiled_generation_connects_tiles() {
+    let mut app = App::new_test();
+    app.spawn_mesh(
+        Cuboid::new(20.0, 1.0, 20.0),
+        Transform::from_xyz(10.0, -0.5, 10.0),
+    );
+    let handle = app.generate_navmesh(NavmeshSettings {
+        tiling: true,
+        tile_size: 16,
+        ..NavmeshSettings::from_agent_3d(0.5, 2.0)
+    });
+    let navmesh = app.get_navmesh(&handle);
+    assert!(!navmesh.is_empty());
+    // The polygons along the tile borders are linked to their neighbors in the adjacent tiles.
+    assert_eq!(navmesh.polygon.connected_components().len(), 1);
+    assert_eq!(navmesh.detail.meshes.len(), navmesh.polygon.polygon_count());
+    assert_eq!(navmesh.clearances.len(), navmesh.polygon.polygon_count());
+
+    let start = navmesh
+        .polygon
+        .find_polygon(Vec3::new(2.0, 0.0, 2.0))
+        .unwrap();
+    let end = navmesh
+        .polygon
+        .find_polygon(Vec3::new(18.0, 0.0, 18.0))
+        .unwrap();
+    assert!(
+        navmesh
+            .polygon
+            .find_path(start, end, &HashSet::new())
+            .is_some()
+    );
+}
 
 #[test]
-fn gltf_generation() {
+fn regenerate_tiles_splices_rebuilt_tiles() {
     let mut app = App::new_test();
-    let gltf_handle = app.world().load_asset("models/dungeon.glb#Scene0");
-    app.world_mut().spawn(SceneRoot(gltf_handle)).observe(
-        |_: On<SceneInstanceReady>, mut commands: Commands| {
-            commands.insert_resource(GltfLoaded);
+    app.spawn_mesh(
+        Cuboid::new(20.0, 1.0, 20.0),
+        Transform::from_xyz(10.0, -0.5, 10.0),
+    );
+    // A wall splitting the floor in two.
+    let wall = app.spawn_mesh(
+        Cuboid::new(1.0, 4.0, 20.0),
+        Transform::from_xyz(10.0, 2.0, 10.0),
+    );
+    let settings = NavmeshSettings {
+        tiling: true,
+        tile_size: 16,
+        aabb: Some(Aabb3d::new(
+            Vec3::new(10.0, 2.0, 10.0),
+            Vec3::new(10.0, 3.0, 10.0),
+        )),
+        ..NavmeshSettings::from_agent_3d(0.5, 2.0)
+    };
+    let handle = app.generate_navmesh(settings.clone());
+    let walled = app.get_navmesh(&handle);
+    assert!(walled.polygon.connected_components().len() > 1);
+    app.world_mut().remove_resource::<NavmeshReadyResource>();
+
+    app.world_mut().despawn(wall);
+    let tiles = walled.tiles.as_ref().unwrap();
+    let wall_aabb = Aabb3d::new(Vec3::new(10.0, 2.0, 10.0), Vec3::new(0.5, 2.0, 10.0));
+    let changed = tiles.overlapping(wall_aabb);
+    assert!(!changed.is_empty());
+    assert!(changed.len() < (tiles.grid_size().x * tiles.grid_size().y) as usize);
+    let queued = app
+        .world_mut()
+        .run_system_once({
+            let handle = handle.clone();
+            let settings = settings.clone();
+            move |mut generator: NavmeshGenerator| {
+                generator.regenerate_tiles(&handle, changed.clone(), settings.clone())
+            }
+        })
+        .unwrap();
+    assert!(queued);
+    app.wait_for_navmesh_ready(&handle);
+    let spliced = app.get_navmesh(&handle);
+    assert_eq!(spliced.polygon.connected_components().len(), 1);
+
+    // Splicing the rebuilt tiles gives the same navmesh as rebuilding all of them.
+    let rebuilt = app.generate_navmesh(settings);
+    let rebuilt = app.get_navmesh(&rebuilt);
+    assert_eq!(spliced.polygon, rebuilt.polygon);
+    assert_eq!(spliced.detail, rebuilt.detail);
+}
+
+#[test]
+fn regenerate_tiles_of_untiled_navmesh_fails() {
+    let mut app = App::new_test();
+    app.spawn_mesh(
+        Cuboid::new(20.0, 1.0, 20.0),
+        Transform::from_xyz(10.0, -0.5, 10.0),
+    );
+    app.capture(|failed: &NavmeshGenerationFailed| failed.id);
+    let settings = NavmeshSettings::from_agent_3d(0.5, 2.0);
+    let handle = app.generate_navmesh(settings.clone());
+    assert!(app.get_navmesh(&handle).tiles.is_none());
+
+    app.world_mut()
+        .run_system_once({
+            let handle = handle.clone();
+            move |mut generator: NavmeshGenerator| {
+                generator.regenerate_tiles(&handle, [IVec2::ZERO], settings.clone())
+            }
+        })
+        .unwrap();
+    app.update();
+    assert_eq!(app.captured::<AssetId<Navmesh>>(), Some(&handle.id()));
+    // The navmesh keeps its previous value.
+    let navmeshes = app.world().resource::<Assets<Navmesh>>();
+    assert!(navmeshes.get(&handle).is_some());
+}
+
+#[test]
+fn regenerate_dirty_rebuilds_changed_tiles() {
+    let mut app = App::new_test();
+    app.spawn_mesh(
+        Cuboid::new(20.0, 1.0, 20.0),
+        Transform::from_xyz(10.0, -0.5, 10.0),
+    );
+    // A wall splitting the floor in two.
+    let wall = app.spawn_mesh(
+        Cuboid::new(1.0, 4.0, 20.0),
+        Transform::from_xyz(10.0, 2.0, 10.0),
+    );
+    let settings = NavmeshSettings {
+        tiling: true,
+        tile_size: 16,
+        aabb: Some(Aabb3d::new(
+            Vec3::new(10.0, 2.0, 10.0),
+            Vec3::new(10.0, 3.0, 10.0),
+        )),
+        ..NavmeshSettings::from_agent_3d(0.5, 2.0)
+    };
+    let handle = app.generate_navmesh(settings.clone());
+    assert!(
+        app.get_navmesh(&handle)
+            .polygon
+            .connected_components()
+            .len()
+            > 1
+    );
+    app.world_mut().remove_resource::<NavmeshReadyResource>();
+
+    app.world_mut().despawn(wall);
+    let queued = app
+        .world_mut()
+        .run_system_once({
+            let handle = handle.clone();
+            let settings = settings.clone();
+            move |mut generator: NavmeshGenerator| {
+                generator.regenerate_dirty(&handle, settings.clone())
+            }
+        })
+        .unwrap();
+    assert!(queued);
+    app.wait_for_navmesh_ready(&handle);
+    let spliced = app.get_navmesh(&handle);
+    assert_eq!(spliced.polygon.connected_components().len(), 1);
+
+    // Rebuilding the dirty tiles gives the same navmesh as rebuilding all of them.
+    let rebuilt = app.generate_navmesh(settings);
+    let rebuilt = app.get_navmesh(&rebuilt);
+    assert_eq!(spliced.polygon, rebuilt.polygon);
+    assert_eq!(spliced.detail, rebuilt.detail);
+}
+
+#[test]
+fn keep_input() {
+    let mut app = App::new_test();
+    app.spawn_mesh(
+        Cuboid::new(20.0, 1.0, 20.0),
+        Transform::from_xyz(5.0, 0.0, 0.0),
+    );
+
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings::default());
+    let navmesh = app.get_navmesh(&navmesh_handle);
+    assert_eq!(navmesh.input, None);
+
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings {
+        keep_input: true,
+        ..default()
+    });
+    let navmesh = app.get_navmesh(&navmesh_handle);
+    let input = navmesh
+        .input
+        .expect("Navmeshes generated with `keep_input` should store their input");
+    assert_eq!(input.indices.len(), 12);
+    // The obstacles are stored in world space, after being transformed by the backend.
+    for vertex in &input.vertices {
+        assert!((-5.0..=15.0).contains(&vertex.x));
+        assert!(ops::abs(vertex.y) <= 0.5 && ops::abs(vertex.z) <= 10.0);
+    }
+    // Only the top face of the ground is walkable.
+    let walkable = input
+        .area_types
+        .iter()
+        .filter(|area| area.is_walkable())
+        .count();
+    assert_eq!(walkable, 2);
+}
+
+#[test]
+fn heightmap_backend_composes_with_mesh_backend() {
+    let mut app = App::new();
+    app.add_plugins((
+        headless_plugins,
+        NavmeshPlugins::default()
+            .build()
+            .disable::<NavmeshDebugPlugin>()
+            .disable::<NavmeshEditorIntegrationPlugin>()
+            .enable::<HeightmapBackendPlugin>(),
+        Mesh3dBackendPlugin::default(),
+    ));
+    app.finish();
+    app.cleanup();
+
+    let image = |width: u32, pixels: &[u8]| {
+        let size = Extent3d {
+            width,
+            height: 1,
+            depth_or_array_layers: 1,
+        };
+        Image::new(
+            size,
+            TextureDimension::D2,
+            pixels.to_vec(),
+            TextureFormat::R8Unorm,
+            RenderAssetUsages::default(),
+        )
+    };
+    let mut images = app.world_mut().get_resource_or_init::<Assets<Image>>();
+    // A flat terrain at the top of its height, of which only the half at negative X is left unmasked.
+    let heightmap = images.add(image(1, &[255]));
+    let mask = images.add(image(2, &[255, 0]));
+    app.world_mut().spawn((
+        HeightmapTerrain {
+            mask: Some(mask),
+            ..HeightmapTerrain::new(heightmap, Vec3::new(20.0, 2.0, 20.0))
         },
+        Transform::from_xyz(0.0, 1.0, 0.0),
+    ));
+    // A floor next to the terrain, collected by the mesh backend.
+    app.spawn_mesh(
+        Cuboid::new(10.0, 1.0, 10.0),
+        Transform::from_xyz(30.0, -0.5, 0.0),
     );
 
-    let now = Instant::now();
-    while app.world().get_resource::<GltfLoaded>().is_none() {
-        app.update();
-        if now.elapsed().as_secs() > 5 {
-            panic!("Timeout waiting for glTF to load");
+    let handle = app.generate_navmesh(NavmeshSettings::from_agent_3d(0.5, 2.0));
+    let navmesh = app.get_navmesh(&handle);
+    let vertices = &navmesh.detail.vertices;
+    let on_terrain = vertices
+        .iter()
+        .filter(|vertex| ops::abs(vertex.y - 3.0) < 0.5);
+    assert!(on_terrain.clone().any(|vertex| vertex.x < -5.0));
+    // The masked half of the terrain is left out.
+    assert!(on_terrain.clone().all(|vertex| vertex.x < 1.0));
+    assert!(
+        vertices
+            .iter()
+            .any(|vertex| ops::abs(vertex.y) < 0.5 && vertex.x > 25.0)
+    );
+}
+
+#[test]
+fn added_backends_are_merged() {
+    let mut app = App::new_test();
+    let floor = |x: f32| {
+        move |_: In<NavmeshSettings>| {
+            let floor = TriMesh::from_mesh(&Cuboid::new(10.0, 1.0, 10.0).into()).unwrap();
+            let transform = GlobalTransform::from_translation(Vec3::new(x, -0.5, 0.0));
+            TriMesh::combine([(transform, floor)])
         }
+    };
+    app.set_navmesh_backend(floor(0.0));
+    app.add_navmesh_backend(floor(20.0));
+
+    let handle = app.generate_navmesh(NavmeshSettings::from_agent_3d(0.5, 2.0));
+    let navmesh = app.get_navmesh(&handle);
+    assert_eq!(navmesh.polygon.connected_components().len(), 2);
+    let vertices = &navmesh.detail.vertices;
+    assert!(vertices.iter().any(|vertex| vertex.x < 5.0));
+    assert!(vertices.iter().any(|vertex| vertex.x > 15.0));
+
+    // Setting a backend replaces all previous ones.
+    app.set_navmesh_backend(floor(20.0));
+    let handle = app.generate_navmesh(NavmeshSettings::from_agent_3d(0.5, 2.0));
+    let navmesh = app.get_navmesh(&handle);
+    assert_eq!(navmesh.polygon.connected_components().len(), 1);
+}
+
+#[test]
+fn height_at_follows_ramp() {
+    let mut app = App::new_test();
+    // A ramp rising along x and a platform above its middle.
+    let angle = 0.2_f32;
+    for (cuboid, transform) in [
+        (
+            Cuboid::new(20.0, 1.0, 6.0),
+            Transform::from_rotation(Quat::from_rotation_z(angle)),
+        ),
+        (
+            Cuboid::new(6.0, 0.5, 6.0),
+            Transform::from_xyz(0.0, 5.0, 0.0),
+        ),
+    ] {
+        app.spawn_mesh(cuboid, transform);
     }
-    let navmesh_handle = app.generate_navmesh(NavmeshSettings::default());
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings::from_agent_3d(0.5, 2.0));
     let navmesh = app.get_navmesh(&navmesh_handle);
-    let expected_navmesh = app.read_navmesh("test/dungeon/navmesh.nav");
+    // Rasterization rounds the surface up to whole cells, and the detail mesh lies one cell above the spans.
+    let tolerance = 2.0 * navmesh.polygon.cell_height + ops::tan(angle) * navmesh.polygon.cell_size;
+    let id = navmesh_handle.id();
+    let mut height_at = |x: f32, reference_height: f32| {
+        app.world_mut()
+            .run_system_once(move |queries: NavmeshQueries| {
+                queries.height_at(id, Vec3::new(x, reference_height, 0.0))
+            })
+            .unwrap()
+    };
+    let assert_near = |height: f32, expected: f32| {
+        assert!(
+            (0.0..tolerance).contains(&(height - expected)),
+            "Height is {height}, expected {expected}"
+        );
+    };
 
-    assert_eq!(
-        expected_navmesh.polygon, navmesh.polygon,
-        "Generated polygon navmesh does not match reference"
+    // The top of the ramp is the plane through (0, 0.5 / cos(angle), 0) with a slope of tan(angle).
+    let ramp_height = |x: f32| 0.5 / ops::cos(angle) + ops::tan(angle) * x;
+    for x in [-8.0, -5.0, -2.0, 4.0, 7.0] {
+        let height = height_at(x, 0.0).expect("The ramp should be covered by the navmesh");
+        assert_near(height, ramp_height(x));
+    }
+    let (low, high) = (height_at(-6.0, 0.0).unwrap(), height_at(6.0, 0.0).unwrap());
+    let middle = height_at(-4.0, 0.0).unwrap();
+    let expected_middle = low + (high - low) * (2.0 / 12.0);
+    assert!(
+        ops::abs(middle - expected_middle) < 0.1,
+        "The height should interpolate linearly across the ramp, {middle} != {expected_middle}"
     );
 
-    assert_eq!(
-        expected_navmesh.detail, navmesh.detail,
-        "Generated detail navmesh does not match reference"
-    );
+    // Above the ramp, the reference height picks the floor.
+    let platform = height_at(0.0, 6.0).expect("The platform should be covered by the navmesh");
+    assert_near(platform, 5.25);
+    assert_near(height_at(0.0, 0.0).unwrap(), ramp_height(0.0));
+    assert_eq!(height_at(30.0, 0.0), None);
 }
 
+#[derive(Component)]
+struct Room(Aabb3d);
+
 #[test]
-fn primitive_2d_regeneration() {
+fn navmesh_refs_keep_regions_apart() {
     let mut app = App::new_test();
-    let ground_handle = app
-        .world_mut()
-        .resource_mut::<Assets<Mesh>>()
-        .add(Cuboid::new(1000.0, 1000.0, 1.0));
-    let cube_handle = app
+    // Two rooms far apart, each owning the navmesh of its floor.
+    for x in [0.0, 50.0] {
+        app.spawn_mesh(
+            Cuboid::new(10.0, 1.0, 10.0),
+            Transform::from_xyz(x, -0.5, 0.0),
+        );
+        app.world_mut().spawn(Room(Aabb3d::new(
+            Vec3::new(x, 0.0, 0.0),
+            Vec3::new(6.0, 3.0, 6.0),
+        )));
+    }
+    let settings = |room: &Room| NavmeshSettings {
+        aabb: Some(room.0),
+        ..NavmeshSettings::from_agent_3d(0.5, 2.0)
+    };
+    app.world_mut()
+        .run_system_once(
+            move |rooms: Query<(Entity, &Room)>,
+                  mut generator: NavmeshGenerator,
+                  mut commands: Commands| {
+                for (entity, room) in &rooms {
+                    let handle = generator.generate(settings(room));
+                    commands.entity(entity).insert(NavmeshRef(handle));
+                }
+            },
+        )
+        .unwrap();
+    let mut rooms = app
         .world_mut()
-        .resource_mut::<Assets<Mesh>>()
-        .add(Cuboid::new(10.0, 10.0, 10.0));
-    app.world_mut().spawn(Mesh3d(ground_handle));
-    let cube_entity = app.world_mut().spawn(Mesh3d(cube_handle)).id();
+        .query::<(&Room, &NavmeshRef)>()
+        .iter(app.world())
+        .map(|(room, navmesh)| (room.0, navmesh.clone()))
+        .collect::<Vec<_>>();
+    rooms.sort_by(|(a, _), (b, _)| a.min.x.total_cmp(&b.min.x));
+    let [(first_room, first), (second_room, second)] = rooms.try_into().unwrap();
+    assert_ne!(first, second);
 
-    let settings = NavmeshSettings {
-        aabb: Some(Aabb3d::new(Vec3::ZERO, Vec3::new(100.0, 100.0, 5.0))),
-        ..NavmeshSettings::from_agent_2d(5.0, 2.0)
+    for (room, navmesh) in [(first_room, &first), (second_room, &second)] {
+        let navmesh = app.get_navmesh(navmesh);
+        assert!(!navmesh.is_empty());
+        let aabb = navmesh.aabb();
+        assert!(aabb.min.x >= room.min.x && aabb.max.x <= room.max.x);
+    }
+
+    let first_navmesh = app.get_navmesh(&first);
+    let mut find_path = |navmesh: NavmeshRef, start: Vec3, end: Vec3| {
+        app.world_mut()
+            .run_system_once(move |queries: NavmeshQueries| {
+                let half_extents = NavmeshQueries::DEFAULT_HALF_EXTENTS;
+                queries.find_path(&navmesh, start, end, half_extents, 0.0)
+            })
+            .unwrap()
     };
-    let navmesh_handle = app.generate_navmesh(settings.clone());
+    let (first_center, second_center) = (Vec3::ZERO, Vec3::new(50.0, 0.0, 0.0));
+    assert!(find_path(first.clone(), first_center, first_center + Vec3::X).is_some());
+    assert!(find_path(second.clone(), second_center, second_center + Vec3::X).is_some());
+    assert!(
+        find_path(first.clone(), first_center, second_center).is_none(),
+        "The first navmesh should not cover the second room"
+    );
+
+    // Regenerating the second room leaves the first one alone.
+    let regenerated_settings = NavmeshSettings {
+        aabb: Some(second_room),
+        ..NavmeshSettings::from_agent_3d(1.0, 2.0)
+    };
+    assert!(app.regenerate_navmesh(&second, regenerated_settings));
+    app.world_mut().remove_resource::<NavmeshReadyResource>();
+    app.wait_for_navmesh_ready(&second);
+    assert_eq!(app.get_navmesh(&second).settings.agent_radius, 1.0);
+    assert_eq!(app.get_navmesh(&first), first_navmesh);
+}
+
+#[test]
+fn named_navmeshes_survive_regeneration() {
+    let mut app = App::new_test();
+    app.spawn_mesh(
+        Cuboid::new(10.0, 1.0, 10.0),
+        Transform::from_xyz(0.0, -0.5, 0.0),
+    );
+
+    let mut generate_named = |name: &'static str, radius: f32| {
+        app.world_mut()
+            .run_system_once(move |mut generator: NavmeshGenerator| {
+                let settings = NavmeshSettings::from_agent_3d(radius, 2.0);
+                generator.generate_named(name, settings)
+            })
+            .unwrap()
+    };
+    let large = generate_named("large_agent", 1.0);
+    let small = generate_named("small_agent", 0.25);
+    assert_ne!(large, small);
+    assert_eq!(generate_named("large_agent", 1.5), large);
+
+    let get_named = |app: &mut App, name: &'static str| {
+        app.world_mut()
+            .run_system_once(move |generator: NavmeshGenerator| generator.get_named(name).cloned())
+            .unwrap()
+    };
+    assert_eq!(get_named(&mut app, "large_agent"), Some(large.clone()));
+    assert_eq!(get_named(&mut app, "small_agent"), Some(small.clone()));
+    assert_eq!(get_named(&mut app, "medium_agent"), None);
+
+    assert_eq!(app.get_navmesh(&small).settings.agent_radius, 0.25);
+    // The pending bake with the old settings was replaced.
+    assert_eq!(app.get_navmesh(&large).settings.agent_radius, 1.5);
+
+    // Once baked, the name keeps referring to the regenerated navmesh.
+    app.world_mut().remove_resource::<NavmeshReadyResource>();
+    let regenerated = app
+        .world_mut()
+        .run_system_once(|mut generator: NavmeshGenerator| {
+            generator.generate_named("large_agent", NavmeshSettings::from_agent_3d(0.5, 2.0))
+        })
+        .unwrap();
+    assert_eq!(regenerated, large);
+    app.wait_for_navmesh_ready(&large);
+    assert_eq!(app.get_navmesh(&large).settings.agent_radius, 0.5);
+}
+
+#[test]
+fn contains_point_on_floor() {
+    let mut app = App::new_test();
+    app.spawn_mesh(
+        Cuboid::new(10.0, 1.0, 10.0),
+        Transform::from_xyz(0.0, -0.5, 0.0),
+    );
+    let navmesh_handle = app.generate_navmesh(NavmeshSettings::from_agent_3d(0.5, 2.0));
     let navmesh = app.get_navmesh(&navmesh_handle);
-    let expected_navmesh = app.read_navmesh("test/primitives/navmesh_1.nav");
+    let id = navmesh_handle.id();
+    let mut contains_point = |point: Vec3, vertical_tolerance: f32| {
+        app.world_mut()
+            .run_system_once(move |queries: NavmeshQueries| {
+                queries.contains_point(id, point, vertical_tolerance)
+            })
+            .unwrap()
+    };
+
+    assert!(contains_point(Vec3::ZERO, 0.5));
+    assert!(contains_point(Vec3::new(3.0, 0.0, -3.0), 0.5));
+    // Outside of the navmesh, next to it and far away.
+    assert!(!contains_point(Vec3::new(6.0, 0.0, 0.0), 0.5));
+    assert!(!contains_point(Vec3::new(50.0, 0.0, 50.0), 0.5));
+    // The polygons are quantized to cells, so points less than a cell off their edge are on the navmesh as well.
+    let cell_size = navmesh.polygon.cell_size;
+    let edge = navmesh.polygon.aabb.min.x
+        + f32::from(navmesh.polygon.vertices.iter().map(|v| v.x).max().unwrap()) * cell_size;
+    assert!(contains_point(
+        Vec3::new(edge + cell_size / 2.0, 0.0, 0.0),
+        0.5
+    ));
+    assert!(!contains_point(
+        Vec3::new(edge + 2.0 * cell_size, 0.0, 0.0),
+        0.5
+    ));
+    // Just above the surface.
+    assert!(contains_point(Vec3::new(0.0, 0.3, 0.0), 0.5));
+    assert!(!contains_point(Vec3::new(0.0, 0.3, 0.0), 0.0));
+    assert!(!contains_point(Vec3::new(0.0, 2.0, 0.0), 0.5));
+
+    // The polygons are used if there is no detail mesh.
+    let mut coarse = navmesh;
+    coarse.detail = DetailNavmesh::default();
+    let coarse_id = app
+        .world_mut()
+        .resource_mut::<Assets<Navmesh>>()
+        .add(coarse)
+        .id();
+    let contains_coarse = |app: &mut App, point: Vec3| {
+        app.world_mut()
+            .run_system_once(move |queries: NavmeshQueries| {
+                queries.contains_point(coarse_id, point, 0.5)
+            })
+            .unwrap()
+    };
+    assert!(contains_coarse(&mut app, Vec3::ZERO));
+    assert!(!contains_coarse(&mut app, Vec3::new(6.0, 0.0, 0.0)));
+    assert!(!contains_coarse(&mut app, Vec3::new(0.0, 2.0, 0.0)));
+}
 
+#[test]
+fn metadata_round_trips() {
+    let mut app = App::new_test();
+    // Reference files saved before the metadata existed have none.
+    let loaded = app.read_navmesh("test/primitives/navmesh_1.nav");
+    assert_eq!(loaded.metadata, NavmeshMetadata::default());
+
+    app.spawn_mesh(Cuboid::new(20.0, 1.0, 20.0), Transform::default());
+    let settings = NavmeshSettings::default();
+    let navmesh_handle = app.generate_navmesh(settings.clone());
+    let mut navmesh = app.get_navmesh(&navmesh_handle);
     assert_eq!(
-        expected_navmesh.polygon, navmesh.polygon,
-        "Initial generated polygon navmesh does not match reference"
+        navmesh.metadata.settings_hash,
+        Some(NavmeshMetadata::hash_settings(&settings))
     );
+    assert_ne!(
+        NavmeshMetadata::hash_settings(&settings),
+        NavmeshMetadata::hash_settings(&NavmeshSettings::from_agent_3d(1.0, 2.0))
+    );
+    assert_eq!(navmesh.metadata.vertex_count, Some(navmesh.vertex_count()));
+
+    navmesh.metadata.source = Some("levels/arena.glb".to_string());
+    navmesh.metadata.timestamp = Some(1_700_000_000);
+    navmesh.metadata.content_hash = Some(0xdead_beef);
+    navmesh
+        .metadata
+        .entries
+        .insert("author".to_string(), "level team".to_string());
+    let config = bincode::config::standard();
+    let bytes = bincode::serde::encode_to_vec(&navmesh, config).unwrap();
+    let (decoded, _size): (Navmesh, _) = bincode::serde::decode_from_slice(&bytes, config).unwrap();
+    assert_eq!(decoded.metadata, navmesh.metadata);
+    assert_eq!(decoded.detail, navmesh.detail);
+}
 
+#[test]
+fn settings_sidecar_splits_navmesh_files() {
+    let mut app = App::new_test();
+    let expected = app.read_navmesh("test/primitives/navmesh_1.nav");
+    let geometry = std::fs::read("../../assets/test/primitives/navmesh_1_split.nav").unwrap();
+    let settings =
+        std::fs::read("../../assets/test/primitives/navmesh_1_split.nav.settings").unwrap();
+    assert_eq!(expected.to_geometry_nav_bytes(), geometry);
+    assert_eq!(expected.to_settings_sidecar_bytes(), settings);
     assert_eq!(
-        expected_navmesh.detail, navmesh.detail,
-        "Initial generated detail navmesh does not match reference"
+        Navmesh::from_split_nav_bytes(&geometry, &settings).unwrap(),
+        expected
     );
 
-    app.world_mut().despawn(cube_entity);
-    app.regenerate_navmesh(&navmesh_handle, settings);
-    app.wait_for_navmesh_ready(&navmesh_handle);
-    let navmesh = app.get_navmesh(&navmesh_handle);
-    let expected_navmesh = app.read_navmesh("test/primitives/navmesh_2.nav");
+    for staged in [false, true] {
+        // The asset server ignores the settings of repeated loads of the same path, so use a fresh app.
+        let mut app = App::new_test();
+        let handle: Handle<Navmesh> = app.world().resource::<AssetServer>().load_with_settings(
+            "test/primitives/navmesh_1_split.nav",
+            move |settings: &mut NavmeshLoaderSettings| {
+                settings.settings_sidecar = true;
+                settings.staged = staged;
+            },
+        );
+        let navmesh = app.get_navmesh(&handle);
+        assert_eq!(navmesh.polygon, expected.polygon);
+        assert_eq!(navmesh.settings, expected.settings);
+        assert_eq!(navmesh.pending_detail.is_some(), staged);
+    }
+}
 
-    assert_eq!(
-        expected_navmesh.polygon, navmesh.polygon,
-        "Regenerated polygon navmesh does not match reference"
+#[test]
+fn build_stats_are_recorded() {
+    for tiling in [false, true] {
+        let mut app = App::new_synchronous_test();
+        app.spawn_mesh(
+            Cuboid::new(10.0, 1.0, 10.0),
+            Transform::from_xyz(0.0, -0.5, 0.0),
+        );
+        app.update();
+
+        let handle = app.generate_navmesh(NavmeshSettings {
+            tiling,
+            ..NavmeshSettings::from_agent_3d(0.5, 2.0)
+        });
+        app.update();
+        let navmesh = app
+            .world()
+            .resource::<Assets<Navmesh>>()
+            .get(&handle)
+            .unwrap();
+        let stats = app.world().resource::<LastBuildStats>()[&handle.id()];
+        assert_eq!(stats.input_triangles, 12);
+        assert_eq!(stats.polygons, navmesh.polygon_count());
+        assert_eq!(stats.detail_triangles, navmesh.detail_triangle_count());
+        assert!(stats.polygons > 0);
+        if !tiling {
+            let stages = stats.rasterize
+                + stats.erode
+                + stats.regions
+                + stats.contours
+                + stats.polymesh
+                + stats.detail;
+            assert!(stages <= stats.total);
+        }
+    }
+}
+
+#[test]
+fn generation_errors_are_typed() {
+    let cases = [
+        (
+            NavmeshSettings {
+                up: Vec3::ONE,
+                ..default()
+            },
+            "unsupported_up_vector",
+        ),
+        (
+            NavmeshSettings {
+                agent_radius: -0.5,
+                ..default()
+            },
+            "invalid_agent_radius",
+        ),
+    ];
+    for (settings, expected) in cases {
+        let mut app = App::new_test();
+        app.capture(|failed: &NavmeshGenerationFailed| match &failed.error {
+            NavmeshGenerationError::UnsupportedUpVector(up) if *up == Vec3::ONE => {
+                "unsupported_up_vector"
+            }
+            NavmeshGenerationError::InvalidSettings(NavmeshSettingsError::AgentRadius(radius))
+                if *radius == -0.5 =>
+            {
+                "invalid_agent_radius"
+            }
+            _ => "other",
+        });
+        let _handle = app.generate_navmesh(settings);
+        assert_eq!(*app.wait_for_capture::<&str>(), expected);
+    }
+}
+
+#[test]
+fn mesh_backend_recollects_changed_obstacles() {
+    let mut app = App::new_synchronous_test();
+    let floor = app.spawn_mesh(
+        Cuboid::new(10.0, 1.0, 10.0),
+        Transform::from_xyz(0.0, -0.5, 0.0),
+    );
+    app.update();
+    let generate = |app: &mut App, agent_radius: f32| {
+        let handle = app.generate_navmesh(NavmeshSettings::from_agent_3d(agent_radius, 2.0));
+        app.update();
+        app.world()
+            .resource::<Assets<Navmesh>>()
+            .get(&handle)
+            .unwrap()
+            .clone()
+    };
+
+    // Both navmeshes are generated from the same, unchanged obstacles.
+    let small = generate(&mut app, 0.5);
+    let large = generate(&mut app, 1.0);
+    assert!(!small.is_empty());
+    assert!(!large.is_empty());
+    assert!(small.detail.vertices.iter().all(|vertex| vertex.x < 5.0));
+
+    app.world_mut()
+        .entity_mut(floor)
+        .insert(Transform::from_xyz(20.0, -0.5, 0.0));
+    app.update();
+    let moved = generate(&mut app, 0.5);
+    assert!(moved.detail.vertices.iter().all(|vertex| vertex.x > 15.0));
+
+    app.world_mut().entity_mut(floor).despawn();
+    assert!(generate(&mut app, 0.5).is_empty());
+}
+
+#[test]
+fn too_many_cells_fail_before_allocating() {
+    for tiling in [false, true] {
+        let mut app = App::new_test_with(
+            RerecastPlugin::default()
+                .with_synchronous_builds(true)
+                .with_max_heightfield_cells(100),
+            (),
+        );
+        app.spawn_mesh(
+            Cuboid::new(10.0, 1.0, 10.0),
+            Transform::from_xyz(0.0, -0.5, 0.0),
+        );
+        app.capture(|failed: &NavmeshGenerationFailed| match failed.error {
+            NavmeshGenerationError::TooManyCells { cells, limit } => Some((cells, limit)),
+            _ => None,
+        });
+        app.update();
+
+        let handle = app.generate_navmesh(NavmeshSettings {
+            tiling,
+            ..NavmeshSettings::from_agent_3d(0.5, 2.0)
+        });
+        app.update();
+        let Some(&Some((cells, limit))) = app.captured::<Option<(u64, u64)>>() else {
+            panic!("Generation must fail with too many cells");
+        };
+        assert_eq!(limit, 100);
+        assert!(cells > 100);
+        assert!(
+            app.world()
+                .resource::<Assets<Navmesh>>()
+                .get(&handle)
+                .is_none()
+        );
+    }
+}
+
+#[test]
+fn mesh2d_backend_generates_flat_navmesh() {
+    let mut app = App::new_test_with(
+        RerecastPlugin::default().with_synchronous_builds(true),
+        Mesh2dBackendPlugin::default().with_height(1.0),
+    );
+    let mesh = app
+        .world_mut()
+        .resource_mut::<Assets<Mesh>>()
+        .add(RegularPolygon::new(10.0, 6));
+    // The Z translation only sorts 2D meshes, so it must not affect the navmesh.
+    app.world_mut()
+        .spawn((Mesh2d(mesh), Transform::from_xyz(5.0, 0.0, 3.0)));
+    app.update();
+
+    let handle = app.generate_navmesh(NavmeshSettings {
+        aabb: Some(Aabb3d::new(
+            Vec3::new(5.0, 0.0, 1.0),
+            Vec3::new(15.0, 15.0, 2.0),
+        )),
+        ..NavmeshSettings::from_agent_2d(0.5, 1.0)
+    });
+    app.update();
+    let navmesh = app.get_navmesh(&handle);
+    assert!(!navmesh.is_empty());
+    // The voxelized surface lies up to two cells above the plane the meshes are flattened onto.
+    let tolerance = 2.0 * navmesh.polygon.cell_height;
+    for vertex in &navmesh.detail.vertices {
+        assert!(ops::abs(vertex.z - 1.0) <= tolerance, "{vertex}");
+        assert!(
+            vertex.truncate().distance(Vec2::new(5.0, 0.0)) <= 10.0,
+            "{vertex}"
+        );
+    }
+}
+
+#[cfg(feature = "primitives")]
+#[test]
+fn primitive_backend_generates_navmesh_without_mesh_assets() {
+    use bevy_rerecast::{NavmeshPrimitive, PrimitiveBackendPlugin};
+
+    let mut app = App::new_test_with(
+        RerecastPlugin::default().with_synchronous_builds(true),
+        PrimitiveBackendPlugin::default(),
+    );
+    app.capture(NavmeshInputCaptured::clone);
+    let ground = NavmeshPrimitive::from(Cuboid::new(20.0, 1.0, 20.0));
+    assert_eq!(ground.to_trimesh(16).indices.len(), 12);
+    app.world_mut()
+        .spawn((ground, Transform::from_xyz(0.0, -0.5, 0.0)));
+    app.update();
+    assert!(app.world().resource::<Assets<Mesh>>().is_empty());
+
+    let handle = app.generate_navmesh(NavmeshSettings {
+        capture_input: true,
+        ..default()
+    });
+    app.update();
+    let navmesh = app.get_navmesh(&handle);
+    assert!(!navmesh.is_empty());
+    let captured = app.captured::<NavmeshInputCaptured>().unwrap();
+    assert_eq!(captured.trimesh.indices.len(), 12);
+}
+
+#[test]
+fn mesh_backend_shares_instanced_meshes() {
+    let mut app = App::new_synchronous_test();
+    app.capture(NavmeshInputCaptured::clone);
+    let tile = Cuboid::new(2.0, 1.0, 2.0);
+    let mesh = app.world_mut().resource_mut::<Assets<Mesh>>().add(tile);
+    let transforms = (0..5)
+        .flat_map(|x| {
+            (0..5).map(move |z| Transform::from_xyz(x as f32 * 2.0, -0.5, z as f32 * 2.0))
+        })
+        .collect::<Vec<_>>();
+    for transform in &transforms {
+        app.world_mut().spawn((Mesh3d(mesh.clone()), *transform));
+    }
+    app.update();
+    let capture = |app: &mut App| {
+        let handle = app.generate_navmesh(NavmeshSettings {
+            capture_input: true,
+            ..default()
+        });
+        app.update();
+        assert!(!app.get_navmesh(&handle).is_empty());
+        app.captured::<NavmeshInputCaptured>()
+            .unwrap()
+            .trimesh
+            .clone()
+    };
+
+    let shared = Arc::new(TriMesh::from_mesh(&tile.into()).unwrap());
+    let expected = TriMesh::combine_instances(
+        transforms
+            .iter()
+            .map(|transform| (GlobalTransform::from(*transform), shared.clone())),
     );
+    let captured = capture(&mut app);
+    assert_same_triangles(&captured, &expected);
 
+    // Changing the shared mesh affects all of its instances.
+    app.world_mut()
+        .resource_mut::<Assets<Mesh>>()
+        .insert(&mesh, Cuboid::new(2.0, 3.0, 2.0).into())
+        .unwrap();
+    app.update();
+    let captured = capture(&mut app);
+    assert_eq!(captured.indices.len(), expected.indices.len());
     assert_eq!(
-        expected_navmesh.detail, navmesh.detail,
-        "Regenerated detail navmesh does not match reference"
+        captured.compute_aabb().unwrap().min.y,
+        -2.0,
+        "All instances must use the changed mesh"
+    );
+}
+
+#[test]
+fn generation_waits_for_loading_meshes() {
+    let mut app = App::new_test();
+    app.capture(NavmeshInputCaptured::clone);
+    let mesh: Handle<Mesh> = app
+        .world()
+        .load_asset("models/dungeon.glb#Mesh0/Primitive0");
+    app.world_mut().spawn(Mesh3d(mesh.clone()));
+    // Queued right away, while the mesh is still loading.
+    let handle = app.generate_navmesh(NavmeshSettings {
+        capture_input: true,
+        ..default()
+    });
+    app.get_navmesh(&handle);
+
+    let mesh = app.world().resource::<Assets<Mesh>>().get(&mesh).unwrap();
+    let expected = TriMesh::from_mesh(mesh).unwrap();
+    let captured = app.captured::<NavmeshInputCaptured>().unwrap();
+    assert!(!captured.trimesh.indices.is_empty());
+    assert_eq!(captured.trimesh.indices, expected.indices);
+}
+
+#[test]
+fn generation_fails_for_meshes_that_failed_to_load() {
+    let mut app = App::new_test();
+    app.capture(|failed: &NavmeshGenerationFailed| match &failed.error {
+        NavmeshGenerationError::MissingAssets(ids) => Some(ids.len()),
+        _ => None,
+    });
+    let mesh: Handle<Mesh> = app.world().load_asset("models/does_not_exist.glb");
+    app.world_mut().spawn(Mesh3d(mesh));
+    let handle = app.generate_navmesh(NavmeshSettings::default());
+    assert_eq!(app.wait_for_capture::<Option<usize>>(), &Some(1));
+    assert!(!app.world().resource::<Assets<Navmesh>>().contains(&handle));
+}
+
+#[test]
+fn excluded_meshes_contribute_no_triangles() {
+    let mut app = App::new_synchronous_test();
+    app.capture(NavmeshInputCaptured::clone);
+    app.spawn_mesh(
+        Cuboid::new(10.0, 1.0, 10.0),
+        Transform::from_xyz(0.0, -0.5, 0.0),
     );
+    let decoration = app.spawn_mesh(Sphere::new(1.0), Transform::from_xyz(0.0, 1.0, 0.0));
+    app.world_mut()
+        .entity_mut(decoration)
+        .insert(NavmeshExcluded);
+    app.update();
+
+    let handle = app.generate_navmesh(NavmeshSettings {
+        capture_input: true,
+        ..default()
+    });
+    app.update();
+    assert!(!app.get_navmesh(&handle).is_empty());
+    // Only the 12 triangles of the floor are collected.
+    let captured = app.captured::<NavmeshInputCaptured>().unwrap();
+    assert_eq!(captured.trimesh.indices.len(), 12);
 }
 
 #[derive(Resource)]
@@ -107,6 +2155,15 @@ trait TestApp {
     fn wait_for_navmesh_ready(&mut self, handle: &Handle<Navmesh>);
     fn read_navmesh(&mut self, path: &str) -> Navmesh;
     fn new_test() -> App;
+    fn new_test_with<M>(rerecast: RerecastPlugin, plugins: impl Plugins<M>) -> App;
+    fn new_synchronous_test() -> App;
+    fn spawn_mesh(&mut self, mesh: impl Into<Mesh>, transform: Transform) -> Entity;
+    fn capture<E: Event, T: Send + Sync + 'static>(
+        &mut self,
+        extract: impl Fn(&E) -> T + Send + Sync + 'static,
+    );
+    fn captured<T: Send + Sync + 'static>(&self) -> Option<&T>;
+    fn wait_for_capture<T: Send + Sync + 'static>(&mut self) -> &T;
 }
 
 impl TestApp for App {
@@ -153,16 +2210,22 @@ impl TestApp for App {
         }
     }
     fn new_test() -> App {
+        Self::new_test_with(RerecastPlugin::default(), ())
+    }
+
+    fn new_test_with<M>(rerecast: RerecastPlugin, plugins: impl Plugins<M>) -> App {
         let mut app = App::new();
         app.add_plugins(headless_plugins);
 
         app.add_plugins((
             NavmeshPlugins::default()
                 .build()
+                .set(rerecast)
                 .disable::<NavmeshDebugPlugin>()
                 .disable::<NavmeshEditorIntegrationPlugin>(),
             Mesh3dBackendPlugin::default(),
-        ));
+        ))
+        .add_plugins(plugins);
 
         app.finish();
         app.cleanup();
@@ -195,15 +2258,54 @@ impl TestApp for App {
             })
             .unwrap()
     }
+
+    fn new_synchronous_test() -> App {
+        Self::new_test_with(RerecastPlugin::default().with_synchronous_builds(true), ())
+    }
+
+    fn spawn_mesh(&mut self, mesh: impl Into<Mesh>, transform: Transform) -> Entity {
+        let mesh = self.world_mut().resource_mut::<Assets<Mesh>>().add(mesh);
+        self.world_mut().spawn((Mesh3d(mesh), transform)).id()
+    }
+
+    /// Stores what `extract` returns for the latest `E` as a [`Captured`] resource.
+    fn capture<E: Event, T: Send + Sync + 'static>(
+        &mut self,
+        extract: impl Fn(&E) -> T + Send + Sync + 'static,
+    ) {
+        self.add_observer(move |event: On<E>, mut commands: Commands| {
+            commands.insert_resource(Captured(extract(event.event())));
+        });
+    }
+
+    fn captured<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.world()
+            .get_resource::<Captured<T>>()
+            .map(|captured| &captured.0)
+    }
+
+    fn wait_for_capture<T: Send + Sync + 'static>(&mut self) -> &T {
+        let now = Instant::now();
+        while self.captured::<T>().is_none() {
+            self.update();
+            assert!(
+                now.elapsed().as_secs() < 5,
+                "Timeout waiting for the captured event"
+            );
+        }
+        self.captured::<T>().unwrap()
+    }
 }
 
 #[derive(Debug, Resource, Clone, PartialEq, Eq, Hash, Deref, DerefMut)]
 struct NavmeshReadyResource(AssetId<Navmesh>);
 
+#[derive(Resource)]
+struct Captured<T>(T);
+
 #[allow(dead_code)]
 fn write_navmesh_to_file(navmesh: &Navmesh, file_path: &str) {
-    let bincode = bincode::serde::encode_to_vec(navmesh, bincode::config::standard()).unwrap();
-    std::fs::write(file_path, bincode).unwrap();
+    std::fs::write(file_path, navmesh.to_nav_bytes()).unwrap();
 }
 
 fn headless_plugins(app: &mut App) {
@@ -2,15 +2,23 @@
 
 use alloc::vec::Vec;
 use bevy_app::prelude::*;
-use bevy_asset::{AssetApp as _, AssetLoader, LoadContext, io::Reader};
-use serde::{Deserialize, Serialize};
+use bevy_asset::{
+    AssetApp as _, AssetLoader, AssetPath, LoadContext, ReadAssetBytesError, io::Reader, prelude::*,
+};
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::prelude::*;
+use bevy_tasks::{AsyncComputeTaskPool, Task, futures_lite::future};
+use rerecast::{DetailNavmesh, PolygonNavmesh, RegionId};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use thiserror::Error;
 
-use crate::Navmesh;
+use crate::{Navmesh, NavmeshMetadata, NavmeshSettings};
 
 pub(super) fn plugin(app: &mut App) {
     app.init_asset::<Navmesh>();
     app.init_asset_loader::<NavmeshLoader>();
+    app.init_resource::<DetailTasks>();
+    app.add_systems(Update, (poll_detail_tasks, decode_pending_details).chain());
 }
 
 /// The [`AssetLoader`] for [`Navmesh`] assets. Loads files ending in `.nav`.
@@ -21,7 +29,36 @@ pub struct NavmeshLoader;
 /// Settings for the [`NavmeshLoader`].
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[non_exhaustive]
-pub struct NavmeshLoaderSettings;
+pub struct NavmeshLoaderSettings {
+    /// Provide the navmesh as soon as everything but its [`Navmesh::detail`] is decoded,
+    /// and decode the detail mesh in the background afterwards. Until then, the detail mesh is empty
+    /// and [`Navmesh::pending_detail`] is set. [`NavmeshDetailReady`] is triggered once the detail mesh is available.
+    ///
+    /// This lets big worlds start pathfinding on the polygon mesh earlier. Defaults to `false`.
+    ///
+    /// Files written by `bevy_rerecast` 0.3 and earlier store the detail mesh in front of the settings,
+    /// so they are always loaded at once and don't trigger a [`NavmeshDetailReady`].
+    pub staged: bool,
+    /// Read the [`Navmesh::settings`] from a sidecar file next to the navmesh instead of from the navmesh itself,
+    /// i.e. `foo.nav.settings` for `foo.nav`. The `.nav` file then only contains the geometry.
+    ///
+    /// Splitting the files keeps version control diffs small, as the `.nav` file does not change when only the settings do.
+    /// Write the files with [`Navmesh::to_geometry_nav_bytes`] and [`Navmesh::to_settings_sidecar_bytes`].
+    /// Defaults to `false`.
+    pub settings_sidecar: bool,
+}
+
+/// The encoded detail mesh of a navmesh loaded with [`NavmeshLoaderSettings::staged`]. See [`Navmesh::pending_detail`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PendingDetail {
+    bytes: Vec<u8>,
+    /// Where the detail mesh starts in the file.
+    offset: usize,
+}
+
+/// Triggered when the [`Navmesh::detail`] of a navmesh loaded with [`NavmeshLoaderSettings::staged`] has been decoded.
+#[derive(Debug, Event, Deref, DerefMut)]
+pub struct NavmeshDetailReady(pub AssetId<Navmesh>);
 
 /// Errors that can occur when loading a [`Navmesh`] asset.
 #[derive(Debug, Error)]
@@ -33,6 +70,55 @@ pub enum NavmeshLoaderError {
     /// An error occurred while decoding the navmesh.
     #[error("Could not decode navmesh: {0}")]
     DecodeError(#[from] bincode::error::DecodeError),
+    /// The file ended before the navmesh was fully decoded, e.g. because saving it was interrupted.
+    #[error("Could not decode navmesh: The file is truncated after {offset} bytes")]
+    Truncated {
+        /// The length of the file, i.e. the offset at which decoding ran out of bytes.
+        offset: usize,
+    },
+    /// The settings sidecar requested by [`NavmeshLoaderSettings::settings_sidecar`] could not be read.
+    #[error("Could not load navmesh settings sidecar: {0}")]
+    SettingsSidecar(#[from] ReadAssetBytesError),
+    /// The navmesh was decoded, but its contents are inconsistent, e.g. because the file is corrupted.
+    #[error("Malformed navmesh: {0}")]
+    Malformed(&'static str),
+    /// The file was written by a newer version of the `.nav` format than this crate supports.
+    #[error(
+        "Could not decode navmesh: Unsupported format version {actual}, expected at most {expected}"
+    )]
+    UnsupportedVersion {
+        /// The version found in the file.
+        actual: u8,
+        /// The newest version supported by this crate.
+        expected: u8,
+    },
+}
+
+/// Magic bytes at the start of every `.nav` file and settings sidecar written by this crate.
+const MAGIC: &[u8; 4] = b"RNVF";
+
+/// The current version of the `.nav` format, which follows the [`MAGIC`] as a single byte.
+/// Bump this whenever the serialized layout of [`Navmesh`] or [`NavmeshSettings`] changes,
+/// and keep decoding the previous versions in [`decode_without_detail`].
+///
+/// - Version 0: Files written by `bevy_rerecast` 0.3 and earlier, which have no header. See [`legacy`].
+/// - Version 1: The [`Navmesh`] or, for settings sidecars, the [`NavmeshSettings`].
+const VERSION: u8 = 1;
+
+/// The fields of a serialized [`Navmesh`] in front of its [`Navmesh::detail`].
+type NavmeshWithoutDetail = (PolygonNavmesh, NavmeshSettings, Vec<f32>, NavmeshMetadata);
+
+/// The fields of a `.nav` file with a settings sidecar in front of its [`Navmesh::detail`].
+type GeometryWithoutDetail = (PolygonNavmesh, Vec<f32>, NavmeshMetadata);
+
+/// The decoded fields of a `.nav` file in front of its [`Navmesh::detail`].
+struct WithoutDetail {
+    polygon: PolygonNavmesh,
+    settings: NavmeshSettings,
+    clearances: Vec<f32>,
+    metadata: NavmeshMetadata,
+    /// Where the detail mesh starts in the file.
+    detail_offset: usize,
 }
 
 impl AssetLoader for NavmeshLoader {
@@ -43,17 +129,549 @@ impl AssetLoader for NavmeshLoader {
     async fn load(
         &self,
         reader: &mut dyn Reader,
-        _settings: &Self::Settings,
-        _load_context: &mut LoadContext<'_>,
+        settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).await?;
-        let config = bincode::config::standard();
-        let (value, _size) = bincode::serde::decode_from_slice(&bytes, config)?;
-        Ok(value)
+        let sidecar = if settings.settings_sidecar {
+            let path = AssetPath::from(load_context.path().with_extension("nav.settings"))
+                .with_source(load_context.asset_path().source().clone_owned());
+            Some(load_context.read_asset_bytes(path).await?)
+        } else {
+            None
+        };
+        // Legacy files store the detail mesh in front of the settings, so they are always decoded at once.
+        if !settings.staged || split_header(&bytes)?.0 == 0 {
+            return Navmesh::decode_nav(&bytes, sidecar.as_deref());
+        }
+        // The detail mesh is serialized last, so everything in front of it can be decoded on its own.
+        let WithoutDetail {
+            polygon,
+            settings: navmesh_settings,
+            clearances,
+            metadata,
+            detail_offset,
+        } = decode_without_detail(&bytes, sidecar.as_deref())?;
+        bytes.drain(..detail_offset);
+        let pending_detail = PendingDetail {
+            bytes,
+            offset: detail_offset,
+        };
+        Ok(Navmesh {
+            pending_detail: Some(pending_detail),
+            ..assemble(
+                polygon,
+                navmesh_settings,
+                clearances,
+                metadata,
+                DetailNavmesh::default(),
+            )
+        })
     }
 
     fn extensions(&self) -> &[&str] {
         &["nav"]
     }
 }
+
+/// Builds a loaded [`Navmesh`], leaving everything that is not persisted empty.
+fn assemble(
+    polygon: PolygonNavmesh,
+    settings: NavmeshSettings,
+    clearances: Vec<f32>,
+    metadata: NavmeshMetadata,
+    detail: DetailNavmesh,
+) -> Navmesh {
+    Navmesh {
+        polygon,
+        settings,
+        clearances,
+        metadata,
+        detail,
+        pending_detail: None,
+        tiles: None,
+        diagnostics: Vec::new(),
+        #[cfg(feature = "debug_plugin")]
+        contours: None,
+        #[cfg(feature = "debug_plugin")]
+        heightfield: None,
+        #[cfg(feature = "debug_plugin")]
+        distance_field: None,
+        #[cfg(feature = "debug_plugin")]
+        input: None,
+    }
+}
+
+impl Navmesh {
+    /// Encodes the navmesh into the contents of a `.nav` file, which can be loaded with the [`NavmeshLoader`].
+    pub fn to_nav_bytes(&self) -> Vec<u8> {
+        encode_with_header(self)
+    }
+
+    /// Decodes a navmesh from the contents of a `.nav` file, like the [`NavmeshLoader`] does.
+    /// Files written by older versions of this crate are supported as well.
+    ///
+    /// The bytes are not trusted: truncated, corrupted, or malicious files result in an error
+    /// instead of a panic or a huge allocation, and so do navmeshes whose indices are out of bounds.
+    pub fn from_nav_bytes(bytes: &[u8]) -> Result<Self, NavmeshLoaderError> {
+        Self::decode_nav(bytes, None)
+    }
+
+    /// Decodes a navmesh from a `.nav` file written by [`Navmesh::to_geometry_nav_bytes`]
+    /// and its sidecar written by [`Navmesh::to_settings_sidecar_bytes`].
+    /// See [`NavmeshLoaderSettings::settings_sidecar`].
+    pub fn from_split_nav_bytes(
+        geometry: &[u8],
+        settings: &[u8],
+    ) -> Result<Self, NavmeshLoaderError> {
+        Self::decode_nav(geometry, Some(settings))
+    }
+
+    /// Encodes everything but the [`Navmesh::settings`] for a `.nav` file with a settings sidecar.
+    /// See [`NavmeshLoaderSettings::settings_sidecar`].
+    pub fn to_geometry_nav_bytes(&self) -> Vec<u8> {
+        // The detail mesh stays last, so that staged loading works for these files as well.
+        encode_with_header((
+            &self.polygon,
+            &self.clearances,
+            &self.metadata,
+            &self.detail,
+        ))
+    }
+
+    /// Encodes the [`Navmesh::settings`] for the sidecar of a `.nav` file written by [`Navmesh::to_geometry_nav_bytes`].
+    /// See [`NavmeshLoaderSettings::settings_sidecar`].
+    pub fn to_settings_sidecar_bytes(&self) -> Vec<u8> {
+        encode_with_header(&self.settings)
+    }
+
+    fn decode_nav(bytes: &[u8], sidecar: Option<&[u8]>) -> Result<Self, NavmeshLoaderError> {
+        if let (0, body) = split_header(bytes)?
+            && sidecar.is_none()
+        {
+            let ((polygon, detail, settings), _size): (legacy::NavmeshV0, _) = decode(body, 0)?;
+            let polygon = polygon.into();
+            validate_polygon(&polygon, &[])?;
+            validate_detail(&detail, polygon.polygon_count())?;
+            return Ok(assemble(
+                polygon,
+                settings.into(),
+                Vec::new(),
+                NavmeshMetadata::default(),
+                detail,
+            ));
+        }
+        let WithoutDetail {
+            polygon,
+            settings,
+            clearances,
+            metadata,
+            detail_offset,
+        } = decode_without_detail(bytes, sidecar)?;
+        let (detail, _size) = decode(&bytes[detail_offset..], detail_offset)?;
+        validate_detail(&detail, polygon.polygon_count())?;
+        Ok(assemble(polygon, settings, clearances, metadata, detail))
+    }
+}
+
+/// Encodes `value` behind the [`MAGIC`] and the current [`VERSION`].
+fn encode_with_header(value: impl Serialize) -> Vec<u8> {
+    let mut bytes = Vec::from(*MAGIC);
+    bytes.push(VERSION);
+    bytes.extend(
+        bincode::serde::encode_to_vec(value, bincode::config::standard())
+            .expect("Encoding a navmesh into memory cannot fail"),
+    );
+    bytes
+}
+
+/// Splits a file into its format version and the bytes following the header.
+/// Files without the [`MAGIC`] are treated as version 0, which had no header.
+fn split_header(bytes: &[u8]) -> Result<(u8, &[u8]), NavmeshLoaderError> {
+    match bytes.strip_prefix(MAGIC) {
+        Some(rest) => rest
+            .split_first()
+            .map(|(&version, body)| (version, body))
+            .ok_or(NavmeshLoaderError::Truncated {
+                offset: bytes.len(),
+            }),
+        // Legacy files are never this short, so this is a header that was cut off.
+        None if bytes.len() < MAGIC.len() && MAGIC.starts_with(bytes) => {
+            Err(NavmeshLoaderError::Truncated {
+                offset: bytes.len(),
+            })
+        }
+        None => Ok((0, bytes)),
+    }
+}
+
+/// Decodes and validates everything in front of the [`Navmesh::detail`] of a `.nav` file with a header,
+/// and its settings sidecar if it has one.
+fn decode_without_detail(
+    bytes: &[u8],
+    sidecar: Option<&[u8]>,
+) -> Result<WithoutDetail, NavmeshLoaderError> {
+    let (version, body) = split_header(bytes)?;
+    let offset = bytes.len() - body.len();
+    let ((polygon, settings, clearances, metadata), size) = match (version, sidecar) {
+        (VERSION, None) => decode::<NavmeshWithoutDetail>(body, offset)?,
+        (VERSION, Some(sidecar)) => {
+            let ((polygon, clearances, metadata), size): (GeometryWithoutDetail, _) =
+                decode(body, offset)?;
+            let settings = match split_header(sidecar)? {
+                (VERSION, settings) => decode(settings, sidecar.len() - settings.len())?.0,
+                (actual, _) => return Err(unsupported_version(actual)),
+            };
+            ((polygon, settings, clearances, metadata), size)
+        }
+        // Legacy files are decoded by `Navmesh::decode_nav`. Settings sidecars were introduced together with the header,
+        // so there are no legacy geometry files.
+        (actual, _) => return Err(unsupported_version(actual)),
+    };
+    validate_polygon(&polygon, &clearances)?;
+    Ok(WithoutDetail {
+        polygon,
+        settings,
+        clearances,
+        metadata,
+        detail_offset: offset + size,
+    })
+}
+
+fn unsupported_version(actual: u8) -> NavmeshLoaderError {
+    NavmeshLoaderError::UnsupportedVersion {
+        actual,
+        expected: VERSION,
+    }
+}
+
+/// Decodes a value from `bytes`, which start `offset` bytes into the file.
+/// Running out of bytes is reported as [`NavmeshLoaderError::Truncated`].
+fn decode<T: DeserializeOwned>(
+    bytes: &[u8],
+    offset: usize,
+) -> Result<(T, usize), NavmeshLoaderError> {
+    bincode::serde::decode_from_slice(bytes, bincode::config::standard()).map_err(|err| match err {
+        bincode::error::DecodeError::UnexpectedEnd { .. } => NavmeshLoaderError::Truncated {
+            offset: offset + bytes.len(),
+        },
+        err => err.into(),
+    })
+}
+
+/// Checks the invariants of a decoded [`PolygonNavmesh`] that queries index with, so that a malformed file
+/// is rejected when loading instead of causing a panic later on.
+fn validate_polygon(
+    polygon: &PolygonNavmesh,
+    clearances: &[f32],
+) -> Result<(), NavmeshLoaderError> {
+    use NavmeshLoaderError::Malformed;
+    let nvp = polygon.max_vertices_per_polygon as usize;
+    if nvp < 3 {
+        return Err(Malformed("polygons must allow at least 3 vertices"));
+    }
+    if !polygon.polygons.len().is_multiple_of(nvp) {
+        return Err(Malformed(
+            "polygon indices are not a multiple of the vertices per polygon",
+        ));
+    }
+    let polygon_count = polygon.polygon_count();
+    if polygon_count >= PolygonNavmesh::NO_CONNECTION as usize {
+        return Err(Malformed("too many polygons"));
+    }
+    if polygon.polygon_neighbors.len() != polygon.polygons.len() {
+        return Err(Malformed("polygon neighbors do not match the polygons"));
+    }
+    let per_polygon = [
+        polygon.flags.len(),
+        polygon.regions.len(),
+        polygon.areas.len(),
+    ];
+    if per_polygon.iter().any(|&len| len != polygon_count) {
+        return Err(Malformed(
+            "polygon flags, regions, or areas do not match the polygons",
+        ));
+    }
+    if !clearances.is_empty() && clearances.len() != polygon_count {
+        return Err(Malformed("clearances do not match the polygons"));
+    }
+    for indices in polygon.polygons.chunks_exact(nvp) {
+        let count = indices
+            .iter()
+            .take_while(|&&index| index != PolygonNavmesh::NO_INDEX)
+            .count();
+        if count < 3 {
+            return Err(Malformed("polygon has fewer than 3 vertices"));
+        }
+        if indices[..count]
+            .iter()
+            .any(|&index| index as usize >= polygon.vertices.len())
+        {
+            return Err(Malformed("polygon vertex index out of bounds"));
+        }
+    }
+    // Links to other tiles are marked with the border flag, which also covers `NO_CONNECTION`.
+    if polygon.polygon_neighbors.iter().any(|&neighbor| {
+        !RegionId::from(neighbor).intersects(RegionId::BORDER_REGION)
+            && neighbor as usize >= polygon_count
+    }) {
+        return Err(Malformed("polygon neighbor index out of bounds"));
+    }
+    Ok(())
+}
+
+/// Checks that the sub-meshes of a decoded [`DetailNavmesh`] stay within its vertices and triangles.
+/// An empty detail mesh is valid, as navmeshes can be generated without one.
+fn validate_detail(detail: &DetailNavmesh, polygon_count: usize) -> Result<(), NavmeshLoaderError> {
+    use NavmeshLoaderError::Malformed;
+    if !detail.meshes.is_empty() && detail.meshes.len() != polygon_count {
+        return Err(Malformed("detail sub-meshes do not match the polygons"));
+    }
+    if detail.triangle_flags.len() != detail.triangles.len() {
+        return Err(Malformed(
+            "detail triangle flags do not match the triangles",
+        ));
+    }
+    for mesh in &detail.meshes {
+        let vertices = u64::from(mesh.base_vertex_index) + u64::from(mesh.vertex_count);
+        let triangles = u64::from(mesh.base_triangle_index) + u64::from(mesh.triangle_count);
+        if vertices > detail.vertices.len() as u64 || triangles > detail.triangles.len() as u64 {
+            return Err(Malformed("detail sub-mesh out of bounds"));
+        }
+        let triangles =
+            &detail.triangles[mesh.base_triangle_index as usize..][..mesh.triangle_count as usize];
+        if triangles
+            .iter()
+            .flatten()
+            .any(|&index| u32::from(index) >= mesh.vertex_count)
+        {
+            return Err(Malformed("detail triangle vertex index out of bounds"));
+        }
+    }
+    Ok(())
+}
+
+/// The layout of the headerless `.nav` files written by `bevy_rerecast` 0.3 and earlier, i.e. version 0 of the format.
+mod legacy {
+    use bevy_ecs::entity::Entity;
+    use bevy_math::bounding::Aabb3d;
+    use bevy_platform::collections::HashSet;
+    use glam::{U16Vec3, Vec3};
+    use rerecast::{AreaType, BuildContoursFlags, ConvexVolume, RegionId};
+
+    use super::*;
+
+    /// A version 0 navmesh. Its [`DetailNavmesh`] has not changed since, but was stored in front of the settings.
+    pub(super) type NavmeshV0 = (PolygonNavmeshV0, DetailNavmesh, NavmeshSettingsV0);
+
+    /// A [`PolygonNavmesh`] before its flags and areas were run-length encoded.
+    #[derive(Deserialize)]
+    pub(super) struct PolygonNavmeshV0 {
+        vertices: Vec<U16Vec3>,
+        polygons: Vec<u16>,
+        polygon_neighbors: Vec<u16>,
+        flags: Vec<u16>,
+        regions: Vec<RegionId>,
+        areas: Vec<AreaType>,
+        max_vertices_per_polygon: u16,
+        aabb: rerecast::Aabb3d,
+        cell_size: f32,
+        cell_height: f32,
+        border_size: u16,
+        max_edge_error: f32,
+    }
+
+    impl From<PolygonNavmeshV0> for PolygonNavmesh {
+        fn from(mut polygon: PolygonNavmeshV0) -> Self {
+            // Version 0 stored a region for every polygon that could have been created, not just for the actual ones.
+            let polygon_count =
+                polygon.polygons.len() / polygon.max_vertices_per_polygon.max(1) as usize;
+            polygon.regions.truncate(polygon_count);
+            Self {
+                vertices: polygon.vertices,
+                polygons: polygon.polygons,
+                polygon_neighbors: polygon.polygon_neighbors,
+                flags: polygon.flags,
+                regions: polygon.regions,
+                areas: polygon.areas,
+                max_vertices_per_polygon: polygon.max_vertices_per_polygon,
+                aabb: polygon.aabb,
+                cell_size: polygon.cell_size,
+                cell_height: polygon.cell_height,
+                border_size: polygon.border_size,
+                max_edge_error: polygon.max_edge_error,
+            }
+        }
+    }
+
+    /// The [`NavmeshSettings`] up to [`NavmeshSettings::up`]. All fields added since take their default values.
+    #[derive(Deserialize)]
+    pub(super) struct NavmeshSettingsV0 {
+        cell_size_fraction: f32,
+        cell_height_fraction: f32,
+        agent_height: f32,
+        agent_radius: f32,
+        walkable_climb: f32,
+        walkable_slope_angle: f32,
+        min_region_size: u16,
+        merge_region_size: u16,
+        edge_max_len_factor: u16,
+        max_simplification_error: f32,
+        max_vertices_per_polygon: u16,
+        detail_sample_dist: f32,
+        detail_sample_max_error: f32,
+        tile_size: u16,
+        aabb: Option<Aabb3d>,
+        contour_flags: BuildContoursFlags,
+        tiling: bool,
+        area_volumes: Vec<ConvexVolume>,
+        filter: Option<HashSet<Entity>>,
+        up: Vec3,
+    }
+
+    impl From<NavmeshSettingsV0> for NavmeshSettings {
+        fn from(settings: NavmeshSettingsV0) -> Self {
+            Self {
+                cell_size_fraction: settings.cell_size_fraction,
+                cell_height_fraction: settings.cell_height_fraction,
+                agent_height: settings.agent_height,
+                agent_radius: settings.agent_radius,
+                walkable_climb: settings.walkable_climb,
+                walkable_slope_angle: settings.walkable_slope_angle,
+                min_region_size: settings.min_region_size,
+                merge_region_size: settings.merge_region_size,
+                edge_max_len_factor: settings.edge_max_len_factor,
+                max_simplification_error: settings.max_simplification_error,
+                max_vertices_per_polygon: settings.max_vertices_per_polygon,
+                detail_sample_dist: settings.detail_sample_dist,
+                detail_sample_max_error: settings.detail_sample_max_error,
+                tile_size: settings.tile_size,
+                aabb: settings.aabb,
+                contour_flags: settings.contour_flags,
+                tiling: settings.tiling,
+                area_volumes: settings.area_volumes,
+                filter: settings.filter,
+                up: settings.up,
+                ..Self::default()
+            }
+        }
+    }
+}
+
+#[derive(Resource, Default, Deref, DerefMut)]
+struct DetailTasks(
+    Vec<(
+        AssetId<Navmesh>,
+        Task<Result<DetailNavmesh, NavmeshLoaderError>>,
+    )>,
+);
+
+fn decode_pending_details(
+    mut asset_events: MessageReader<AssetEvent<Navmesh>>,
+    navmeshes: Res<Assets<Navmesh>>,
+    mut tasks: ResMut<DetailTasks>,
+) {
+    for event in asset_events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::Modified { id }) = event else {
+            continue;
+        };
+        let id = *id;
+        if tasks.iter().any(|(task_id, _task)| *task_id == id) {
+            continue;
+        }
+        let Some(navmesh) = navmeshes.get(id) else {
+            continue;
+        };
+        let Some(PendingDetail { bytes, offset }) = navmesh.pending_detail.clone() else {
+            continue;
+        };
+        let polygon_count = navmesh.polygon.polygon_count();
+        let task = AsyncComputeTaskPool::get().spawn(async move {
+            let (detail, _size) = decode(&bytes, offset)?;
+            validate_detail(&detail, polygon_count)?;
+            Ok(detail)
+        });
+        tasks.push((id, task));
+    }
+}
+
+fn poll_detail_tasks(
+    mut commands: Commands,
+    mut tasks: ResMut<DetailTasks>,
+    mut navmeshes: ResMut<Assets<Navmesh>>,
+) {
+    tasks.retain_mut(|(id, task)| {
+        let Some(result) = future::block_on(future::poll_once(task)) else {
+            return true;
+        };
+        let Some(navmesh) = navmeshes.get_mut(*id) else {
+            // The navmesh was unloaded in the meantime.
+            return false;
+        };
+        navmesh.pending_detail = None;
+        match result {
+            Ok(detail) => {
+                navmesh.detail = detail;
+                commands.trigger(NavmeshDetailReady(*id));
+            }
+            Err(_err) => {
+                #[cfg(feature = "tracing")]
+                tracing::error!("Failed to decode navmesh detail: {_err}");
+            }
+        }
+        false
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUNGEON: &[u8] = include_bytes!("../../../assets/test/dungeon/navmesh.nav");
+
+    #[test]
+    fn malformed_nav_files_are_rejected() {
+        let navmesh = Navmesh::from_nav_bytes(DUNGEON).unwrap();
+
+        let mut out_of_bounds = navmesh.clone();
+        out_of_bounds.polygon.polygons[0] = out_of_bounds.polygon.vertices.len() as u16;
+        assert!(matches!(
+            Navmesh::from_nav_bytes(&out_of_bounds.to_nav_bytes()),
+            Err(NavmeshLoaderError::Malformed(_))
+        ));
+
+        // Random and corrupted files may or may not decode, but must never panic or allocate without bounds.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut random = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..200 {
+            let len = random() as usize % 4096;
+            let noise: Vec<u8> = (0..len).map(|_| random() as u8).collect();
+            let _ = Navmesh::from_nav_bytes(&noise);
+
+            let mut corrupted = DUNGEON.to_vec();
+            for _ in 0..8 {
+                let index = random() as usize % corrupted.len();
+                corrupted[index] = random() as u8;
+            }
+            let _ = Navmesh::from_nav_bytes(&corrupted);
+        }
+    }
+
+    #[test]
+    fn truncated_nav_files_report_offset() {
+        // Most lengths are skipped to keep the test fast.
+        for len in (0..DUNGEON.len()).step_by(97).chain([1, DUNGEON.len() - 1]) {
+            let result = Navmesh::from_nav_bytes(&DUNGEON[..len]);
+            assert!(
+                matches!(result, Err(NavmeshLoaderError::Truncated { offset }) if offset == len),
+                "Expected truncation at {len} bytes, got {result:?}"
+            );
+        }
+    }
+}
@@ -1,28 +1,107 @@
 use alloc::vec::Vec;
 use bevy_app::prelude::*;
 use bevy_derive::{Deref, DerefMut};
-use bevy_ecs::{prelude::*, system::SystemId};
+use bevy_ecs::{
+    prelude::*,
+    system::{RegisteredSystemError, SystemId},
+};
 use bevy_math::bounding::Aabb3d;
 use bevy_platform::collections::HashSet;
 use bevy_reflect::prelude::*;
+use core::time::Duration;
 use glam::Vec3;
-use rerecast::{BuildContoursFlags, ConfigBuilder, ConvexVolume, TriMesh};
+use rerecast::{AreaType, BuildContoursFlags, ConfigBuilder, ConvexVolume, TriMesh};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-/// The current backend registered through [`NavmeshApp::set_navmesh_backend`]
-#[derive(Resource, Debug, Clone, Deref, DerefMut)]
-pub struct NavmeshBackend(pub SystemId<In<NavmeshSettings>, TriMesh>);
+use crate::CoordinateRemap;
+
+/// The backends registered through [`NavmeshApp::set_navmesh_backend`] and [`NavmeshApp::add_navmesh_backend`],
+/// in the order they were added.
+#[derive(Resource, Debug, Clone, Default, Deref, DerefMut)]
+pub struct NavmeshBackends(pub Vec<SystemId<In<NavmeshSettings>, TriMesh>>);
+
+impl NavmeshBackends {
+    /// Runs all backends with the given input and concatenates their obstacles in the order the backends were added.
+    pub fn run(
+        &self,
+        world: &mut World,
+        input: &NavmeshSettings,
+    ) -> Result<TriMesh, RegisteredSystemError<In<NavmeshSettings>, TriMesh>> {
+        self.iter()
+            .map(|&backend| world.run_system_with(backend, input.clone()))
+            .collect()
+    }
+}
+
+/// The affector bounds systems registered through [`NavmeshApp::set_navmesh_affector_bounds`]
+/// and [`NavmeshApp::add_navmesh_affector_bounds`], in the order they were added.
+#[derive(Resource, Debug, Clone, Default, Deref, DerefMut)]
+pub struct NavmeshAffectorBounds(pub Vec<SystemId<In<NavmeshSettings>, Vec<(Entity, Aabb3d)>>>);
+
+impl NavmeshAffectorBounds {
+    /// Runs all affector bounds systems with the given input and concatenates the bounds they report.
+    pub fn run(
+        &self,
+        world: &mut World,
+        input: &NavmeshSettings,
+    ) -> Result<
+        Vec<(Entity, Aabb3d)>,
+        RegisteredSystemError<In<NavmeshSettings>, Vec<(Entity, Aabb3d)>>,
+    > {
+        let mut bounds = Vec::new();
+        for &system in self.iter() {
+            bounds.extend(world.run_system_with(system, input.clone())?);
+        }
+        Ok(bounds)
+    }
+}
 
 /// Extension used to implement [`NavmeshApp::set_navmesh_backend`] on [`App`]
 pub trait NavmeshApp {
-    /// Set the backend for generating navmesh obstacles. Only one backend can be set at a time.
-    /// Setting a backend will replace any existing backend. By default, no backend is set.
+    /// Set the backend for generating navmesh obstacles.
+    /// Setting a backend will replace all existing backends. By default, no backend is set.
+    /// Since the affector bounds of the replaced backends don't apply anymore, this also removes
+    /// all systems set with [`NavmeshApp::set_navmesh_affector_bounds`].
     ///
     /// The backend is supposed to return a single [`TriMesh`] containing the geometry for all obstacles in the scene in global units.
+    /// The obstacles should be merged in a stable order, e.g. sorted by [`Entity`], so that identical scenes produce identical navmeshes.
+    /// If [`NavmeshSettings::aabb`] is set, the backend may cull obstacles outside of it.
+    /// Entities for which [`NavmeshSettings::includes`] returns `false` must be skipped.
     fn set_navmesh_backend<M>(
         &mut self,
         system: impl IntoSystem<In<NavmeshSettings>, TriMesh, M> + 'static,
     ) -> &mut App;
+
+    /// Add a backend for generating navmesh obstacles next to the existing ones, see [`NavmeshApp::set_navmesh_backend`].
+    /// All backends run with the same input, and their obstacles are concatenated in the order the backends were added.
+    ///
+    /// This is useful for worlds whose obstacles come from different sources, e.g. render meshes, colliders, and terrain.
+    /// Each obstacle should only be reported by a single backend, as it is merged twice otherwise.
+    fn add_navmesh_backend<M>(
+        &mut self,
+        system: impl IntoSystem<In<NavmeshSettings>, TriMesh, M> + 'static,
+    ) -> &mut App;
+
+    /// Set the system reporting the world-space bounds of each obstacle the backends would merge for the given settings.
+    /// Setting a system will replace all existing ones. The systems should cover the obstacles of all backends,
+    /// and skip the same entities, i.e. those for which [`NavmeshSettings::includes`] returns `false`.
+    ///
+    /// The bounds are recorded for each generated navmesh, so that
+    /// [`NavmeshGenerator::regenerate_dirty`](crate::generator::NavmeshGenerator::regenerate_dirty)
+    /// can find the tiles whose obstacles were added, removed, or moved since.
+    fn set_navmesh_affector_bounds<M>(
+        &mut self,
+        system: impl IntoSystem<In<NavmeshSettings>, Vec<(Entity, Aabb3d)>, M> + 'static,
+    ) -> &mut App;
+
+    /// Add a system reporting the world-space bounds of obstacles next to the existing ones,
+    /// usually together with the backend added by [`NavmeshApp::add_navmesh_backend`].
+    /// See [`NavmeshApp::set_navmesh_affector_bounds`].
+    fn add_navmesh_affector_bounds<M>(
+        &mut self,
+        system: impl IntoSystem<In<NavmeshSettings>, Vec<(Entity, Aabb3d)>, M> + 'static,
+    ) -> &mut App;
 }
 
 impl NavmeshApp for App {
@@ -31,7 +110,40 @@ impl NavmeshApp for App {
         system: impl IntoSystem<In<NavmeshSettings>, TriMesh, M> + 'static,
     ) -> &mut App {
         let id = self.register_system(system);
-        self.world_mut().insert_resource(NavmeshBackend(id));
+        self.world_mut().insert_resource(NavmeshBackends(vec![id]));
+        self.world_mut().remove_resource::<NavmeshAffectorBounds>();
+        self
+    }
+
+    fn add_navmesh_backend<M>(
+        &mut self,
+        system: impl IntoSystem<In<NavmeshSettings>, TriMesh, M> + 'static,
+    ) -> &mut App {
+        let id = self.register_system(system);
+        self.world_mut()
+            .get_resource_or_init::<NavmeshBackends>()
+            .push(id);
+        self
+    }
+
+    fn set_navmesh_affector_bounds<M>(
+        &mut self,
+        system: impl IntoSystem<In<NavmeshSettings>, Vec<(Entity, Aabb3d)>, M> + 'static,
+    ) -> &mut App {
+        let id = self.register_system(system);
+        self.world_mut()
+            .insert_resource(NavmeshAffectorBounds(vec![id]));
+        self
+    }
+
+    fn add_navmesh_affector_bounds<M>(
+        &mut self,
+        system: impl IntoSystem<In<NavmeshSettings>, Vec<(Entity, Aabb3d)>, M> + 'static,
+    ) -> &mut App {
+        let id = self.register_system(system);
+        self.world_mut()
+            .get_resource_or_init::<NavmeshAffectorBounds>()
+            .push(id);
         self
     }
 }
@@ -159,12 +271,21 @@ pub struct NavmeshSettings {
     /// The navmesh's AABB [Units: wu]
     ///
     /// If left at `None`, the AABB will be automatically computed based on the available navmesh obstacles.
+    ///
+    /// When set, the backend receives it in world space before collecting obstacles,
+    /// so it may skip obstacles that lie completely outside of it.
     pub aabb: Option<Aabb3d>,
     /// Flags controlling the [`ContourSet`](crate::rerecast::ContourSet) generation process.
     pub contour_flags: BuildContoursFlags,
     /// Whether the navmesh should be tiled or not.
+    ///
+    /// When enabled, the AABB is split into tiles of [`Self::tile_size`] cells, which are built in parallel on the
+    /// [`ComputeTaskPool`](bevy_tasks::ComputeTaskPool) and then merged into a single navmesh.
+    /// Each tile is padded by a border on all sides, so that it sees the obstacles of its neighbors.
+    /// Tiled navmeshes don't keep the contours, heightfield and distance field used by the `debug_plugin` feature.
     pub tiling: bool,
     /// Volumes that define areas with specific areas IDs.
+    /// Volumes with [`AreaType::NOT_WALKABLE`] carve holes into the navmesh.
     pub area_volumes: Vec<ConvexVolume>,
     /// An optional list of entities to consider as navmesh obstacles.
     /// If `Some`, the backend is expected to only consider the specified entities when generating a trimesh for the obstacles.
@@ -175,6 +296,66 @@ pub struct NavmeshSettings {
     /// - [`Vec3::Z`]: Typically used in 2D
     /// - [`Vec3::X`]
     pub up: Vec3,
+    /// Whether to generate the [`Navmesh::detail`](crate::Navmesh::detail) mesh.
+    ///
+    /// Generating the detail mesh takes up a considerable part of the generation time and file size.
+    /// If your agents only ever walk on flat ground, e.g. in top-down games, you can set this to `false`
+    /// to leave the detail mesh empty.
+    pub generate_detail: bool,
+    /// The algorithm used to partition the walkable area into regions, which are then turned into polygons.
+    pub partition: PartitionMethod,
+    /// The area type assigned to all walkable surfaces that are not covered by one of the [`Self::area_volumes`].
+    /// Defaults to [`AreaType::DEFAULT_WALKABLE`].
+    ///
+    /// Must not be [`AreaType::NOT_WALKABLE`], as that would make the whole navmesh unwalkable.
+    pub default_walkable_area: AreaType,
+    /// Whether to store the walkable spans of the heightfield in the generated navmesh so that they can be drawn
+    /// with a `HeightfieldGizmo`. This is useful to debug holes in the navmesh, but costs memory, so it defaults to `false`.
+    ///
+    /// Has no effect unless the `debug_plugin` feature is enabled. Not persisted when serializing the settings.
+    #[serde(skip)]
+    pub keep_heightfield: bool,
+    /// Whether to store the distance field of the heightfield in the generated navmesh so that it can be drawn
+    /// with a `DistanceFieldGizmo`. The distance field is built for this even if the [`Self::partition`] does not need it.
+    /// Defaults to `false`.
+    ///
+    /// Has no effect unless the `debug_plugin` feature is enabled. Not persisted when serializing the settings.
+    #[serde(skip)]
+    pub keep_distance_field: bool,
+    /// The handedness of the coordinate system of the obstacles, together with [`Self::up`].
+    /// Bevy is right-handed, but obstacles imported from left-handed engines such as Unreal or Unity
+    /// should use [`Handedness::LeftHanded`] so that their surfaces are not considered to be facing down.
+    ///
+    /// See [`CoordinateRemap`](crate::CoordinateRemap) for how the obstacles are converted.
+    pub handedness: Handedness,
+    /// Whether to trigger a [`NavmeshInputCaptured`](crate::generator::NavmeshInputCaptured) with the obstacles
+    /// this navmesh is generated from. Defaults to `false`, as the obstacles need to be cloned for this.
+    /// Not persisted when serializing the settings.
+    #[serde(skip)]
+    pub capture_input: bool,
+    /// How long generating the navmesh may take, as projected by [`Self::estimate_build_time`].
+    /// If the estimate exceeds the budget, the navmesh is generated with coarser cells and a larger [`Self::detail_sample_dist`]
+    /// until it fits. Both are scaled by at most [`Self::MAX_TIME_BUDGET_COARSENING`]. If that is still not enough,
+    /// the detail mesh is skipped as if [`Self::generate_detail`] was `false`.
+    ///
+    /// The adjusted settings are stored in [`Navmesh::settings`](crate::Navmesh::settings) and the adjustment is reported
+    /// as a [`NavmeshDiagnostic::CoarsenedForTimeBudget`](crate::NavmeshDiagnostic::CoarsenedForTimeBudget).
+    /// This is useful to trade quality for responsiveness while authoring a level. Defaults to `None`, i.e. no budget.
+    pub time_budget: Option<Duration>,
+    /// Islands of the polygon mesh with fewer polygons than this are removed from the navmesh,
+    /// as determined by [`PolygonNavmesh::connected_components`](rerecast::PolygonNavmesh::connected_components).
+    /// Tiny isolated fragments, e.g. a single polygon on a rooftop, are usually noise that no agent can reach.
+    ///
+    /// The removal is reported as a [`NavmeshDiagnostic::PrunedIslands`](crate::NavmeshDiagnostic::PrunedIslands).
+    /// Defaults to `None`, i.e. all islands are kept.
+    pub prune_unreachable_below: Option<usize>,
+    /// Whether to store the obstacles the navmesh was rasterized from in the generated navmesh so that they can be drawn
+    /// with an `InputGizmo`. This is useful to tell whether a wrong navmesh is caused by the obstacles the backend collected
+    /// or by the generation itself, but costs memory, so it defaults to `false`.
+    ///
+    /// Has no effect unless the `debug_plugin` feature is enabled. Not persisted when serializing the settings.
+    #[serde(skip)]
+    pub keep_input: bool,
 }
 
 impl Default for NavmeshSettings {
@@ -201,11 +382,43 @@ impl Default for NavmeshSettings {
             cell_height_fraction: cfg.cell_height_fraction,
             edge_max_len_factor: cfg.edge_max_len_factor,
             up: Vec3::Y,
+            generate_detail: true,
+            partition: PartitionMethod::default(),
+            default_walkable_area: AreaType::DEFAULT_WALKABLE,
+            keep_heightfield: false,
+            keep_distance_field: false,
+            handedness: Handedness::default(),
+            capture_input: false,
+            time_budget: None,
+            prune_unreachable_below: None,
+            keep_input: false,
         }
     }
 }
 
 impl NavmeshSettings {
+    /// Creates a [`NavmeshSettingsBuilder`] to set only the fields that differ from the [`Default`].
+    ///
+    /// ```
+    /// # use bevy_rerecast_core::NavmeshSettings;
+    /// let settings = NavmeshSettings::builder()
+    ///     .agent_radius(0.5)
+    ///     .agent_height(2.0)
+    ///     .tile_size(64)
+    ///     .build();
+    /// ```
+    pub fn builder() -> NavmeshSettingsBuilder {
+        NavmeshSettingsBuilder::default()
+    }
+
+    /// Whether the backends should consider `entity` as navmesh obstacle, i.e. whether it is in [`Self::filter`]
+    /// or no filter is set.
+    pub fn includes(&self, entity: Entity) -> bool {
+        self.filter
+            .as_ref()
+            .is_none_or(|entities| entities.contains(&entity))
+    }
+
     /// Creates a new [`NavmeshSettings`] instance from a 3D agent's radius and height.
     pub fn from_agent_3d(radius: f32, height: f32) -> Self {
         Self {
@@ -226,7 +439,171 @@ impl NavmeshSettings {
         }
     }
 
-    #[cfg(feature = "bevy_asset")]
+    /// Preset for human-sized agents walking through buildings, e.g. in a first-person game.
+    ///
+    /// Assumes that one world unit is one meter. The agent is 0.4 m wide with a height of 2 m including some padding,
+    /// and can walk up steps of up to 0.4 m.
+    /// The cells are finer than the default to capture doors and narrow corridors, and small regions are kept
+    /// so that e.g. the space between furniture is not removed.
+    pub fn indoor_human() -> Self {
+        Self {
+            cell_size_fraction: 3.0,
+            cell_height_fraction: 6.0,
+            walkable_climb: 0.4,
+            walkable_slope_angle: 45.0_f32.to_radians(),
+            min_region_size: 4,
+            merge_region_size: 20,
+            ..Self::from_agent_3d(0.2, 2.0)
+        }
+    }
+
+    /// Preset for human-sized agents in large, open outdoor levels, e.g. in an open-world game.
+    ///
+    /// Assumes that one world unit is one meter. The agent has a radius of 0.5 m and a height of 2 m,
+    /// and can walk up steps of up to 0.5 m and slopes of up to 50°.
+    /// The cells are coarse to keep generation fast, small islands such as rooftops are removed,
+    /// and long edges are allowed to keep the polygon count low.
+    /// [`Self::tile_size`] is set to 64 cells for when [`Self::tiling`] is enabled.
+    pub fn outdoor_large_world() -> Self {
+        Self {
+            cell_size_fraction: 2.0,
+            cell_height_fraction: 4.0,
+            walkable_climb: 0.5,
+            walkable_slope_angle: 50.0_f32.to_radians(),
+            min_region_size: 16,
+            merge_region_size: 40,
+            edge_max_len_factor: 12,
+            tile_size: 64,
+            ..Self::from_agent_3d(0.5, 2.0)
+        }
+    }
+
+    /// Preset for agents in top-down 2D games, where [`Self::up`] is [`Vec3::Z`] and the ground is flat.
+    ///
+    /// Assumes that one world unit is one meter. The agent has a radius of 0.5 m, and its height of 1 m
+    /// only matters for obstacles that do not fully cover the ground.
+    /// Since the ground is flat, no detail mesh is generated and the agent cannot climb onto obstacles.
+    pub fn top_down_2d() -> Self {
+        Self {
+            walkable_climb: 0.0,
+            generate_detail: false,
+            min_region_size: 4,
+            ..Self::from_agent_2d(0.5, 1.0)
+        }
+    }
+
+    /// Checks that the settings can be used to generate a navmesh.
+    /// [`NavmeshGenerator`](crate::generator::NavmeshGenerator) calls this before generating,
+    /// so invalid settings result in a [`NavmeshSettingsError`] instead of a panic or a meaningless navmesh.
+    pub fn validate(&self) -> Result<(), NavmeshSettingsError> {
+        // Rejects NaN as well.
+        let is_positive = |value: f32| value > 0.0;
+        if !is_positive(self.cell_size_fraction) {
+            return Err(NavmeshSettingsError::CellSizeFraction(
+                self.cell_size_fraction,
+            ));
+        }
+        if !is_positive(self.cell_height_fraction) {
+            return Err(NavmeshSettingsError::CellHeightFraction(
+                self.cell_height_fraction,
+            ));
+        }
+        if !is_positive(self.agent_radius) {
+            return Err(NavmeshSettingsError::AgentRadius(self.agent_radius));
+        }
+        if !is_positive(self.agent_height) {
+            return Err(NavmeshSettingsError::AgentHeight(self.agent_height));
+        }
+        if !(0.0..=self.agent_height).contains(&self.walkable_climb) {
+            return Err(NavmeshSettingsError::WalkableClimb {
+                walkable_climb: self.walkable_climb,
+                agent_height: self.agent_height,
+            });
+        }
+        if self.tiling && self.tile_size == 0 {
+            return Err(NavmeshSettingsError::TileSize);
+        }
+        if let Some(aabb) = self.aabb.filter(|aabb| !aabb.min.cmple(aabb.max).all()) {
+            return Err(NavmeshSettingsError::Aabb(aabb));
+        }
+        if !self.default_walkable_area.is_walkable() {
+            return Err(NavmeshSettingsError::DefaultWalkableArea);
+        }
+        if ![Vec3::X, Vec3::Y, Vec3::Z].contains(&self.up) {
+            return Err(NavmeshSettingsError::Up(self.up));
+        }
+        Ok(())
+    }
+
+    /// Resolves the absolute cell dimensions and the size of the voxel grid that these settings will produce
+    /// for the given AABB. This is useful to make the effect of the fraction-based settings such as
+    /// [`Self::cell_size_fraction`] and [`Self::cell_height_fraction`] predictable.
+    ///
+    /// The AABB is interpreted in the coordinate system given by [`Self::up`] and [`Self::handedness`].
+    pub fn resolve_grid(&self, aabb: Aabb3d) -> GridDims {
+        let aabb = rerecast::Aabb3d {
+            min: aabb.min.into(),
+            max: aabb.max.into(),
+        };
+        let config = ConfigBuilder {
+            aabb: CoordinateRemap::from(self).aabb_to_recast(aabb),
+            ..self.clone().into_rerecast_config()
+        }
+        .build();
+        GridDims {
+            cell_size: config.cell_size,
+            cell_height: config.cell_height,
+            width: config.width,
+            height: config.height,
+        }
+    }
+
+    /// The most [`Self::time_budget`] may coarsen the cells, relative to [`Self::cell_size_fraction`].
+    pub const MAX_TIME_BUDGET_COARSENING: f32 = 8.0;
+
+    /// Estimates how long generating a navmesh from `trimesh` with these settings takes, without generating it.
+    ///
+    /// The estimate grows with the number of triangles, the number of columns of the voxel grid, see [`Self::resolve_grid`],
+    /// and the number of height samples of the detail mesh, see [`Self::detail_sample_dist`].
+    /// It is calibrated with release builds on a desktop CPU and only meant to compare settings against each other and against
+    /// a [`Self::time_budget`], not to predict the exact build time on any given machine.
+    pub fn estimate_build_time(&self, trimesh: &TriMesh) -> Duration {
+        // Measured by generating floors of 100 to 200 units with pillars on them, with and without subdividing the floor
+        // into ~180k triangles, at cell size fractions of 2 and 4. The grid columns dominate open floors,
+        // which took 650 to 800 ns per column. The subdivided floors took another 500 ns per triangle on top of that.
+        // The detail mesh took 30 to 70 ns per column at the default sample distance of 6 cells,
+        // i.e. about 1.5 µs per sample.
+        /// The time spent per grid column on rasterization, filtering, regions, contours and polygons.
+        const NANOS_PER_COLUMN: u64 = 700;
+        /// The time spent per height sample of the detail mesh.
+        const NANOS_PER_DETAIL_SAMPLE: u64 = 1500;
+        /// The time spent per input triangle on marking and rasterizing it, disregarding its size.
+        const NANOS_PER_TRIANGLE: u64 = 500;
+
+        let aabb = match self.aabb {
+            Some(aabb) => aabb,
+            None => {
+                let Some(aabb) = trimesh.compute_aabb() else {
+                    return Duration::ZERO;
+                };
+                Aabb3d {
+                    min: aabb.min.into(),
+                    max: aabb.max.into(),
+                }
+            }
+        };
+        let grid = self.resolve_grid(aabb);
+        let columns = grid.width as u64 * grid.height as u64;
+        let mut nanos =
+            columns * NANOS_PER_COLUMN + trimesh.indices.len() as u64 * NANOS_PER_TRIANGLE;
+        // Below 0.9 cells, the detail mesh only samples the polygon edges, which is negligible.
+        if self.generate_detail && self.detail_sample_dist >= 0.9 {
+            let samples = columns as f32 / (self.detail_sample_dist * self.detail_sample_dist);
+            nanos += samples as u64 * NANOS_PER_DETAIL_SAMPLE;
+        }
+        Duration::from_nanos(nanos)
+    }
+
     pub(crate) fn into_rerecast_config(self) -> rerecast::ConfigBuilder {
         rerecast::ConfigBuilder {
             agent_height: self.agent_height,
@@ -256,3 +633,346 @@ impl NavmeshSettings {
         }
     }
 }
+
+/// Errors returned by [`NavmeshSettings::validate`]. Each variant names the offending field.
+#[derive(Debug, Clone, PartialEq, Error)]
+#[non_exhaustive]
+pub enum NavmeshSettingsError {
+    /// [`NavmeshSettings::cell_size_fraction`] is not greater than 0.
+    #[error("`NavmeshSettings::cell_size_fraction` must be greater than 0, but got {0}")]
+    CellSizeFraction(f32),
+    /// [`NavmeshSettings::cell_height_fraction`] is not greater than 0.
+    #[error("`NavmeshSettings::cell_height_fraction` must be greater than 0, but got {0}")]
+    CellHeightFraction(f32),
+    /// [`NavmeshSettings::agent_radius`] is not greater than 0.
+    #[error("`NavmeshSettings::agent_radius` must be greater than 0, but got {0}")]
+    AgentRadius(f32),
+    /// [`NavmeshSettings::agent_height`] is not greater than 0.
+    #[error("`NavmeshSettings::agent_height` must be greater than 0, but got {0}")]
+    AgentHeight(f32),
+    /// [`NavmeshSettings::walkable_climb`] is negative or greater than [`NavmeshSettings::agent_height`].
+    #[error(
+        "`NavmeshSettings::walkable_climb` must be between 0 and `NavmeshSettings::agent_height` ({agent_height}), but got {walkable_climb}"
+    )]
+    WalkableClimb {
+        /// The configured [`NavmeshSettings::walkable_climb`].
+        walkable_climb: f32,
+        /// The configured [`NavmeshSettings::agent_height`].
+        agent_height: f32,
+    },
+    /// [`NavmeshSettings::tile_size`] is 0 while [`NavmeshSettings::tiling`] is enabled.
+    #[error(
+        "`NavmeshSettings::tile_size` must be greater than 0 when `NavmeshSettings::tiling` is enabled"
+    )]
+    TileSize,
+    /// [`NavmeshSettings::aabb`] has a minimum corner that is greater than its maximum corner on some axis.
+    #[error(
+        "`NavmeshSettings::aabb` must have its minimum corner below its maximum corner, but got min {} and max {}",
+        .0.min,
+        .0.max
+    )]
+    Aabb(Aabb3d),
+    /// [`NavmeshSettings::default_walkable_area`] is [`AreaType::NOT_WALKABLE`].
+    #[error(
+        "`NavmeshSettings::default_walkable_area` must be walkable, but got `AreaType::NOT_WALKABLE`"
+    )]
+    DefaultWalkableArea,
+    /// [`NavmeshSettings::up`] is not one of [`Vec3::X`], [`Vec3::Y`], or [`Vec3::Z`].
+    #[error("`NavmeshSettings::up` must be one of Vec3::X, Vec3::Y, or Vec3::Z, but got {0}")]
+    Up(Vec3),
+}
+
+/// Builder for [`NavmeshSettings`], created with [`NavmeshSettings::builder`].
+///
+/// All fields that are not set keep their [`Default`] values.
+/// Use [`NavmeshSettingsBuilder::from`] to start from other settings, e.g. [`NavmeshSettings::indoor_human`].
+#[derive(Debug, Clone, Default, PartialEq)]
+#[must_use]
+pub struct NavmeshSettingsBuilder(NavmeshSettings);
+
+impl From<NavmeshSettings> for NavmeshSettingsBuilder {
+    fn from(settings: NavmeshSettings) -> Self {
+        Self(settings)
+    }
+}
+
+macro_rules! setters {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        $(
+            #[doc = concat!("Sets [`NavmeshSettings::", stringify!($field), "`].")]
+            pub fn $field(mut self, $field: $ty) -> Self {
+                self.0.$field = $field;
+                self
+            }
+        )*
+    };
+}
+
+impl NavmeshSettingsBuilder {
+    setters! {
+        cell_size_fraction: f32,
+        cell_height_fraction: f32,
+        agent_height: f32,
+        agent_radius: f32,
+        walkable_climb: f32,
+        walkable_slope_angle: f32,
+        min_region_size: u16,
+        merge_region_size: u16,
+        edge_max_len_factor: u16,
+        max_simplification_error: f32,
+        max_vertices_per_polygon: u16,
+        detail_sample_dist: f32,
+        detail_sample_max_error: f32,
+        tile_size: u16,
+        contour_flags: BuildContoursFlags,
+        tiling: bool,
+        area_volumes: Vec<ConvexVolume>,
+        up: Vec3,
+        generate_detail: bool,
+        partition: PartitionMethod,
+        default_walkable_area: AreaType,
+        keep_heightfield: bool,
+        keep_distance_field: bool,
+        handedness: Handedness,
+        capture_input: bool,
+        keep_input: bool,
+    }
+
+    /// Sets [`NavmeshSettings::aabb`].
+    pub fn aabb(mut self, aabb: impl Into<Option<Aabb3d>>) -> Self {
+        self.0.aabb = aabb.into();
+        self
+    }
+
+    /// Sets [`NavmeshSettings::filter`].
+    pub fn filter(mut self, filter: impl Into<Option<HashSet<Entity>>>) -> Self {
+        self.0.filter = filter.into();
+        self
+    }
+
+    /// Sets [`NavmeshSettings::time_budget`].
+    pub fn time_budget(mut self, time_budget: impl Into<Option<Duration>>) -> Self {
+        self.0.time_budget = time_budget.into();
+        self
+    }
+
+    /// Sets [`NavmeshSettings::prune_unreachable_below`].
+    pub fn prune_unreachable_below(
+        mut self,
+        prune_unreachable_below: impl Into<Option<usize>>,
+    ) -> Self {
+        self.0.prune_unreachable_below = prune_unreachable_below.into();
+        self
+    }
+
+    /// Builds the [`NavmeshSettings`].
+    pub fn build(self) -> NavmeshSettings {
+        self.0
+    }
+}
+
+/// The handedness of a coordinate system, as used by [`NavmeshSettings::handedness`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub enum Handedness {
+    /// A right-handed coordinate system, like Bevy's, Godot's, or Blender's.
+    #[default]
+    RightHanded,
+    /// A left-handed coordinate system, like Unreal's or Unity's.
+    LeftHanded,
+}
+
+/// The algorithm used by [`NavmeshSettings::partition`] to partition the walkable area into regions.
+///
+/// See the [Recast sample](https://github.com/recastnavigation/recastnavigation/blob/main/RecastDemo/Source/Sample_SoloMesh.cpp)
+/// for a comparison of the different methods.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub enum PartitionMethod {
+    /// Watershed partitioning using [`CompactHeightfield::build_regions`](rerecast::CompactHeightfield::build_regions).
+    ///
+    /// - The classic Recast partitioning, creates the nicest tessellation.
+    /// - Usually the slowest.
+    /// - Partitions the heightfield into nice regions without holes or overlaps.
+    /// - There are some corner cases where this method creates holes and overlaps, e.g. when small obstacles are close to large open areas.
+    ///
+    /// Best choice if you precompute the navmesh.
+    #[default]
+    Watershed,
+    /// Monotone partitioning using [`CompactHeightfield::build_regions_monotone`](rerecast::CompactHeightfield::build_regions_monotone).
+    ///
+    /// - Fastest, does not need a distance field.
+    /// - Partitions the heightfield into regions without holes and overlaps (guaranteed).
+    /// - Creates long thin polygons, which sometimes causes paths with detours.
+    ///
+    /// Use this if you want fast navmesh generation.
+    Monotone,
+    /// Layer partitioning using [`CompactHeightfield::build_layer_regions`](rerecast::CompactHeightfield::build_layer_regions).
+    ///
+    /// - Quite fast, does not need a distance field.
+    /// - Partitions the heightfield into non-overlapping regions.
+    /// - Relies on the triangulation code to cope with holes, so it is slower than monotone partitioning.
+    /// - Produces better triangles than monotone partitioning.
+    /// - Does not have the corner cases of watershed partitioning.
+    ///
+    /// Good choice for tiled navmeshes with medium and small sized tiles.
+    Layers,
+}
+
+/// The absolute dimensions of the voxel grid resolved from [`NavmeshSettings`] through [`NavmeshSettings::resolve_grid`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct GridDims {
+    /// The size of each cell on the horizontal plane. `[Units: wu]`
+    pub cell_size: f32,
+    /// The size of each cell on the up-axis. `[Units: wu]`
+    pub cell_height: f32,
+    /// The number of cells along the first horizontal axis. `[Units: vx]`
+    pub width: u16,
+    /// The number of cells along the second horizontal axis. `[Units: vx]`
+    pub height: u16,
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3A;
+
+    use super::*;
+
+    #[test]
+    fn resolve_grid_scales_with_cell_size_fraction() {
+        let aabb = Aabb3d::new(Vec3::ZERO, Vec3::new(50.0, 10.0, 50.0));
+        let coarse = NavmeshSettings {
+            agent_radius: 0.5,
+            cell_size_fraction: 2.0,
+            ..Default::default()
+        };
+        let fine = NavmeshSettings {
+            cell_size_fraction: 4.0,
+            ..coarse.clone()
+        };
+        let coarse_grid = coarse.resolve_grid(aabb);
+        let fine_grid = fine.resolve_grid(aabb);
+
+        assert_eq!(coarse_grid.cell_size, 0.25);
+        assert_eq!(fine_grid.cell_size, 0.125);
+        assert_eq!(coarse_grid.width, 400);
+        assert_eq!(fine_grid.width, coarse_grid.width * 2);
+    }
+
+    #[test]
+    fn presets_use_their_up_axis() {
+        assert_eq!(NavmeshSettings::indoor_human().up, Vec3::Y);
+        assert_eq!(NavmeshSettings::outdoor_large_world().up, Vec3::Y);
+        let top_down = NavmeshSettings::top_down_2d();
+        assert_eq!(top_down.up, Vec3::Z);
+        assert!(!top_down.generate_detail);
+    }
+
+    #[test]
+    fn settings_builder() {
+        let aabb = Aabb3d::new(Vec3::ZERO, Vec3::splat(10.0));
+        let settings = NavmeshSettings::builder()
+            .agent_radius(0.5)
+            .agent_height(2.0)
+            .tile_size(64)
+            .aabb(aabb)
+            .build();
+        assert_eq!(
+            settings,
+            NavmeshSettings {
+                agent_radius: 0.5,
+                agent_height: 2.0,
+                tile_size: 64,
+                aabb: Some(aabb),
+                ..Default::default()
+            }
+        );
+
+        let preset = NavmeshSettingsBuilder::from(NavmeshSettings::indoor_human())
+            .generate_detail(false)
+            .build();
+        assert_eq!(
+            preset,
+            NavmeshSettings {
+                generate_detail: false,
+                ..NavmeshSettings::indoor_human()
+            }
+        );
+    }
+
+    #[test]
+    fn settings_validation() {
+        for settings in [
+            NavmeshSettings::default(),
+            NavmeshSettings::indoor_human(),
+            NavmeshSettings::outdoor_large_world(),
+            NavmeshSettings::top_down_2d(),
+        ] {
+            assert_eq!(settings.validate(), Ok(()));
+        }
+
+        let invalid = |settings: NavmeshSettingsBuilder| settings.build().validate().unwrap_err();
+        assert_eq!(
+            invalid(NavmeshSettings::builder().cell_size_fraction(0.0)),
+            NavmeshSettingsError::CellSizeFraction(0.0)
+        );
+        assert_eq!(
+            invalid(NavmeshSettings::builder().cell_height_fraction(-1.0)),
+            NavmeshSettingsError::CellHeightFraction(-1.0)
+        );
+        assert_eq!(
+            invalid(NavmeshSettings::builder().agent_radius(-0.5)),
+            NavmeshSettingsError::AgentRadius(-0.5)
+        );
+        assert!(matches!(
+            invalid(NavmeshSettings::builder().agent_radius(f32::NAN)),
+            NavmeshSettingsError::AgentRadius(_)
+        ));
+        assert_eq!(
+            invalid(NavmeshSettings::builder().agent_height(0.0)),
+            NavmeshSettingsError::AgentHeight(0.0)
+        );
+        assert_eq!(
+            invalid(
+                NavmeshSettings::builder()
+                    .agent_height(1.0)
+                    .walkable_climb(1.5)
+            ),
+            NavmeshSettingsError::WalkableClimb {
+                walkable_climb: 1.5,
+                agent_height: 1.0
+            }
+        );
+        assert_eq!(
+            invalid(NavmeshSettings::builder().walkable_climb(-0.1)),
+            NavmeshSettingsError::WalkableClimb {
+                walkable_climb: -0.1,
+                agent_height: NavmeshSettings::default().agent_height
+            }
+        );
+        assert_eq!(
+            invalid(NavmeshSettings::builder().tiling(true).tile_size(0)),
+            NavmeshSettingsError::TileSize
+        );
+        assert_eq!(
+            NavmeshSettings::builder()
+                .tiling(false)
+                .tile_size(0)
+                .build()
+                .validate(),
+            Ok(())
+        );
+        let inverted = Aabb3d {
+            min: Vec3A::new(0.0, 0.0, 5.0),
+            max: Vec3A::new(5.0, 5.0, 0.0),
+        };
+        assert_eq!(
+            invalid(NavmeshSettings::builder().aabb(inverted)),
+            NavmeshSettingsError::Aabb(inverted)
+        );
+        assert_eq!(
+            invalid(NavmeshSettings::builder().default_walkable_area(AreaType::NOT_WALKABLE)),
+            NavmeshSettingsError::DefaultWalkableArea
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use bevy_math::ops;
+use glam::{U16Vec3, Vec3};
+use rerecast::{Aabb3d, PolygonNavmesh};
+
+use crate::{Handedness, NavmeshSettings};
+
+/// Converts between the coordinate system of the navmesh obstacles and the right-handed, Y-up coordinate system Recast works in.
+///
+/// The coordinate system of the obstacles is described by [`NavmeshSettings::up`] and [`NavmeshSettings::handedness`].
+/// The axes are rotated so that `up` becomes the Y axis. For [`Handedness::LeftHanded`] coordinate systems,
+/// Recast's Z axis is additionally mirrored so that surfaces facing up in the source also face up for Recast.
+/// Otherwise, all triangles of a left-handed source would be wound the wrong way and be considered unwalkable.
+///
+/// Generated navmeshes are converted back, so [`Navmesh::polygon`](crate::Navmesh::polygon) and
+/// [`Navmesh::detail`](crate::Navmesh::detail) are in the coordinate system of the obstacles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoordinateRemap {
+    /// The direction considered up. See [`NavmeshSettings::up`].
+    pub up: Vec3,
+    /// The handedness of the coordinate system. See [`NavmeshSettings::handedness`].
+    pub handedness: Handedness,
+}
+
+impl Default for CoordinateRemap {
+    fn default() -> Self {
+        Self {
+            up: Vec3::Y,
+            handedness: Handedness::default(),
+        }
+    }
+}
+
+impl From<&NavmeshSettings> for CoordinateRemap {
+    fn from(settings: &NavmeshSettings) -> Self {
+        Self {
+            up: settings.up,
+            handedness: settings.handedness,
+        }
+    }
+}
+
+impl CoordinateRemap {
+    /// Whether the conversion is the identity, i.e. the obstacles are already in Recast's coordinate system.
+    pub fn is_identity(self) -> bool {
+        self == Self::default()
+    }
+
+    /// Converts a position into Recast's coordinate system.
+    pub fn to_recast(self, v: Vec3) -> Vec3 {
+        let v = Vec3::from_array(self.axes_to_recast(v.to_array()));
+        if self.is_mirrored() {
+            v.with_z(-v.z)
+        } else {
+            v
+        }
+    }
+
+    /// Converts a position from Recast's coordinate system. This is the inverse of [`Self::to_recast`].
+    pub fn from_recast(self, v: Vec3) -> Vec3 {
+        let v = if self.is_mirrored() {
+            v.with_z(-v.z)
+        } else {
+            v
+        };
+        Vec3::from_array(self.axes_from_recast(v.to_array()))
+    }
+
+    /// Converts an AABB into Recast's coordinate system.
+    pub fn aabb_to_recast(self, aabb: Aabb3d) -> Aabb3d {
+        let (a, b) = (self.to_recast(aabb.min), self.to_recast(aabb.max));
+        Aabb3d {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    /// The size of a cell in the coordinate system of the obstacles, i.e. the scale from the cells
+    /// a [`PolygonNavmesh`] stores its vertices in to world units.
+    pub fn cell_scale(self, cell_size: f32, cell_height: f32) -> Vec3 {
+        Vec3::from_array(self.axes_from_recast([cell_size, cell_height, cell_size]))
+    }
+
+    /// Converts a polygon navmesh in place from Recast's coordinate system.
+    ///
+    /// Since the vertices are stored as cells relative to [`PolygonNavmesh::aabb`], a mirrored AABB is
+    /// grown to a whole number of cells along the mirrored axis.
+    pub fn polygon_from_recast(self, mesh: &mut PolygonNavmesh) {
+        let mut aabb = mesh.aabb;
+        self.cells_from_recast(&mut aabb, mesh.cell_size, &mut mesh.vertices);
+        mesh.aabb = aabb;
+    }
+
+    /// Converts a polygon navmesh in place into Recast's coordinate system.
+    /// This is the inverse of [`Self::polygon_from_recast`].
+    pub fn polygon_to_recast(self, mesh: &mut PolygonNavmesh) {
+        let min = Vec3::from_array(self.axes_to_recast(mesh.aabb.min.to_array()));
+        let max = Vec3::from_array(self.axes_to_recast(mesh.aabb.max.to_array()));
+        let cells = self.mirrored_cells(min.z, max.z, mesh.cell_size);
+        for vertex in &mut mesh.vertices {
+            let v = U16Vec3::from_array(self.axes_to_recast(vertex.to_array()));
+            *vertex = if self.is_mirrored() {
+                v.with_z(cells.saturating_sub(v.z))
+            } else {
+                v
+            };
+        }
+        mesh.aabb = if self.is_mirrored() {
+            Aabb3d {
+                min: min.with_z(-max.z),
+                max: max.with_z(-max.z + cells as f32 * mesh.cell_size),
+            }
+        } else {
+            Aabb3d { min, max }
+        };
+    }
+
+    /// Converts vertices stored as cells of size `cell_size` relative to `aabb` from Recast's coordinate system,
+    /// together with `aabb` itself.
+    pub(crate) fn cells_from_recast<'a>(
+        self,
+        aabb: &mut Aabb3d,
+        cell_size: f32,
+        vertices: impl IntoIterator<Item = &'a mut U16Vec3>,
+    ) {
+        let cells = self.mirrored_cells(aabb.min.z, aabb.max.z, cell_size);
+        for vertex in vertices {
+            let v = if self.is_mirrored() {
+                vertex.with_z(cells.saturating_sub(vertex.z))
+            } else {
+                *vertex
+            };
+            *vertex = U16Vec3::from_array(self.axes_from_recast(v.to_array()));
+        }
+        let (mut min, mut max) = (aabb.min, aabb.max);
+        if self.is_mirrored() {
+            (min.z, max.z) = (-(min.z + cells as f32 * cell_size), -min.z);
+        }
+        *aabb = Aabb3d {
+            min: Vec3::from_array(self.axes_from_recast(min.to_array())),
+            max: Vec3::from_array(self.axes_from_recast(max.to_array())),
+        };
+    }
+
+    fn is_mirrored(self) -> bool {
+        self.handedness == Handedness::LeftHanded
+    }
+
+    /// The number of cells between `min` and `max` along the mirrored axis.
+    fn mirrored_cells(self, min: f32, max: f32, cell_size: f32) -> u16 {
+        if self.is_mirrored() {
+            ops::round((max - min) / cell_size) as u16
+        } else {
+            0
+        }
+    }
+
+    fn axes_to_recast<T>(self, [x, y, z]: [T; 3]) -> [T; 3] {
+        match self.up {
+            Vec3::Z => [y, z, x],
+            Vec3::X => [z, x, y],
+            _ => [x, y, z],
+        }
+    }
+
+    fn axes_from_recast<T>(self, [x, y, z]: [T; 3]) -> [T; 3] {
+        match self.up {
+            Vec3::Z => [z, x, y],
+            Vec3::X => [y, z, x],
+            _ => [x, y, z],
+        }
+    }
+}
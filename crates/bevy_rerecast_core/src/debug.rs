@@ -3,21 +3,25 @@ use alloc::vec::Vec;
 use bevy_app::prelude::*;
 use bevy_asset::{RenderAssetUsages, prelude::*};
 use bevy_camera::{prelude::*, visibility::RenderLayers};
-use bevy_color::{Alpha as _, palettes::tailwind};
+use bevy_color::{Alpha as _, Color, ColorToComponents as _, LinearRgba, palettes::tailwind};
 use bevy_ecs::{lifecycle::HookContext, prelude::*, world::DeferredWorld};
 use bevy_gizmos::prelude::*;
 use bevy_light::{NotShadowCaster, NotShadowReceiver};
+use bevy_math::Isometry3d;
 use bevy_mesh::{Indices, Mesh, Mesh3d, PrimitiveTopology};
 use bevy_pbr::prelude::*;
-use bevy_reflect::prelude::*;
+use bevy_reflect::{GetTypeRegistration, prelude::*};
 use bevy_render::prelude::*;
-use glam::vec3;
-use rerecast::PolygonNavmesh;
+use bevy_transform::components::Transform;
+use glam::{Vec3, vec3};
+use rerecast::{CompactHeightfield, PolygonNavmesh};
 
-use crate::Navmesh;
+use crate::{CoordinateRemap, Navmesh};
 
 /// Plugin for visualizing navmeshes for debugging purposes.
-/// After adding the plugin, spawn a [`DetailNavmeshGizmo`] or [`PolygonNavmeshGizmo`] to visualize a navmesh.
+/// After adding the plugin, spawn a [`DetailNavmeshGizmo`], [`PolygonNavmeshGizmo`], [`ContourGizmo`],
+/// [`HeightfieldGizmo`], [`DistanceFieldGizmo`], or [`InputGizmo`] to visualize a navmesh.
+/// Spawn a [`PathGizmo`] to visualize a path through a navmesh.
 #[derive(Debug, Default)]
 #[non_exhaustive]
 pub struct NavmeshDebugPlugin;
@@ -25,29 +29,102 @@ pub struct NavmeshDebugPlugin;
 impl Plugin for NavmeshDebugPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<NavmeshGizmoConfig>()
+            .init_resource::<GizmoColorScheme>()
             .init_resource::<GizmoHandles>();
         app.register_type::<NavmeshGizmoConfig>()
-            .register_type::<DetailNavmeshGizmo>()
-            .register_type::<PolygonNavmeshGizmo>();
+            .register_type::<GizmoColorScheme>();
+        add_gizmo::<PolygonNavmeshGizmo>(app);
+        add_gizmo::<DetailNavmeshGizmo>(app);
+        add_gizmo::<ContourGizmo>(app);
+        add_gizmo::<PathGizmo>(app);
+        add_gizmo::<HeightfieldGizmo>(app);
+        add_gizmo::<DistanceFieldGizmo>(app);
+        add_gizmo::<InputGizmo>(app);
         app.add_systems(
             PreUpdate,
-            (
-                mark_gizmos_dirty_on_config_change,
-                mark_gizmos_dirty_on_asset_change,
-                update_dirty_polygon_gizmos,
-                update_dirty_detail_gizmos,
-            )
-                .chain(),
+            mark_polygon_gizmos_dirty_on_color_scheme_change
+                .before(update_dirty_gizmos::<PolygonNavmeshGizmo>),
         );
     }
 }
 
-fn mark_gizmos_dirty_on_config_change(
+/// Registers a kind of navmesh gizmo and the systems that redraw it.
+fn add_gizmo<G: NavmeshGizmo + GetTypeRegistration>(app: &mut App) {
+    app.register_type::<G>();
+    app.add_systems(
+        PreUpdate,
+        (
+            mark_gizmos_dirty_on_config_change::<G>,
+            mark_gizmos_dirty_on_change::<G>,
+            mark_gizmos_dirty_on_asset_change::<G>,
+            update_dirty_gizmos::<G>,
+        )
+            .chain(),
+    );
+}
+
+/// A component that draws a part of a [`Navmesh`] with a [`Gizmo`].
+trait NavmeshGizmo: Component {
+    /// The navmesh that is drawn.
+    fn navmesh(&self) -> AssetId<Navmesh>;
+
+    /// The configuration for all gizmos of this kind.
+    fn config(config: &NavmeshGizmoConfig) -> &GizmoConfig;
+
+    /// Whether gizmos of this kind need to be redrawn when the configuration changes from `old` to `new`.
+    fn config_changed(old: &NavmeshGizmoConfig, new: &NavmeshGizmoConfig) -> bool {
+        !cfg_eq(Self::config(old), Self::config(new))
+    }
+
+    /// The material that gizmos of this kind fill the surface of the navmesh with, if they do.
+    fn material(_handles: &GizmoHandles) -> Option<Handle<StandardMaterial>> {
+        None
+    }
+
+    /// Draws the navmesh into the cleared `gizmo`.
+    /// Gizmos that also fill the surface of the navmesh return the mesh and material to fill it with.
+    fn draw(
+        &self,
+        navmesh: &Navmesh,
+        gizmo: &mut GizmoAsset,
+        context: &GizmoDrawContext,
+    ) -> Option<(Mesh, Handle<StandardMaterial>)>;
+}
+
+/// Sets up the [`Gizmo`] of a newly added navmesh gizmo of kind `G`.
+fn init_gizmo<G: NavmeshGizmo>(mut world: DeferredWorld, ctx: HookContext) {
+    let gizmo_handle = world
+        .resource_mut::<Assets<GizmoAsset>>()
+        .add(GizmoAsset::new());
+    let config = G::config(world.resource::<NavmeshGizmoConfig>()).clone();
+    let material = G::material(world.resource::<GizmoHandles>());
+    let mut commands = world.commands();
+    let mut entity = commands.entity(ctx.entity);
+    entity.insert((
+        Gizmo {
+            handle: gizmo_handle,
+            line_config: config.line,
+            depth_bias: config.depth_bias,
+        },
+        config.render_layers,
+    ));
+    if let Some(material) = material {
+        entity.insert((MeshMaterial3d(material), NotShadowCaster, NotShadowReceiver));
+    }
+}
+
+/// The resources available to [`NavmeshGizmo::draw`].
+struct GizmoDrawContext<'a> {
+    config: &'a NavmeshGizmoConfig,
+    color_scheme: GizmoColorScheme,
+    handles: &'a GizmoHandles,
+}
+
+fn mark_gizmos_dirty_on_config_change<G: NavmeshGizmo>(
     mut commands: Commands,
     config: Res<NavmeshGizmoConfig>,
     mut last_config: Local<Option<NavmeshGizmoConfig>>,
-    polygon_gizmos: Query<Entity, With<PolygonNavmeshGizmo>>,
-    detail_gizmos: Query<Entity, With<DetailNavmeshGizmo>>,
+    gizmos: Query<Entity, With<G>>,
 ) {
     if !config.is_changed() {
         return;
@@ -59,55 +136,56 @@ fn mark_gizmos_dirty_on_config_change(
         return;
     };
 
-    if !cfg_eq(&last_config.polygon_navmesh, &config.polygon_navmesh) {
-        for entity in polygon_gizmos.iter() {
-            commands.entity(entity).insert(DirtyNavmeshGizmo);
-        }
-    }
-    if !cfg_eq(&last_config.detail_navmesh, &config.detail_navmesh) {
-        for entity in detail_gizmos.iter() {
+    if G::config_changed(last_config, &config) {
+        for entity in gizmos.iter() {
             commands.entity(entity).insert(DirtyNavmeshGizmo);
         }
     }
     *last_config = config.clone();
 }
 
-fn mark_gizmos_dirty_on_asset_change(
+fn mark_polygon_gizmos_dirty_on_color_scheme_change(
+    mut commands: Commands,
+    color_scheme: Res<GizmoColorScheme>,
+    polygon_gizmos: Query<Entity, With<PolygonNavmeshGizmo>>,
+) {
+    // All gizmos are spawned as dirty, so the initial insertion of the resource can be skipped.
+    if !color_scheme.is_changed() || color_scheme.is_added() {
+        return;
+    }
+    for entity in polygon_gizmos.iter() {
+        commands.entity(entity).insert(DirtyNavmeshGizmo);
+    }
+}
+
+fn mark_gizmos_dirty_on_change<G: NavmeshGizmo>(
+    mut commands: Commands,
+    gizmos: Query<Entity, Changed<G>>,
+) {
+    for entity in gizmos.iter() {
+        commands.entity(entity).insert(DirtyNavmeshGizmo);
+    }
+}
+
+fn mark_gizmos_dirty_on_asset_change<G: NavmeshGizmo>(
     mut commands: Commands,
     mut asset_events: MessageReader<AssetEvent<Navmesh>>,
-    polygon_gizmos: Query<(Entity, &PolygonNavmeshGizmo)>,
-    detail_gizmos: Query<(Entity, &DetailNavmeshGizmo)>,
+    gizmos: Query<(Entity, &G)>,
 ) {
     for event in asset_events.read() {
         match event {
             AssetEvent::Added { id }
             | AssetEvent::LoadedWithDependencies { id }
             | AssetEvent::Modified { id } => {
-                for (entity, current_id) in polygon_gizmos
-                    .iter()
-                    .map(|(entity, handle)| (entity, handle.0))
-                    .chain(
-                        detail_gizmos
-                            .iter()
-                            .map(|(entity, handle)| (entity, handle.0)),
-                    )
-                {
-                    if current_id == *id {
+                for (entity, gizmo) in gizmos.iter() {
+                    if gizmo.navmesh() == *id {
                         commands.entity(entity).insert(DirtyNavmeshGizmo);
                     }
                 }
             }
             AssetEvent::Removed { id } | AssetEvent::Unused { id } => {
-                for (entity, current_id) in polygon_gizmos
-                    .iter()
-                    .map(|(entity, handle)| (entity, handle.0))
-                    .chain(
-                        detail_gizmos
-                            .iter()
-                            .map(|(entity, handle)| (entity, handle.0)),
-                    )
-                {
-                    if current_id == *id {
+                for (entity, gizmo) in gizmos.iter() {
+                    if gizmo.navmesh() == *id {
                         commands.entity(entity).try_despawn();
                     }
                 }
@@ -116,6 +194,54 @@ fn mark_gizmos_dirty_on_asset_change(
     }
 }
 
+fn update_dirty_gizmos<G: NavmeshGizmo>(
+    mut commands: Commands,
+    mut gizmos: Query<
+        (Entity, &mut Gizmo, &mut RenderLayers, &G, &mut Visibility),
+        With<DirtyNavmeshGizmo>,
+    >,
+    mut gizmo_assets: ResMut<Assets<GizmoAsset>>,
+    navmeshes: Res<Assets<Navmesh>>,
+    config: Res<NavmeshGizmoConfig>,
+    color_scheme: Res<GizmoColorScheme>,
+    handles: Res<GizmoHandles>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let context = GizmoDrawContext {
+        config: &config,
+        color_scheme: *color_scheme,
+        handles: &handles,
+    };
+    for (entity, mut gizmo_handle, mut layers, navmesh_gizmo, mut visibility) in gizmos.iter_mut() {
+        let Some(gizmo) = gizmo_assets.get_mut(&gizmo_handle.handle) else {
+            continue;
+        };
+        let gizmo_config = G::config(&config);
+        if !gizmo_config.enabled {
+            gizmo.clear();
+            commands.entity(entity).remove::<DirtyNavmeshGizmo>();
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        let Some(navmesh) = navmeshes.get(navmesh_gizmo.navmesh()) else {
+            continue;
+        };
+        gizmo.clear();
+
+        if let Some((mesh, material)) = navmesh_gizmo.draw(navmesh, gizmo, &context) {
+            commands
+                .entity(entity)
+                .insert((Mesh3d(meshes.add(mesh)), MeshMaterial3d(material)));
+        }
+
+        gizmo_handle.line_config = gizmo_config.line.clone();
+        gizmo_handle.depth_bias = gizmo_config.depth_bias;
+        *layers = gizmo_config.render_layers.clone();
+        *visibility = Visibility::Inherited;
+        commands.entity(entity).remove::<DirtyNavmeshGizmo>();
+    }
+}
+
 fn cfg_eq(a: &GizmoConfig, b: &GizmoConfig) -> bool {
     a.enabled == b.enabled
         && a.line.width == b.line.width
@@ -134,8 +260,8 @@ struct DirtyNavmeshGizmo;
 #[derive(Debug, Clone, Component, Reflect)]
 #[reflect(Component)]
 #[require(DirtyNavmeshGizmo, Visibility)]
-#[cfg_attr(feature = "bevy_mesh", require(crate::mesh::ExcludeMeshFromNavmesh))]
-#[component(on_add = init_detail_navmesh_gizmo)]
+#[cfg_attr(feature = "bevy_mesh", require(crate::mesh::NavmeshExcluded))]
+#[component(on_add = init_gizmo::<DetailNavmeshGizmo>)]
 pub struct DetailNavmeshGizmo(pub AssetId<Navmesh>);
 
 impl DetailNavmeshGizmo {
@@ -145,67 +271,124 @@ impl DetailNavmeshGizmo {
     }
 }
 
-fn init_detail_navmesh_gizmo(mut world: DeferredWorld, ctx: HookContext) {
-    let gizmo_handle = world
-        .resource_mut::<Assets<GizmoAsset>>()
-        .add(GizmoAsset::new());
-    let material_handle = world.resource::<GizmoHandles>().detail_material.clone();
-    let config = world
-        .resource::<NavmeshGizmoConfig>()
-        .detail_navmesh
-        .clone();
-    world.commands().entity(ctx.entity).insert((
-        Gizmo {
-            handle: gizmo_handle,
-            line_config: config.line,
-            depth_bias: config.depth_bias,
-        },
-        config.render_layers,
-        MeshMaterial3d(material_handle),
-        NotShadowCaster,
-        NotShadowReceiver,
-    ));
-}
+impl NavmeshGizmo for DetailNavmeshGizmo {
+    fn navmesh(&self) -> AssetId<Navmesh> {
+        self.0
+    }
 
-fn update_dirty_polygon_gizmos(
-    mut commands: Commands,
-    mut gizmos: Query<
-        (
-            Entity,
-            &mut Gizmo,
-            &mut RenderLayers,
-            &PolygonNavmeshGizmo,
-            &mut Visibility,
-        ),
-        With<DirtyNavmeshGizmo>,
-    >,
-    mut gizmo_assets: ResMut<Assets<GizmoAsset>>,
-    navmeshes: Res<Assets<Navmesh>>,
-    config: Res<NavmeshGizmoConfig>,
-    mut meshes: ResMut<Assets<Mesh>>,
-) {
-    for (entity, mut gizmo_handle, mut layers, navmesh_handle, mut visibility) in gizmos.iter_mut()
-    {
-        let Some(gizmo) = gizmo_assets.get_mut(&gizmo_handle.handle) else {
-            continue;
-        };
-        let config = config.polygon_navmesh.clone();
-        if !config.enabled {
-            gizmo.clear();
-            commands.entity(entity).remove::<DirtyNavmeshGizmo>();
-            *visibility = Visibility::Hidden;
-            continue;
+    fn config(config: &NavmeshGizmoConfig) -> &GizmoConfig {
+        &config.detail_navmesh
+    }
+
+    fn config_changed(old: &NavmeshGizmoConfig, new: &NavmeshGizmoConfig) -> bool {
+        !cfg_eq(&old.detail_navmesh, &new.detail_navmesh)
+            || old.detail_edge_color != new.detail_edge_color
+    }
+
+    fn material(handles: &GizmoHandles) -> Option<Handle<StandardMaterial>> {
+        Some(handles.detail_material.clone())
+    }
+
+    fn draw(
+        &self,
+        navmesh: &Navmesh,
+        gizmo: &mut GizmoAsset,
+        context: &GizmoDrawContext,
+    ) -> Option<(Mesh, Handle<StandardMaterial>)> {
+        let edge_color = context.config.detail_edge_color;
+        let mesh = &navmesh.detail;
+
+        for submesh in &mesh.meshes {
+            let submesh_verts = &mesh.vertices[submesh.base_vertex_index as usize..]
+                [..submesh.vertex_count as usize];
+            let submesh_tris = &mesh.triangles[submesh.base_triangle_index as usize..]
+                [..submesh.triangle_count as usize];
+            for tri in submesh_tris {
+                let mut verts = tri
+                    .iter()
+                    .map(|i| submesh_verts[*i as usize])
+                    .collect::<Vec<_>>();
+                // Connect back to first vertex to finish the polygon
+                verts.push(verts[0]);
+
+                gizmo.linestrip(verts, edge_color);
+            }
         }
 
-        let Some(navmesh) = navmeshes.get(navmesh_handle.0) else {
-            continue;
-        };
-        gizmo.clear();
+        let mut visual_mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::all());
+        let mut visual_verts = Vec::new();
+        let mut visual_indices = Vec::new();
+
+        for submesh in &mesh.meshes {
+            let submesh_verts = &mesh.vertices[submesh.base_vertex_index as usize..]
+                [..submesh.vertex_count as usize];
+
+            let submesh_tris = &mesh.triangles[submesh.base_triangle_index as usize..]
+                [..submesh.triangle_count as usize];
+            for tri in submesh_tris.iter() {
+                for &i in tri {
+                    visual_indices.push(i as u32 + visual_verts.len() as u32);
+                }
+            }
+            visual_verts.extend(submesh_verts.iter().copied());
+        }
+        visual_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, visual_verts);
+        visual_mesh.insert_indices(Indices::U32(visual_indices));
+        visual_mesh.compute_normals();
+
+        Some((visual_mesh, context.handles.detail_material.clone()))
+    }
+}
+
+/// Component that draws a [`PolygonNavmesh`].
+#[derive(Debug, Clone, Component, Reflect)]
+#[reflect(Component)]
+#[require(DirtyNavmeshGizmo, Visibility)]
+#[cfg_attr(feature = "bevy_mesh", require(crate::mesh::NavmeshExcluded))]
+#[component(on_add = init_gizmo::<PolygonNavmeshGizmo>)]
+pub struct PolygonNavmeshGizmo(pub AssetId<Navmesh>);
+
+impl PolygonNavmeshGizmo {
+    /// Creates a new [`PolygonNavmeshGizmo`] visualizing the given navmesh once its done generating.
+    pub fn new(navmesh: impl Into<AssetId<Navmesh>>) -> Self {
+        Self(navmesh.into())
+    }
+}
+
+impl NavmeshGizmo for PolygonNavmeshGizmo {
+    fn navmesh(&self) -> AssetId<Navmesh> {
+        self.0
+    }
+
+    fn config(config: &NavmeshGizmoConfig) -> &GizmoConfig {
+        &config.polygon_navmesh
+    }
+
+    fn config_changed(old: &NavmeshGizmoConfig, new: &NavmeshGizmoConfig) -> bool {
+        !cfg_eq(&old.polygon_navmesh, &new.polygon_navmesh)
+            || old.polygon_edge_color != new.polygon_edge_color
+            || old.vertex_point_size != new.vertex_point_size
+    }
+
+    fn material(handles: &GizmoHandles) -> Option<Handle<StandardMaterial>> {
+        Some(handles.polygon_material.clone())
+    }
+
+    fn draw(
+        &self,
+        navmesh: &Navmesh,
+        gizmo: &mut GizmoAsset,
+        context: &GizmoDrawContext,
+    ) -> Option<(Mesh, Handle<StandardMaterial>)> {
+        let edge_color = context.config.polygon_edge_color;
+        let vertex_point_size = context.config.vertex_point_size;
+        let color_scheme = context.color_scheme;
 
         let mesh = &navmesh.polygon;
         let nvp = mesh.max_vertices_per_polygon as usize;
         let origin = mesh.aabb.min;
-        let to_local = vec3(mesh.cell_size, mesh.cell_height, mesh.cell_size);
+        let to_local =
+            CoordinateRemap::from(&navmesh.settings).cell_scale(mesh.cell_size, mesh.cell_height);
         for i in 0..mesh.polygon_count() {
             let poly = &mesh.polygons[i * nvp..];
             let mut verts = poly[..nvp]
@@ -220,18 +403,34 @@ fn update_dirty_polygon_gizmos(
             // Connect back to first vertex to finish the polygon
             verts.push(verts[0]);
 
-            gizmo.linestrip(verts, tailwind::SKY_700);
+            let color = color_scheme.polygon_color(mesh, i).unwrap_or(edge_color);
+            gizmo.linestrip(verts, color);
+        }
+        if vertex_point_size > 0.0 {
+            for vert in &mesh.vertices {
+                let vert = origin + vert.as_vec3() * to_local;
+                gizmo.sphere(
+                    Isometry3d::from_translation(vert),
+                    vertex_point_size / 2.0,
+                    edge_color,
+                );
+            }
         }
 
         let mut visual_mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::all());
         let mut visual_verts = Vec::new();
+        let mut visual_colors = Vec::new();
         let mut visual_indices = Vec::new();
 
         for i in 0..mesh.polygon_count() {
             let poly = &mesh.polygons[i * nvp..];
+            let color = color_scheme
+                .polygon_color(mesh, i)
+                .map(|color| LinearRgba::from(color).to_f32_array());
             let a = origin + mesh.vertices[poly[0] as usize].as_vec3() * to_local;
             let a_idx = visual_verts.len() as u32;
             visual_verts.push(a);
+            visual_colors.extend(color);
 
             // Fan triangulation
             for val in poly[1..nvp].windows(2) {
@@ -247,6 +446,8 @@ fn update_dirty_polygon_gizmos(
                 visual_verts.push(b);
                 let c_vi = visual_verts.len() as u32;
                 visual_verts.push(c);
+                visual_colors.extend(color);
+                visual_colors.extend(color);
 
                 visual_indices.push(a_idx);
                 visual_indices.push(b_vi);
@@ -254,148 +455,434 @@ fn update_dirty_polygon_gizmos(
             }
         }
         visual_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, visual_verts);
+        // Polygons with a color scheme are tinted by their vertex colors, the others by the material.
+        let material = if visual_colors.is_empty() {
+            context.handles.polygon_material.clone()
+        } else {
+            visual_mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, visual_colors);
+            context.handles.colored_polygon_material.clone()
+        };
         visual_mesh.insert_indices(Indices::U32(visual_indices));
         visual_mesh.compute_normals();
 
-        commands
-            .entity(entity)
-            .insert((Mesh3d(meshes.add(visual_mesh)),));
-
-        gizmo_handle.line_config = config.line;
-        gizmo_handle.depth_bias = config.depth_bias;
-        *layers = config.render_layers;
-        *visibility = Visibility::Inherited;
-        commands.entity(entity).remove::<DirtyNavmeshGizmo>();
+        Some((visual_mesh, material))
     }
 }
 
-fn update_dirty_detail_gizmos(
-    mut commands: Commands,
-    mut gizmos: Query<
-        (
-            Entity,
-            &mut Gizmo,
-            &mut RenderLayers,
-            &DetailNavmeshGizmo,
-            &mut Visibility,
-        ),
-        With<DirtyNavmeshGizmo>,
-    >,
-    mut gizmo_assets: ResMut<Assets<GizmoAsset>>,
-    navmeshes: Res<Assets<Navmesh>>,
-    config: Res<NavmeshGizmoConfig>,
-    mut meshes: ResMut<Assets<Mesh>>,
-) {
-    for (entity, mut gizmo_handle, mut layers, navmesh_handle, mut visibility) in gizmos.iter_mut()
-    {
-        let Some(gizmo) = gizmo_assets.get_mut(&gizmo_handle.handle) else {
-            continue;
-        };
+/// Component that draws the contours a [`PolygonNavmesh`] was built from.
+/// The raw contours are drawn in amber, the simplified contours in fuchsia.
+/// Comparing the two helps to find out whether the contour simplification is responsible for a misshapen navmesh.
+///
+/// Only navmeshes created by the [`NavmeshGenerator`](crate::generator::NavmeshGenerator) store their contours,
+/// so nothing is drawn for navmeshes loaded from disk.
+#[derive(Debug, Clone, Component, Reflect)]
+#[reflect(Component)]
+#[require(DirtyNavmeshGizmo, Visibility)]
+#[cfg_attr(feature = "bevy_mesh", require(crate::mesh::NavmeshExcluded))]
+#[component(on_add = init_gizmo::<ContourGizmo>)]
+pub struct ContourGizmo(pub AssetId<Navmesh>);
 
-        let config = config.detail_navmesh.clone();
-        if !config.enabled {
-            gizmo.clear();
-            commands.entity(entity).remove::<DirtyNavmeshGizmo>();
-            *visibility = Visibility::Hidden;
-            continue;
-        }
-        let Some(navmesh) = navmeshes.get(navmesh_handle.0) else {
-            continue;
-        };
-        gizmo.clear();
+impl ContourGizmo {
+    /// Creates a new [`ContourGizmo`] visualizing the contours of the given navmesh once its done generating.
+    pub fn new(navmesh: impl Into<AssetId<Navmesh>>) -> Self {
+        Self(navmesh.into())
+    }
+}
 
-        let mesh = &navmesh.detail;
+impl NavmeshGizmo for ContourGizmo {
+    fn navmesh(&self) -> AssetId<Navmesh> {
+        self.0
+    }
 
-        for submesh in &mesh.meshes {
-            let submesh_verts = &mesh.vertices[submesh.base_vertex_index as usize..]
-                [..submesh.vertex_count as usize];
-            let submesh_tris = &mesh.triangles[submesh.base_triangle_index as usize..]
-                [..submesh.triangle_count as usize];
-            for tri in submesh_tris {
-                let mut verts = tri
-                    .iter()
-                    .map(|i| submesh_verts[*i as usize])
-                    .collect::<Vec<_>>();
-                // Connect back to first vertex to finish the polygon
-                verts.push(verts[0]);
+    fn config(config: &NavmeshGizmoConfig) -> &GizmoConfig {
+        &config.contours
+    }
 
-                gizmo.linestrip(verts, tailwind::GREEN_700);
+    fn draw(
+        &self,
+        navmesh: &Navmesh,
+        gizmo: &mut GizmoAsset,
+        _context: &GizmoDrawContext,
+    ) -> Option<(Mesh, Handle<StandardMaterial>)> {
+        // Navmeshes loaded from disk do not contain contours, so there's nothing to draw.
+        let contours = navmesh.contours.as_ref()?;
+        let origin = contours.aabb.min;
+        let to_local = CoordinateRemap::from(&navmesh.settings)
+            .cell_scale(contours.cell_size, contours.cell_height);
+        for contour in &contours.contours {
+            let mut raw_verts = contour
+                .raw_vertices
+                .iter()
+                .map(|(vert, _)| origin + vert.as_vec3() * to_local)
+                .collect::<Vec<_>>();
+            let mut simplified_verts = contour
+                .vertices
+                .iter()
+                .map(|(vert, _)| origin + vert.as_vec3() * to_local)
+                .collect::<Vec<_>>();
+            if raw_verts.is_empty() || simplified_verts.is_empty() {
+                continue;
             }
+            // Connect back to first vertex to finish the contour
+            raw_verts.push(raw_verts[0]);
+            simplified_verts.push(simplified_verts[0]);
+
+            gizmo.linestrip(raw_verts, tailwind::AMBER_500);
+            gizmo.linestrip(simplified_verts, tailwind::FUCHSIA_600);
         }
+        None
+    }
+}
 
-        let mut visual_mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::all());
-        let mut visual_verts = Vec::new();
-        let mut visual_indices = Vec::new();
+/// Component that draws the walkable spans of the heightfield a [`Navmesh`] was generated from as small cyan boxes.
+/// This helps to find out whether rasterization or filtering is responsible for a hole in the navmesh.
+///
+/// The heightfield is only stored when generating the navmesh with [`NavmeshSettings::keep_heightfield`](crate::NavmeshSettings::keep_heightfield),
+/// so nothing is drawn for other navmeshes.
+#[derive(Debug, Clone, Component, Reflect)]
+#[reflect(Component)]
+#[require(DirtyNavmeshGizmo, Visibility)]
+#[cfg_attr(feature = "bevy_mesh", require(crate::mesh::NavmeshExcluded))]
+#[component(on_add = init_gizmo::<HeightfieldGizmo>)]
+pub struct HeightfieldGizmo(pub AssetId<Navmesh>);
 
-        for submesh in &mesh.meshes {
-            let submesh_verts = &mesh.vertices[submesh.base_vertex_index as usize..]
-                [..submesh.vertex_count as usize];
+impl HeightfieldGizmo {
+    /// Creates a new [`HeightfieldGizmo`] visualizing the heightfield of the given navmesh once its done generating.
+    pub fn new(navmesh: impl Into<AssetId<Navmesh>>) -> Self {
+        Self(navmesh.into())
+    }
+}
 
-            let submesh_tris = &mesh.triangles[submesh.base_triangle_index as usize..]
-                [..submesh.triangle_count as usize];
-            for tri in submesh_tris.iter() {
-                for &i in tri {
-                    visual_indices.push(i as u32 + visual_verts.len() as u32);
-                }
-            }
-            visual_verts.extend(submesh_verts.iter().copied());
+impl NavmeshGizmo for HeightfieldGizmo {
+    fn navmesh(&self) -> AssetId<Navmesh> {
+        self.0
+    }
+
+    fn config(config: &NavmeshGizmoConfig) -> &GizmoConfig {
+        &config.heightfield
+    }
+
+    fn draw(
+        &self,
+        navmesh: &Navmesh,
+        gizmo: &mut GizmoAsset,
+        _context: &GizmoDrawContext,
+    ) -> Option<(Mesh, Handle<StandardMaterial>)> {
+        // Only navmeshes generated with `NavmeshSettings::keep_heightfield` contain spans.
+        let heightfield = navmesh.heightfield.as_ref()?;
+        for &center in &heightfield.centers {
+            let transform = Transform::from_translation(center).with_scale(heightfield.span_size);
+            gizmo.cuboid(transform, tailwind::CYAN_400);
         }
-        visual_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, visual_verts);
-        visual_mesh.insert_indices(Indices::U32(visual_indices));
-        visual_mesh.compute_normals();
+        None
+    }
+}
 
-        commands
-            .entity(entity)
-            .insert((Mesh3d(meshes.add(visual_mesh)),));
+/// A compact summary of the walkable spans of a [`CompactHeightfield`], as stored in [`Navmesh::heightfield`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HeightfieldSpans {
+    /// The size of a single span in world units.
+    pub span_size: Vec3,
+    /// The center of each walkable span in world space.
+    pub centers: Vec<Vec3>,
+}
 
-        gizmo_handle.line_config = config.line;
-        gizmo_handle.depth_bias = config.depth_bias;
-        *layers = config.render_layers;
-        *visibility = Visibility::Inherited;
-        commands.entity(entity).remove::<DirtyNavmeshGizmo>();
+impl HeightfieldSpans {
+    /// Collects the walkable spans of a heightfield.
+    /// `remap` converts from the coordinate system the heightfield was built in to world space.
+    pub fn from_heightfield(heightfield: &CompactHeightfield, remap: CoordinateRemap) -> Self {
+        let half_height = remap.from_recast(vec3(0.0, heightfield.cell_height / 2.0, 0.0));
+        let centers = walkable_span_floors(heightfield, remap)
+            .map(|(_index, floor)| floor + half_height)
+            .collect();
+        let span_size = vec3(
+            heightfield.cell_size,
+            heightfield.cell_height,
+            heightfield.cell_size,
+        );
+        Self {
+            span_size: remap.from_recast(span_size).abs(),
+            centers,
+        }
     }
 }
 
-/// Component that draws a [`PolygonNavmesh`].
+/// Component that draws the distance field of the heightfield a [`Navmesh`] was generated from.
+/// Each walkable cell is outlined with a color ranging from red at the border of the walkable area
+/// to green at the cells farthest away from it.
+/// This helps to tune the erosion by [`NavmeshSettings::agent_radius`](crate::NavmeshSettings::agent_radius)
+/// and to debug the watershed partitioning.
+///
+/// The distance field is only stored when generating the navmesh with
+/// [`NavmeshSettings::keep_distance_field`](crate::NavmeshSettings::keep_distance_field),
+/// so nothing is drawn for other navmeshes.
 #[derive(Debug, Clone, Component, Reflect)]
 #[reflect(Component)]
 #[require(DirtyNavmeshGizmo, Visibility)]
-#[cfg_attr(feature = "bevy_mesh", require(crate::mesh::ExcludeMeshFromNavmesh))]
-#[component(on_add = init_polygon_navmesh_gizmo)]
-pub struct PolygonNavmeshGizmo(pub AssetId<Navmesh>);
+#[cfg_attr(feature = "bevy_mesh", require(crate::mesh::NavmeshExcluded))]
+#[component(on_add = init_gizmo::<DistanceFieldGizmo>)]
+pub struct DistanceFieldGizmo(pub AssetId<Navmesh>);
 
-impl PolygonNavmeshGizmo {
-    /// Creates a new [`PolygonNavmeshGizmo`] visualizing the given navmesh once its done generating.
+impl DistanceFieldGizmo {
+    /// Creates a new [`DistanceFieldGizmo`] visualizing the distance field of the given navmesh once its done generating.
     pub fn new(navmesh: impl Into<AssetId<Navmesh>>) -> Self {
         Self(navmesh.into())
     }
 }
 
-fn init_polygon_navmesh_gizmo(mut world: DeferredWorld, ctx: HookContext) {
-    let gizmo_handle = world
-        .resource_mut::<Assets<GizmoAsset>>()
-        .add(GizmoAsset::new());
-    let material_handle = world.resource::<GizmoHandles>().polygon_material.clone();
-    let config = world
-        .resource::<NavmeshGizmoConfig>()
-        .polygon_navmesh
-        .clone();
-    world.commands().entity(ctx.entity).insert((
-        Gizmo {
-            handle: gizmo_handle,
-            line_config: config.line,
-            depth_bias: config.depth_bias,
-        },
-        config.render_layers,
-        MeshMaterial3d(material_handle),
-        NotShadowCaster,
-        NotShadowReceiver,
-    ));
+impl NavmeshGizmo for DistanceFieldGizmo {
+    fn navmesh(&self) -> AssetId<Navmesh> {
+        self.0
+    }
+
+    fn config(config: &NavmeshGizmoConfig) -> &GizmoConfig {
+        &config.distance_field
+    }
+
+    fn draw(
+        &self,
+        navmesh: &Navmesh,
+        gizmo: &mut GizmoAsset,
+        _context: &GizmoDrawContext,
+    ) -> Option<(Mesh, Handle<StandardMaterial>)> {
+        // Only navmeshes generated with `NavmeshSettings::keep_distance_field` contain a distance field.
+        let distance_field = navmesh.distance_field.as_ref()?;
+        let (a, b) = match navmesh.settings.up {
+            Vec3::Z => (Vec3::X, Vec3::Y),
+            Vec3::X => (Vec3::Y, Vec3::Z),
+            _ => (Vec3::X, Vec3::Z),
+        };
+        // Slightly shrink the cells so that neighboring outlines don't overlap.
+        let half_size = distance_field.cell_size * 0.45;
+        let (a, b) = (a * half_size, b * half_size);
+        let max_distance = distance_field.max_distance.max(1) as f32;
+        for &(floor, distance) in &distance_field.spans {
+            let t = distance as f32 / max_distance;
+            let color = Color::hsl(120.0 * t, 0.8, 0.5);
+            gizmo.linestrip(
+                [
+                    floor - a - b,
+                    floor + a - b,
+                    floor + a + b,
+                    floor - a + b,
+                    floor - a - b,
+                ],
+                color,
+            );
+        }
+        None
+    }
+}
+
+/// Component that draws the obstacles a [`Navmesh`] was rasterized from as a wireframe.
+/// Triangles that were walkable are drawn in lime, all others in red.
+/// Comparing this with the navmesh tells whether a problem lies in the obstacles collected by the backend
+/// or in the generation itself.
+///
+/// The obstacles are only stored when generating the navmesh with
+/// [`NavmeshSettings::keep_input`](crate::NavmeshSettings::keep_input),
+/// so nothing is drawn for other navmeshes.
+#[derive(Debug, Clone, Component, Reflect)]
+#[reflect(Component)]
+#[require(DirtyNavmeshGizmo, Visibility)]
+#[cfg_attr(feature = "bevy_mesh", require(crate::mesh::NavmeshExcluded))]
+#[component(on_add = init_gizmo::<InputGizmo>)]
+pub struct InputGizmo(pub AssetId<Navmesh>);
+
+impl InputGizmo {
+    /// Creates a new [`InputGizmo`] visualizing the obstacles of the given navmesh once its done generating.
+    pub fn new(navmesh: impl Into<AssetId<Navmesh>>) -> Self {
+        Self(navmesh.into())
+    }
+}
+
+impl NavmeshGizmo for InputGizmo {
+    fn navmesh(&self) -> AssetId<Navmesh> {
+        self.0
+    }
+
+    fn config(config: &NavmeshGizmoConfig) -> &GizmoConfig {
+        &config.input
+    }
+
+    fn draw(
+        &self,
+        navmesh: &Navmesh,
+        gizmo: &mut GizmoAsset,
+        _context: &GizmoDrawContext,
+    ) -> Option<(Mesh, Handle<StandardMaterial>)> {
+        // Only navmeshes generated with `NavmeshSettings::keep_input` contain their input.
+        let input = navmesh.input.as_ref()?;
+        for (indices, area) in input.indices.iter().zip(&input.area_types) {
+            let [a, b, c] = indices
+                .to_array()
+                .map(|i| Vec3::from(input.vertices[i as usize]));
+            let color = if area.is_walkable() {
+                tailwind::LIME_500
+            } else {
+                tailwind::RED_500
+            };
+            gizmo.linestrip([a, b, c, a], color);
+        }
+        None
+    }
+}
+
+/// The distance field of a [`CompactHeightfield`], as stored in [`Navmesh::distance_field`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DistanceField {
+    /// The width and depth of a single cell in world units.
+    pub cell_size: f32,
+    /// The center of the floor of each walkable span in world space,
+    /// together with its distance to the nearest border of the walkable area in cell units.
+    pub spans: Vec<(Vec3, u16)>,
+    /// The largest distance in [`Self::spans`].
+    pub max_distance: u16,
+}
+
+impl DistanceField {
+    /// Collects the distance field of a heightfield.
+    /// [`CompactHeightfield::build_distance_field`] must have been called before.
+    /// `remap` converts from the coordinate system the heightfield was built in to world space.
+    pub fn from_heightfield(heightfield: &CompactHeightfield, remap: CoordinateRemap) -> Self {
+        let spans = walkable_span_floors(heightfield, remap)
+            .map(|(index, floor)| (floor, heightfield.dist.get(index).copied().unwrap_or(0)))
+            .collect();
+        Self {
+            cell_size: heightfield.cell_size,
+            spans,
+            max_distance: heightfield.max_distance,
+        }
+    }
+}
+
+/// Iterates over the walkable spans of a heightfield,
+/// yielding the index of each span and the center of its floor in world space.
+fn walkable_span_floors(
+    heightfield: &CompactHeightfield,
+    remap: CoordinateRemap,
+) -> impl Iterator<Item = (usize, Vec3)> + '_ {
+    let origin = heightfield.aabb.min;
+    (0..heightfield.height)
+        .flat_map(move |z| (0..heightfield.width).map(move |x| (x, z)))
+        .flat_map(move |(x, z)| {
+            heightfield
+                .cell_at(x, z)
+                .index_range()
+                .map(move |i| (x, z, i))
+        })
+        .filter(|(_x, _z, i)| heightfield.areas[*i].is_walkable())
+        .map(move |(x, z, i)| {
+            let local = vec3(
+                (x as f32 + 0.5) * heightfield.cell_size,
+                heightfield.spans[i].y as f32 * heightfield.cell_height,
+                (z as f32 + 0.5) * heightfield.cell_size,
+            );
+            (i, remap.from_recast(origin + local))
+        })
+}
+
+/// Component that draws a path through a navmesh, as computed by a pathfinding library.
+/// The polygons of the [`corridor`](Self::corridor) are outlined in yellow, the portals between them in orange,
+/// and the [`waypoints`](Self::waypoints) are connected by a lime line.
+///
+/// Changing the component redraws the path.
+#[derive(Debug, Clone, Component, Reflect)]
+#[reflect(Component)]
+#[require(DirtyNavmeshGizmo, Visibility)]
+#[cfg_attr(feature = "bevy_mesh", require(crate::mesh::NavmeshExcluded))]
+#[component(on_add = init_gizmo::<PathGizmo>)]
+pub struct PathGizmo {
+    /// The navmesh the path was computed on.
+    pub navmesh: AssetId<Navmesh>,
+    /// The indices of the polygons in [`Navmesh::polygon`] that the path passes through, in order.
+    pub corridor: Vec<u32>,
+    /// The points of the final, smoothed path in world space.
+    pub waypoints: Vec<Vec3>,
+}
+
+impl PathGizmo {
+    /// Creates a new [`PathGizmo`] visualizing the given path once the navmesh is available.
+    pub fn new(
+        navmesh: impl Into<AssetId<Navmesh>>,
+        corridor: impl Into<Vec<u32>>,
+        waypoints: impl Into<Vec<Vec3>>,
+    ) -> Self {
+        Self {
+            navmesh: navmesh.into(),
+            corridor: corridor.into(),
+            waypoints: waypoints.into(),
+        }
+    }
+}
+
+impl NavmeshGizmo for PathGizmo {
+    fn navmesh(&self) -> AssetId<Navmesh> {
+        self.navmesh
+    }
+
+    fn config(config: &NavmeshGizmoConfig) -> &GizmoConfig {
+        &config.path
+    }
+
+    fn draw(
+        &self,
+        navmesh: &Navmesh,
+        gizmo: &mut GizmoAsset,
+        _context: &GizmoDrawContext,
+    ) -> Option<(Mesh, Handle<StandardMaterial>)> {
+        let mesh = &navmesh.polygon;
+        let nvp = mesh.max_vertices_per_polygon as usize;
+        let origin = mesh.aabb.min;
+        let remap = CoordinateRemap::from(&navmesh.settings);
+        let to_local = remap.cell_scale(mesh.cell_size, mesh.cell_height);
+        for &poly in &self.corridor {
+            let Some(poly) = mesh.polygons.get(poly as usize * nvp..) else {
+                continue;
+            };
+            let mut verts = poly[..nvp]
+                .iter()
+                .filter(|i| **i != PolygonNavmesh::NO_INDEX)
+                .map(|i| origin + mesh.vertices[*i as usize].as_vec3() * to_local)
+                .collect::<Vec<_>>();
+            // Connect back to first vertex to finish the polygon
+            verts.push(verts[0]);
+
+            gizmo.linestrip(verts, tailwind::YELLOW_400);
+        }
+
+        let polygon_count = mesh.polygon_count();
+        if self
+            .corridor
+            .iter()
+            .all(|&poly| (poly as usize) < polygon_count)
+        {
+            // The portals are computed in Recast's coordinate system, like the path itself.
+            let portals = if remap.is_identity() {
+                mesh.portals(&self.corridor)
+            } else {
+                let mut mesh = mesh.clone();
+                remap.polygon_to_recast(&mut mesh);
+                mesh.portals(&self.corridor)
+            };
+            for (left, right) in portals {
+                gizmo.line(
+                    remap.from_recast(left),
+                    remap.from_recast(right),
+                    tailwind::ORANGE_600,
+                );
+            }
+        }
+
+        gizmo.linestrip(self.waypoints.iter().copied(), tailwind::LIME_500);
+        None
+    }
 }
 
 #[derive(Resource)]
 struct GizmoHandles {
     polygon_material: Handle<StandardMaterial>,
+    colored_polygon_material: Handle<StandardMaterial>,
     detail_material: Handle<StandardMaterial>,
 }
 
@@ -412,6 +899,16 @@ impl FromWorld for GizmoHandles {
                     ..Default::default()
                 },
             ),
+            colored_polygon_material: world.resource_mut::<Assets<StandardMaterial>>().add(
+                StandardMaterial {
+                    base_color: Color::WHITE.with_alpha(0.3),
+                    unlit: true,
+                    double_sided: true,
+                    alpha_mode: AlphaMode::Blend,
+                    depth_bias: -0.003,
+                    ..Default::default()
+                },
+            ),
             detail_material: world.resource_mut::<Assets<StandardMaterial>>().add(
                 StandardMaterial {
                     base_color: tailwind::EMERALD_200.with_alpha(0.2).into(),
@@ -426,14 +923,64 @@ impl FromWorld for GizmoHandles {
     }
 }
 
+/// Determines how the polygons of [`PolygonNavmeshGizmo`]s are colored.
+/// Useful to check which regions were generated and which polygons were marked by
+/// [`NavmeshSettings::area_volumes`](crate::NavmeshSettings::area_volumes).
+///
+/// The colors are pseudo-random, but stable: the same ID is always drawn in the same color.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+#[reflect(Resource)]
+pub enum GizmoColorScheme {
+    /// Draw all polygons in the same color.
+    #[default]
+    Uniform,
+    /// Color each polygon by its [`PolygonNavmesh::regions`].
+    ByRegion,
+    /// Color each polygon by its [`PolygonNavmesh::areas`].
+    ByArea,
+}
+
+impl GizmoColorScheme {
+    /// Returns the color of the given polygon, or `None` for [`GizmoColorScheme::Uniform`].
+    fn polygon_color(self, mesh: &PolygonNavmesh, polygon: usize) -> Option<Color> {
+        let id = match self {
+            GizmoColorScheme::Uniform => return None,
+            GizmoColorScheme::ByRegion => mesh.regions[polygon].bits(),
+            GizmoColorScheme::ByArea => mesh.areas[polygon].0 as u16,
+        };
+        // Step around the color wheel by the golden angle so that consecutive IDs get distinct hues.
+        let hue = (id as f32 * 137.507_77) % 360.0;
+        Some(Color::hsl(hue, 0.75, 0.5))
+    }
+}
+
 /// Global configuration for all navmesh gizmos.
+/// Line widths are configured through [`GizmoLineConfig::width`] of the respective [`GizmoConfig`].
 #[derive(Resource, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct NavmeshGizmoConfig {
     /// Configuration for all [`PolygonNavmeshGizmo`]s.
     pub polygon_navmesh: GizmoConfig,
+    /// The color of the polygon edges drawn by [`PolygonNavmeshGizmo`]s.
+    /// Only used when the [`GizmoColorScheme`] is [`GizmoColorScheme::Uniform`].
+    pub polygon_edge_color: Color,
+    /// The diameter of the spheres drawn at the vertices of [`PolygonNavmeshGizmo`]s in world units.
+    /// Vertices are not drawn when this is `0.0`, which is the default.
+    pub vertex_point_size: f32,
     /// Configuration for all [`DetailNavmeshGizmo`]s.
     pub detail_navmesh: GizmoConfig,
+    /// The color of the triangle edges drawn by [`DetailNavmeshGizmo`]s.
+    pub detail_edge_color: Color,
+    /// Configuration for all [`ContourGizmo`]s.
+    pub contours: GizmoConfig,
+    /// Configuration for all [`PathGizmo`]s.
+    pub path: GizmoConfig,
+    /// Configuration for all [`HeightfieldGizmo`]s.
+    pub heightfield: GizmoConfig,
+    /// Configuration for all [`DistanceFieldGizmo`]s.
+    pub distance_field: GizmoConfig,
+    /// Configuration for all [`InputGizmo`]s.
+    pub input: GizmoConfig,
 }
 
 impl Default for NavmeshGizmoConfig {
@@ -449,6 +996,8 @@ impl Default for NavmeshGizmoConfig {
                 depth_bias: -0.001,
                 ..Default::default()
             },
+            polygon_edge_color: tailwind::SKY_700.into(),
+            vertex_point_size: 0.0,
             detail_navmesh: GizmoConfig {
                 enabled: true,
                 line: GizmoLineConfig {
@@ -459,6 +1008,55 @@ impl Default for NavmeshGizmoConfig {
                 depth_bias: -0.002,
                 ..Default::default()
             },
+            detail_edge_color: tailwind::GREEN_700.into(),
+            contours: GizmoConfig {
+                enabled: true,
+                line: GizmoLineConfig {
+                    perspective: true,
+                    width: 4.0,
+                    ..Default::default()
+                },
+                depth_bias: -0.003,
+                ..Default::default()
+            },
+            path: GizmoConfig {
+                enabled: true,
+                line: GizmoLineConfig {
+                    perspective: true,
+                    width: 8.0,
+                    ..Default::default()
+                },
+                depth_bias: -0.004,
+                ..Default::default()
+            },
+            heightfield: GizmoConfig {
+                enabled: true,
+                line: GizmoLineConfig {
+                    perspective: true,
+                    width: 2.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            distance_field: GizmoConfig {
+                enabled: true,
+                line: GizmoLineConfig {
+                    perspective: true,
+                    width: 2.0,
+                    ..Default::default()
+                },
+                depth_bias: -0.0005,
+                ..Default::default()
+            },
+            input: GizmoConfig {
+                enabled: true,
+                line: GizmoLineConfig {
+                    perspective: true,
+                    width: 1.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
         }
     }
 }
@@ -0,0 +1,69 @@
+use bevy_reflect::prelude::*;
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+
+/// A non-fatal problem detected while generating a [`Navmesh`](crate::Navmesh).
+///
+/// Diagnostics don't prevent a navmesh from being built, but usually indicate that
+/// the [`NavmeshSettings`](crate::NavmeshSettings) don't fit the level geometry well.
+/// They are collected in [`Navmesh::diagnostics`](crate::Navmesh::diagnostics) so that tooling can surface them.
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum NavmeshDiagnostic {
+    /// The cells are too coarse on the up-axis to represent the agent's height.
+    /// Recast needs the agent to be at least 3 cells tall to reliably detect overhangs.
+    ///
+    /// Increase [`NavmeshSettings::cell_height_fraction`](crate::NavmeshSettings::cell_height_fraction) to fix this.
+    TooCoarseCells {
+        /// The agent height in cells. `[Units: vx]`
+        walkable_height: u16,
+        /// The minimum agent height in cells that is considered fine enough. `[Units: vx]`
+        min_walkable_height: u16,
+    },
+    /// Eroding the walkable area by the agent radius removed all walkable area.
+    ///
+    /// Decrease [`NavmeshSettings::agent_radius`](crate::NavmeshSettings::agent_radius) if the level is meant to be walkable.
+    OverErosion {
+        /// The number of walkable spans before erosion.
+        walkable_spans_before: usize,
+    },
+    /// The input contains ledges or platforms that are thinner than a single cell on the up-axis.
+    /// Such features are easily lost during rasterization, which is a common cause of thin platforms having no navmesh.
+    ///
+    /// The thickness is estimated by sampling the input geometry, so not every thin feature is guaranteed to be reported.
+    /// Increase [`NavmeshSettings::cell_height_fraction`](crate::NavmeshSettings::cell_height_fraction) to fix this.
+    ThinFeatures {
+        /// The thickness of the thinnest sampled feature. `[Units: wu]`
+        thickness: f32,
+        /// The cell height that was used for generation. `[Units: wu]`
+        cell_height: f32,
+    },
+    /// Some input triangles have no area and were ignored.
+    /// This usually points to broken meshes or colliders.
+    DegenerateTriangles {
+        /// The number of degenerate triangles.
+        count: usize,
+    },
+    /// The build was projected to exceed the [`NavmeshSettings::time_budget`](crate::NavmeshSettings::time_budget),
+    /// so the cells and the detail samples were coarsened. [`Navmesh::settings`](crate::Navmesh::settings) contains the adjusted settings.
+    CoarsenedForTimeBudget {
+        /// The estimated build time with the original settings.
+        estimate: Duration,
+        /// The time budget.
+        budget: Duration,
+        /// How many times larger the cells were made.
+        /// The [`NavmeshSettings::detail_sample_dist`](crate::NavmeshSettings::detail_sample_dist) was scaled by the same factor.
+        factor: f32,
+        /// Whether the detail mesh was skipped, because even the coarsest cells did not fit into the budget.
+        skipped_detail: bool,
+    },
+    /// Islands smaller than [`NavmeshSettings::prune_unreachable_below`](crate::NavmeshSettings::prune_unreachable_below)
+    /// were removed from the navmesh.
+    PrunedIslands {
+        /// The number of removed islands.
+        islands: usize,
+        /// The total number of polygons in the removed islands.
+        polygons: usize,
+    },
+}
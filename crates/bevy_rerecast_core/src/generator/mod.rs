@@ -1,25 +1,51 @@
 //! Utilities for generating navmeshes at runtime.
 
-use alloc::vec::Vec;
-use anyhow::{Context as _, anyhow};
+use alloc::{string::String, vec::Vec};
 use bevy_app::prelude::*;
-use bevy_asset::prelude::*;
+use bevy_asset::{InvalidGenerationError, UntypedAssetId, prelude::*};
 use bevy_derive::{Deref, DerefMut};
-use bevy_ecs::{prelude::*, system::SystemParam};
-use bevy_platform::collections::HashMap;
-use bevy_tasks::{AsyncComputeTaskPool, Task, futures_lite::future};
+use bevy_ecs::{entity::EntityHashMap, prelude::*, system::SystemParam};
+use bevy_math::{bounding::BoundingVolume as _, ops};
+use bevy_platform::collections::{HashMap, HashSet};
+use bevy_tasks::{
+    AsyncComputeTaskPool, ComputeTaskPool, ParallelSliceMut as _, Task, TaskPool,
+    futures_lite::future,
+};
 use bevy_transform::TransformSystems;
-use glam::{U16Vec3, Vec3, Vec3A};
-use rerecast::{Aabb3d, DetailNavmesh, HeightfieldBuilder, TriMesh};
+use glam::{IVec2, Vec3, Vec3A};
+use rerecast::{
+    Aabb3d, BuildRegionsError, CompactHeightfield, CompactHeightfieldError, Config, DetailNavmesh,
+    DetailNavmeshError, Heightfield, HeightfieldBuilder, HeightfieldBuilderError, PolygonNavmesh,
+    PolygonNavmeshError, RasterizationError, TriMesh,
+};
+use thiserror::Error;
 
+mod stats;
+use stats::Stopwatch;
+pub use stats::{LastBuildStats, NavmeshBuildStats};
+mod tiles;
+use tiles::TileRebuild;
+pub use tiles::{NavmeshTiles, TileRegenerationError};
 mod upgradable_asset_id;
 use upgradable_asset_id::UpgradableAssetId;
 
-use crate::{Navmesh, NavmeshBackend, NavmeshSettings};
+use crate::{
+    CoordinateRemap, Navmesh, NavmeshAffectorBounds, NavmeshBackends, NavmeshDiagnostic,
+    NavmeshMetadata, NavmeshSettings, NavmeshSettingsError, PartitionMethod,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<NavmeshQueue>();
+    app.init_resource::<NavmeshBatchQueue>();
+    app.init_resource::<NavmeshTileQueue>();
+    app.init_resource::<NavmeshDirtyQueue>();
+    app.init_resource::<NavmeshDeferredQueue>();
+    app.init_resource::<NavmeshMissingAssets>();
+    app.init_resource::<NavmeshAffectorSnapshots>();
+    app.init_resource::<NavmeshRegistry>();
     app.init_resource::<NavmeshTaskQueue>();
+    app.init_resource::<LastBuildStats>();
+    app.init_resource::<SynchronousBuilds>();
     app.add_systems(
         PostUpdate,
         (drain_queue_into_tasks, poll_tasks)
@@ -29,6 +55,11 @@ pub(super) fn plugin(app: &mut App) {
 }
 
 /// System parameter for generating navmeshes.
+///
+/// Generation is deterministic: the same obstacles and [`NavmeshSettings`] always produce the same [`Navmesh`],
+/// regardless of how many navmeshes are queued at once. For this to hold, the [`NavmeshBackends`] must merge their obstacles
+/// in a stable order, which all builtin backends do by sorting them by [`Entity`].
+/// Use the `libm` feature to extend this guarantee across platforms.
 #[derive(SystemParam)]
 pub struct NavmeshGenerator<'w> {
     #[system_param(
@@ -36,7 +67,12 @@ pub struct NavmeshGenerator<'w> {
     )]
     navmeshes: Res<'w, Assets<Navmesh>>,
     queue: ResMut<'w, NavmeshQueue>,
+    batch_queue: ResMut<'w, NavmeshBatchQueue>,
+    tile_queue: ResMut<'w, NavmeshTileQueue>,
+    dirty_queue: ResMut<'w, NavmeshDirtyQueue>,
+    deferred_queue: ResMut<'w, NavmeshDeferredQueue>,
     task_queue: ResMut<'w, NavmeshTaskQueue>,
+    registry: ResMut<'w, NavmeshRegistry>,
 }
 
 impl<'w> NavmeshGenerator<'w> {
@@ -47,10 +83,37 @@ impl<'w> NavmeshGenerator<'w> {
     pub fn generate(&mut self, settings: NavmeshSettings) -> Handle<Navmesh> {
         let handle = self.navmeshes.reserve_handle();
         let weak_handle = UpgradableAssetId::new(&handle);
-        self.queue.insert(weak_handle, settings);
+        self.queue.push((weak_handle, settings));
         handle
     }
 
+    /// Queue multiple navmesh generation tasks that share the same obstacles.
+    /// This is useful for e.g. generating navmeshes for agents of different sizes.
+    ///
+    /// Unlike calling [`NavmeshGenerator::generate`] in a loop, the backend only runs once for the whole batch,
+    /// so all navmeshes are guaranteed to be generated from the same snapshot of obstacles.
+    /// The backend receives the first settings of the batch as its input, so all settings should agree on
+    /// the backend-relevant fields such as [`NavmeshSettings::filter`].
+    /// The exception is [`NavmeshSettings::aabb`], which is set to the union of all AABBs in the batch.
+    ///
+    /// Returns the handles in the same order as the settings.
+    pub fn generate_batch(
+        &mut self,
+        settings: impl IntoIterator<Item = NavmeshSettings>,
+    ) -> Vec<Handle<Navmesh>> {
+        let mut handles = Vec::new();
+        let mut batch = Vec::new();
+        for settings in settings {
+            let handle = self.navmeshes.reserve_handle();
+            batch.push((UpgradableAssetId::new(&handle), settings));
+            handles.push(handle);
+        }
+        if !batch.is_empty() {
+            self.batch_queue.push(batch);
+        }
+        handles
+    }
+
     /// Queue a navmesh regeneration task.
     /// When you call this method, an existing navmesh will be regenerated asynchronously.
     /// Calling it multiple times will have no effect until the regeneration is complete.
@@ -59,25 +122,211 @@ impl<'w> NavmeshGenerator<'w> {
     /// Returns `true` if the regeneration was successfully queued now, `false` if it was already previously queued.
     pub fn regenerate(&mut self, id: &Handle<Navmesh>, settings: NavmeshSettings) -> bool {
         let id = UpgradableAssetId::new(id);
-        if self
-            .queue
-            .iter()
-            .map(|(a, _b)| a)
-            .chain(self.task_queue.iter().map(|(a, _b)| a))
-            .any(|queued_id| queued_id == &id)
-        {
+        if self.pending_ids().any(|queued_id| queued_id == &id) {
+            return false;
+        }
+        self.queue.push((id, settings));
+        true
+    }
+
+    /// Queue the regeneration of some tiles of a navmesh generated with [`NavmeshSettings::tiling`].
+    /// Only the tiles at `tile_coords` are built again and then merged with the other tiles of the existing navmesh,
+    /// which is much cheaper than [`NavmeshGenerator::regenerate`] when e.g. a single wall was destroyed.
+    /// Use [`NavmeshTiles::overlapping`] to find the tiles affected by a change.
+    ///
+    /// The backend only has to collect the obstacles of these tiles, so it receives their bounds as [`NavmeshSettings::aabb`].
+    /// The settings must produce the same tile grid as the ones the navmesh was generated with.
+    /// Otherwise, or if the navmesh has no [`Navmesh::tiles`], [`NavmeshGenerationFailed`] is triggered with a [`TileRegenerationError`].
+    ///
+    /// Returns `true` if the regeneration was successfully queued now, `false` if the navmesh was already previously queued.
+    pub fn regenerate_tiles(
+        &mut self,
+        id: &Handle<Navmesh>,
+        tile_coords: impl IntoIterator<Item = IVec2>,
+        settings: NavmeshSettings,
+    ) -> bool {
+        let id = UpgradableAssetId::new(id);
+        if self.pending_ids().any(|queued_id| queued_id == &id) {
             return false;
         }
-        self.queue.insert(id, settings);
+        self.tile_queue
+            .push((id, tile_coords.into_iter().collect(), settings));
         true
     }
+
+    /// Queue the regeneration of all tiles of a navmesh generated with [`NavmeshSettings::tiling`]
+    /// whose obstacles were added, removed, or moved since the navmesh was last generated.
+    ///
+    /// The bounds of the obstacles are recorded on every generation by the system set with
+    /// [`NavmeshApp::set_navmesh_affector_bounds`](crate::NavmeshApp::set_navmesh_affector_bounds), which all builtin backends do.
+    /// They are compared with the current bounds, and the tiles overlapping a difference are passed on to
+    /// [`NavmeshGenerator::regenerate_tiles`]. If nothing changed, [`NavmeshReady`] is triggered without rebuilding any tile.
+    ///
+    /// Returns `true` if the regeneration was successfully queued now, `false` if the navmesh was already previously queued.
+    pub fn regenerate_dirty(&mut self, id: &Handle<Navmesh>, settings: NavmeshSettings) -> bool {
+        let id = UpgradableAssetId::new(id);
+        if self.pending_ids().any(|queued_id| queued_id == &id) {
+            return false;
+        }
+        self.dirty_queue.push((id, settings));
+        true
+    }
+
+    /// Queue the generation of the navmesh registered under `name` in the [`NavmeshRegistry`].
+    ///
+    /// The first call generates a new navmesh with [`NavmeshGenerator::generate`] and registers its handle.
+    /// Later calls with the same name regenerate that navmesh with [`NavmeshGenerator::regenerate`],
+    /// so the name always refers to the same handle and thus to the current bake.
+    /// A generation of that navmesh that is still pending is cancelled first, so that the latest settings win.
+    ///
+    /// Returns the handle registered under `name`.
+    pub fn generate_named(
+        &mut self,
+        name: impl Into<String>,
+        settings: NavmeshSettings,
+    ) -> Handle<Navmesh> {
+        let name = name.into();
+        if let Some(handle) = self.registry.get(&name).cloned() {
+            self.cancel(&handle);
+            self.regenerate(&handle, settings);
+            return handle;
+        }
+        let handle = self.generate(settings);
+        self.registry.insert(name, handle.clone());
+        handle
+    }
+
+    /// Returns the handle of the navmesh registered under `name` with [`NavmeshGenerator::generate_named`].
+    pub fn get_named(&self, name: &str) -> Option<&Handle<Navmesh>> {
+        self.registry.get(name)
+    }
+
+    /// Cancel the generation of a navmesh that is queued or currently being generated.
+    /// The navmesh asset keeps its previous value, if any, and no [`NavmeshReady`] is triggered for the cancelled generation.
+    ///
+    /// This is useful to restart a generation whose settings became outdated, e.g. because the
+    /// [`NavmeshSettings::aabb`] changed. Call [`NavmeshGenerator::regenerate`] afterwards to queue it again.
+    ///
+    /// Returns `true` if a generation was cancelled, `false` if the navmesh was not pending.
+    pub fn cancel(&mut self, id: impl Into<AssetId<Navmesh>>) -> bool {
+        let id = id.into();
+        let pending_count = self.pending_count();
+        self.queue
+            .retain(|(queued_id, _settings)| queued_id.id() != id);
+        for batch in self.batch_queue.iter_mut() {
+            batch.retain(|(queued_id, _settings)| queued_id.id() != id);
+        }
+        self.batch_queue.retain(|batch| !batch.is_empty());
+        self.tile_queue
+            .retain(|(queued_id, _coords, _settings)| queued_id.id() != id);
+        self.dirty_queue
+            .retain(|(queued_id, _settings)| queued_id.id() != id);
+        for (batch, _loading) in self.deferred_queue.iter_mut() {
+            batch.retain(|(queued_id, _settings, _rebuild)| queued_id.id() != id);
+        }
+        self.deferred_queue
+            .retain(|(batch, _loading)| !batch.is_empty());
+        // Dropping the task cancels it.
+        self.task_queue
+            .retain(|queued_id, _task| queued_id.id() != id);
+        self.pending_count() != pending_count
+    }
+
+    /// Returns `true` if the navmesh is queued for generation or currently being generated.
+    /// Once this returns `false` again, the navmesh is either available in [`Assets<Navmesh>`] or its generation failed.
+    pub fn is_pending(&self, id: impl Into<AssetId<Navmesh>>) -> bool {
+        let id = id.into();
+        self.pending_ids().any(|queued_id| queued_id.id() == id)
+    }
+
+    /// Returns the number of navmeshes that are queued for generation or currently being generated.
+    pub fn pending_count(&self) -> usize {
+        self.pending_ids().count()
+    }
+
+    fn pending_ids(&self) -> impl Iterator<Item = &UpgradableAssetId<Navmesh>> {
+        self.queue
+            .iter()
+            .map(|(id, _settings)| id)
+            .chain(self.batch_queue.iter().flatten().map(|(id, _settings)| id))
+            .chain(self.tile_queue.iter().map(|(id, _coords, _settings)| id))
+            .chain(self.dirty_queue.iter().map(|(id, _settings)| id))
+            .chain(
+                self.deferred_queue
+                    .iter()
+                    .flat_map(|(batch, _loading)| batch.iter().map(|(id, ..)| id)),
+            )
+            .chain(self.task_queue.keys())
+    }
 }
 
+/// Navmeshes queued for generation. This is a `Vec` instead of a `HashMap` so that the backend
+/// is run in a deterministic order.
+#[derive(Debug, Resource, Default, Deref, DerefMut)]
+struct NavmeshQueue(Vec<(UpgradableAssetId<Navmesh>, NavmeshSettings)>);
+
+/// Batches queued by [`NavmeshGenerator::generate_batch`]. All navmeshes in a batch share the same obstacles.
 #[derive(Debug, Resource, Default, Deref, DerefMut)]
-struct NavmeshQueue(HashMap<UpgradableAssetId<Navmesh>, NavmeshSettings>);
+struct NavmeshBatchQueue(Vec<Vec<(UpgradableAssetId<Navmesh>, NavmeshSettings)>>);
+
+/// Tiles queued by [`NavmeshGenerator::regenerate_tiles`].
+#[derive(Debug, Resource, Default, Deref, DerefMut)]
+struct NavmeshTileQueue(Vec<(UpgradableAssetId<Navmesh>, Vec<IVec2>, NavmeshSettings)>);
+
+/// Navmeshes queued by [`NavmeshGenerator::regenerate_dirty`].
+#[derive(Debug, Resource, Default, Deref, DerefMut)]
+struct NavmeshDirtyQueue(Vec<(UpgradableAssetId<Navmesh>, NavmeshSettings)>);
+
+/// Batches whose obstacles use assets that are still loading, together with these assets.
+/// They are generated once all of the assets finished loading.
+#[derive(Debug, Resource, Default, Deref, DerefMut)]
+struct NavmeshDeferredQueue(Vec<(Vec<QueuedGeneration>, Vec<UntypedAssetId>)>);
+
+/// The assets that obstacles collected by the [`NavmeshBackends`] refer to, but that are not available in their [`Assets`].
+///
+/// Cleared before the backends run. Backends should report the missing assets of their obstacles here,
+/// as all builtin backends do, so that the generation does not silently produce a navmesh with holes:
+/// - If any of the assets is still loading, the generation is deferred until all of them finished loading.
+/// - If any of the assets failed to load, the generation fails with [`NavmeshGenerationError::MissingAssets`].
+/// - Otherwise, e.g. for assets that were removed, the navmesh is generated without them and a warning is logged.
+#[derive(Debug, Resource, Clone, Default, Deref, DerefMut)]
+pub struct NavmeshMissingAssets(pub Vec<UntypedAssetId>);
+
+/// The handles of the navmeshes generated with [`NavmeshGenerator::generate_named`], by name.
+///
+/// Since the registry holds strong handles, registered navmeshes stay alive until they are removed from it.
+#[derive(Debug, Resource, Clone, Default, Deref, DerefMut)]
+pub struct NavmeshRegistry(pub HashMap<String, Handle<Navmesh>>);
+
+/// The bounds of the affectors each navmesh was last generated from, as reported by [`NavmeshAffectorBounds`].
+/// Compared with the current bounds by [`NavmeshGenerator::regenerate_dirty`].
+#[derive(Debug, Resource, Default, Deref, DerefMut)]
+struct NavmeshAffectorSnapshots(
+    HashMap<AssetId<Navmesh>, EntityHashMap<bevy_math::bounding::Aabb3d>>,
+);
+
+/// A navmesh to generate, together with the tiles to splice into it if it is queued by [`NavmeshGenerator::regenerate_tiles`].
+type QueuedGeneration = (
+    UpgradableAssetId<Navmesh>,
+    NavmeshSettings,
+    Option<TileRebuild>,
+);
+
+/// Whether navmeshes are generated on the current thread, see [`RerecastPlugin::synchronous_builds`](crate::RerecastPlugin::synchronous_builds).
+#[derive(Resource, Default)]
+pub(crate) struct SynchronousBuilds(pub(crate) bool);
+
+/// The maximum number of heightfield columns, see [`RerecastPlugin::max_heightfield_cells`](crate::RerecastPlugin::max_heightfield_cells).
+#[derive(Resource)]
+pub(crate) struct MaxHeightfieldCells(pub(crate) u64);
 
 #[derive(Resource, Default, Deref, DerefMut)]
-struct NavmeshTaskQueue(HashMap<UpgradableAssetId<Navmesh>, Task<Result<Navmesh>>>);
+struct NavmeshTaskQueue(
+    HashMap<
+        UpgradableAssetId<Navmesh>,
+        Task<Result<(Navmesh, NavmeshBuildStats), NavmeshGenerationError>>,
+    >,
+);
 
 fn drain_queue_into_tasks(world: &mut World) {
     let queue = {
@@ -90,17 +339,166 @@ fn drain_queue_into_tasks(world: &mut World) {
         };
         core::mem::take(&mut queue.0)
     };
-    for (handle, input) in queue {
-        let Some(_strong) = handle.upgrade() else {
-            // User dropped the handle in the meantime, no need to process it
+    let batches = {
+        let Some(mut batches) = world.get_resource_mut::<NavmeshBatchQueue>() else {
+            #[cfg(feature = "tracing")]
+            tracing::error!(
+                "Cannot generate navmesh: No batch queue available. Please submit a bug report"
+            );
+            return;
+        };
+        core::mem::take(&mut batches.0)
+    };
+    let tile_queue = {
+        let Some(mut tile_queue) = world.get_resource_mut::<NavmeshTileQueue>() else {
+            #[cfg(feature = "tracing")]
+            tracing::error!(
+                "Cannot generate navmesh: No tile queue available. Please submit a bug report"
+            );
+            return;
+        };
+        core::mem::take(&mut tile_queue.0)
+    };
+    let mut rebuilds = Vec::new();
+    for (handle, coords, settings) in tile_queue {
+        let tiles = world
+            .get_resource::<Assets<Navmesh>>()
+            .and_then(|navmeshes| navmeshes.get(handle.id()))
+            .and_then(|navmesh| navmesh.tiles.clone());
+        let Some(tiles) = tiles else {
+            let error = TileRegenerationError::NotTiled;
+            #[cfg(feature = "tracing")]
+            tracing::error!("Failed to generate navmesh: {error}");
+            world.trigger(NavmeshGenerationFailed {
+                id: handle.id(),
+                error: error.into(),
+            });
             continue;
         };
-        let Some(backend) = world.get_resource::<NavmeshBackend>() else {
+        rebuilds.push(vec![(
+            handle,
+            settings,
+            Some(TileRebuild { tiles, coords }),
+        )]);
+    }
+    let dirty_queue = {
+        let Some(mut dirty_queue) = world.get_resource_mut::<NavmeshDirtyQueue>() else {
+            #[cfg(feature = "tracing")]
+            tracing::error!(
+                "Cannot generate navmesh: No dirty queue available. Please submit a bug report"
+            );
+            return;
+        };
+        core::mem::take(&mut dirty_queue.0)
+    };
+    for (handle, settings) in dirty_queue {
+        let tiles = world
+            .get_resource::<Assets<Navmesh>>()
+            .and_then(|navmeshes| navmeshes.get(handle.id()))
+            .and_then(|navmesh| navmesh.tiles.clone());
+        let previous = world
+            .get_resource::<NavmeshAffectorSnapshots>()
+            .and_then(|snapshots| snapshots.get(&handle.id()).cloned());
+        let current = affector_bounds(world, settings.clone());
+        let result = match (tiles, previous, current) {
+            (None, ..) => Err(TileRegenerationError::NotTiled),
+            (Some(tiles), Some(previous), Some(current)) => Ok((tiles, previous, current)),
+            _ => Err(TileRegenerationError::NoAffectorBounds),
+        };
+        let (tiles, previous, current) = match result {
+            Ok(result) => result,
+            Err(error) => {
+                #[cfg(feature = "tracing")]
+                tracing::error!("Failed to generate navmesh: {error}");
+                world.trigger(NavmeshGenerationFailed {
+                    id: handle.id(),
+                    error: error.into(),
+                });
+                continue;
+            }
+        };
+        let mut coords = dirty_bounds(&previous, &current)
+            .into_iter()
+            .flat_map(|aabb| tiles.overlapping(aabb))
+            .collect::<Vec<_>>();
+        coords.sort_by_key(|coords| (coords.y, coords.x));
+        coords.dedup();
+        if coords.is_empty() {
+            // Nothing changed, so the navmesh is already up to date.
+            world.trigger(NavmeshReady(handle.id()));
+            continue;
+        }
+        rebuilds.push(vec![(
+            handle,
+            settings,
+            Some(TileRebuild { tiles, coords }),
+        )]);
+    }
+    let deferred = {
+        let Some(mut deferred) = world.get_resource_mut::<NavmeshDeferredQueue>() else {
+            #[cfg(feature = "tracing")]
+            tracing::error!(
+                "Cannot generate navmesh: No deferred queue available. Please submit a bug report"
+            );
+            return;
+        };
+        core::mem::take(&mut deferred.0)
+    };
+    let asset_server = world.get_resource::<AssetServer>().cloned();
+    let (still_loading, ready): (Vec<_>, Vec<_>) =
+        deferred.into_iter().partition(|(_batch, loading)| {
+            loading
+                .iter()
+                .any(|&id| is_loading(asset_server.as_ref(), id))
+        });
+    world.resource_mut::<NavmeshDeferredQueue>().0 = still_loading;
+    // Deferred batches were queued before the new ones, so they are generated first.
+    let batches = ready
+        .into_iter()
+        .map(|(batch, _loading)| batch)
+        .chain(
+            queue
+                .into_iter()
+                .map(|queued| vec![queued])
+                .chain(batches)
+                .map(|batch| {
+                    batch
+                        .into_iter()
+                        .map(|(handle, settings)| (handle, settings, None))
+                        .collect::<Vec<QueuedGeneration>>()
+                }),
+        )
+        .chain(rebuilds);
+    for batch in batches {
+        let batch: Vec<_> = batch
+            .into_iter()
+            .filter(|(handle, _input, _rebuild)| {
+                let is_alive = handle.upgrade().is_some();
+                if !is_alive {
+                    // User dropped the handle in the meantime, no need to process it
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!("Skipping navmesh generation: The navmesh handle was dropped");
+                }
+                is_alive
+            })
+            .collect();
+        let Some((_handle, settings, _rebuild)) = batch.first() else {
+            continue;
+        };
+        let settings = settings.clone();
+        let input = NavmeshSettings {
+            aabb: backend_aabb(&batch),
+            ..settings.clone()
+        };
+        let Some(backends) = world.get_resource::<NavmeshBackends>().cloned() else {
             #[cfg(feature = "tracing")]
             tracing::error!("Cannot generate navmesh: No backend available");
             return;
         };
-        let obstacles = match world.run_system_with(backend.0, input.clone()) {
+        if let Some(mut missing) = world.get_resource_mut::<NavmeshMissingAssets>() {
+            missing.clear();
+        }
+        let obstacles = match backends.run(world, &input) {
             Ok(obstacles) => obstacles,
             Err(err) => {
                 #[cfg(feature = "tracing")]
@@ -110,6 +508,104 @@ fn drain_queue_into_tasks(world: &mut World) {
                 continue;
             }
         };
+        let mut missing = world
+            .get_resource_mut::<NavmeshMissingAssets>()
+            .map(|mut missing| core::mem::take(&mut missing.0))
+            .unwrap_or_default();
+        let mut reported = HashSet::new();
+        missing.retain(|&id| reported.insert(id));
+        let failed = missing
+            .iter()
+            .copied()
+            .filter(|&id| {
+                asset_server
+                    .as_ref()
+                    .and_then(|asset_server| asset_server.get_load_state(id))
+                    .is_some_and(|state| state.is_failed())
+            })
+            .collect::<Vec<_>>();
+        if !failed.is_empty() {
+            for (handle, _input, _rebuild) in &batch {
+                let error = NavmeshGenerationError::MissingAssets(failed.clone());
+                #[cfg(feature = "tracing")]
+                tracing::error!("Failed to generate navmesh: {error}");
+                world.trigger(NavmeshGenerationFailed {
+                    id: handle.id(),
+                    error,
+                });
+            }
+            continue;
+        }
+        let loading = missing
+            .iter()
+            .copied()
+            .filter(|&id| is_loading(asset_server.as_ref(), id))
+            .collect::<Vec<_>>();
+        if !loading.is_empty() {
+            #[cfg(feature = "tracing")]
+            tracing::info!(
+                "Deferring navmesh generation until {} obstacle assets finished loading: {loading:?}",
+                loading.len()
+            );
+            world
+                .resource_mut::<NavmeshDeferredQueue>()
+                .push((batch, loading));
+            continue;
+        }
+        #[cfg(feature = "tracing")]
+        if !missing.is_empty() {
+            tracing::warn!(
+                "Generating navmesh without {} obstacle assets that are not available: {missing:?}",
+                missing.len()
+            );
+        }
+        if let Some(bounds) = affector_bounds(world, settings)
+            && let Some(mut snapshots) = world.get_resource_mut::<NavmeshAffectorSnapshots>()
+        {
+            for (handle, _input, _rebuild) in &batch {
+                snapshots.insert(handle.id(), bounds.clone());
+            }
+        }
+        for (handle, input, _rebuild) in &batch {
+            if input.capture_input {
+                world.trigger(NavmeshInputCaptured {
+                    id: handle.id(),
+                    trimesh: obstacles.clone(),
+                });
+            }
+        }
+        let max_cells = world
+            .get_resource::<MaxHeightfieldCells>()
+            .map_or(u64::MAX, |max_cells| max_cells.0);
+        if world
+            .get_resource::<SynchronousBuilds>()
+            .is_some_and(|synchronous| synchronous.0)
+        {
+            for (handle, input, rebuild) in batch {
+                let navmesh = future::block_on(generate_navmesh(
+                    obstacles.clone(),
+                    input,
+                    rebuild,
+                    max_cells,
+                ));
+                let Some(strong) = handle.upgrade() else {
+                    continue;
+                };
+                world.resource_scope(|world, mut navmeshes: Mut<Assets<Navmesh>>| {
+                    world.resource_scope(|world, mut stats: Mut<LastBuildStats>| {
+                        finish_generation(
+                            &mut world.commands(),
+                            &mut navmeshes,
+                            &mut stats,
+                            strong.id(),
+                            navmesh,
+                        );
+                    });
+                });
+            }
+            world.flush();
+            continue;
+        }
         let Some(mut tasks_queue) = world.get_resource_mut::<NavmeshTaskQueue>() else {
             #[cfg(feature = "tracing")]
             tracing::error!(
@@ -118,8 +614,15 @@ fn drain_queue_into_tasks(world: &mut World) {
             return;
         };
         let thread_pool = AsyncComputeTaskPool::get();
-        let task = thread_pool.spawn(generate_navmesh(obstacles.clone(), input));
-        tasks_queue.insert(handle, task);
+        for (handle, input, rebuild) in batch {
+            let task = thread_pool.spawn(generate_navmesh(
+                obstacles.clone(),
+                input,
+                rebuild,
+                max_cells,
+            ));
+            tasks_queue.insert(handle, task);
+        }
     }
 }
 
@@ -127,10 +630,14 @@ fn poll_tasks(
     mut commands: Commands,
     mut tasks: ResMut<NavmeshTaskQueue>,
     mut navmeshes: ResMut<Assets<Navmesh>>,
+    mut stats: ResMut<LastBuildStats>,
 ) {
     let mut removed_ids = Vec::new();
     for (id, task) in tasks.iter_mut() {
         let Some(strong) = id.upgrade() else {
+            // Inserting into a dropped asset ID would make Bevy panic, so discard the task instead.
+            #[cfg(feature = "tracing")]
+            tracing::warn!("Discarding generated navmesh: The navmesh handle was dropped");
             removed_ids.push(id.clone());
             continue;
         };
@@ -138,88 +645,365 @@ fn poll_tasks(
             continue;
         };
         removed_ids.push(id.clone());
-        let navmesh = match navmesh {
-            Ok(navmesh) => navmesh,
-            Err(err) => {
-                #[cfg(feature = "tracing")]
-                tracing::error!("Failed to generate navmesh: {err}");
-                let _ = err;
-                continue;
-            }
-        };
-        // Process the generated navmesh
-        if let Err(err) = navmeshes.insert(strong.id(), navmesh) {
-            #[cfg(feature = "tracing")]
-            tracing::error!("Failed to insert navmesh: {err}");
-            let _ = err;
-            continue;
-        }
-        commands.trigger(NavmeshReady(strong.id()));
+        finish_generation(
+            &mut commands,
+            &mut navmeshes,
+            &mut stats,
+            strong.id(),
+            navmesh,
+        );
     }
     for id in removed_ids {
         tasks.remove(&id);
     }
 }
 
+/// Inserts a generated navmesh, records its [`NavmeshBuildStats`] and triggers [`NavmeshReady`],
+/// or triggers [`NavmeshGenerationFailed`] if the generation failed.
+fn finish_generation(
+    commands: &mut Commands,
+    navmeshes: &mut Assets<Navmesh>,
+    stats: &mut LastBuildStats,
+    id: AssetId<Navmesh>,
+    navmesh: Result<(Navmesh, NavmeshBuildStats), NavmeshGenerationError>,
+) {
+    let (mut navmesh, build_stats) = match navmesh {
+        Ok(navmesh) => navmesh,
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            tracing::error!("Failed to generate navmesh: {err}");
+            commands.trigger(NavmeshGenerationFailed { id, error: err });
+            return;
+        }
+    };
+    navmesh.metadata.settings_hash = Some(NavmeshMetadata::hash_settings(&navmesh.settings));
+    navmesh.metadata.vertex_count = Some(navmesh.vertex_count());
+    // Process the generated navmesh
+    if let Err(err) = navmeshes.insert(id, navmesh) {
+        #[cfg(feature = "tracing")]
+        tracing::error!("Failed to insert navmesh: {err}");
+        commands.trigger(NavmeshGenerationFailed {
+            id,
+            error: err.into(),
+        });
+        return;
+    }
+    stats.insert(id, build_stats);
+    commands.trigger(NavmeshReady(id));
+}
+
+/// Whether the asset is still being loaded by the [`AssetServer`], so that it will become available later.
+fn is_loading(asset_server: Option<&AssetServer>, id: UntypedAssetId) -> bool {
+    asset_server
+        .and_then(|asset_server| asset_server.get_load_state(id))
+        .is_some_and(|state| state.is_loading())
+}
+
+/// The bounds the backend needs to collect obstacles for, i.e. the union of all AABBs in the batch.
+/// Returns `None` if any navmesh in the batch computes its AABB from the obstacles, as the backend cannot cull anything then.
+fn backend_aabb(batch: &[QueuedGeneration]) -> Option<bevy_math::bounding::Aabb3d> {
+    batch
+        .iter()
+        .map(|(_handle, settings, rebuild)| match rebuild {
+            // Only the obstacles of the rebuilt tiles are needed.
+            Some(rebuild) => rebuild.tiles.obstacle_aabb(&rebuild.coords),
+            None => settings.aabb,
+        })
+        .reduce(|acc, aabb| Some(acc?.merge(&aabb?)))
+        .flatten()
+}
+
+/// Runs the [`NavmeshAffectorBounds`] systems, if any are set.
+fn affector_bounds(
+    world: &mut World,
+    settings: NavmeshSettings,
+) -> Option<EntityHashMap<bevy_math::bounding::Aabb3d>> {
+    let systems = world.get_resource::<NavmeshAffectorBounds>()?.clone();
+    match systems.run(world, &settings) {
+        Ok(bounds) => Some(bounds.into_iter().collect()),
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            tracing::error!("Cannot record affector bounds: {err}");
+            let _ = err;
+            None
+        }
+    }
+}
+
+/// The bounds of all affectors that were added, removed, or moved between two snapshots.
+/// A moved affector contributes both its old and its new bounds.
+fn dirty_bounds(
+    previous: &EntityHashMap<bevy_math::bounding::Aabb3d>,
+    current: &EntityHashMap<bevy_math::bounding::Aabb3d>,
+) -> Vec<bevy_math::bounding::Aabb3d> {
+    let changed = current
+        .iter()
+        .filter(|(entity, aabb)| previous.get(*entity) != Some(*aabb));
+    let stale = previous
+        .iter()
+        .filter(|(entity, aabb)| current.get(*entity) != Some(*aabb));
+    changed.chain(stale).map(|(_entity, aabb)| *aabb).collect()
+}
+
 /// Triggered when a navmesh created by the [`NavmeshGenerator`] is ready.
 #[derive(Debug, Event, Deref, DerefMut)]
 pub struct NavmeshReady(pub AssetId<Navmesh>);
 
-async fn generate_navmesh(mut trimesh: TriMesh, settings: NavmeshSettings) -> Result<Navmesh> {
-    let up = settings.up;
-    match up {
-        Vec3::Y => {
-            // already Bevy's coordinate system
-        }
-        Vec3::Z => {
-            for vertex in &mut trimesh.vertices {
-                *vertex = Vec3A::new(vertex.y, vertex.z, vertex.x);
-            }
+/// Triggered when the generation of a navmesh created by the [`NavmeshGenerator`] fails.
+/// The navmesh asset keeps its previous value, if any.
+#[derive(Debug, Event)]
+pub struct NavmeshGenerationFailed {
+    /// The navmesh whose generation failed.
+    pub id: AssetId<Navmesh>,
+    /// Why the generation failed.
+    pub error: NavmeshGenerationError,
+}
+
+/// Why the generation of a navmesh failed, see [`NavmeshGenerationFailed`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum NavmeshGenerationError {
+    /// [`NavmeshSettings::up`] is not one of the supported axes.
+    /// Reported instead of [`NavmeshSettingsError::Up`] so that it can be matched on directly.
+    #[error("`NavmeshSettings::up` must be one of Vec3::X, Vec3::Y, or Vec3::Z, but got {0}")]
+    UnsupportedUpVector(Vec3),
+    /// The affectors collected by the [`NavmeshBackends`] contain triangles, but no vertices to compute the AABB of the navmesh from.
+    /// Affectors without any triangles produce an empty navmesh instead.
+    #[error("Failed to compute the AABB of the navmesh: the affectors contain no vertices")]
+    EmptyAffectors,
+    /// The [`NavmeshSettings`] are invalid, see [`NavmeshSettings::validate`].
+    #[error("Invalid navmesh settings: {0}")]
+    InvalidSettings(NavmeshSettingsError),
+    /// The tiles could not be regenerated, see [`NavmeshGenerator::regenerate_tiles`].
+    #[error(transparent)]
+    TileRegeneration(#[from] TileRegenerationError),
+    /// The heightfield would have more columns than allowed by [`RerecastPlugin::max_heightfield_cells`](crate::RerecastPlugin::max_heightfield_cells).
+    #[error(
+        "The heightfield would have {cells} cells, which exceeds the limit of {limit}. Use coarser cells or a smaller AABB"
+    )]
+    TooManyCells {
+        /// The number of columns the heightfield would have.
+        cells: u64,
+        /// The configured [`RerecastPlugin::max_heightfield_cells`](crate::RerecastPlugin::max_heightfield_cells).
+        limit: u64,
+    },
+    /// Obstacle assets reported in [`NavmeshMissingAssets`] failed to load, so the navmesh would have holes.
+    #[error("{n} obstacle assets failed to load: {0:?}", n = .0.len())]
+    MissingAssets(Vec<UntypedAssetId>),
+    /// A stage of Recast failed.
+    #[error("Recast failed to build the navmesh: {0}")]
+    Rerecast(#[from] RerecastError),
+    /// The navmesh was generated, but its asset could not be inserted.
+    #[error("Failed to insert navmesh: {0}")]
+    Insert(#[from] InvalidGenerationError),
+}
+
+impl From<NavmeshSettingsError> for NavmeshGenerationError {
+    fn from(error: NavmeshSettingsError) -> Self {
+        match error {
+            NavmeshSettingsError::Up(up) => Self::UnsupportedUpVector(up),
+            error => Self::InvalidSettings(error),
         }
-        Vec3::X => {
-            for vertex in &mut trimesh.vertices {
-                *vertex = Vec3A::new(vertex.z, vertex.x, vertex.y);
+    }
+}
+
+/// The stage of Recast that failed, see [`NavmeshGenerationError::Rerecast`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum RerecastError {
+    /// Building the heightfield failed.
+    #[error(transparent)]
+    Heightfield(#[from] HeightfieldBuilderError),
+    /// Rasterizing the affectors into the heightfield failed.
+    #[error(transparent)]
+    Rasterization(#[from] RasterizationError),
+    /// Building the compact heightfield failed.
+    #[error(transparent)]
+    CompactHeightfield(#[from] CompactHeightfieldError),
+    /// Partitioning the walkable area into regions failed.
+    #[error(transparent)]
+    BuildRegions(#[from] BuildRegionsError),
+    /// Building or merging the polygon mesh failed.
+    #[error(transparent)]
+    PolygonNavmesh(#[from] PolygonNavmeshError),
+    /// Building the detail mesh failed.
+    #[error(transparent)]
+    DetailNavmesh(#[from] DetailNavmeshError),
+}
+
+/// Lets `?` convert the errors of the Recast stages straight into a [`NavmeshGenerationError`].
+macro_rules! impl_from_rerecast_error {
+    ($($error:ty),* $(,)?) => {
+        $(
+            impl From<$error> for NavmeshGenerationError {
+                fn from(error: $error) -> Self {
+                    Self::Rerecast(error.into())
+                }
             }
+        )*
+    };
+}
+
+impl_from_rerecast_error!(
+    HeightfieldBuilderError,
+    RasterizationError,
+    CompactHeightfieldError,
+    BuildRegionsError,
+    PolygonNavmeshError,
+    DetailNavmeshError,
+);
+
+/// Triggered right before a navmesh created by the [`NavmeshGenerator`] starts generating,
+/// if its [`NavmeshSettings::capture_input`] is enabled.
+///
+/// Contains the obstacles the [`NavmeshBackends`] merged for the navmesh, in the coordinate system of the obstacles.
+/// Useful for validating or saving the exact input of a generation.
+#[derive(Debug, Clone, Event)]
+pub struct NavmeshInputCaptured {
+    /// The navmesh that is generated from [`Self::trimesh`].
+    pub id: AssetId<Navmesh>,
+    /// The merged obstacles.
+    pub trimesh: TriMesh,
+}
+
+async fn generate_navmesh(
+    mut trimesh: TriMesh,
+    settings: NavmeshSettings,
+    rebuild: Option<TileRebuild>,
+    max_cells: u64,
+) -> Result<(Navmesh, NavmeshBuildStats), NavmeshGenerationError> {
+    let started = Stopwatch::start();
+    settings.validate()?;
+    if rebuild.is_some() && !settings.tiling {
+        return Err(TileRegenerationError::TilingDisabled.into());
+    }
+    if trimesh.indices.is_empty() && rebuild.is_none() {
+        // No affectors, e.g. because the level has not spawned any geometry yet.
+        // An empty navmesh is still a valid navmesh, so don't treat this as an error.
+        let stats = NavmeshBuildStats {
+            total: started.elapsed(),
+            ..Default::default()
+        };
+        return Ok((empty_navmesh(settings), stats));
+    }
+    let mut stats = NavmeshBuildStats {
+        input_triangles: trimesh.indices.len(),
+        ..Default::default()
+    };
+    let mut diagnostics = Vec::new();
+    // Coarsening the cells would not fit the tile grid of the rebuilt navmesh anymore.
+    let fitted = if rebuild.is_some() {
+        None
+    } else {
+        fit_time_budget(&settings, &trimesh)
+    };
+    let settings = match fitted {
+        Some((coarsened, diagnostic)) => {
+            diagnostics.push(diagnostic);
+            coarsened
         }
-        _ => {
-            return Err(BevyError::from(anyhow!(
-                "Unsupported up direction. Expected one of Vec3::Y, Vec3::Z, or Vec3X, but got {up}"
-            )));
-        }
+        None => settings,
+    };
+    let remap = CoordinateRemap::from(&settings);
+    if !remap.is_identity() {
+        // Every vertex is converted on its own, so the result does not depend on how the chunks are scheduled.
+        let pool = ComputeTaskPool::get_or_init(TaskPool::default);
+        trimesh
+            .vertices
+            .par_splat_map_mut(pool, None, |_index, vertices| {
+                for vertex in vertices {
+                    *vertex = remap.to_recast((*vertex).into()).into();
+                }
+            });
     }
 
     let mut config_builder = settings.clone().into_rerecast_config();
     let config = {
-        if config_builder.aabb == Aabb3d::default() {
+        if let Some(rebuild) = &rebuild {
+            // Keep the tile grid of the navmesh, as the backend only collected the obstacles of the rebuilt tiles.
+            config_builder.aabb = rebuild.tiles.aabb();
+        } else if config_builder.aabb == Aabb3d::default() {
+            // The trimesh is already converted, so its AABB is too.
             config_builder.aabb = trimesh
                 .compute_aabb()
-                .context("Failed to compute AABB: trimesh is empty")?;
-        }
-        let min = &mut config_builder.aabb.min;
-        let max = &mut config_builder.aabb.max;
-        match up {
-            Vec3::Y => {
-                // already Bevy's coordinate system
-            }
-            Vec3::Z => {
-                *min = Vec3::new(min.y, min.z, min.x);
-                *max = Vec3::new(max.y, max.z, max.x);
-            }
-            Vec3::X => {
-                *min = Vec3::new(min.z, min.x, min.y);
-                *max = Vec3::new(max.z, max.x, max.y);
-            }
-            _ => {
-                return Err(BevyError::from(anyhow!(
-                    "Unsupported up direction. Expected one of Vec3::Y, Vec3::Z, or Vec3X, but got {up}"
-                )));
-            }
+                .ok_or(NavmeshGenerationError::EmptyAffectors)?;
+        } else {
+            config_builder.aabb = remap.aabb_to_recast(config_builder.aabb);
         }
         config_builder.build()
     };
+    check_heightfield_size(&config, settings.tiling, max_cells)?;
 
-    trimesh.mark_walkable_triangles(config.walkable_slope_angle);
+    if config.walkable_height < MIN_WALKABLE_HEIGHT {
+        diagnostics.push(NavmeshDiagnostic::TooCoarseCells {
+            walkable_height: config.walkable_height,
+            min_walkable_height: MIN_WALKABLE_HEIGHT,
+        });
+    }
+    if let Some(thickness) = sample_min_feature_thickness(&trimesh)
+        && thickness < config.cell_height
+    {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            "Navmesh input contains features that are {thickness} units thick, which is thinner than the cell height of {}. \
+            Thin ledges and platforms may not receive a navmesh. Consider increasing `NavmeshSettings::cell_height_fraction`.",
+            config.cell_height
+        );
+        diagnostics.push(NavmeshDiagnostic::ThinFeatures {
+            thickness,
+            cell_height: config.cell_height,
+        });
+    }
+    let degenerate_triangles = count_degenerate_triangles(&trimesh);
+    if degenerate_triangles > 0 {
+        diagnostics.push(NavmeshDiagnostic::DegenerateTriangles {
+            count: degenerate_triangles,
+        });
+    }
 
+    trimesh.mark_walkable_triangles_as(config.walkable_slope_angle, settings.default_walkable_area);
+
+    #[cfg(feature = "debug_plugin")]
+    let debug_input = settings.keep_input.then(|| {
+        let mut input = trimesh.clone();
+        if !remap.is_identity() {
+            for vertex in &mut input.vertices {
+                *vertex = remap.from_recast((*vertex).into()).into();
+            }
+        }
+        input
+    });
+
+    if settings.tiling {
+        let tiles = match rebuild {
+            Some(TileRebuild { mut tiles, coords }) => {
+                tiles.rebuild(&trimesh, &config, &settings, remap, &coords, &mut stats)?;
+                tiles
+            }
+            None => NavmeshTiles::build(&trimesh, &config, &settings, remap, &mut stats)?,
+        };
+        let merge = Stopwatch::start();
+        let (polygon, detail, clearances) = tiles.merge(&settings, &mut diagnostics)?;
+        // Merging the tiles builds the final polygon mesh.
+        stats.polymesh += merge.elapsed();
+        let mut navmesh = Navmesh {
+            polygon,
+            detail,
+            clearances,
+            diagnostics,
+            tiles: Some(tiles),
+            #[cfg(feature = "debug_plugin")]
+            input: debug_input,
+            ..empty_navmesh(settings)
+        };
+        if !remap.is_identity() {
+            meshes_from_recast(&mut navmesh, remap);
+        }
+        let stats = stats.finish(&navmesh, &started);
+        return Ok((navmesh, stats));
+    }
+
+    let mut stopwatch = Stopwatch::start();
     let mut heightfield = HeightfieldBuilder {
         aabb: config.aabb,
         cell_size: config.cell_size,
@@ -228,83 +1012,352 @@ async fn generate_navmesh(mut trimesh: TriMesh, settings: NavmeshSettings) -> Re
     .build()?;
 
     heightfield.rasterize_triangles(&trimesh, config.walkable_climb)?;
+    // The obstacles are not needed anymore once they are rasterized.
+    // Free them before the compact heightfield is built next to the heightfield.
+    drop(trimesh);
 
-    // Once all geometry is rasterized, we do initial pass of filtering to
-    // remove unwanted overhangs caused by the conservative rasterization
-    // as well as filter spans where the character cannot possibly stand.
-    heightfield.filter_low_hanging_walkable_obstacles(config.walkable_climb);
-    heightfield.filter_ledge_spans(config.walkable_height, config.walkable_climb);
-    heightfield.filter_walkable_low_height_spans(config.walkable_height);
+    filter_spans(&mut heightfield, &config);
+    stats.rasterize = stopwatch.lap();
 
     let mut compact_heightfield =
         heightfield.into_compact(config.walkable_height, config.walkable_climb)?;
 
+    let walkable_spans_before = count_walkable_spans(&compact_heightfield);
     compact_heightfield.erode_walkable_area(config.walkable_radius);
+    if walkable_spans_before > 0 && count_walkable_spans(&compact_heightfield) == 0 {
+        diagnostics.push(NavmeshDiagnostic::OverErosion {
+            walkable_spans_before,
+        });
+    }
 
     for volume in &config.area_volumes {
         compact_heightfield.mark_convex_poly_area(volume);
     }
+    stats.erode = stopwatch.lap();
+
+    #[cfg(feature = "debug_plugin")]
+    let debug_heightfield = settings
+        .keep_heightfield
+        .then(|| crate::debug::HeightfieldSpans::from_heightfield(&compact_heightfield, remap));
+    #[cfg(feature = "debug_plugin")]
+    stopwatch.lap();
+
+    let needs_distance_field = settings.partition == PartitionMethod::Watershed;
+    #[cfg(feature = "debug_plugin")]
+    let needs_distance_field = needs_distance_field || settings.keep_distance_field;
+    if needs_distance_field {
+        compact_heightfield.build_distance_field();
+    }
+    #[cfg(feature = "debug_plugin")]
+    let debug_distance_field = settings
+        .keep_distance_field
+        .then(|| crate::debug::DistanceField::from_heightfield(&compact_heightfield, remap));
 
-    compact_heightfield.build_distance_field();
+    build_regions(&mut compact_heightfield, settings.partition, &config)?;
+    stats.regions = stopwatch.lap();
 
-    compact_heightfield.build_regions(
-        config.border_size,
-        config.min_region_area,
-        config.merge_region_area,
-    )?;
+    // Only the region partitioning reads the distance field.
+    compact_heightfield.dist = Vec::new();
 
     let contours = compact_heightfield.build_contours(
         config.max_simplification_error,
         config.max_edge_len,
         config.contour_flags,
     );
+    stats.contours = stopwatch.lap();
 
-    let poly_mesh = contours.into_polygon_mesh(config.max_vertices_per_polygon)?;
+    #[cfg(feature = "debug_plugin")]
+    let debug_contours = contours.clone();
+    #[cfg(feature = "debug_plugin")]
+    stopwatch.lap();
+    let mut poly_mesh = contours.into_polygon_mesh(config.max_vertices_per_polygon)?;
+    prune_islands(&mut poly_mesh, &settings, &mut diagnostics);
+    let clearances = poly_mesh.clearances(&compact_heightfield);
+    stats.polymesh = stopwatch.lap();
 
-    let detail_mesh = DetailNavmesh::new(
-        &poly_mesh,
-        &compact_heightfield,
-        config.detail_sample_dist,
-        config.detail_sample_max_error,
-    )?;
+    let detail_mesh = if settings.generate_detail {
+        DetailNavmesh::new(
+            &poly_mesh,
+            &compact_heightfield,
+            config.detail_sample_dist,
+            config.detail_sample_max_error,
+        )?
+    } else {
+        DetailNavmesh::default()
+    };
+    stats.detail = stopwatch.lap();
+    // The detail mesh is the last stage that reads the compact heightfield. Free it before the navmesh is
+    // assembled and converted back, so that the peak memory is the compact heightfield plus the final meshes,
+    // instead of additionally holding the input obstacles and distance field until the end of the generation.
+    drop(compact_heightfield);
 
     let mut navmesh = Navmesh {
         polygon: poly_mesh,
         detail: detail_mesh,
         settings,
+        clearances,
+        metadata: NavmeshMetadata::default(),
+        diagnostics,
+        pending_detail: None,
+        tiles: None,
+        #[cfg(feature = "debug_plugin")]
+        contours: Some(debug_contours),
+        #[cfg(feature = "debug_plugin")]
+        heightfield: debug_heightfield,
+        #[cfg(feature = "debug_plugin")]
+        distance_field: debug_distance_field,
+        #[cfg(feature = "debug_plugin")]
+        input: debug_input,
     };
-    let min = &mut navmesh.polygon.aabb.min;
-    let max = &mut navmesh.polygon.aabb.max;
-    match up {
-        Vec3::Y => {
-            // already Bevy's coordinate system
+    if !remap.is_identity() {
+        meshes_from_recast(&mut navmesh, remap);
+        #[cfg(feature = "debug_plugin")]
+        if let Some(contours) = &mut navmesh.contours {
+            let vertices = contours.contours.iter_mut().flat_map(|contour| {
+                let vertices = contour.vertices.iter_mut().map(|(vertex, _)| vertex);
+                let raw_vertices = contour.raw_vertices.iter_mut().map(|(vertex, _)| vertex);
+                vertices.chain(raw_vertices)
+            });
+            remap.cells_from_recast(&mut contours.aabb, contours.cell_size, vertices);
         }
-        Vec3::Z => {
-            for vertex in &mut navmesh.polygon.vertices {
-                *vertex = U16Vec3::new(vertex.z, vertex.x, vertex.y);
-            }
-            for vertex in &mut navmesh.detail.vertices {
-                *vertex = Vec3::new(vertex.z, vertex.x, vertex.y);
-            }
-            *min = Vec3::new(min.z, min.x, min.y);
-            *max = Vec3::new(max.z, max.x, max.y);
+    }
+
+    let stats = stats.finish(&navmesh, &started);
+    Ok((navmesh, stats))
+}
+
+/// Fails with [`NavmeshGenerationError::TooManyCells`] if a heightfield built for `config` would have more than `max_cells` columns.
+/// For tiled navmeshes, this checks the heightfield of a single tile.
+fn check_heightfield_size(
+    config: &Config,
+    tiling: bool,
+    max_cells: u64,
+) -> Result<(), NavmeshGenerationError> {
+    let (width, depth) = if tiling {
+        let tile = u64::from(config.tile_size) + 2 * u64::from(config.border_size);
+        (tile, tile)
+    } else {
+        // Same rounding as `HeightfieldBuilder::build`.
+        let size = config.aabb.max - config.aabb.min;
+        (
+            (size.x / config.cell_size + 0.5) as u64,
+            (size.z / config.cell_size + 0.5) as u64,
+        )
+    };
+    let cells = width.saturating_mul(depth);
+    if cells <= max_cells {
+        return Ok(());
+    }
+    #[cfg(feature = "tracing")]
+    tracing::error!(
+        "Cannot generate navmesh: The heightfield would be {width}x{depth} cells with a cell size of {}, \
+        which exceeds the limit of {max_cells} cells. Consider decreasing `NavmeshSettings::cell_size_fraction`, \
+        shrinking `NavmeshSettings::aabb`, or enabling `NavmeshSettings::tiling`.",
+        config.cell_size
+    );
+    Err(NavmeshGenerationError::TooManyCells {
+        cells,
+        limit: max_cells,
+    })
+}
+
+/// Once all geometry is rasterized, we do initial pass of filtering to
+/// remove unwanted overhangs caused by the conservative rasterization
+/// as well as filter spans where the character cannot possibly stand.
+fn filter_spans(heightfield: &mut Heightfield, config: &Config) {
+    heightfield.filter_low_hanging_walkable_obstacles(config.walkable_climb);
+    heightfield.filter_ledge_spans(config.walkable_height, config.walkable_climb);
+    heightfield.filter_walkable_low_height_spans(config.walkable_height);
+}
+
+fn build_regions(
+    compact_heightfield: &mut CompactHeightfield,
+    partition: PartitionMethod,
+    config: &Config,
+) -> Result<(), BuildRegionsError> {
+    match partition {
+        PartitionMethod::Watershed => {
+            compact_heightfield.build_regions(
+                config.border_size,
+                config.min_region_area,
+                config.merge_region_area,
+            )?;
         }
-        Vec3::X => {
-            for vertex in &mut navmesh.polygon.vertices {
-                *vertex = U16Vec3::new(vertex.y, vertex.z, vertex.x);
-            }
-            for vertex in &mut navmesh.detail.vertices {
-                *vertex = Vec3::new(vertex.y, vertex.z, vertex.x);
-            }
-            *min = Vec3::new(min.y, min.z, min.x);
-            *max = Vec3::new(max.y, max.z, max.x);
+        PartitionMethod::Monotone => {
+            compact_heightfield.build_regions_monotone(
+                config.border_size,
+                config.min_region_area,
+                config.merge_region_area,
+            )?;
         }
-        _ => {
-            return Err(BevyError::from(anyhow!(
-                "Unsupported up direction. Expected one of Vec3::Y, Vec3::Z, or Vec3X, but got {up}"
-            )));
+        PartitionMethod::Layers => {
+            compact_heightfield.build_layer_regions(config.border_size, config.min_region_area)?;
         }
     }
+    Ok(())
+}
+
+/// Applies [`NavmeshSettings::prune_unreachable_below`] and returns the pruned islands.
+fn prune_islands(
+    polygon: &mut PolygonNavmesh,
+    settings: &NavmeshSettings,
+    diagnostics: &mut Vec<NavmeshDiagnostic>,
+) -> Vec<Vec<u32>> {
+    let Some(min_polygons) = settings.prune_unreachable_below else {
+        return Vec::new();
+    };
+    let pruned = polygon.prune_islands(min_polygons);
+    if !pruned.is_empty() {
+        diagnostics.push(NavmeshDiagnostic::PrunedIslands {
+            islands: pruned.len(),
+            polygons: pruned.iter().map(Vec::len).sum(),
+        });
+    }
+    pruned
+}
+
+fn meshes_from_recast(navmesh: &mut Navmesh, remap: CoordinateRemap) {
+    remap.polygon_from_recast(&mut navmesh.polygon);
+    for vertex in &mut navmesh.detail.vertices {
+        *vertex = remap.from_recast(*vertex);
+    }
+}
+
+fn empty_navmesh(settings: NavmeshSettings) -> Navmesh {
+    Navmesh {
+        polygon: PolygonNavmesh {
+            max_vertices_per_polygon: settings.max_vertices_per_polygon,
+            ..Default::default()
+        },
+        detail: DetailNavmesh::default(),
+        settings,
+        clearances: Vec::new(),
+        metadata: NavmeshMetadata::default(),
+        diagnostics: Vec::new(),
+        pending_detail: None,
+        tiles: None,
+        #[cfg(feature = "debug_plugin")]
+        contours: None,
+        #[cfg(feature = "debug_plugin")]
+        heightfield: None,
+        #[cfg(feature = "debug_plugin")]
+        distance_field: None,
+        #[cfg(feature = "debug_plugin")]
+        input: None,
+    }
+}
+
+/// Coarsens the cells and detail samples of `settings` until [`NavmeshSettings::estimate_build_time`] fits into its time budget,
+/// or until they are [`NavmeshSettings::MAX_TIME_BUDGET_COARSENING`] times larger. If the settings still don't fit,
+/// the detail mesh is skipped as well. Returns `None` if there is no time budget or if the settings already fit.
+fn fit_time_budget(
+    settings: &NavmeshSettings,
+    trimesh: &TriMesh,
+) -> Option<(NavmeshSettings, NavmeshDiagnostic)> {
+    let budget = settings.time_budget?;
+    let estimate = settings.estimate_build_time(trimesh);
+    if estimate <= budget {
+        return None;
+    }
+    let mut coarsened = settings.clone();
+    let mut factor = 1.0_f32;
+    while coarsened.estimate_build_time(trimesh) > budget
+        && factor < NavmeshSettings::MAX_TIME_BUDGET_COARSENING
+    {
+        factor = (factor * 1.25).min(NavmeshSettings::MAX_TIME_BUDGET_COARSENING);
+        coarsened.cell_size_fraction = settings.cell_size_fraction / factor;
+        coarsened.detail_sample_dist = settings.detail_sample_dist * factor;
+    }
+    let skipped_detail =
+        coarsened.generate_detail && coarsened.estimate_build_time(trimesh) > budget;
+    if skipped_detail {
+        coarsened.generate_detail = false;
+    }
+    let diagnostic = NavmeshDiagnostic::CoarsenedForTimeBudget {
+        estimate,
+        budget,
+        factor,
+        skipped_detail,
+    };
+    Some((coarsened, diagnostic))
+}
+
+/// Recast needs an agent to be at least this many cells tall to reliably detect overhangs.
+const MIN_WALKABLE_HEIGHT: u16 = 3;
+
+fn count_degenerate_triangles(trimesh: &TriMesh) -> usize {
+    trimesh
+        .indices
+        .iter()
+        .filter(|indices| {
+            let a = trimesh.vertices[indices.x as usize];
+            let b = trimesh.vertices[indices.y as usize];
+            let c = trimesh.vertices[indices.z as usize];
+            (b - a).cross(c - a).length_squared() <= f32::EPSILON * f32::EPSILON
+        })
+        .count()
+}
+
+/// The maximum number of upward-facing triangles sampled by [`sample_min_feature_thickness`].
+const FEATURE_THICKNESS_SAMPLES: usize = 64;
+
+/// Estimates the thickness of the thinnest horizontal feature in the trimesh, such as a ledge or platform.
+///
+/// For a sample of upward-facing triangles, this measures the vertical distance from their centroid
+/// down to the closest downward-facing triangle directly below.
+/// Returns `None` if no such pair of triangles was found.
+fn sample_min_feature_thickness(trimesh: &TriMesh) -> Option<f32> {
+    // This runs on every generation, so the triangles are streamed from the trimesh instead of being collected.
+    let triangles = || {
+        trimesh.indices.iter().map(|indices| {
+            [indices.x, indices.y, indices.z].map(|index| trimesh.vertices[index as usize])
+        })
+    };
+    let normal_y = |[a, b, c]: &[Vec3A; 3]| (*b - *a).cross(*c - *a).y;
+    let is_top = |triangle: &[Vec3A; 3]| normal_y(triangle) > 0.0;
+    let is_bottom = |triangle: &[Vec3A; 3]| normal_y(triangle) < 0.0;
+    let top_count = triangles().filter(is_top).count();
+    if top_count == 0 || !triangles().any(|triangle| is_bottom(&triangle)) {
+        return None;
+    }
+
+    let step = top_count.div_ceil(FEATURE_THICKNESS_SAMPLES);
+    triangles()
+        .filter(is_top)
+        .step_by(step)
+        .filter_map(|[a, b, c]| {
+            let centroid = (a + b + c) / 3.0;
+            triangles()
+                .filter(is_bottom)
+                .filter_map(|bottom| height_at(&bottom, centroid))
+                .map(|y| centroid.y - y)
+                .filter(|thickness| *thickness > 0.0)
+                .min_by(f32::total_cmp)
+        })
+        .min_by(f32::total_cmp)
+}
+
+/// Returns the height of the triangle at the point's xz-coordinates, or `None` if the point is not above or below the triangle.
+pub(crate) fn height_at([a, b, c]: &[Vec3A; 3], point: Vec3A) -> Option<f32> {
+    let v0 = *c - *a;
+    let v1 = *b - *a;
+    let v2 = point - *a;
+    let denom = v0.x * v1.z - v0.z * v1.x;
+    if ops::abs(denom) <= f32::EPSILON {
+        return None;
+    }
+    let u = (v1.z * v2.x - v1.x * v2.z) / denom;
+    let v = (v0.x * v2.z - v0.z * v2.x) / denom;
+    if u < 0.0 || v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    Some(a.y + v0.y * u + v1.y * v)
+}
 
-    Ok(navmesh)
+fn count_walkable_spans(compact_heightfield: &CompactHeightfield) -> usize {
+    compact_heightfield
+        .areas
+        .iter()
+        .filter(|area| area.is_walkable())
+        .count()
 }
@@ -0,0 +1,87 @@
+use bevy_asset::prelude::*;
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::prelude::*;
+use bevy_platform::{collections::HashMap, time::Instant};
+use core::time::Duration;
+
+use crate::Navmesh;
+
+/// How long the stages of a navmesh generation took, and how big the result is.
+/// Published in [`LastBuildStats`] once the navmesh is ready.
+///
+/// For navmeshes generated with [`NavmeshSettings::tiling`](crate::NavmeshSettings::tiling), the stage times are summed over all
+/// built tiles, and merging the tiles counts towards [`NavmeshBuildStats::polymesh`].
+/// Since tiles are built in parallel, the sum of the stages can exceed [`NavmeshBuildStats::total`].
+/// Only the rebuilt tiles are counted for [`NavmeshGenerator::regenerate_tiles`](super::NavmeshGenerator::regenerate_tiles).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NavmeshBuildStats {
+    /// Building the heightfield, rasterizing the obstacles into it, and filtering its spans.
+    pub rasterize: Duration,
+    /// Building the compact heightfield, eroding the walkable area, and marking area volumes.
+    pub erode: Duration,
+    /// Building the distance field, if needed, and partitioning the walkable area into regions.
+    pub regions: Duration,
+    /// Tracing and simplifying the region contours.
+    pub contours: Duration,
+    /// Building the polygon mesh from the contours, including pruning islands and computing clearances.
+    pub polymesh: Duration,
+    /// Building the detail mesh. Zero if [`NavmeshSettings::generate_detail`](crate::NavmeshSettings::generate_detail) is disabled.
+    pub detail: Duration,
+    /// The wall-clock time of the whole generation, excluding the backend collecting the obstacles.
+    pub total: Duration,
+    /// The number of obstacle triangles the navmesh was generated from.
+    pub input_triangles: usize,
+    /// The number of polygons in the generated [`Navmesh::polygon`].
+    pub polygons: usize,
+    /// The number of triangles in the generated [`Navmesh::detail`].
+    pub detail_triangles: usize,
+}
+
+impl NavmeshBuildStats {
+    /// Adds the stage times of `other`, e.g. of another tile.
+    pub(super) fn add_stages(&mut self, other: &Self) {
+        self.rasterize += other.rasterize;
+        self.erode += other.erode;
+        self.regions += other.regions;
+        self.contours += other.contours;
+        self.polymesh += other.polymesh;
+        self.detail += other.detail;
+    }
+
+    /// Records the size of the generated `navmesh` and the total time since the generation `started`.
+    pub(super) fn finish(mut self, navmesh: &Navmesh, started: &Stopwatch) -> Self {
+        self.total = started.elapsed();
+        self.polygons = navmesh.polygon_count();
+        self.detail_triangles = navmesh.detail_triangle_count();
+        self
+    }
+}
+
+/// The [`NavmeshBuildStats`] of the last successful generation of each navmesh created by the [`NavmeshGenerator`](super::NavmeshGenerator).
+///
+/// Updated right before [`NavmeshReady`](super::NavmeshReady) is triggered, so observers of it can read the stats of the new navmesh.
+/// Failed generations keep the stats of the previous one.
+#[derive(Debug, Resource, Clone, Default, Deref, DerefMut)]
+pub struct LastBuildStats(pub HashMap<AssetId<Navmesh>, NavmeshBuildStats>);
+
+/// Measures the time between consecutive stages.
+pub(super) struct Stopwatch(Instant);
+
+impl Stopwatch {
+    pub(super) fn start() -> Self {
+        Self(Instant::now())
+    }
+
+    /// Returns the time since the stopwatch was started or last lapped, without lapping it.
+    pub(super) fn elapsed(&self) -> Duration {
+        Instant::now().saturating_duration_since(self.0)
+    }
+
+    /// Returns the time since the last lap, or since the stopwatch was started.
+    pub(super) fn lap(&mut self) -> Duration {
+        let now = Instant::now();
+        let elapsed = now.saturating_duration_since(self.0);
+        self.0 = now;
+        elapsed
+    }
+}
@@ -0,0 +1,464 @@
+use alloc::vec::Vec;
+use bevy_math::{bounding::BoundingVolume as _, ops};
+use bevy_tasks::{ComputeTaskPool, TaskPool};
+use glam::{IVec2, UVec2, Vec3};
+use rerecast::{Aabb3d, Config, DetailNavmesh, HeightfieldBuilder, PolygonNavmesh, TriMesh};
+use thiserror::Error;
+
+use super::{
+    NavmeshBuildStats, NavmeshGenerationError, Stopwatch, build_regions, count_walkable_spans,
+    filter_spans, prune_islands,
+};
+use crate::{CoordinateRemap, NavmeshDiagnostic, NavmeshSettings, PartitionMethod};
+
+/// The tiles a navmesh generated with [`NavmeshSettings::tiling`] was built from, see [`Navmesh::tiles`](crate::Navmesh::tiles).
+///
+/// [`NavmeshGenerator::regenerate_tiles`](super::NavmeshGenerator::regenerate_tiles) builds single tiles again
+/// and merges them with the unchanged ones, which is why the tiles are kept next to the merged navmesh.
+///
+/// Tiles are addressed by their coordinates in the tile grid, which starts at the minimum corner of the navmesh's AABB.
+/// The grid spans the horizontal axes of Recast's coordinate system, i.e. X and Z for the default [`NavmeshSettings::up`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavmeshTiles {
+    /// The AABB of the whole grid in Recast's coordinate system.
+    aabb: Aabb3d,
+    cell_size: f32,
+    cell_height: f32,
+    tile_size: u16,
+    border_size: u16,
+    max_vertices_per_polygon: u16,
+    remap: CoordinateRemap,
+    grid_size: UVec2,
+    /// The tiles, row by row.
+    tiles: Vec<TileMeshes>,
+}
+
+impl NavmeshTiles {
+    /// Builds all tiles of the grid covering `config.aabb` in parallel. Their stage times are added to `stats`.
+    pub(super) fn build(
+        trimesh: &TriMesh,
+        config: &Config,
+        settings: &NavmeshSettings,
+        remap: CoordinateRemap,
+        stats: &mut NavmeshBuildStats,
+    ) -> Result<Self, NavmeshGenerationError> {
+        let tile_size = config.tile_size as u32;
+        let cells_x = ((config.aabb.max.x - config.aabb.min.x) / config.cell_size + 0.5) as u32;
+        let cells_z = ((config.aabb.max.z - config.aabb.min.z) / config.cell_size + 0.5) as u32;
+        let mut tiles = Self {
+            aabb: config.aabb,
+            cell_size: config.cell_size,
+            cell_height: config.cell_height,
+            tile_size: config.tile_size,
+            border_size: config.border_size,
+            max_vertices_per_polygon: config.max_vertices_per_polygon,
+            remap,
+            grid_size: UVec2::new(
+                cells_x.div_ceil(tile_size).max(1),
+                cells_z.div_ceil(tile_size).max(1),
+            ),
+            tiles: Vec::new(),
+        };
+        let grid_size = tiles.grid_size;
+        let coords = (0..grid_size.y)
+            .flat_map(|z| (0..grid_size.x).map(move |x| IVec2::new(x as i32, z as i32)))
+            .collect::<Vec<_>>();
+        tiles.tiles = tiles.build_tiles(trimesh, config, settings, &coords, stats)?;
+        Ok(tiles)
+    }
+
+    /// Builds the tiles at `coords` again and replaces them. Their stage times are added to `stats`.
+    pub(super) fn rebuild(
+        &mut self,
+        trimesh: &TriMesh,
+        config: &Config,
+        settings: &NavmeshSettings,
+        remap: CoordinateRemap,
+        coords: &[IVec2],
+        stats: &mut NavmeshBuildStats,
+    ) -> Result<(), NavmeshGenerationError> {
+        if config.cell_size != self.cell_size
+            || config.cell_height != self.cell_height
+            || config.tile_size != self.tile_size
+            || config.border_size != self.border_size
+            || config.max_vertices_per_polygon != self.max_vertices_per_polygon
+            || remap != self.remap
+        {
+            return Err(TileRegenerationError::GridMismatch.into());
+        }
+        let indices = coords
+            .iter()
+            .map(|&coords| {
+                self.index(coords)
+                    .ok_or(TileRegenerationError::OutOfBounds(coords, self.grid_size))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let tiles = self.build_tiles(trimesh, config, settings, coords, stats)?;
+        for (index, tile) in indices.into_iter().zip(tiles) {
+            self.tiles[index] = tile;
+        }
+        Ok(())
+    }
+
+    /// Merges the tiles into a single polygon mesh, detail mesh and list of clearances,
+    /// all still in Recast's coordinate system.
+    pub(super) fn merge(
+        &self,
+        settings: &NavmeshSettings,
+        diagnostics: &mut Vec<NavmeshDiagnostic>,
+    ) -> Result<(PolygonNavmesh, DetailNavmesh, Vec<f32>), NavmeshGenerationError> {
+        let mut polygons = Vec::with_capacity(self.tiles.len());
+        let mut details = Vec::with_capacity(self.tiles.len());
+        let mut clearances = Vec::new();
+        let mut walkable_spans_before = 0;
+        let mut walkable_spans_after = 0;
+        for tile in &self.tiles {
+            polygons.push(tile.polygon.clone());
+            details.push(tile.detail.clone());
+            clearances.extend_from_slice(&tile.clearances);
+            walkable_spans_before += tile.walkable_spans_before;
+            walkable_spans_after += tile.walkable_spans_after;
+        }
+        if walkable_spans_before > 0 && walkable_spans_after == 0 {
+            diagnostics.push(NavmeshDiagnostic::OverErosion {
+                walkable_spans_before,
+            });
+        }
+
+        let mut polygon = PolygonNavmesh::merge(&polygons)?;
+        drop(polygons);
+        let mut detail = DetailNavmesh::merge(&details);
+        drop(details);
+
+        let pruned = prune_islands(&mut polygon, settings, diagnostics);
+        if !pruned.is_empty() {
+            // The islands are pruned after merging, as they can span multiple tiles.
+            // Remove the data of the tiles that belongs to the pruned polygons as well.
+            let mut kept = vec![true; clearances.len()];
+            for &poly in pruned.iter().flatten() {
+                kept[poly as usize] = false;
+            }
+            let mut kept_clearances = kept.iter();
+            clearances.retain(|_clearance| *kept_clearances.next().unwrap());
+            if !detail.meshes.is_empty() {
+                let mut kept_meshes = kept.iter();
+                detail.meshes.retain(|_mesh| *kept_meshes.next().unwrap());
+            }
+        }
+        Ok((polygon, detail, clearances))
+    }
+
+    /// The number of tiles along both axes of the grid.
+    pub fn grid_size(&self) -> UVec2 {
+        self.grid_size
+    }
+
+    /// The AABB of the whole grid in Recast's coordinate system.
+    pub(super) fn aabb(&self) -> Aabb3d {
+        self.aabb
+    }
+
+    /// Returns the coordinates of the tiles that depend on the obstacles inside of `aabb`,
+    /// which is in the coordinate system of the obstacles.
+    ///
+    /// Each tile is built with a border of cells around it, so this also includes the tiles whose border reaches into `aabb`.
+    /// Pass the result to [`NavmeshGenerator::regenerate_tiles`](super::NavmeshGenerator::regenerate_tiles)
+    /// after the obstacles inside of `aabb` changed.
+    pub fn overlapping(&self, aabb: bevy_math::bounding::Aabb3d) -> Vec<IVec2> {
+        let aabb = self.remap.aabb_to_recast(Aabb3d {
+            min: aabb.min.into(),
+            max: aabb.max.into(),
+        });
+        let border = self.border_size as f32 * self.cell_size;
+        let (min, max) = self.tile_range(aabb.min, aabb.max, border);
+        (min.y..=max.y)
+            .flat_map(|z| (min.x..=max.x).map(move |x| IVec2::new(x, z)))
+            .collect()
+    }
+
+    /// The bounds of the obstacles needed to build the tiles at `coords`, in the coordinate system of the obstacles.
+    /// Returns `None` if `coords` is empty.
+    pub(super) fn obstacle_aabb(&self, coords: &[IVec2]) -> Option<bevy_math::bounding::Aabb3d> {
+        coords
+            .iter()
+            .map(|&coords| {
+                let aabb = self.tile_aabb(coords);
+                let a = self.remap.from_recast(aabb.min);
+                let b = self.remap.from_recast(aabb.max);
+                bevy_math::bounding::Aabb3d {
+                    min: a.min(b).into(),
+                    max: a.max(b).into(),
+                }
+            })
+            .reduce(|acc, aabb| acc.merge(&aabb))
+    }
+
+    /// Builds the tiles at `coords` in parallel. The tiles are returned in the same order, and their stage times are added to `stats`.
+    fn build_tiles(
+        &self,
+        trimesh: &TriMesh,
+        config: &Config,
+        settings: &NavmeshSettings,
+        coords: &[IVec2],
+        stats: &mut NavmeshBuildStats,
+    ) -> Result<Vec<TileMeshes>, NavmeshGenerationError> {
+        let buckets = self.bucket_triangles(trimesh, coords);
+        let pool = ComputeTaskPool::get_or_init(TaskPool::default);
+        let mut tiles = pool.scope(|scope| {
+            for (index, (&coords, triangles)) in coords.iter().zip(&buckets).enumerate() {
+                let aabb = self.tile_aabb(coords);
+                scope.spawn(async move {
+                    (
+                        index,
+                        build_tile(trimesh, triangles, config, settings, aabb),
+                    )
+                });
+            }
+        });
+        // Keep the order of the tiles independent of how they were scheduled.
+        tiles.sort_unstable_by_key(|(index, _tile)| *index);
+        tiles
+            .into_iter()
+            .map(|(_index, tile)| {
+                let (tile, tile_stats) = tile?;
+                stats.add_stages(&tile_stats);
+                Ok(tile)
+            })
+            .collect()
+    }
+
+    /// Sorts the triangles of `trimesh` into the tiles at `coords` whose heightfield, including the border, they overlap.
+    /// Returns the ascending triangle indices of each tile in the same order as `coords`,
+    /// so that every tile only rasterizes its own triangles instead of the whole trimesh.
+    fn bucket_triangles(&self, trimesh: &TriMesh, coords: &[IVec2]) -> Vec<Vec<u32>> {
+        let mut buckets = vec![Vec::new(); coords.len()];
+        let mut bucket_of_tile = vec![None; self.grid_size.element_product() as usize];
+        for (bucket, &coords) in coords.iter().enumerate() {
+            if let Some(index) = self.index(coords) {
+                bucket_of_tile[index] = Some(bucket);
+            }
+        }
+        // Pad the border by a cell, so that triangles touching the edge of a heightfield are never missed.
+        let margin = (self.border_size + 1) as f32 * self.cell_size;
+        for (triangle, indices) in trimesh.indices.iter().enumerate() {
+            let [a, b, c] = indices
+                .to_array()
+                .map(|index| trimesh.vertices[index as usize]);
+            let (min, max) =
+                self.tile_range(a.min(b).min(c).into(), a.max(b).max(c).into(), margin);
+            for z in min.y..=max.y {
+                for x in min.x..=max.x {
+                    let index = self.index(IVec2::new(x, z)).unwrap();
+                    if let Some(bucket) = bucket_of_tile[index] {
+                        buckets[bucket].push(triangle as u32);
+                    }
+                }
+            }
+        }
+        buckets
+    }
+
+    /// Returns the first and last coordinates of the tiles overlapping the horizontal extent of `min` to `max`,
+    /// which is in Recast's coordinate system, when it is grown by `margin`. The coordinates are clamped to the grid,
+    /// so the range is empty if the extent lies outside of it.
+    fn tile_range(&self, min: Vec3, max: Vec3, margin: f32) -> (IVec2, IVec2) {
+        let tile_width = self.tile_size as f32 * self.cell_size;
+        let tile_at = |position: f32, min: f32| ops::floor((position - min) / tile_width) as i32;
+        let last = self.grid_size.as_ivec2() - 1;
+        let first = IVec2::new(
+            tile_at(min.x - margin, self.aabb.min.x).max(0),
+            tile_at(min.z - margin, self.aabb.min.z).max(0),
+        );
+        let last = IVec2::new(
+            tile_at(max.x + margin, self.aabb.min.x).min(last.x),
+            tile_at(max.z + margin, self.aabb.min.z).min(last.y),
+        );
+        (first, last)
+    }
+
+    fn index(&self, coords: IVec2) -> Option<usize> {
+        let grid_size = self.grid_size.as_ivec2();
+        let in_bounds = coords.cmpge(IVec2::ZERO).all() && coords.cmplt(grid_size).all();
+        in_bounds.then(|| coords.y as usize * self.grid_size.x as usize + coords.x as usize)
+    }
+
+    /// The AABB of the heightfield of the tile at `coords` in Recast's coordinate system.
+    ///
+    /// The tile is padded with the border on all sides, so that erosion and region building
+    /// see the obstacles of the neighboring tiles. The border is cut off again when building the contours.
+    fn tile_aabb(&self, coords: IVec2) -> Aabb3d {
+        let tile_width = self.tile_size as f32 * self.cell_size;
+        let border = self.border_size as f32 * self.cell_size;
+        let min = self.aabb.min + Vec3::new(coords.x as f32, 0.0, coords.y as f32) * tile_width;
+        let max = min + Vec3::new(tile_width, 0.0, tile_width);
+        Aabb3d {
+            min: min - Vec3::new(border, 0.0, border),
+            max: Vec3::new(max.x + border, self.aabb.max.y, max.z + border),
+        }
+    }
+}
+
+/// The reasons why [`NavmeshGenerator::regenerate_tiles`](super::NavmeshGenerator::regenerate_tiles) failed.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum TileRegenerationError {
+    /// The navmesh was not generated with [`NavmeshSettings::tiling`], or it was loaded from a file.
+    #[error(
+        "The navmesh has no tiles. Only navmeshes generated with `NavmeshSettings::tiling` can regenerate tiles"
+    )]
+    NotTiled,
+    /// [`NavmeshSettings::tiling`] is disabled in the settings passed for the regeneration.
+    #[error("`NavmeshSettings::tiling` must be enabled to regenerate tiles")]
+    TilingDisabled,
+    /// The settings passed for the regeneration produce a different tile grid than the navmesh was generated with,
+    /// e.g. because the cell size or the tile size changed.
+    #[error(
+        "The settings must produce the same tile grid as the settings the navmesh was generated with"
+    )]
+    GridMismatch,
+    /// A tile is outside of the tile grid of the navmesh.
+    #[error("Tile {0} is outside of the tile grid of the navmesh, which has {1} tiles")]
+    OutOfBounds(IVec2, UVec2),
+    /// No affector bounds were recorded when the navmesh was generated,
+    /// because no system was set with [`NavmeshApp::set_navmesh_affector_bounds`](crate::NavmeshApp::set_navmesh_affector_bounds).
+    #[error(
+        "No affector bounds were recorded for the navmesh. Did you forget to call `NavmeshApp::set_navmesh_affector_bounds`?"
+    )]
+    NoAffectorBounds,
+}
+
+/// Tiles of an existing navmesh that are queued by [`NavmeshGenerator::regenerate_tiles`](super::NavmeshGenerator::regenerate_tiles)
+/// or [`NavmeshGenerator::regenerate_dirty`](super::NavmeshGenerator::regenerate_dirty).
+#[derive(Debug)]
+pub(super) struct TileRebuild {
+    pub(super) tiles: NavmeshTiles,
+    pub(super) coords: Vec<IVec2>,
+}
+
+/// The meshes of a single tile in Recast's coordinate system.
+#[derive(Debug, Clone, PartialEq)]
+struct TileMeshes {
+    polygon: PolygonNavmesh,
+    detail: DetailNavmesh,
+    clearances: Vec<f32>,
+    walkable_spans_before: usize,
+    walkable_spans_after: usize,
+}
+
+/// Builds the tile with the heightfield bounds `aabb` from the `triangles` of `trimesh` that overlap it.
+fn build_tile(
+    trimesh: &TriMesh,
+    triangles: &[u32],
+    config: &Config,
+    settings: &NavmeshSettings,
+    aabb: Aabb3d,
+) -> Result<(TileMeshes, NavmeshBuildStats), NavmeshGenerationError> {
+    let mut stats = NavmeshBuildStats::default();
+    let mut stopwatch = Stopwatch::start();
+    let mut heightfield = HeightfieldBuilder {
+        aabb,
+        cell_size: config.cell_size,
+        cell_height: config.cell_height,
+    }
+    .build()?;
+    for &triangle in triangles {
+        let indices = trimesh.indices[triangle as usize];
+        let vertices = indices
+            .to_array()
+            .map(|index| trimesh.vertices[index as usize]);
+        let area_type = trimesh.area_types[triangle as usize];
+        heightfield.rasterize_triangle(vertices, area_type, config.walkable_climb)?;
+    }
+    filter_spans(&mut heightfield, config);
+    stats.rasterize = stopwatch.lap();
+
+    let mut compact_heightfield =
+        heightfield.into_compact(config.walkable_height, config.walkable_climb)?;
+    let walkable_spans_before = count_walkable_spans(&compact_heightfield);
+    compact_heightfield.erode_walkable_area(config.walkable_radius);
+    let walkable_spans_after = count_walkable_spans(&compact_heightfield);
+    for volume in &config.area_volumes {
+        compact_heightfield.mark_convex_poly_area(volume);
+    }
+    stats.erode = stopwatch.lap();
+    if settings.partition == PartitionMethod::Watershed {
+        compact_heightfield.build_distance_field();
+    }
+    build_regions(&mut compact_heightfield, settings.partition, config)?;
+    stats.regions = stopwatch.lap();
+    compact_heightfield.dist = Vec::new();
+
+    let contours = compact_heightfield.build_contours(
+        config.max_simplification_error,
+        config.max_edge_len,
+        config.contour_flags,
+    );
+    stats.contours = stopwatch.lap();
+    let polygon = contours.into_polygon_mesh(config.max_vertices_per_polygon)?;
+    let clearances = polygon.clearances(&compact_heightfield);
+    stats.polymesh = stopwatch.lap();
+    let detail = if settings.generate_detail {
+        DetailNavmesh::new(
+            &polygon,
+            &compact_heightfield,
+            config.detail_sample_dist,
+            config.detail_sample_max_error,
+        )?
+    } else {
+        DetailNavmesh::default()
+    };
+    stats.detail = stopwatch.lap();
+    let tile = TileMeshes {
+        polygon,
+        detail,
+        clearances,
+        walkable_spans_before,
+        walkable_spans_after,
+    };
+    Ok((tile, stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{UVec3, Vec3A};
+    use rerecast::AreaType;
+
+    use super::*;
+
+    #[test]
+    fn triangles_are_bucketed_into_overlapping_tiles() {
+        let tiles = NavmeshTiles {
+            aabb: Aabb3d {
+                min: Vec3::ZERO,
+                max: Vec3::new(20.0, 1.0, 10.0),
+            },
+            cell_size: 1.0,
+            cell_height: 1.0,
+            tile_size: 10,
+            border_size: 2,
+            max_vertices_per_polygon: 6,
+            remap: CoordinateRemap::default(),
+            grid_size: UVec2::new(2, 1),
+            tiles: Vec::new(),
+        };
+        let triangle_at = |x: f32| [x, x + 2.0, x].map(|x| Vec3A::new(x, 0.0, 5.0));
+        // Inside the first tile, inside the second tile, on the edge between them, and outside of the grid.
+        let triangles = [2.0, 15.0, 9.0, 50.0].map(triangle_at);
+        let trimesh = TriMesh {
+            vertices: triangles.iter().flatten().copied().collect(),
+            indices: (0..triangles.len() as u32)
+                .map(|triangle| UVec3::new(0, 1, 2) + triangle * 3)
+                .collect(),
+            area_types: vec![AreaType::DEFAULT_WALKABLE; triangles.len()],
+        };
+
+        let all = [IVec2::new(0, 0), IVec2::new(1, 0)];
+        assert_eq!(
+            tiles.bucket_triangles(&trimesh, &all),
+            [vec![0, 2], vec![1, 2]]
+        );
+        assert_eq!(
+            tiles.bucket_triangles(&trimesh, &[IVec2::new(1, 0)]),
+            [vec![1, 2]]
+        );
+    }
+}
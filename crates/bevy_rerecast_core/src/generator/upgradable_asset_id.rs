@@ -27,6 +27,10 @@ impl<T: Asset> UpgradableAssetId<T> {
         Self { id, handle }
     }
 
+    pub(crate) fn id(&self) -> AssetId<T> {
+        self.id
+    }
+
     pub(crate) fn upgrade(&self) -> Option<Handle<T>> {
         let strong_handle = self.handle.upgrade()?;
         Some(Handle::Strong(strong_handle))
@@ -0,0 +1,201 @@
+use alloc::vec::Vec;
+use bevy_app::prelude::*;
+use bevy_asset::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_image::Image;
+use bevy_math::bounding::Aabb3d;
+use bevy_reflect::prelude::*;
+use bevy_transform::components::{GlobalTransform, Transform};
+use glam::{UVec2, Vec2, Vec3, Vec3Swizzles as _};
+use rerecast::{Aabb2d, TriMesh};
+
+use crate::{
+    CombineTriMeshes as _, NavmeshApp as _, NavmeshSettings, generator::NavmeshMissingAssets,
+};
+
+/// A backend for navmesh generation.
+/// Uses all entities with a [`HeightmapTerrain`] component as navmesh obstacles.
+///
+/// The backend is added with [`NavmeshApp::add_navmesh_backend`](crate::NavmeshApp::add_navmesh_backend), so it can be combined with other backends,
+/// e.g. the `Mesh3dBackendPlugin` for the meshes placed on the terrain.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct HeightmapBackendPlugin;
+
+impl Plugin for HeightmapBackendPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_navmesh_backend(heightmap_backend);
+        app.add_navmesh_affector_bounds(heightmap_affector_bounds);
+        app.register_type::<HeightmapTerrain>();
+    }
+}
+
+/// A terrain whose heights are read from a heightmap [`Image`], used as navmesh obstacle by the [`HeightmapBackendPlugin`].
+///
+/// The terrain is centered on the entity and spans [`Self::size`] on its local X and Z axes,
+/// with the first row of the heightmap at the minimum Z. Like a `Mesh3d`,
+/// it is placed in the world by its [`GlobalTransform`].
+/// Generations wait for the images of the terrain to finish loading, see [`NavmeshMissingAssets`].
+#[derive(Debug, Clone, PartialEq, Component, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct HeightmapTerrain {
+    /// The heights of the terrain, read from the red channel in linear space.
+    /// A value of 0 is the bottom and a value of 1 the top of the terrain.
+    /// Load the image with `is_srgb: false` so that the heights are not gamma-corrected.
+    pub heightmap: Handle<Image>,
+    /// Leaves out the parts of the terrain where the red channel of this image is below 0.5.
+    /// The mask is stretched across the terrain just like the [`Self::heightmap`].
+    pub mask: Option<Handle<Image>>,
+    /// The size of the terrain in local units. The heights range from 0 to `size.y`.
+    pub size: Vec3,
+    /// The distance between two height samples in local units.
+    /// Should be at most the cell size of the navmesh to not lose detail.
+    pub spacing: f32,
+    /// Leaves out the triangles whose corners all lie below this height in local units, e.g. the bottom of a lake.
+    pub water_level: Option<f32>,
+}
+
+impl HeightmapTerrain {
+    /// Creates a new [`HeightmapTerrain`] of the given size, sampled every 0.25 units without a mask or water level.
+    pub fn new(heightmap: Handle<Image>, size: Vec3) -> Self {
+        Self {
+            heightmap,
+            mask: None,
+            size,
+            spacing: 0.25,
+            water_level: None,
+        }
+    }
+}
+
+fn heightmap_backend(
+    input: In<NavmeshSettings>,
+    images: Res<Assets<Image>>,
+    terrains: Query<(Entity, &GlobalTransform, &HeightmapTerrain)>,
+    mut missing: ResMut<NavmeshMissingAssets>,
+) -> TriMesh {
+    let mut terrains = terrains
+        .iter()
+        .filter(|(entity, ..)| input.includes(*entity))
+        .collect::<Vec<_>>();
+    // Merge in a stable order so that identical scenes always produce identical navmeshes.
+    terrains.sort_by_key(|(entity, ..)| *entity);
+    TriMesh::combine(
+        terrains
+            .into_iter()
+            .filter_map(|(_entity, transform, terrain)| {
+                let mut get_image = |image: &Handle<Image>| {
+                    let found = images.get(image);
+                    if found.is_none() {
+                        missing.push(image.id().untyped());
+                    }
+                    found
+                };
+                let heightmap = get_image(&terrain.heightmap)?;
+                let mask = match &terrain.mask {
+                    Some(mask) => Some(get_image(mask)?),
+                    None => None,
+                };
+                let trimesh = TriMesh::from_heightmap(terrain, heightmap, mask);
+                Some((*transform, trimesh))
+            }),
+    )
+}
+
+fn heightmap_affector_bounds(
+    input: In<NavmeshSettings>,
+    terrains: Query<(Entity, &GlobalTransform, &HeightmapTerrain)>,
+) -> Vec<(Entity, Aabb3d)> {
+    terrains
+        .iter()
+        .filter(|(entity, ..)| input.includes(*entity))
+        .map(|(entity, transform, terrain)| {
+            let half_size = terrain.size.xz() / 2.0;
+            let local = [
+                Vec3::new(-half_size.x, 0.0, -half_size.y),
+                Vec3::new(half_size.x, terrain.size.y, half_size.y),
+            ];
+            let first = transform.transform_point(local[0]);
+            let (min, max) = (1..8)
+                .map(|i| Vec3::new(local[i & 1].x, local[(i >> 1) & 1].y, local[(i >> 2) & 1].z))
+                .map(|corner| transform.transform_point(corner))
+                .fold((first, first), |(min, max), point| {
+                    (min.min(point), max.max(point))
+                });
+            let aabb = Aabb3d {
+                min: min.into(),
+                max: max.into(),
+            };
+            (entity, aabb)
+        })
+        .collect()
+}
+
+/// Used to add [`TriMeshFromHeightmap::from_heightmap`] to [`TriMesh`].
+pub trait TriMeshFromHeightmap {
+    /// Samples a [`HeightmapTerrain`] on a regular grid and triangulates it in the terrain's local space.
+    /// `heightmap` and `mask` are the loaded [`HeightmapTerrain::heightmap`] and [`HeightmapTerrain::mask`].
+    fn from_heightmap(
+        terrain: &HeightmapTerrain,
+        heightmap: &Image,
+        mask: Option<&Image>,
+    ) -> TriMesh;
+}
+
+impl TriMeshFromHeightmap for TriMesh {
+    fn from_heightmap(
+        terrain: &HeightmapTerrain,
+        heightmap: &Image,
+        mask: Option<&Image>,
+    ) -> TriMesh {
+        let half_size = terrain.size.xz() / 2.0;
+        let bounds = Aabb2d {
+            min: -half_size,
+            max: half_size,
+        };
+        let sampler = |x: f32, z: f32| {
+            let uv = (Vec2::new(x, z) + half_size) / terrain.size.xz();
+            if let Some(mask) = mask
+                && sample_red(mask, uv)? < 0.5
+            {
+                return None;
+            }
+            Some(sample_red(heightmap, uv)? * terrain.size.y)
+        };
+        let mut trimesh = TriMesh::from_height_sampler(&sampler, bounds, terrain.spacing);
+        if let Some(water_level) = terrain.water_level {
+            let vertices = &trimesh.vertices;
+            trimesh.indices.retain(|triangle| {
+                !triangle
+                    .to_array()
+                    .iter()
+                    .all(|&index| vertices[index as usize].y < water_level)
+            });
+            // All triangles share the same area type.
+            trimesh.area_types.truncate(trimesh.indices.len());
+        }
+        trimesh
+    }
+}
+
+/// Bilinearly samples the red channel of an image in linear space.
+/// `uv` ranges from 0 to 1 across the image. Returns `None` if the format of the image is not supported.
+fn sample_red(image: &Image, uv: Vec2) -> Option<f32> {
+    let size = image.size();
+    if size.min_element() == 0 {
+        return None;
+    }
+    let last = size - UVec2::ONE;
+    let position = uv.clamp(Vec2::ZERO, Vec2::ONE) * last.as_vec2();
+    let min = position.as_uvec2();
+    let max = (min + UVec2::ONE).min(last);
+    let t = position - min.as_vec2();
+    let red = |x: u32, y: u32| {
+        let color = image.get_color_at(x, y).ok()?;
+        Some(color.to_linear().red)
+    };
+    let top = red(min.x, min.y)? * (1.0 - t.x) + red(max.x, min.y)? * t.x;
+    let bottom = red(min.x, max.y)? * (1.0 - t.x) + red(max.x, max.y)? * t.x;
+    Some(top * (1.0 - t.y) + bottom * t.y)
+}
@@ -5,19 +5,46 @@
 use bevy_app::prelude::*;
 #[cfg(feature = "bevy_asset")]
 use bevy_asset::prelude::*;
+#[cfg(feature = "bevy_asset")]
+use bevy_derive::{Deref, DerefMut};
+#[cfg(feature = "bevy_asset")]
+use bevy_ecs::prelude::*;
 #[cfg(feature = "bevy_mesh")]
 mod mesh;
 use bevy_reflect::prelude::*;
 #[cfg(feature = "bevy_mesh")]
-pub use mesh::{Mesh3dBackendPlugin, TriMeshFromBevyMesh};
+pub use mesh::{Mesh2dBackendPlugin, Mesh3dBackendPlugin, NavmeshExcluded, TriMeshFromBevyMesh};
+#[cfg(feature = "primitives")]
+mod primitives;
+#[cfg(feature = "primitives")]
+pub use primitives::{NavmeshPrimitive, PrimitiveBackendPlugin};
+#[cfg(feature = "heightmap")]
+mod heightmap;
+#[cfg(feature = "heightmap")]
+pub use heightmap::{HeightmapBackendPlugin, HeightmapTerrain, TriMeshFromHeightmap};
 mod backend;
+mod coordinates;
+pub use coordinates::CoordinateRemap;
 #[cfg(feature = "debug_plugin")]
 pub mod debug;
 #[cfg(feature = "bevy_asset")]
 pub mod generator;
+#[cfg(feature = "bevy_asset")]
+pub mod queries;
 pub use backend::*;
+mod diagnostic;
+pub use diagnostic::*;
+mod metadata;
+pub use metadata::NavmeshMetadata;
+mod obj;
+mod trimesh;
+pub use trimesh::CombineTriMeshes;
 #[cfg(feature = "bevy_asset")]
 pub mod asset_loader;
+#[cfg(feature = "bevy_asset")]
+pub mod network;
+#[cfg(feature = "ron")]
+pub mod settings_file;
 #[allow(
     unused_imports,
     reason = "Some features use vec!, some don't. Let's keep it simple."
@@ -27,29 +54,105 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+use alloc::{collections::BTreeMap, vec::Vec};
+use glam::Vec3;
 pub use rerecast;
+#[cfg(feature = "debug_plugin")]
+use rerecast::ContourSet;
 use rerecast::{DetailNavmesh, PolygonNavmesh};
 use serde::{Deserialize, Serialize};
 
 /// Everything you need to use the crate.
 pub mod prelude {
     #[cfg(feature = "bevy_asset")]
-    pub use crate::generator::{NavmeshGenerator, NavmeshReady};
-    pub use crate::{Navmesh, NavmeshApp as _, NavmeshSettings};
+    pub use crate::NavmeshRef;
+    #[cfg(feature = "bevy_asset")]
+    pub use crate::asset_loader::NavmeshDetailReady;
+    #[cfg(feature = "bevy_asset")]
+    pub use crate::generator::{
+        NavmeshGenerationFailed, NavmeshGenerator, NavmeshInputCaptured, NavmeshReady,
+    };
+    #[cfg(feature = "bevy_asset")]
+    pub use crate::queries::NavmeshQueries;
+    pub use crate::{Navmesh, NavmeshApp as _, NavmeshDiagnostic, NavmeshSettings};
 }
 
 /// The main plugin of the crate. Adds functionality for creating and managing navmeshes.
 #[non_exhaustive]
-#[derive(Default)]
-pub struct RerecastPlugin;
+pub struct RerecastPlugin {
+    /// Whether to generate navmeshes on the main thread while the queued generations are processed in [`PostUpdate`],
+    /// instead of on the [`AsyncComputeTaskPool`](bevy_tasks::AsyncComputeTaskPool).
+    /// [`NavmeshReady`](generator::NavmeshReady) is then triggered in the same update as the generation was queued.
+    ///
+    /// This removes the scheduling nondeterminism of the task pool, which is useful for tests and debugging,
+    /// but blocks the app while generating. Defaults to `false`.
+    pub synchronous_builds: bool,
+    /// The maximum number of columns a heightfield may have, i.e. its width times its depth in cells.
+    /// Generating a navmesh whose heightfield would be larger fails with
+    /// [`NavmeshGenerationError::TooManyCells`](generator::NavmeshGenerationError::TooManyCells) instead of allocating it.
+    /// For navmeshes generated with [`NavmeshSettings::tiling`], the limit applies to the heightfield of each tile.
+    ///
+    /// Fine cells in a large AABB can otherwise create heightfields with billions of cells, which exhausts the memory
+    /// before any error surfaces. Defaults to [`Self::DEFAULT_MAX_HEIGHTFIELD_CELLS`]. Set it to [`u64::MAX`] to disable the limit.
+    pub max_heightfield_cells: u64,
+}
+
+impl Default for RerecastPlugin {
+    fn default() -> Self {
+        Self {
+            synchronous_builds: false,
+            max_heightfield_cells: Self::DEFAULT_MAX_HEIGHTFIELD_CELLS,
+        }
+    }
+}
+
+impl RerecastPlugin {
+    /// The default of [`Self::max_heightfield_cells`], which allows e.g. a heightfield of 8192 by 8192 cells.
+    pub const DEFAULT_MAX_HEIGHTFIELD_CELLS: u64 = 1 << 26;
+
+    /// Sets [`Self::synchronous_builds`].
+    pub fn with_synchronous_builds(mut self, synchronous_builds: bool) -> Self {
+        self.synchronous_builds = synchronous_builds;
+        self
+    }
+
+    /// Sets [`Self::max_heightfield_cells`].
+    pub fn with_max_heightfield_cells(mut self, max_heightfield_cells: u64) -> Self {
+        self.max_heightfield_cells = max_heightfield_cells;
+        self
+    }
+}
 
 impl Plugin for RerecastPlugin {
     fn build(&self, app: &mut App) {
         #[cfg(feature = "bevy_asset")]
-        app.add_plugins(generator::plugin);
+        app.add_plugins(generator::plugin)
+            .insert_resource(generator::SynchronousBuilds(self.synchronous_builds))
+            .insert_resource(generator::MaxHeightfieldCells(self.max_heightfield_cells));
+        #[cfg(feature = "bevy_asset")]
+        app.add_plugins((asset_loader::plugin, queries::plugin));
+        #[cfg(feature = "ron")]
+        app.add_plugins(settings_file::plugin);
+        // Register the whole type tree of a navmesh so that inspectors can drill into it.
+        app.register_type::<Navmesh>()
+            .register_type::<NavmeshSettings>()
+            .register_type::<PartitionMethod>()
+            .register_type::<Handedness>()
+            .register_type::<GridDims>()
+            .register_type::<NavmeshDiagnostic>()
+            .register_type::<NavmeshMetadata>()
+            .register_type::<PolygonNavmesh>()
+            .register_type::<DetailNavmesh>()
+            .register_type::<rerecast::SubMesh>()
+            .register_type::<rerecast::Aabb3d>()
+            .register_type::<rerecast::AreaType>()
+            .register_type::<rerecast::RegionId>()
+            .register_type::<rerecast::ConvexVolume>()
+            .register_type::<rerecast::BuildContoursFlags>()
+            .register_type::<rerecast::Config>()
+            .register_type::<rerecast::ConfigBuilder>();
         #[cfg(feature = "bevy_asset")]
-        app.add_plugins(asset_loader::plugin);
-        let _ = app;
+        app.register_type::<NavmeshRef>();
     }
 }
 
@@ -67,13 +170,513 @@ pub struct Navmesh {
     /// If you can spare the performance cost, you can also always use [`Navmesh::detail`] to pathfind instead.
     pub polygon: PolygonNavmesh,
 
+    /// The configuration that was used to generate this navmesh.
+    pub settings: NavmeshSettings,
+
+    /// The vertical clearance above each polygon of the [`Navmesh::polygon`] in world units.
+    /// See [`PolygonNavmesh::clearances`](rerecast::PolygonNavmesh::clearances).
+    ///
+    /// Used by [`NavmeshQueries`](queries::NavmeshQueries) to keep tall agents out of polygons below low ceilings.
+    /// Empty for navmeshes that were saved before clearances were recorded, in which case every polygon is assumed to be high enough.
+    pub clearances: Vec<f32>,
+
+    /// Free-form information about the navmesh, e.g. where it was generated from.
+    /// Defaults to empty metadata for formats that can omit fields.
+    #[serde(default)]
+    pub metadata: NavmeshMetadata,
+
     /// The detail navmesh data. This is a more detailed representation of the navmesh that
     /// accurately follows geometry. It contains more data than the [`Navmesh::polygon`], so
     /// the latter is more efficient for pathfinding. Use this navmesh to refine the path.
     ///
     /// If you can spare the performance cost, you can also always use this navmesh to pathfind instead.
+    ///
+    /// This is serialized last, so that [`NavmeshLoaderSettings::staged`](asset_loader::NavmeshLoaderSettings::staged)
+    /// can provide the rest of the navmesh before the detail mesh has been decoded.
     pub detail: DetailNavmesh,
 
-    /// The configuration that was used to generate this navmesh.
-    pub settings: NavmeshSettings,
+    /// The encoded [`Navmesh::detail`] of a navmesh loaded with
+    /// [`NavmeshLoaderSettings::staged`](asset_loader::NavmeshLoaderSettings::staged) that is still being decoded.
+    /// `None` once the detail mesh is available. Not persisted when serializing the navmesh.
+    #[cfg(feature = "bevy_asset")]
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pub pending_detail: Option<asset_loader::PendingDetail>,
+
+    /// The tiles of a navmesh generated with [`NavmeshSettings::tiling`], which
+    /// [`NavmeshGenerator::regenerate_tiles`](generator::NavmeshGenerator::regenerate_tiles) splices rebuilt tiles into.
+    /// `None` for navmeshes that were not tiled. Not persisted when serializing the navmesh.
+    #[cfg(feature = "bevy_asset")]
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pub tiles: Option<generator::NavmeshTiles>,
+
+    /// Problems detected while generating this navmesh.
+    /// These are not persisted when serializing the navmesh.
+    #[serde(skip)]
+    pub diagnostics: Vec<NavmeshDiagnostic>,
+
+    /// The contours the [`Navmesh::polygon`] was built from, as drawn by a [`ContourGizmo`](debug::ContourGizmo).
+    /// Only stored for generated navmeshes and not persisted when serializing the navmesh.
+    #[cfg(feature = "debug_plugin")]
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pub contours: Option<ContourSet>,
+
+    /// The walkable spans of the heightfield the navmesh was generated from, as drawn by a [`HeightfieldGizmo`](debug::HeightfieldGizmo).
+    /// Only stored when generating with [`NavmeshSettings::keep_heightfield`] and not persisted when serializing the navmesh.
+    #[cfg(feature = "debug_plugin")]
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pub heightfield: Option<debug::HeightfieldSpans>,
+
+    /// The distance field of the heightfield the navmesh was generated from, as drawn by a [`DistanceFieldGizmo`](debug::DistanceFieldGizmo).
+    /// Only stored when generating with [`NavmeshSettings::keep_distance_field`] and not persisted when serializing the navmesh.
+    #[cfg(feature = "debug_plugin")]
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pub distance_field: Option<debug::DistanceField>,
+
+    /// The merged obstacles the navmesh was rasterized from in world space, as drawn by an [`InputGizmo`](debug::InputGizmo).
+    /// The [`TriMesh::area_types`](rerecast::TriMesh::area_types) tell which triangles were walkable.
+    /// Only stored when generating with [`NavmeshSettings::keep_input`] and not persisted when serializing the navmesh.
+    #[cfg(feature = "debug_plugin")]
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pub input: Option<rerecast::TriMesh>,
+}
+
+impl Navmesh {
+    /// The number of polygons in the [`Navmesh::polygon`].
+    pub fn polygon_count(&self) -> usize {
+        self.polygon.polygon_count()
+    }
+
+    /// The number of vertices in the [`Navmesh::polygon`].
+    pub fn vertex_count(&self) -> usize {
+        self.polygon.vertices.len()
+    }
+
+    /// The number of triangles in the [`Navmesh::detail`].
+    pub fn detail_triangle_count(&self) -> usize {
+        self.detail.triangles.len()
+    }
+
+    /// The bounds of the [`Navmesh::polygon`] in world space.
+    /// These are the [`NavmeshSettings::aabb`] if one was supplied, or the bounds of the obstacles otherwise,
+    /// shrunk horizontally by the border of cells Recast erodes the walkable area from
+    /// and raised by the [`NavmeshSettings::agent_height`] to leave room for an agent on the highest surface.
+    ///
+    /// Every polygon lies within these bounds, so points outside of them can be rejected before querying the navmesh.
+    pub fn aabb(&self) -> bevy_math::bounding::Aabb3d {
+        bevy_math::bounding::Aabb3d {
+            min: self.polygon.aabb.min.into(),
+            max: self.polygon.aabb.max.into(),
+        }
+    }
+
+    /// Returns `true` if the navmesh has no polygons, e.g. because there were no obstacles to generate it from
+    /// or because no surface was walkable.
+    pub fn is_empty(&self) -> bool {
+        self.polygon_count() == 0
+    }
+
+    /// Returns a hash of the geometry of the navmesh, i.e. of the [`Navmesh::polygon`] and the [`Navmesh::detail`].
+    /// The [`Navmesh::settings`] and all other fields are ignored.
+    ///
+    /// Navmeshes with the same geometry have the same hash, e.g. to skip exporting navmeshes that did not change
+    /// or to compare navmeshes more cheaply than field by field. The hash is stable across runs and platforms,
+    /// but not across versions of this crate, since it depends on how the navmesh is serialized.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = metadata::Fnv1a::new();
+        hasher.hash(&self.polygon);
+        hasher.hash(&self.detail);
+        hasher.finish()
+    }
+
+    /// Returns `true` if both navmeshes have the same geometry, allowing the [`Navmesh::detail`] vertices to differ
+    /// by up to `tolerance` on each axis. Useful in tests, where float noise from a different compiler or platform
+    /// should not count as a difference.
+    ///
+    /// The [`Navmesh::polygon`] is quantized, so it must match exactly, as must the topology of the detail mesh.
+    /// Like [`Navmesh::content_hash`], this ignores the [`Navmesh::settings`] and all other fields.
+    pub fn approx_eq(&self, other: &Navmesh, tolerance: f32) -> bool {
+        self.polygon == other.polygon
+            && self.detail.meshes == other.detail.meshes
+            && self.detail.triangles == other.detail.triangles
+            && self.detail.triangle_flags == other.detail.triangle_flags
+            && self.detail.vertices.len() == other.detail.vertices.len()
+            && self
+                .detail
+                .vertices
+                .iter()
+                .zip(&other.detail.vertices)
+                .all(|(a, b)| a.abs_diff_eq(*b, tolerance))
+    }
+
+    /// How far the origins of two navmeshes passed to [`Navmesh::merge`] may be off the shared cell grid, in cells.
+    /// This allows for the rounding of AABBs computed in floating point.
+    const MERGE_CELL_EPSILON: f32 = 1.0e-3;
+
+    /// Merges `other` into a copy of this navmesh, e.g. to query a static world navmesh together with
+    /// the navmeshes of destructible sections that were generated separately.
+    ///
+    /// The polygons of `other` follow the polygons of this navmesh, so their indices are offset by [`Navmesh::polygon_count`].
+    /// Vertices that lie on the same cell and within two cell heights of each other are welded together,
+    /// and edges without a neighbor that run along the same two vertices in both navmeshes are connected.
+    /// The [`Navmesh::detail`] and [`Navmesh::clearances`] are only kept if both navmeshes have them.
+    ///
+    /// The [`Navmesh::settings`] of this navmesh are kept. The navmeshes must have been generated on the same cell grid,
+    /// i.e. with the same cell size and with AABBs that are a whole number of cells apart,
+    /// and with the same [`NavmeshSettings::max_vertices_per_polygon`], [`NavmeshSettings::up`] and [`NavmeshSettings::handedness`].
+    /// Otherwise, an error is returned.
+    ///
+    /// The [`Navmesh::metadata`] of this navmesh is kept, except for the fields describing the merged navmesh:
+    /// [`NavmeshMetadata::settings_hash`] and [`NavmeshMetadata::vertex_count`] are recomputed,
+    /// and [`NavmeshMetadata::content_hash`] is cleared, as the merged navmesh has more than one source.
+    pub fn merge(&self, other: &Navmesh) -> anyhow::Result<Navmesh> {
+        let (a, b) = (&self.polygon, &other.polygon);
+        anyhow::ensure!(
+            a.cell_size == b.cell_size && a.cell_height == b.cell_height,
+            "Cannot merge navmeshes with different cell sizes: {}x{} and {}x{}",
+            a.cell_size,
+            a.cell_height,
+            b.cell_size,
+            b.cell_height
+        );
+        anyhow::ensure!(
+            a.max_vertices_per_polygon == b.max_vertices_per_polygon,
+            "Cannot merge navmeshes with different maximum vertices per polygon: {} and {}",
+            a.max_vertices_per_polygon,
+            b.max_vertices_per_polygon
+        );
+        let remap = CoordinateRemap::from(&self.settings);
+        anyhow::ensure!(
+            remap == CoordinateRemap::from(&other.settings),
+            "Cannot merge navmeshes with different coordinate systems"
+        );
+
+        // The vertices are welded on Recast's cell grid.
+        let (mut a, mut b) = (a.clone(), b.clone());
+        remap.polygon_to_recast(&mut a);
+        remap.polygon_to_recast(&mut b);
+        let cell = Vec3::new(a.cell_size, a.cell_height, a.cell_size);
+        let offset = (b.aabb.min - a.aabb.min) / cell;
+        anyhow::ensure!(
+            (offset - offset.round()).abs().max_element() <= Self::MERGE_CELL_EPSILON,
+            "Cannot merge navmeshes that are not on the same cell grid: their origins are {offset} cells apart"
+        );
+        let mut polygon = PolygonNavmesh::merge(&[a, b])?;
+        stitch_coincident_edges(&mut polygon);
+        remap.polygon_from_recast(&mut polygon);
+
+        let has_detail = |navmesh: &Navmesh| navmesh.detail.meshes.len() == navmesh.polygon_count();
+        let detail = if has_detail(self) && has_detail(other) {
+            DetailNavmesh::merge(&[self.detail.clone(), other.detail.clone()])
+        } else {
+            DetailNavmesh::default()
+        };
+        let has_clearances =
+            |navmesh: &Navmesh| navmesh.clearances.len() == navmesh.polygon_count();
+        let clearances = if has_clearances(self) && has_clearances(other) {
+            [self.clearances.as_slice(), other.clearances.as_slice()].concat()
+        } else {
+            Vec::new()
+        };
+
+        let metadata = NavmeshMetadata {
+            settings_hash: Some(NavmeshMetadata::hash_settings(&self.settings)),
+            vertex_count: Some(polygon.vertices.len()),
+            content_hash: None,
+            ..self.metadata.clone()
+        };
+
+        Ok(Navmesh {
+            polygon,
+            settings: self.settings.clone(),
+            clearances,
+            metadata,
+            detail,
+            #[cfg(feature = "bevy_asset")]
+            pending_detail: None,
+            #[cfg(feature = "bevy_asset")]
+            tiles: None,
+            diagnostics: [self.diagnostics.as_slice(), other.diagnostics.as_slice()].concat(),
+            #[cfg(feature = "debug_plugin")]
+            contours: None,
+            #[cfg(feature = "debug_plugin")]
+            heightfield: None,
+            #[cfg(feature = "debug_plugin")]
+            distance_field: None,
+            #[cfg(feature = "debug_plugin")]
+            input: None,
+        })
+    }
+}
+
+/// Connects the edges without a neighbor that run along the same two vertices in opposite directions.
+fn stitch_coincident_edges(polygon: &mut PolygonNavmesh) {
+    let nvp = polygon.max_vertices_per_polygon as usize;
+    let mut walls = BTreeMap::new();
+    for (poly, vertices) in polygon.polygons().enumerate() {
+        let vertices = vertices.collect::<Vec<_>>();
+        for (edge, &a) in vertices.iter().enumerate() {
+            let slot = poly * nvp + edge;
+            if polygon.polygon_neighbors[slot] == PolygonNavmesh::NO_CONNECTION {
+                let b = vertices[(edge + 1) % vertices.len()];
+                walls.insert((a, b), slot);
+            }
+        }
+    }
+    for (&(a, b), &slot) in &walls {
+        if let Some(&other) = walls.get(&(b, a)) {
+            polygon.polygon_neighbors[slot] = (other / nvp) as u16;
+        }
+    }
+}
+
+/// Component attaching a [`Navmesh`] to an entity, e.g. to a level or room that is streamed in,
+/// so that every region can own its navmesh instead of sharing a single global one.
+///
+/// Since the component holds a strong handle, the navmesh is kept alive until the entity is despawned.
+/// Pass the component wherever an [`AssetId<Navmesh>`] is expected, e.g. to [`NavmeshQueries`](queries::NavmeshQueries),
+/// [`NavmeshGenerator::cancel`](generator::NavmeshGenerator::cancel), or the debug gizmos. Since it dereferences to its
+/// [`Handle`], it can also be passed to [`NavmeshGenerator::regenerate`](generator::NavmeshGenerator::regenerate).
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_math::bounding::Aabb3d;
+/// # use bevy_rerecast_core::{prelude::*, NavmeshRef};
+/// fn generate_room_navmesh(
+///     room: Single<(Entity, &Room)>,
+///     mut generator: NavmeshGenerator,
+///     mut commands: Commands,
+/// ) {
+///     let (entity, room) = *room;
+///     // Only generate the navmesh within the bounds of this room.
+///     let settings = NavmeshSettings {
+///         aabb: Some(room.bounds),
+///         ..NavmeshSettings::from_agent_3d(0.5, 2.0)
+///     };
+///     commands.entity(entity).insert(NavmeshRef(generator.generate(settings)));
+/// }
+///
+/// fn find_path_in_room(room: Single<&NavmeshRef, With<Room>>, queries: NavmeshQueries) {
+///     let _path = queries.find_path_between(*room, Entity::PLACEHOLDER, Entity::PLACEHOLDER, 0.0);
+/// }
+///
+/// #[derive(Component)]
+/// struct Room {
+///     bounds: Aabb3d,
+/// }
+/// ```
+#[cfg(feature = "bevy_asset")]
+#[derive(Component, Debug, Clone, PartialEq, Eq, Default, Reflect, Deref, DerefMut)]
+#[reflect(Component)]
+pub struct NavmeshRef(pub Handle<Navmesh>);
+
+#[cfg(feature = "bevy_asset")]
+impl From<Handle<Navmesh>> for NavmeshRef {
+    fn from(handle: Handle<Navmesh>) -> Self {
+        Self(handle)
+    }
+}
+
+#[cfg(feature = "bevy_asset")]
+impl From<&NavmeshRef> for AssetId<Navmesh> {
+    fn from(navmesh: &NavmeshRef) -> Self {
+        navmesh.id()
+    }
+}
+
+#[cfg(feature = "bevy_asset")]
+impl From<NavmeshRef> for AssetId<Navmesh> {
+    fn from(navmesh: NavmeshRef) -> Self {
+        navmesh.id()
+    }
+}
+
+#[cfg(all(test, feature = "bevy_asset"))]
+mod tests {
+    use bevy_platform::collections::HashSet;
+    use glam::U16Vec3;
+    use rerecast::AreaType;
+
+    use super::*;
+
+    /// How far detail vertices may drift in [`Navmesh::approx_eq`].
+    const TOLERANCE: f32 = 1.0e-4;
+
+    fn navmesh_1() -> Navmesh {
+        Navmesh::from_nav_bytes(include_bytes!(
+            "../../../assets/test/primitives/navmesh_1.nav"
+        ))
+        .unwrap()
+    }
+
+    fn navmesh_2() -> Navmesh {
+        Navmesh::from_nav_bytes(include_bytes!(
+            "../../../assets/test/primitives/navmesh_2.nav"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn size_accessors() {
+        let navmesh = navmesh_1();
+        assert!(!navmesh.is_empty());
+        assert_eq!(navmesh.polygon_count(), navmesh.polygon.polygon_count());
+        assert_eq!(navmesh.vertex_count(), navmesh.polygon.vertices.len());
+        assert_eq!(
+            navmesh.detail_triangle_count(),
+            navmesh.detail.triangles.len()
+        );
+
+        let mut empty = navmesh;
+        empty.polygon.polygons.clear();
+        assert!(empty.is_empty());
+        assert_eq!(empty.polygon_count(), 0);
+    }
+
+    /// Replaces the polygons of `template` with a `size` x `size` grid of unit quads starting at `origin`.
+    fn grid_navmesh(template: &Navmesh, origin: Vec3, size: u16) -> Navmesh {
+        const NC: u16 = PolygonNavmesh::NO_CONNECTION;
+        let vertex = |x: u16, z: u16| z * (size + 1) + x;
+        let poly = |x: u16, z: u16| z * size + x;
+        let mut polygon = PolygonNavmesh {
+            vertices: (0..=size)
+                .flat_map(|z| (0..=size).map(move |x| U16Vec3::new(x, 0, z)))
+                .collect(),
+            max_vertices_per_polygon: 4,
+            aabb: rerecast::Aabb3d {
+                min: origin,
+                max: origin + Vec3::new(size as f32, 0.0, size as f32),
+            },
+            cell_size: 1.0,
+            cell_height: 1.0,
+            ..Default::default()
+        };
+        for z in 0..size {
+            for x in 0..size {
+                polygon.polygons.extend([
+                    vertex(x, z),
+                    vertex(x, z + 1),
+                    vertex(x + 1, z + 1),
+                    vertex(x + 1, z),
+                ]);
+                polygon.polygon_neighbors.extend([
+                    if x > 0 { poly(x - 1, z) } else { NC },
+                    if z < size - 1 { poly(x, z + 1) } else { NC },
+                    if x < size - 1 { poly(x + 1, z) } else { NC },
+                    if z > 0 { poly(x, z - 1) } else { NC },
+                ]);
+            }
+        }
+        let polygon_count = usize::from(size * size);
+        polygon.flags = vec![0; polygon_count];
+        polygon.regions = vec![1.into(); polygon_count];
+        polygon.areas = vec![AreaType::DEFAULT_WALKABLE; polygon_count];
+
+        let mut navmesh = template.clone();
+        navmesh.polygon = polygon;
+        navmesh.detail = DetailNavmesh::default();
+        navmesh.clearances = vec![3.0; polygon_count];
+        navmesh.settings.up = Vec3::Y;
+        navmesh.settings.handedness = Handedness::default();
+        navmesh
+    }
+
+    #[test]
+    fn merge_adjacent_navmeshes() {
+        let template = navmesh_1();
+        let left = grid_navmesh(&template, Vec3::ZERO, 2);
+        let right = grid_navmesh(&template, Vec3::new(2.0, 0.0, 0.0), 2);
+        assert_eq!(left.polygon.find_polygon(Vec3::new(2.5, 0.0, 0.5)), None);
+
+        let merged = left.merge(&right).unwrap();
+        assert_eq!(merged.polygon_count(), 8);
+        assert_eq!(merged.settings, left.settings);
+        assert_eq!(merged.clearances.len(), 8);
+        // The vertices on the seam are welded.
+        assert_eq!(merged.vertex_count(), 15);
+        assert_eq!(merged.polygon.connected_components().len(), 1);
+
+        let (start, end) = (Vec3::new(0.5, 0.0, 0.5), Vec3::new(3.5, 0.0, 1.5));
+        let start_poly = merged.polygon.find_polygon(start).unwrap();
+        let end_poly = merged.polygon.find_polygon(end).unwrap();
+        assert!(start_poly < 4 && end_poly >= 4);
+        let corridor = merged
+            .polygon
+            .find_path(start_poly, end_poly, &HashSet::new())
+            .unwrap();
+        assert!(corridor.iter().any(|&poly| poly < 4) && corridor.iter().any(|&poly| poly >= 4));
+        let path = merged.polygon.find_smooth_path(start, end).unwrap();
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+
+        let mut coarse = right.clone();
+        coarse.polygon.cell_size = 2.0;
+        assert!(left.merge(&coarse).is_err());
+
+        // The origins must be a whole number of cells apart, up to float rounding.
+        let shifted = grid_navmesh(&template, Vec3::new(2.5, 0.0, 0.0), 2);
+        assert!(left.merge(&shifted).is_err());
+        let rounded = grid_navmesh(&template, Vec3::new(2.0 + 1.0e-5, 0.0, 0.0), 2);
+        assert_eq!(left.merge(&rounded).unwrap().vertex_count(), 15);
+    }
+
+    #[test]
+    fn merge_recomputes_metadata() {
+        let template = navmesh_1();
+        let mut left = grid_navmesh(&template, Vec3::ZERO, 2);
+        left.metadata = NavmeshMetadata {
+            source: Some("static".into()),
+            content_hash: Some(1),
+            settings_hash: Some(2),
+            vertex_count: Some(9),
+            ..Default::default()
+        };
+        let right = grid_navmesh(&template, Vec3::new(2.0, 0.0, 0.0), 2);
+
+        let merged = left.merge(&right).unwrap();
+        assert_eq!(merged.metadata.source.as_deref(), Some("static"));
+        assert_eq!(merged.metadata.content_hash, None);
+        assert_eq!(
+            merged.metadata.settings_hash,
+            Some(NavmeshMetadata::hash_settings(&left.settings))
+        );
+        assert_eq!(merged.metadata.vertex_count, Some(merged.vertex_count()));
+    }
+
+    #[test]
+    fn content_hash_ignores_settings() {
+        let navmesh = navmesh_1();
+        let other = navmesh_2();
+        assert_eq!(navmesh.content_hash(), navmesh.clone().content_hash());
+        assert_ne!(navmesh.content_hash(), other.content_hash());
+
+        let mut tweaked = navmesh.clone();
+        tweaked.settings.agent_radius *= 2.0;
+        tweaked.metadata.source = Some("elsewhere".into());
+        assert_eq!(tweaked.content_hash(), navmesh.content_hash());
+        tweaked.detail.vertices[0].y += 1.0;
+        assert_ne!(tweaked.content_hash(), navmesh.content_hash());
+    }
+
+    #[test]
+    fn approx_eq_tolerates_float_noise() {
+        let navmesh = navmesh_1();
+        let other = navmesh_2();
+        assert!(!navmesh.approx_eq(&other, TOLERANCE));
+
+        let mut noisy = navmesh.clone();
+        noisy.detail.vertices[0].y += TOLERANCE / 2.0;
+        assert!(noisy.approx_eq(&navmesh, TOLERANCE));
+        noisy.detail.vertices[0].y += TOLERANCE;
+        assert!(!noisy.approx_eq(&navmesh, TOLERANCE));
+
+        let mut moved = navmesh.clone();
+        moved.polygon.vertices[0].y += 1;
+        assert!(!moved.approx_eq(&navmesh, TOLERANCE));
+    }
 }
@@ -1,57 +1,272 @@
-use alloc::vec::Vec;
+use alloc::{sync::Arc, vec::Vec};
 use bevy_app::prelude::*;
-use bevy_asset::prelude::*;
+use bevy_asset::{RenderAssetUsages, prelude::*};
 use bevy_ecs::prelude::*;
-use bevy_mesh::{Mesh, Mesh3d, PrimitiveTopology};
+use bevy_math::bounding::Aabb3d;
+use bevy_mesh::{Indices, Mesh, Mesh2d, Mesh3d, PrimitiveTopology};
+use bevy_platform::collections::{HashMap, HashSet};
 use bevy_reflect::prelude::*;
-use bevy_transform::components::GlobalTransform;
+use bevy_tasks::{ComputeTaskPool, ParallelSlice as _, TaskPool};
+use bevy_transform::components::{GlobalTransform, Transform};
 use glam::{UVec3, Vec3A};
 use rerecast::{AreaType, TriMesh};
 
-use crate::{NavmeshApp as _, NavmeshSettings};
+use crate::{Navmesh, NavmeshApp as _, NavmeshSettings, generator::NavmeshMissingAssets};
 
 /// A backend for navmesh generation.
 /// Uses all entities with a [`Mesh3d`] component as navmesh obstacles.
+/// Generations wait for meshes that are still loading, see [`NavmeshMissingAssets`].
+/// The backend is added with [`NavmeshApp::add_navmesh_backend`](crate::NavmeshApp::add_navmesh_backend),
+/// so it can be combined with other backends.
 #[derive(Debug, Default)]
 #[non_exhaustive]
 pub struct Mesh3dBackendPlugin;
 
 impl Plugin for Mesh3dBackendPlugin {
     fn build(&self, app: &mut App) {
-        app.set_navmesh_backend(mesh3d_backend);
-        app.register_type::<ExcludeMeshFromNavmesh>();
+        app.add_navmesh_backend(mesh3d_backend);
+        app.add_navmesh_affector_bounds(mesh3d_affector_bounds);
+        app.register_type::<NavmeshExcluded>();
     }
 }
 
-/// Component to opt-out a [`Mesh3d`] from navmesh generation when using [`Mesh3dBackendPlugin`].
-/// If that backend is not used, this component has no effect.
+/// A backend for navmesh generation in 2D games.
+/// Uses all entities with a [`Mesh2d`] component as walkable ground, flattened onto the plane at [`Self::height`] along the Z axis.
+/// The backend is added with [`NavmeshApp::add_navmesh_backend`](crate::NavmeshApp::add_navmesh_backend),
+/// so it can be combined with other backends.
+///
+/// Since 2D meshes lie in the XY plane, use settings whose [`NavmeshSettings::up`] is [`Vec3::Z`](glam::Vec3::Z),
+/// such as [`NavmeshSettings::from_agent_2d`] or [`NavmeshSettings::top_down_2d`].
+/// The [`GlobalTransform`] of each mesh is applied before flattening, so 2D translations, rotations and scales are honored,
+/// while the Z translation used for sorting sprites is ignored.
+#[derive(Debug, Default, Clone)]
+#[non_exhaustive]
+pub struct Mesh2dBackendPlugin {
+    /// The height along the Z axis of the plane the meshes are flattened onto. Defaults to `0.0`.
+    pub height: f32,
+}
+
+impl Mesh2dBackendPlugin {
+    /// Sets [`Self::height`].
+    pub fn with_height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+}
+
+impl Plugin for Mesh2dBackendPlugin {
+    fn build(&self, app: &mut App) {
+        let height = self.height;
+        app.add_navmesh_backend(
+            move |input: In<NavmeshSettings>,
+                  meshes: Res<Assets<Mesh>>,
+                  obstacles: Mesh2dObstacles,
+                  mut missing: ResMut<NavmeshMissingAssets>| {
+                mesh2d_backend(input, &meshes, &obstacles, &mut missing, height)
+            },
+        );
+        app.add_navmesh_affector_bounds(
+            move |input: In<NavmeshSettings>,
+                  meshes: Res<Assets<Mesh>>,
+                  obstacles: Mesh2dObstacles| {
+                mesh2d_affector_bounds(input, &meshes, &obstacles, height)
+            },
+        );
+        app.register_type::<NavmeshExcluded>();
+    }
+}
+
+/// Component to opt-out a [`Mesh3d`] or [`Mesh2d`] from navmesh generation when using [`Mesh3dBackendPlugin`] or [`Mesh2dBackendPlugin`].
+/// Use it for decorative geometry that should not affect walkability.
+/// If neither backend is used, this component has no effect.
 #[derive(Debug, Default, Component, Reflect)]
 #[reflect(Component)]
-pub struct ExcludeMeshFromNavmesh;
+pub struct NavmeshExcluded;
+
+/// The obstacles collected by the last run of [`mesh3d_backend`].
+///
+/// Reused as long as no obstacle was added, removed, moved, or had its mesh changed, so that regenerating a static world
+/// with different settings, e.g. for agents of different sizes, only collects the obstacles once.
+/// Each mesh asset is converted into a [`TriMesh`] only once and shared by all obstacles using it,
+/// so worlds placing the same mesh many times, e.g. foliage or modular walls, don't hold a copy per instance.
+#[derive(Default)]
+struct Mesh3dObstacleCache {
+    /// The untransformed trimesh of each mesh used by an obstacle. `None` if the mesh is not loaded or not a triangle list.
+    meshes: HashMap<AssetId<Mesh>, Option<Arc<TriMesh>>>,
+    /// The placed obstacles, sorted by entity.
+    instances: Vec<(Entity, Transform, AssetId<Mesh>)>,
+}
 
 fn mesh3d_backend(
     input: In<NavmeshSettings>,
     meshes: Res<Assets<Mesh>>,
-    obstacles: Query<(Entity, &GlobalTransform, &Mesh3d), Without<ExcludeMeshFromNavmesh>>,
+    obstacles: Query<(Entity, Ref<GlobalTransform>, Ref<Mesh3d>), Without<NavmeshExcluded>>,
+    mut cache: Local<Mesh3dObstacleCache>,
+    mut missing: ResMut<NavmeshMissingAssets>,
 ) -> TriMesh {
-    obstacles
+    let mut obstacles = obstacles.iter().collect::<Vec<_>>();
+    // Merge in a stable order so that identical scenes always produce identical navmeshes.
+    obstacles.sort_by_key(|(entity, ..)| *entity);
+    // Change detection is relative to the last run of this system, so running it again for
+    // another navmesh in the same frame reuses the obstacles.
+    if meshes.is_changed() {
+        cache.meshes.clear();
+    }
+    let unchanged = obstacles
         .iter()
-        .filter_map(|(entity, transform, mesh)| {
-            if input
-                .filter
-                .as_ref()
-                .is_some_and(|entities| !entities.contains(&entity))
-            {
-                return None;
+        .all(|(_entity, transform, mesh)| !transform.is_changed() && !mesh.is_changed())
+        && cache
+            .instances
+            .iter()
+            .map(|(entity, ..)| *entity)
+            .eq(obstacles.iter().map(|(entity, ..)| *entity));
+    if !unchanged {
+        cache.instances = obstacles
+            .iter()
+            .map(|(entity, transform, mesh)| (*entity, transform.compute_transform(), mesh.id()))
+            .collect();
+        let used = cache
+            .instances
+            .iter()
+            .map(|(_entity, _transform, mesh)| *mesh)
+            .collect::<HashSet<_>>();
+        cache.meshes.retain(|id, _trimesh| used.contains(id));
+    }
+
+    // Only meshes that are not cached yet are converted, in parallel chunks.
+    let unconverted = cache
+        .instances
+        .iter()
+        .map(|(_entity, _transform, mesh)| *mesh)
+        .filter(|mesh| !cache.meshes.contains_key(mesh))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    if !unconverted.is_empty() {
+        let pool = ComputeTaskPool::get_or_init(TaskPool::default);
+        let converted = unconverted.par_splat_map(pool, None, |_index, ids| {
+            ids.iter()
+                .map(|id| {
+                    let trimesh = meshes.get(*id).and_then(TriMesh::from_mesh).map(Arc::new);
+                    (*id, trimesh)
+                })
+                .collect::<Vec<_>>()
+        });
+        cache.meshes.extend(converted.into_iter().flatten());
+    }
+
+    let instances = cache
+        .instances
+        .iter()
+        .filter(|(entity, ..)| input.includes(*entity))
+        .filter_map(|(_entity, transform, mesh)| {
+            let trimesh = cache.meshes.get(mesh)?.as_deref();
+            if trimesh.is_none() && !meshes.contains(*mesh) {
+                missing.push(mesh.untyped());
             }
+            Some((transform, trimesh?))
+        })
+        .collect::<Vec<_>>();
+    let mut combined = TriMesh::default();
+    combined.reserve(
+        instances
+            .iter()
+            .map(|(_transform, trimesh)| trimesh.vertices.len())
+            .sum(),
+        instances
+            .iter()
+            .map(|(_transform, trimesh)| trimesh.indices.len())
+            .sum(),
+    );
+    for (transform, trimesh) in instances {
+        // Transformed like `Mesh::transformed_by`, so that the obstacles match the meshes rendered by Bevy.
+        combined.extend_transformed(trimesh, |vertex| {
+            transform.transform_point(vertex.into()).into()
+        });
+    }
+    combined
+}
+
+type Mesh2dObstacles<'w, 's> =
+    Query<'w, 's, (Entity, &'static GlobalTransform, &'static Mesh2d), Without<NavmeshExcluded>>;
+
+fn mesh2d_backend(
+    input: In<NavmeshSettings>,
+    meshes: &Assets<Mesh>,
+    obstacles: &Mesh2dObstacles,
+    missing: &mut NavmeshMissingAssets,
+    height: f32,
+) -> TriMesh {
+    let mut obstacles = obstacles
+        .iter()
+        .filter(|(entity, ..)| input.includes(*entity))
+        .collect::<Vec<_>>();
+    // Merge in a stable order so that identical scenes always produce identical navmeshes.
+    obstacles.sort_by_key(|(entity, ..)| *entity);
+    obstacles
+        .into_iter()
+        .filter_map(|(_entity, transform, mesh)| {
             let transform = transform.compute_transform();
-            let mesh = meshes.get(mesh)?.clone().transformed_by(transform);
-            TriMesh::from_mesh(&mesh)
+            let Some(mesh) = meshes.get(mesh) else {
+                missing.push(mesh.id().untyped());
+                return None;
+            };
+            let mesh = mesh.clone().transformed_by(transform);
+            let mut trimesh = TriMesh::from_mesh(&mesh)?;
+            for vertex in &mut trimesh.vertices {
+                vertex.z = height;
+            }
+            Some(trimesh)
+        })
+        .collect()
+}
+
+fn mesh2d_affector_bounds(
+    input: In<NavmeshSettings>,
+    meshes: &Assets<Mesh>,
+    obstacles: &Mesh2dObstacles,
+    height: f32,
+) -> Vec<(Entity, Aabb3d)> {
+    obstacles
+        .iter()
+        .filter(|(entity, ..)| input.includes(*entity))
+        .filter_map(|(entity, transform, mesh)| {
+            let mut aabb = transformed_aabb(meshes.get(mesh)?, transform)?;
+            aabb.min.z = height;
+            aabb.max.z = height;
+            Some((entity, aabb))
         })
-        .fold(TriMesh::default(), |mut acc, t| {
-            acc.extend(t);
-            acc
+        .collect()
+}
+
+fn mesh3d_affector_bounds(
+    input: In<NavmeshSettings>,
+    meshes: Res<Assets<Mesh>>,
+    obstacles: Query<(Entity, &GlobalTransform, &Mesh3d), Without<NavmeshExcluded>>,
+) -> Vec<(Entity, Aabb3d)> {
+    obstacles
+        .iter()
+        .filter(|(entity, ..)| input.includes(*entity))
+        .filter_map(|(entity, transform, mesh)| {
+            Some((entity, transformed_aabb(meshes.get(mesh)?, transform)?))
         })
+        .collect()
+}
+
+/// The bounds of the vertices of `mesh` after applying `transform`.
+fn transformed_aabb(mesh: &Mesh, transform: &GlobalTransform) -> Option<Aabb3d> {
+    let positions = mesh.attribute(Mesh::ATTRIBUTE_POSITION)?.as_float3()?;
+    let mut points = positions
+        .iter()
+        .map(|&position| transform.transform_point(position.into()));
+    let first = points.next()?;
+    let (min, max) = points.fold((first, first), |(min, max), point| {
+        (min.min(point), max.max(point))
+    });
+    Some(Aabb3d {
+        min: min.into(),
+        max: max.into(),
+    })
 }
 
 /// Used to add [`TriMeshFromBevyMesh::from_mesh`] to [`TriMesh`].
@@ -86,3 +301,58 @@ impl TriMeshFromBevyMesh for TriMesh {
         Some(trimesh)
     }
 }
+
+impl Navmesh {
+    /// Builds a [`PrimitiveTopology::TriangleList`] [`Mesh`] from the triangles of the [`Navmesh::detail`],
+    /// with positions, normals, and indices. This is the counterpart of [`TriMeshFromBevyMesh::from_mesh`].
+    ///
+    /// Spawn it as a [`Mesh3d`] with a transparent material to visualize the walkable surface in-game.
+    /// Remember to add [`NavmeshExcluded`] so that the overlay is not picked up as an obstacle.
+    pub fn to_bevy_mesh(&self) -> Mesh {
+        let detail = &self.detail;
+        let mut indices = Vec::with_capacity(detail.triangles.len() * 3);
+        for submesh in &detail.meshes {
+            let triangles = &detail.triangles[submesh.base_triangle_index as usize..]
+                [..submesh.triangle_count as usize];
+            for triangle in triangles {
+                indices.extend(
+                    triangle
+                        .iter()
+                        .map(|&index| submesh.base_vertex_index + u32::from(index)),
+                );
+            }
+        }
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::all());
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, detail.vertices.clone());
+        mesh.insert_indices(Indices::U32(indices));
+        mesh.compute_normals();
+        mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bevy_mesh_export_matches_detail_mesh() {
+        let navmesh =
+            Navmesh::from_nav_bytes(include_bytes!("../../../assets/test/dungeon/navmesh.nav"))
+                .unwrap();
+        let mesh = navmesh.to_bevy_mesh();
+
+        assert_eq!(mesh.primitive_topology(), PrimitiveTopology::TriangleList);
+        assert_eq!(mesh.count_vertices(), navmesh.detail.vertices.len());
+        assert!(mesh.attribute(Mesh::ATTRIBUTE_NORMAL).is_some());
+
+        let trimesh = TriMesh::from_mesh(&mesh).unwrap();
+        assert_eq!(trimesh.indices.len(), navmesh.detail_triangle_count());
+        let submesh = &navmesh.detail.meshes[1];
+        let triangle = navmesh.detail.triangles[submesh.base_triangle_index as usize];
+        assert_eq!(
+            trimesh.indices[submesh.base_triangle_index as usize],
+            UVec3::from_array(triangle.map(|index| submesh.base_vertex_index + u32::from(index)))
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use alloc::{collections::BTreeMap, string::String};
+use bevy_reflect::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::NavmeshSettings;
+
+/// Free-form information about a [`Navmesh`](crate::Navmesh), e.g. to track its provenance in an asset pipeline.
+///
+/// The metadata is saved and loaded together with the navmesh.
+/// Navmeshes generated by the [`NavmeshGenerator`](crate::generator::NavmeshGenerator) have [`Self::settings_hash`]
+/// and [`Self::vertex_count`] filled in. Everything else is up to the user.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+#[serde(default)]
+pub struct NavmeshMetadata {
+    /// Arbitrary key-value pairs. Sorted by key, so that identical metadata is always saved identically.
+    pub entries: BTreeMap<String, String>,
+    /// The name of the scene the navmesh was generated from.
+    pub source: Option<String>,
+    /// When the navmesh was built, e.g. in seconds since the Unix epoch.
+    pub timestamp: Option<u64>,
+    /// A hash of the content the navmesh was generated from, e.g. of the level files.
+    pub content_hash: Option<u64>,
+    /// The hash of the [`NavmeshSettings`] the navmesh was generated with, see [`NavmeshMetadata::hash_settings`].
+    pub settings_hash: Option<u64>,
+    /// The number of vertices of the [`Navmesh::polygon`](crate::Navmesh::polygon) when it was generated.
+    pub vertex_count: Option<usize>,
+}
+
+impl NavmeshMetadata {
+    /// Hashes the serialized `settings` with 64-bit FNV-1a, which is stable across platforms and versions of Rust.
+    /// Settings with a [`NavmeshSettings::filter`] hash differently each time, since the entities are not sorted.
+    pub fn hash_settings(settings: &NavmeshSettings) -> u64 {
+        let mut hasher = Fnv1a::new();
+        hasher.hash(settings);
+        hasher.finish()
+    }
+}
+
+/// 64-bit FNV-1a over the bincode encoding of values. Unlike the default hasher of a `HashMap`,
+/// this is deterministic across runs and platforms.
+pub(crate) struct Fnv1a(u64);
+
+impl Fnv1a {
+    pub(crate) fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub(crate) fn hash(&mut self, value: &impl Serialize) {
+        // Writing into the hasher can't fail, and neither can encoding the navmesh types.
+        let _ = bincode::serde::encode_into_writer(value, &mut *self, bincode::config::standard());
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl bincode::enc::write::Writer for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), bincode::error::EncodeError> {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+//! Compact binary encoding of [`Navmesh`]es for sending them over the network.
+
+use alloc::vec::Vec;
+use flate2::{Compression, read::ZlibDecoder, write::ZlibEncoder};
+use std::io::{Read as _, Write as _};
+use thiserror::Error;
+
+use crate::{Navmesh, asset_loader::NavmeshLoaderError};
+
+/// The maximum size of a decompressed blob accepted by [`Navmesh::from_network_bytes`],
+/// so that a small malicious blob can't decompress into gigabytes.
+const MAX_DECOMPRESSED_BYTES: u64 = 1 << 30;
+
+/// The compression tag of a blob whose `.nav` bytes are stored as they are.
+const UNCOMPRESSED: u8 = 0;
+/// The compression tag of a blob whose `.nav` bytes are compressed with zlib.
+const ZLIB: u8 = 1;
+
+impl Navmesh {
+    /// Encodes the navmesh into a compressed blob suitable for sending over the network.
+    /// Use [`Navmesh::from_network_bytes`] to decode it again.
+    ///
+    /// The blob is a one-byte compression tag followed by the compressed contents of a `.nav` file,
+    /// see [`Navmesh::to_nav_bytes`], so it is versioned the same way.
+    ///
+    /// This is meant for transferring navmeshes at runtime, e.g. from a server to its clients.
+    /// To load navmeshes from disk, use the [`NavmeshLoader`](crate::asset_loader::NavmeshLoader) instead.
+    pub fn to_network_bytes(&self) -> Vec<u8> {
+        let bytes = self.to_nav_bytes();
+        let mut encoder = ZlibEncoder::new(vec![ZLIB], Compression::best());
+        encoder
+            .write_all(&bytes)
+            .expect("Writing into memory cannot fail");
+        encoder.finish().expect("Writing into memory cannot fail")
+    }
+
+    /// Decodes a navmesh from a blob created by [`Navmesh::to_network_bytes`].
+    ///
+    /// Like [`Navmesh::from_nav_bytes`], the blob is not trusted: corrupted or malicious blobs result in an error
+    /// instead of a panic or a huge allocation.
+    pub fn from_network_bytes(blob: &[u8]) -> Result<Self, NavmeshNetworkError> {
+        let Some((&tag, data)) = blob.split_first() else {
+            return Err(NavmeshNetworkError::MissingCompressionTag);
+        };
+        let bytes = match tag {
+            UNCOMPRESSED => data.to_vec(),
+            ZLIB => {
+                let mut decoder = ZlibDecoder::new(data).take(MAX_DECOMPRESSED_BYTES + 1);
+                let mut bytes = Vec::new();
+                decoder.read_to_end(&mut bytes)?;
+                if bytes.len() as u64 > MAX_DECOMPRESSED_BYTES {
+                    return Err(NavmeshNetworkError::TooLarge);
+                }
+                bytes
+            }
+            tag => return Err(NavmeshNetworkError::UnknownCompressionTag(tag)),
+        };
+        Ok(Self::from_nav_bytes(&bytes)?)
+    }
+}
+
+/// Errors that can occur when decoding a navmesh with [`Navmesh::from_network_bytes`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum NavmeshNetworkError {
+    /// The blob is empty, so it does not even contain the compression tag.
+    #[error("Could not decode navmesh: Expected a compression tag")]
+    MissingCompressionTag,
+    /// The blob starts with a compression tag that this crate doesn't know.
+    #[error("Could not decode navmesh: Unknown compression tag {0}")]
+    UnknownCompressionTag(u8),
+    /// An error occurred while decompressing the navmesh.
+    #[error("Could not decompress navmesh: {0}")]
+    DecompressError(#[from] std::io::Error),
+    /// The decompressed navmesh is larger than the limit that protects against decompression bombs.
+    #[error(
+        "Could not decompress navmesh: Decompressed data is larger than {MAX_DECOMPRESSED_BYTES} bytes"
+    )]
+    TooLarge,
+    /// The decompressed navmesh is not a valid `.nav` file.
+    #[error(transparent)]
+    DecodeError(#[from] NavmeshLoaderError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dungeon() -> Navmesh {
+        Navmesh::from_nav_bytes(include_bytes!("../../../assets/test/dungeon/navmesh.nav")).unwrap()
+    }
+
+    #[test]
+    fn network_bytes_roundtrip() {
+        let navmesh = dungeon();
+        let blob = navmesh.to_network_bytes();
+        let decoded = Navmesh::from_network_bytes(&blob).unwrap();
+        assert_eq!(navmesh, decoded, "Navmesh changed after network roundtrip");
+
+        let uncompressed = navmesh.to_nav_bytes();
+        assert!(
+            blob.len() < uncompressed.len(),
+            "Network blob ({} bytes) should be smaller than the .nav bytes ({} bytes)",
+            blob.len(),
+            uncompressed.len()
+        );
+    }
+
+    #[test]
+    fn network_bytes_rejects_malformed_blobs() {
+        let mut uncompressed = vec![UNCOMPRESSED];
+        uncompressed.extend(dungeon().to_nav_bytes());
+        assert!(Navmesh::from_network_bytes(&uncompressed).is_ok());
+
+        assert!(matches!(
+            Navmesh::from_network_bytes(&[]),
+            Err(NavmeshNetworkError::MissingCompressionTag)
+        ));
+        assert!(matches!(
+            Navmesh::from_network_bytes(&[42, 0]),
+            Err(NavmeshNetworkError::UnknownCompressionTag(42))
+        ));
+        let truncated = &uncompressed[..uncompressed.len() / 2];
+        assert!(matches!(
+            Navmesh::from_network_bytes(truncated),
+            Err(NavmeshNetworkError::DecodeError(
+                NavmeshLoaderError::Truncated { .. }
+            ))
+        ));
+    }
+}
@@ -0,0 +1,179 @@
+//! Export of [`Navmesh`]es to the Wavefront OBJ format for inspecting them in DCC tools such as Blender.
+
+use alloc::string::String;
+use core::fmt::Write as _;
+
+use glam::Vec3;
+
+use crate::{CoordinateRemap, Navmesh};
+
+impl Navmesh {
+    /// Writes the navmesh as a Wavefront OBJ, e.g. to inspect a bake in Blender.
+    ///
+    /// The OBJ contains two objects in the coordinate system of the obstacles:
+    /// - `detail`: The vertices and triangles of the [`Navmesh::detail`] as faces.
+    /// - `polygon`: The outlines of the polygons of the [`Navmesh::polygon`] as lines, for comparison.
+    ///
+    /// Vertices shared between sub-meshes of the detail mesh are written once per sub-mesh, just like they are stored.
+    pub fn to_obj(&self) -> String {
+        let mut obj = String::new();
+        // Writing into a `String` cannot fail.
+        let _ = self.write_obj(&mut obj);
+        obj
+    }
+
+    fn write_obj(&self, obj: &mut String) -> core::fmt::Result {
+        writeln!(obj, "# Navmesh exported by bevy_rerecast")?;
+
+        let detail = &self.detail;
+        writeln!(obj, "o detail")?;
+        for vertex in &detail.vertices {
+            write_vertex(obj, *vertex)?;
+        }
+        for mesh in &detail.meshes {
+            let triangles = &detail.triangles[mesh.base_triangle_index as usize..]
+                [..mesh.triangle_count as usize];
+            // OBJ indices are 1-based and global.
+            let base = mesh.base_vertex_index as usize + 1;
+            for [a, b, c] in triangles {
+                let [a, b, c] = [a, b, c].map(|&index| base + index as usize);
+                writeln!(obj, "f {a} {b} {c}")?;
+            }
+        }
+
+        let mesh = &self.polygon;
+        writeln!(obj, "o polygon")?;
+        let origin = mesh.aabb.min;
+        let to_local =
+            CoordinateRemap::from(&self.settings).cell_scale(mesh.cell_size, mesh.cell_height);
+        for vertex in &mesh.vertices {
+            write_vertex(obj, origin + vertex.as_vec3() * to_local)?;
+        }
+        let base = detail.vertices.len() + 1;
+        for polygon in mesh.polygons() {
+            let mut indices = polygon.map(|index| base + index as usize);
+            let Some(first) = indices.next() else {
+                continue;
+            };
+            write!(obj, "l {first}")?;
+            for index in indices {
+                write!(obj, " {index}")?;
+            }
+            // Close the outline.
+            writeln!(obj, " {first}")?;
+        }
+        Ok(())
+    }
+}
+
+fn write_vertex(obj: &mut String, vertex: Vec3) -> core::fmt::Result {
+    writeln!(obj, "v {} {} {}", vertex.x, vertex.y, vertex.z)
+}
+
+#[cfg(all(test, feature = "bevy_asset"))]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::Handedness;
+
+    use super::*;
+
+    fn dungeon() -> Navmesh {
+        Navmesh::from_nav_bytes(include_bytes!("../../../assets/test/dungeon/navmesh.nav")).unwrap()
+    }
+
+    /// Parses the vertices of the object `name` from an OBJ written by [`Navmesh::to_obj`].
+    fn object_vertices(obj: &str, name: &str) -> Vec<Vec3> {
+        let mut object = "";
+        let mut vertices = Vec::new();
+        for line in obj.lines() {
+            let mut tokens = line.split_whitespace();
+            match tokens.next() {
+                Some("o") => object = tokens.next().unwrap(),
+                Some("v") if object == name => {
+                    let [x, y, z] = [(); 3].map(|_| tokens.next().unwrap().parse::<f32>().unwrap());
+                    vertices.push(Vec3::new(x, y, z));
+                }
+                _ => {}
+            }
+        }
+        vertices
+    }
+
+    #[test]
+    fn obj_export_matches_detail_mesh() {
+        let navmesh = dungeon();
+        let obj = navmesh.to_obj();
+
+        let mut vertices = Vec::new();
+        let mut faces = Vec::new();
+        let mut lines = 0;
+        let mut object = "";
+        for line in obj.lines() {
+            let mut tokens = line.split_whitespace();
+            match tokens.next() {
+                Some("o") => object = tokens.next().unwrap(),
+                Some("v") => {
+                    let [x, y, z] = [(); 3].map(|_| tokens.next().unwrap().parse::<f32>().unwrap());
+                    vertices.push((object, Vec3::new(x, y, z)));
+                }
+                Some("f") => {
+                    assert_eq!(object, "detail");
+                    let face: Vec<usize> = tokens.map(|index| index.parse().unwrap()).collect();
+                    assert_eq!(face.len(), 3);
+                    faces.push(face);
+                }
+                Some("l") => {
+                    assert_eq!(object, "polygon");
+                    lines += 1;
+                }
+                _ => {}
+            }
+        }
+
+        let detail_vertices: Vec<Vec3> = vertices
+            .iter()
+            .filter(|(object, _vertex)| *object == "detail")
+            .map(|(_object, vertex)| *vertex)
+            .collect();
+        assert_eq!(detail_vertices, navmesh.detail.vertices);
+        assert_eq!(faces.len(), navmesh.detail_triangle_count());
+        assert!(
+            faces
+                .iter()
+                .flatten()
+                .all(|&index| (1..=detail_vertices.len()).contains(&index))
+        );
+        assert_eq!(
+            vertices.len() - detail_vertices.len(),
+            navmesh.vertex_count()
+        );
+        assert_eq!(lines, navmesh.polygon_count());
+    }
+
+    #[test]
+    fn obj_export_round_trips_z_up() {
+        let y_up = dungeon();
+        let remap = CoordinateRemap {
+            up: Vec3::Z,
+            handedness: Handedness::default(),
+        };
+        let mut z_up = y_up.clone();
+        z_up.settings.up = Vec3::Z;
+        remap.polygon_from_recast(&mut z_up.polygon);
+        for vertex in &mut z_up.detail.vertices {
+            *vertex = remap.from_recast(*vertex);
+        }
+
+        let expected = object_vertices(&y_up.to_obj(), "polygon");
+        let actual = object_vertices(&z_up.to_obj(), "polygon");
+        assert_eq!(actual.len(), expected.len());
+        for (actual, expected) in actual.into_iter().zip(expected) {
+            let actual = remap.to_recast(actual);
+            assert!(
+                actual.abs_diff_eq(expected, 1.0e-4),
+                "{actual} != {expected}"
+            );
+        }
+    }
+}
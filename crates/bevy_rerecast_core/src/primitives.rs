@@ -0,0 +1,155 @@
+use alloc::vec::Vec;
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_math::{
+    bounding::Aabb3d,
+    primitives::{Cuboid, Cylinder, Plane3d, Sphere},
+};
+use bevy_mesh::{Mesh, MeshBuilder as _, Meshable as _};
+use bevy_reflect::prelude::*;
+use bevy_transform::components::{GlobalTransform, Transform};
+use rerecast::TriMesh;
+
+use crate::{CombineTriMeshes as _, NavmeshApp as _, NavmeshSettings, TriMeshFromBevyMesh as _};
+
+/// A backend for navmesh generation.
+/// Uses all entities with a [`NavmeshPrimitive`] component as navmesh obstacles, without going through a [`Mesh`] asset.
+/// This is handy for blocking out e.g. ramps and walls that only exist for the navmesh.
+///
+/// The backend is added with [`NavmeshApp::add_navmesh_backend`](crate::NavmeshApp::add_navmesh_backend),
+/// so it can be combined with other backends, e.g. the [`Mesh3dBackendPlugin`](crate::Mesh3dBackendPlugin).
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct PrimitiveBackendPlugin {
+    /// The number of segments curved primitives are tessellated into around their circumference,
+    /// i.e. the resolution of a [`Cylinder`] and the number of sectors of a [`Sphere`], which has half as many stacks.
+    /// Higher values follow the shapes more closely at the cost of more triangles to rasterize. Defaults to 16.
+    pub resolution: u32,
+}
+
+impl Default for PrimitiveBackendPlugin {
+    fn default() -> Self {
+        Self { resolution: 16 }
+    }
+}
+
+impl PrimitiveBackendPlugin {
+    /// Sets [`Self::resolution`].
+    pub fn with_resolution(mut self, resolution: u32) -> Self {
+        self.resolution = resolution;
+        self
+    }
+}
+
+impl Plugin for PrimitiveBackendPlugin {
+    fn build(&self, app: &mut App) {
+        let resolution = self.resolution;
+        app.add_navmesh_backend(
+            move |input: In<NavmeshSettings>, primitives: PrimitiveObstacles| {
+                primitive_backend(input, &primitives, resolution)
+            },
+        );
+        app.add_navmesh_affector_bounds(
+            move |input: In<NavmeshSettings>, primitives: PrimitiveObstacles| {
+                primitive_affector_bounds(input, &primitives, resolution)
+            },
+        );
+        app.register_type::<NavmeshPrimitive>();
+    }
+}
+
+/// A primitive shape used as navmesh obstacle by the [`PrimitiveBackendPlugin`].
+/// Like a `Mesh3d`, it is centered on the entity and placed in the world by its [`GlobalTransform`].
+#[derive(Debug, Clone, Copy, PartialEq, Component, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub enum NavmeshPrimitive {
+    /// A box.
+    Cuboid(Cuboid),
+    /// A cylinder along the local Y axis, tessellated with [`PrimitiveBackendPlugin::resolution`] segments.
+    Cylinder(Cylinder),
+    /// A sphere, tessellated with [`PrimitiveBackendPlugin::resolution`] sectors.
+    Sphere(Sphere),
+    /// A bounded plane, e.g. for a floor or a ramp.
+    Plane3d(Plane3d),
+}
+
+impl NavmeshPrimitive {
+    /// Tessellates the primitive into a [`TriMesh`] in local space.
+    /// `resolution` is used for curved primitives, see [`PrimitiveBackendPlugin::resolution`].
+    pub fn to_trimesh(&self, resolution: u32) -> TriMesh {
+        let mesh: Mesh = match *self {
+            Self::Cuboid(cuboid) => cuboid.mesh().build(),
+            Self::Cylinder(cylinder) => cylinder.mesh().resolution(resolution).build(),
+            Self::Sphere(sphere) => sphere.mesh().uv(resolution, (resolution / 2).max(2)),
+            Self::Plane3d(plane) => plane.mesh().build(),
+        };
+        // The meshes of primitives are always indexed triangle lists.
+        TriMesh::from_mesh(&mesh).unwrap_or_default()
+    }
+}
+
+impl From<Cuboid> for NavmeshPrimitive {
+    fn from(cuboid: Cuboid) -> Self {
+        Self::Cuboid(cuboid)
+    }
+}
+
+impl From<Cylinder> for NavmeshPrimitive {
+    fn from(cylinder: Cylinder) -> Self {
+        Self::Cylinder(cylinder)
+    }
+}
+
+impl From<Sphere> for NavmeshPrimitive {
+    fn from(sphere: Sphere) -> Self {
+        Self::Sphere(sphere)
+    }
+}
+
+impl From<Plane3d> for NavmeshPrimitive {
+    fn from(plane: Plane3d) -> Self {
+        Self::Plane3d(plane)
+    }
+}
+
+type PrimitiveObstacles<'w, 's> =
+    Query<'w, 's, (Entity, &'static GlobalTransform, &'static NavmeshPrimitive)>;
+
+fn primitive_backend(
+    input: In<NavmeshSettings>,
+    primitives: &PrimitiveObstacles,
+    resolution: u32,
+) -> TriMesh {
+    let mut primitives = primitives
+        .iter()
+        .filter(|(entity, ..)| input.includes(*entity))
+        .collect::<Vec<_>>();
+    // Merge in a stable order so that identical scenes always produce identical navmeshes.
+    primitives.sort_by_key(|(entity, ..)| *entity);
+    TriMesh::combine(
+        primitives
+            .into_iter()
+            .map(|(_entity, transform, primitive)| (*transform, primitive.to_trimesh(resolution))),
+    )
+}
+
+fn primitive_affector_bounds(
+    input: In<NavmeshSettings>,
+    primitives: &PrimitiveObstacles,
+    resolution: u32,
+) -> Vec<(Entity, Aabb3d)> {
+    primitives
+        .iter()
+        .filter(|(entity, ..)| input.includes(*entity))
+        .filter_map(|(entity, transform, primitive)| {
+            let trimesh = TriMesh::combine([(*transform, primitive.to_trimesh(resolution))]);
+            let aabb = trimesh.compute_aabb()?;
+            let aabb = Aabb3d {
+                min: aabb.min.into(),
+                max: aabb.max.into(),
+            };
+            Some((entity, aabb))
+        })
+        .collect()
+}
@@ -0,0 +1,389 @@
+//! Queries on generated navmeshes, such as pathfinding between entities.
+
+use alloc::{borrow::Cow, sync::Arc, vec::Vec};
+use bevy_app::prelude::*;
+use bevy_asset::{AssetEventSystems, prelude::*};
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::{prelude::*, system::SystemParam};
+use bevy_math::ops;
+use bevy_platform::collections::HashMap;
+use bevy_tasks::{AsyncComputeTaskPool, Task};
+use bevy_transform::components::GlobalTransform;
+use glam::{Vec3, Vec3A, Vec3Swizzles as _};
+use rerecast::{FlowField, PolygonNavmesh};
+
+use crate::{CoordinateRemap, Navmesh, generator::height_at};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<RecastPolygons>()
+        .init_resource::<SharedPolygons>();
+    app.add_systems(
+        PostUpdate,
+        (update_recast_polygons, update_shared_polygons).after(AssetEventSystems),
+    );
+}
+
+/// System parameter for querying navmeshes, e.g. to find paths for agents.
+///
+/// Positions are in world space, i.e. in the coordinate system of the obstacles the navmesh was generated from.
+/// See [`CoordinateRemap`].
+#[derive(SystemParam)]
+pub struct NavmeshQueries<'w, 's> {
+    #[system_param(
+        validation_message = "Failed to find `Assets<Navmesh>`. Did you forget to add `NavmeshPlugins` to your app?"
+    )]
+    navmeshes: Res<'w, Assets<Navmesh>>,
+    recast_polygons: Res<'w, RecastPolygons>,
+    shared_polygons: Res<'w, SharedPolygons>,
+    transforms: Query<'w, 's, &'static GlobalTransform>,
+}
+
+impl NavmeshQueries<'_, '_> {
+    /// The default half extents of the box in which [`Self::find_path_between`] searches for the polygons
+    /// nearest to the entities, in world units. This is enough for agents of about human size whose origin
+    /// is anywhere between their feet and their head.
+    pub const DEFAULT_HALF_EXTENTS: Vec3 = Vec3::new(2.0, 4.0, 2.0);
+
+    /// Snaps the [`GlobalTransform`]s of the entities `start` and `end` to the navmesh and returns
+    /// the smoothed path between them, using [`Self::DEFAULT_HALF_EXTENTS`].
+    /// Polygons with less than `min_clearance` of headroom are avoided, see [`Self::find_path`].
+    ///
+    /// Returns `None` if the navmesh is not loaded, either entity has no [`GlobalTransform`],
+    /// either entity is too far away from the navmesh, or there is no connection between them.
+    pub fn find_path_between(
+        &self,
+        navmesh: impl Into<AssetId<Navmesh>>,
+        start: Entity,
+        end: Entity,
+        min_clearance: f32,
+    ) -> Option<Vec<Vec3>> {
+        let start = self.transforms.get(start).ok()?.translation();
+        let end = self.transforms.get(end).ok()?.translation();
+        self.find_path(
+            navmesh,
+            start,
+            end,
+            Self::DEFAULT_HALF_EXTENTS,
+            min_clearance,
+        )
+    }
+
+    /// Snaps `start` and `end` to the nearest polygons within `half_extents` and returns the smoothed path between them.
+    /// See [`PolygonNavmesh::find_nearest_smooth_path`](rerecast::PolygonNavmesh::find_nearest_smooth_path) for details.
+    ///
+    /// Polygons whose [`Navmesh::clearances`] are below `min_clearance` are neither snapped to nor walked through.
+    /// Pass the height of the agent to keep it out from under low ceilings, or 0 to consider all polygons.
+    ///
+    /// Returns `None` if the navmesh is not loaded, either point is too far away from the navmesh,
+    /// or there is no connection between them.
+    pub fn find_path(
+        &self,
+        navmesh: impl Into<AssetId<Navmesh>>,
+        start: Vec3,
+        end: Vec3,
+        half_extents: Vec3,
+        min_clearance: f32,
+    ) -> Option<Vec<Vec3>> {
+        let (navmesh, polygon, remap) = self.get(navmesh)?;
+        let passable = |poly: u32| {
+            navmesh
+                .clearances
+                .get(poly as usize)
+                .is_none_or(|&clearance| clearance >= min_clearance)
+        };
+        let half_extents = remap.to_recast(half_extents).abs();
+        let path = polygon.find_nearest_smooth_path_where(
+            remap.to_recast(start),
+            remap.to_recast(end),
+            half_extents,
+            passable,
+        )?;
+        Some(
+            path.into_iter()
+                .map(|point| remap.from_recast(point))
+                .collect(),
+        )
+    }
+
+    /// Finds the corridor of polygons from `start_poly` to `end_poly` on the [`AsyncComputeTaskPool`],
+    /// so that searching long corridors on big navmeshes does not stall the frame.
+    /// See [`PolygonNavmesh::find_path`](rerecast::PolygonNavmesh::find_path) for details.
+    ///
+    /// The task shares the polygons of the navmesh instead of copying them, so later changes to the navmesh asset do not affect it.
+    /// Polygons whose [`Navmesh::clearances`] are below `min_clearance` are not walked through, like in [`Self::find_path`].
+    /// The task resolves to `None` if the navmesh is not loaded or there is no connection between the polygons.
+    pub fn find_path_async(
+        &self,
+        navmesh: impl Into<AssetId<Navmesh>>,
+        start_poly: u32,
+        end_poly: u32,
+        min_clearance: f32,
+    ) -> Task<Option<Vec<u32>>> {
+        let id = navmesh.into();
+        let shared = self.shared_polygons.get(&id).cloned().or_else(|| {
+            // Navmeshes added in this frame have not been shared yet.
+            self.navmeshes.get(id).map(SharedPolygon::new)
+        });
+        AsyncComputeTaskPool::get().spawn(async move {
+            let SharedPolygon {
+                polygon,
+                clearances,
+            } = &*shared?;
+            polygon.find_path_where(start_poly, end_poly, |poly| {
+                clearances
+                    .get(poly as usize)
+                    .is_none_or(|&clearance| clearance >= min_clearance)
+            })
+        })
+    }
+
+    /// Snaps `goal` to the nearest polygon within [`Self::DEFAULT_HALF_EXTENTS`] and builds a [`FlowField`] towards it.
+    /// See [`PolygonNavmesh::build_flow_field`](rerecast::PolygonNavmesh::build_flow_field) for details.
+    ///
+    /// Agents sharing the goal look up the polygon they are on and follow [`FlowField::next`]
+    /// instead of each searching for a path.
+    ///
+    /// Returns `None` if the navmesh is not loaded or `goal` is too far away from the navmesh.
+    pub fn build_flow_field(
+        &self,
+        navmesh: impl Into<AssetId<Navmesh>>,
+        goal: Vec3,
+    ) -> Option<FlowField> {
+        let (_navmesh, polygon, remap) = self.get(navmesh)?;
+        let half_extents = remap.to_recast(Self::DEFAULT_HALF_EXTENTS).abs();
+        let (goal_poly, _goal) =
+            polygon.find_nearest_polygon(remap.to_recast(goal), half_extents)?;
+        Some(polygon.build_flow_field(goal_poly))
+    }
+
+    /// Returns the distance from `point` to the nearest wall of the navmesh within `max_radius`,
+    /// together with the nearest point on that wall, e.g. to orient an agent taking cover.
+    /// See [`PolygonNavmesh::distance_to_wall`](rerecast::PolygonNavmesh::distance_to_wall) for details.
+    ///
+    /// Returns `None` if the navmesh is not loaded, `point` is not on the navmesh, or there is no wall within `max_radius`.
+    pub fn distance_to_wall(
+        &self,
+        navmesh: impl Into<AssetId<Navmesh>>,
+        point: Vec3,
+        max_radius: f32,
+    ) -> Option<(f32, Vec3)> {
+        let (_navmesh, polygon, remap) = self.get(navmesh)?;
+        let (distance, wall) = polygon.distance_to_wall(remap.to_recast(point), max_radius)?;
+        Some((distance, remap.from_recast(wall)))
+    }
+
+    /// Moves from `start` in the polygon `start_poly` towards `target`, sliding along the walls it runs into.
+    /// Returns the reached position, which is always on the navmesh, and the polygons walked through.
+    /// See [`PolygonNavmesh::move_along_surface`](rerecast::PolygonNavmesh::move_along_surface) for details.
+    ///
+    /// Returns `None` if the navmesh is not loaded or has no polygon `start_poly`.
+    pub fn move_along_surface(
+        &self,
+        navmesh: impl Into<AssetId<Navmesh>>,
+        start_poly: u32,
+        start: Vec3,
+        target: Vec3,
+    ) -> Option<(Vec3, Vec<u32>)> {
+        let (_navmesh, polygon, remap) = self.get(navmesh)?;
+        if start_poly as usize >= polygon.polygon_count() {
+            return None;
+        }
+        let (position, polys) =
+            polygon.move_along_surface(start_poly, remap.to_recast(start), remap.to_recast(target));
+        Some((remap.from_recast(position), polys))
+    }
+
+    /// Returns the height of the navmesh surface below or above `point`, sampled from the triangles of [`Navmesh::detail`].
+    /// Unlike the polygons, the detail mesh follows ramps, stairs and uneven ground closely.
+    ///
+    /// The height is the coordinate along [`NavmeshSettings::up`](crate::NavmeshSettings::up), e.g. `y` for Y-up worlds.
+    /// If several floors lie above each other, the height of the floor nearest to `point` is returned,
+    /// e.g. pass the current position of an agent to stay on its floor.
+    ///
+    /// Returns `None` if the navmesh is not loaded, has no detail mesh, or does not cover `point`.
+    pub fn height_at(&self, navmesh: impl Into<AssetId<Navmesh>>, point: Vec3) -> Option<f32> {
+        let (navmesh, polygon, remap) = self.get(navmesh)?;
+        // Recast's Y axis is the up axis of the world, so the height needs no conversion back.
+        detail_height_at(navmesh, &polygon, remap.to_recast(point))
+    }
+
+    /// Returns `true` if `point` lies on the navmesh, i.e. within the footprint of one of its polygons
+    /// and at most `vertical_tolerance` above or below its surface. Use this to validate spawn points
+    /// or to check whether a point is worth a path query at all.
+    ///
+    /// Since the polygons are quantized to cells, points up to one cell size outside of their footprint are accepted as well.
+    /// Points outside of the [`Navmesh::aabb`] are rejected right away. The surface is sampled from
+    /// the [`Navmesh::detail`] like in [`Self::height_at`], or from the [`Navmesh::polygon`] if there is no detail mesh.
+    ///
+    /// Returns `false` if the navmesh is not loaded.
+    pub fn contains_point(
+        &self,
+        navmesh: impl Into<AssetId<Navmesh>>,
+        point: Vec3,
+        vertical_tolerance: f32,
+    ) -> bool {
+        let Some((navmesh, polygon, remap)) = self.get(navmesh) else {
+            return false;
+        };
+        // The polygons are quantized to cells, so points within a cell of their footprint count as on them.
+        let horizontal_tolerance = polygon.cell_size;
+        let aabb = navmesh.aabb();
+        let margin = Vec3A::from(
+            remap
+                .from_recast(Vec3::new(
+                    horizontal_tolerance,
+                    vertical_tolerance,
+                    horizontal_tolerance,
+                ))
+                .abs(),
+        );
+        let point_a = Vec3A::from(point);
+        if point_a.cmplt(aabb.min - margin).any() || point_a.cmpgt(aabb.max + margin).any() {
+            return false;
+        }
+        let point = remap.to_recast(point);
+        let half_extents = Vec3::new(
+            horizontal_tolerance,
+            vertical_tolerance,
+            horizontal_tolerance,
+        );
+        let nearest = || {
+            polygon
+                .find_nearest_polygon(point, half_extents)
+                .map(|(_poly, closest)| closest)
+                .filter(|closest| {
+                    closest.xz().distance_squared(point.xz())
+                        <= horizontal_tolerance * horizontal_tolerance
+                })
+        };
+        if navmesh.detail.meshes.len() == navmesh.polygon_count() {
+            return detail_height_at(navmesh, &polygon, point)
+                .or_else(|| {
+                    // Points just outside of the polygons are sampled at the nearest point of their footprint.
+                    detail_height_at(navmesh, &polygon, nearest()?.with_y(point.y))
+                })
+                .is_some_and(|height| ops::abs(height - point.y) <= vertical_tolerance);
+        }
+        // Without a detail mesh, fall back to the coarse surface of the polygons.
+        nearest().is_some_and(|closest| ops::abs(closest.y - point.y) <= vertical_tolerance)
+    }
+
+    /// Returns the navmesh `id` together with its [`Navmesh::polygon`] in Recast's coordinate system,
+    /// which the queries of [`PolygonNavmesh`] expect, and the [`CoordinateRemap`] to convert positions with.
+    fn get(
+        &self,
+        id: impl Into<AssetId<Navmesh>>,
+    ) -> Option<(&Navmesh, Cow<'_, PolygonNavmesh>, CoordinateRemap)> {
+        let id = id.into();
+        let navmesh = self.navmeshes.get(id)?;
+        let remap = CoordinateRemap::from(&navmesh.settings);
+        if remap.is_identity() {
+            return Some((navmesh, Cow::Borrowed(&navmesh.polygon), remap));
+        }
+        let polygon = match self.recast_polygons.get(&id) {
+            Some(polygon) => Cow::Borrowed(polygon),
+            // Navmeshes added in this frame have not been converted yet.
+            None => {
+                let mut polygon = navmesh.polygon.clone();
+                remap.polygon_to_recast(&mut polygon);
+                Cow::Owned(polygon)
+            }
+        };
+        Some((navmesh, polygon, remap))
+    }
+}
+
+/// The [`Navmesh::polygon`]s of the navmeshes whose [`CoordinateRemap`] is not the identity,
+/// converted into Recast's coordinate system once per change instead of on every query.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct RecastPolygons(HashMap<AssetId<Navmesh>, PolygonNavmesh>);
+
+fn update_recast_polygons(
+    mut asset_events: MessageReader<AssetEvent<Navmesh>>,
+    navmeshes: Res<Assets<Navmesh>>,
+    mut recast_polygons: ResMut<RecastPolygons>,
+) {
+    for event in asset_events.read() {
+        match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => {
+                let Some(navmesh) = navmeshes.get(*id) else {
+                    continue;
+                };
+                let remap = CoordinateRemap::from(&navmesh.settings);
+                if remap.is_identity() {
+                    recast_polygons.remove(id);
+                    continue;
+                }
+                let mut polygon = navmesh.polygon.clone();
+                remap.polygon_to_recast(&mut polygon);
+                recast_polygons.insert(*id, polygon);
+            }
+            AssetEvent::Removed { id } | AssetEvent::Unused { id } => {
+                recast_polygons.remove(id);
+            }
+            AssetEvent::LoadedWithDependencies { .. } => {}
+        }
+    }
+}
+
+/// The [`Navmesh::polygon`] and [`Navmesh::clearances`] of a navmesh, shared with the tasks of
+/// [`NavmeshQueries::find_path_async`].
+///
+/// The corridor search only depends on the adjacency and the distances between polygons,
+/// which are the same in every coordinate system, so the polygons are not converted into Recast's coordinate system.
+struct SharedPolygon {
+    polygon: PolygonNavmesh,
+    clearances: Vec<f32>,
+}
+
+impl SharedPolygon {
+    fn new(navmesh: &Navmesh) -> Arc<Self> {
+        Arc::new(Self {
+            polygon: navmesh.polygon.clone(),
+            clearances: navmesh.clearances.clone(),
+        })
+    }
+}
+
+/// The [`SharedPolygon`]s of all loaded navmeshes, copied once per change instead of on every query.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct SharedPolygons(HashMap<AssetId<Navmesh>, Arc<SharedPolygon>>);
+
+fn update_shared_polygons(
+    mut asset_events: MessageReader<AssetEvent<Navmesh>>,
+    navmeshes: Res<Assets<Navmesh>>,
+    mut shared_polygons: ResMut<SharedPolygons>,
+) {
+    for event in asset_events.read() {
+        match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => {
+                if let Some(navmesh) = navmeshes.get(*id) {
+                    shared_polygons.insert(*id, SharedPolygon::new(navmesh));
+                }
+            }
+            AssetEvent::Removed { id } | AssetEvent::Unused { id } => {
+                shared_polygons.remove(id);
+            }
+            AssetEvent::LoadedWithDependencies { .. } => {}
+        }
+    }
+}
+
+/// Returns the height of the [`Navmesh::detail`] at `point`, in Recast's coordinate system.
+///
+/// Only the detail sub-mesh of the polygon containing `point` is sampled. `polygon` is the [`Navmesh::polygon`]
+/// in Recast's coordinate system, whose [`PolygonNavmesh::find_polygon`] picks the floor nearest to `point`
+/// if several lie above each other.
+fn detail_height_at(navmesh: &Navmesh, polygon: &PolygonNavmesh, point: Vec3) -> Option<f32> {
+    let detail = &navmesh.detail;
+    let mesh = detail.meshes.get(polygon.find_polygon(point)? as usize)?;
+    let remap = CoordinateRemap::from(&navmesh.settings);
+    let vertices =
+        &detail.vertices[mesh.base_vertex_index as usize..][..mesh.vertex_count as usize];
+    detail.triangles[mesh.base_triangle_index as usize..][..mesh.triangle_count as usize]
+        .iter()
+        .map(|triangle| {
+            triangle.map(|index| Vec3A::from(remap.to_recast(vertices[index as usize])))
+        })
+        .find_map(|triangle| height_at(&triangle, point.into()))
+}
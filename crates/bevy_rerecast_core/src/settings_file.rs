@@ -0,0 +1,117 @@
+//! Human-readable [`NavmeshSettings`] in the [RON](https://github.com/ron-rs/ron) format,
+//! e.g. to check them into version control and tune them by hand.
+
+use alloc::{string::String, vec::Vec};
+use bevy_app::prelude::*;
+use bevy_asset::{AssetApp as _, AssetLoader, LoadContext, io::Reader, prelude::*};
+use bevy_derive::{Deref, DerefMut};
+use bevy_reflect::prelude::*;
+use ron::ser::PrettyConfig;
+use thiserror::Error;
+
+use crate::{NavmeshSettings, NavmeshSettingsError};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<NavmeshSettingsAsset>();
+    app.register_type::<NavmeshSettingsAsset>();
+    app.init_asset_loader::<NavmeshSettingsLoader>();
+}
+
+impl NavmeshSettings {
+    /// Serializes the settings to pretty-printed RON. Use [`NavmeshSettings::from_ron`] to read them back.
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(self, PrettyConfig::default())
+            .expect("Serializing navmesh settings to RON cannot fail")
+    }
+
+    /// Deserializes settings written by [`NavmeshSettings::to_ron`] or by hand.
+    pub fn from_ron(ron: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::de::from_str(ron)
+    }
+}
+
+/// [`NavmeshSettings`] loaded from a `.navsettings.ron` file by the [`NavmeshSettingsLoader`].
+#[derive(Debug, Clone, PartialEq, Asset, Reflect, Deref, DerefMut)]
+pub struct NavmeshSettingsAsset(pub NavmeshSettings);
+
+/// The [`AssetLoader`] for [`NavmeshSettingsAsset`]s. Loads files ending in `.navsettings.ron`.
+///
+/// The settings are [validated](NavmeshSettings::validate) when loading,
+/// so mistakes made while editing them by hand surface right away.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct NavmeshSettingsLoader;
+
+/// Errors that can occur when loading a [`NavmeshSettingsAsset`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum NavmeshSettingsLoaderError {
+    /// An error occurred while reading the file.
+    #[error("Could not load navmesh settings: {0}")]
+    IoError(#[from] std::io::Error),
+    /// The file is not valid RON or does not describe navmesh settings.
+    #[error("Could not parse navmesh settings: {0}")]
+    ParseError(#[from] ron::error::SpannedError),
+    /// The settings were parsed, but can't be used to generate a navmesh.
+    #[error("Invalid navmesh settings: {0}")]
+    Invalid(#[from] NavmeshSettingsError),
+}
+
+impl AssetLoader for NavmeshSettingsLoader {
+    type Asset = NavmeshSettingsAsset;
+    type Settings = ();
+    type Error = NavmeshSettingsLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let settings: NavmeshSettings = ron::de::from_bytes(&bytes)?;
+        settings.validate()?;
+        Ok(NavmeshSettingsAsset(settings))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["navsettings.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec2;
+    use rerecast::{AreaType, ConvexVolume};
+
+    use super::*;
+
+    #[test]
+    fn settings_ron_round_trip() {
+        let settings = NavmeshSettings {
+            area_volumes: vec![ConvexVolume {
+                vertices: vec![
+                    Vec2::new(-1.0, -1.0),
+                    Vec2::new(1.0, -1.0),
+                    Vec2::new(0.0, 1.0),
+                ],
+                min_y: -1.0,
+                max_y: 1.0,
+                area: AreaType::NOT_WALKABLE,
+            }],
+            ..NavmeshSettings::from_agent_3d(0.5, 2.0)
+        };
+        let ron = settings.to_ron();
+        assert!(ron.contains("area_volumes"));
+        assert!(ron.contains("min_y: -1.0"));
+        assert_eq!(NavmeshSettings::from_ron(&ron).unwrap(), settings);
+
+        let edited = ron.replace("agent_radius: 0.5", "agent_radius: 0.75");
+        assert_eq!(
+            NavmeshSettings::from_ron(&edited).unwrap().agent_radius,
+            0.75
+        );
+        assert!(NavmeshSettings::from_ron("(agent_radius: 0.5)").is_err());
+    }
+}
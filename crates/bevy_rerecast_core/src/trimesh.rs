@@ -0,0 +1,88 @@
+use alloc::{sync::Arc, vec::Vec};
+use bevy_transform::components::GlobalTransform;
+use rerecast::TriMesh;
+
+/// Used to add [`CombineTriMeshes::combine`] and [`CombineTriMeshes::combine_instances`] to [`TriMesh`].
+pub trait CombineTriMeshes {
+    /// Combines multiple [`TriMesh`]es in local space into a single [`TriMesh`] in global space.
+    ///
+    /// Each mesh is moved into global space by its [`GlobalTransform`] before being appended with [`TriMesh::extend`],
+    /// which takes care of offsetting the indices. This is the same step backends use to merge their obstacles,
+    /// so the result can be returned from a custom backend as-is. The combined capacity is reserved up front.
+    fn combine(meshes: impl IntoIterator<Item = (GlobalTransform, TriMesh)>) -> TriMesh;
+
+    /// Combines instances of shared [`TriMesh`]es in local space into a single [`TriMesh`] in global space.
+    ///
+    /// Like [`CombineTriMeshes::combine`], but each instance only holds an [`Arc`] to its mesh, which is appended
+    /// with [`TriMesh::extend_transformed`] instead of being cloned. Use this when the same mesh is placed many times,
+    /// e.g. for foliage or modular walls.
+    fn combine_instances(
+        instances: impl IntoIterator<Item = (GlobalTransform, Arc<TriMesh>)>,
+    ) -> TriMesh;
+}
+
+impl CombineTriMeshes for TriMesh {
+    fn combine(meshes: impl IntoIterator<Item = (GlobalTransform, TriMesh)>) -> TriMesh {
+        meshes
+            .into_iter()
+            .map(|(transform, mut trimesh)| {
+                let affine = transform.affine();
+                for vertex in &mut trimesh.vertices {
+                    *vertex = affine.transform_point3a(*vertex);
+                }
+                trimesh
+            })
+            .collect()
+    }
+
+    fn combine_instances(
+        instances: impl IntoIterator<Item = (GlobalTransform, Arc<TriMesh>)>,
+    ) -> TriMesh {
+        let instances = instances.into_iter().collect::<Vec<_>>();
+        let mut combined = TriMesh::default();
+        combined.reserve(
+            instances
+                .iter()
+                .map(|(_transform, trimesh)| trimesh.vertices.len())
+                .sum(),
+            instances
+                .iter()
+                .map(|(_transform, trimesh)| trimesh.indices.len())
+                .sum(),
+        );
+        for (transform, trimesh) in instances {
+            let affine = transform.affine();
+            combined.extend_transformed(&trimesh, |vertex| affine.transform_point3a(vertex));
+        }
+        combined
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{UVec3, Vec3, Vec3A};
+    use rerecast::AreaType;
+
+    use super::*;
+
+    #[test]
+    fn combine_transformed_trimeshes() {
+        let triangle = TriMesh {
+            vertices: vec![Vec3A::ZERO, Vec3A::X, Vec3A::Z],
+            indices: vec![UVec3::new(0, 1, 2)],
+            area_types: vec![AreaType::DEFAULT_WALKABLE],
+        };
+        let offset = Vec3::new(10.0, 0.0, 0.0);
+        let combined = TriMesh::combine([
+            (GlobalTransform::IDENTITY, triangle.clone()),
+            (GlobalTransform::from_translation(offset), triangle.clone()),
+        ]);
+
+        assert_eq!(combined.vertices.len(), 6);
+        assert_eq!(combined.area_types.len(), 2);
+        // The indices of the second mesh are offset by the vertices of the first one.
+        assert_eq!(combined.indices, [UVec3::new(0, 1, 2), UVec3::new(3, 4, 5)]);
+        assert_eq!(combined.vertices[3], Vec3A::from(offset));
+        assert_eq!(combined.vertices[4], Vec3A::X + Vec3A::from(offset));
+    }
+}
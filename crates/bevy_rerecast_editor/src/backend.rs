@@ -1,4 +1,6 @@
-use bevy::prelude::*;
+use std::time::{Duration, Instant};
+
+use bevy::{math::bounding::Aabb3d, prelude::*, time::Stopwatch};
 use bevy_rerecast::{
     debug::{DetailNavmeshGizmo, PolygonNavmeshGizmo},
     prelude::*,
@@ -11,9 +13,15 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         insert_gizmos.run_if(resource_exists_and_changed::<NavmeshHandle>),
     );
+    app.add_systems(
+        Update,
+        (restart_build_on_aabb_change, rebuild_on_settings_change),
+    );
     app.add_observer(build_navmesh);
     app.init_resource::<GlobalNavmeshSettings>()
         .init_resource::<NavmeshHandle>()
+        .init_resource::<RunningBuild>()
+        .init_resource::<SettingsDebounce>()
         .init_resource::<NavmeshObstacles>();
 }
 
@@ -27,7 +35,7 @@ pub(crate) struct NavmeshObstacles(pub(crate) TriMesh);
 #[derive(Event)]
 pub(crate) struct BuildNavmesh;
 
-#[derive(Resource, Default, Deref, DerefMut)]
+#[derive(Resource, Default, Deref, DerefMut, PartialEq)]
 pub(crate) struct GlobalNavmeshSettings(pub(crate) NavmeshSettings);
 
 #[derive(Resource, Default, Deref, DerefMut)]
@@ -38,9 +46,114 @@ fn build_navmesh(
     mut commands: Commands,
     config: Res<GlobalNavmeshSettings>,
     mut navmesh_generator: NavmeshGenerator,
+    mut running_build: ResMut<RunningBuild>,
+    mut debounce: ResMut<SettingsDebounce>,
 ) {
     let handle = navmesh_generator.generate(config.0.clone());
     commands.insert_resource(NavmeshHandle(handle));
+    *running_build = RunningBuild {
+        aabb: config.aabb,
+        started: Some(Instant::now()),
+        ..default()
+    };
+    debounce.built = Some(config.0.clone());
+}
+
+/// How long the AABB needs to stay the same before a running build is restarted with it.
+const AABB_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Tracks the AABB of the running build so that it can be restarted when the AABB changes.
+#[derive(Resource, Default)]
+pub(crate) struct RunningBuild {
+    /// The AABB the running build was started with.
+    aabb: Option<Aabb3d>,
+    /// The AABB the build will be restarted with, and how long it has been unchanged.
+    next_aabb: Option<(Option<Aabb3d>, Stopwatch)>,
+    /// When the running build was started or last restarted.
+    started: Option<Instant>,
+}
+
+impl RunningBuild {
+    /// The time since the running build was started or last restarted.
+    pub(crate) fn elapsed(&self) -> Option<Duration> {
+        self.started.map(|started| started.elapsed())
+    }
+}
+
+/// Cancels and requeues a running build when the AABB changes, so that the finished navmesh never uses outdated bounds.
+fn restart_build_on_aabb_change(
+    time: Res<Time>,
+    config: Res<GlobalNavmeshSettings>,
+    navmesh: Res<NavmeshHandle>,
+    mut running_build: ResMut<RunningBuild>,
+    mut debounce: ResMut<SettingsDebounce>,
+    mut navmesh_generator: NavmeshGenerator,
+) {
+    if !navmesh_generator.is_pending(&navmesh.0) || config.aabb == running_build.aabb {
+        running_build.next_aabb = None;
+        return;
+    }
+    match &mut running_build.next_aabb {
+        Some((aabb, stopwatch)) if *aabb == config.aabb => {
+            stopwatch.tick(time.delta());
+            if stopwatch.elapsed() < AABB_DEBOUNCE {
+                return;
+            }
+        }
+        next_aabb => {
+            *next_aabb = Some((config.aabb, Stopwatch::new()));
+            return;
+        }
+    }
+
+    navmesh_generator.cancel(&navmesh.0);
+    navmesh_generator.regenerate(&navmesh.0, config.0.clone());
+    *running_build = RunningBuild {
+        aabb: config.aabb,
+        started: Some(Instant::now()),
+        ..default()
+    };
+    debounce.built = Some(config.0.clone());
+}
+
+/// How long the settings need to stay the same before the navmesh is rebuilt with them.
+const SETTINGS_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Tracks the settings of the last build so that the navmesh can be rebuilt when they change.
+#[derive(Resource, Default)]
+struct SettingsDebounce {
+    /// The settings the last build was started with, or `None` if nothing was built yet.
+    built: Option<NavmeshSettings>,
+    /// How long the settings have been unchanged.
+    unchanged: Stopwatch,
+}
+
+/// Rebuilds the navmesh once the settings have stopped changing, so that they can be tweaked without pressing "Build Navmesh".
+/// Waits for a running build to finish instead of queueing another one.
+fn rebuild_on_settings_change(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GlobalNavmeshSettings>,
+    navmesh: Res<NavmeshHandle>,
+    mut debounce: ResMut<SettingsDebounce>,
+    navmesh_generator: NavmeshGenerator,
+) {
+    if config.is_changed() {
+        debounce.unchanged.reset();
+        return;
+    }
+    debounce.unchanged.tick(time.delta());
+    // Only rebuild navmeshes that were built manually at least once, i.e. once a scene is loaded.
+    let Some(built) = &debounce.built else {
+        return;
+    };
+    if *built == config.0
+        || debounce.unchanged.elapsed() < SETTINGS_DEBOUNCE
+        || navmesh_generator.is_pending(&navmesh.0)
+    {
+        return;
+    }
+    commands.trigger(BuildNavmesh);
 }
 
 fn insert_gizmos(mut commands: Commands, navmesh: Res<NavmeshHandle>) {
@@ -0,0 +1,113 @@
+//! Editor configuration that is persisted across sessions.
+
+use std::{fs, io, path::PathBuf};
+
+use bevy::prelude::*;
+use bevy_ui_text_input::TextInputContents;
+use serde::{Deserialize, Serialize};
+
+use crate::ui::ConnectionInput;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(EditorConfig::load());
+    app.add_systems(Update, persist_connection_url);
+    app.add_observer(remember_recent_navmesh);
+}
+
+const DEFAULT_CONNECTION_URL: &str = "http://127.0.0.1:15702";
+
+/// How many recently used navmesh files are remembered.
+const MAX_RECENT_NAVMESHES: usize = 8;
+
+/// Settings of the editor itself, stored in `bevy_rerecast_editor/config.json` in the user's config directory.
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct EditorConfig {
+    /// The URL of the BRP server to load scenes from.
+    pub(crate) connection_url: String,
+    /// Navmesh files that were recently loaded or saved, most recent first.
+    pub(crate) recent_navmeshes: Vec<PathBuf>,
+}
+
+impl Default for EditorConfig {
+    fn default() -> Self {
+        Self {
+            connection_url: DEFAULT_CONNECTION_URL.to_string(),
+            recent_navmeshes: Vec::new(),
+        }
+    }
+}
+
+impl EditorConfig {
+    fn load() -> Self {
+        let Some(path) = config_path() else {
+            return default();
+        };
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return default(),
+            Err(e) => {
+                warn!("Failed to read editor config at {}: {e}", path.display());
+                return default();
+            }
+        };
+        serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Failed to parse editor config at {}: {e}", path.display());
+            default()
+        })
+    }
+
+    fn push_recent_navmesh(&mut self, path: PathBuf) {
+        self.recent_navmeshes.retain(|recent| *recent != path);
+        self.recent_navmeshes.insert(0, path);
+        self.recent_navmeshes.truncate(MAX_RECENT_NAVMESHES);
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let path = config_path().ok_or_else(|| anyhow::anyhow!("No config directory found"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    let dir = std::env::var_os("XDG_CONFIG_HOME")
+        .or_else(|| std::env::var_os("APPDATA"))
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(dir.join("bevy_rerecast_editor").join("config.json"))
+}
+
+fn persist_connection_url(
+    connection_input: Query<
+        &TextInputContents,
+        (With<ConnectionInput>, Changed<TextInputContents>),
+    >,
+    mut config: ResMut<EditorConfig>,
+) {
+    for contents in &connection_input {
+        let url = contents.get();
+        // The input starts out empty until its initial text has been inserted
+        if url.is_empty() || url == config.connection_url {
+            continue;
+        }
+        config.connection_url = url.to_string();
+        if let Err(e) = config.save() {
+            warn!("Failed to save editor config: {e}");
+        }
+    }
+}
+
+/// Records a navmesh file that was successfully loaded or saved in [`EditorConfig::recent_navmeshes`].
+#[derive(Event, Debug, Clone)]
+pub(crate) struct RecentNavmesh(pub(crate) PathBuf);
+
+fn remember_recent_navmesh(recent: On<RecentNavmesh>, mut config: ResMut<EditorConfig>) {
+    config.push_recent_navmesh(recent.event().0.clone());
+    if let Err(e) = config.save() {
+        warn!("Failed to save editor config: {e}");
+    }
+}
@@ -16,21 +16,44 @@ use bevy_rerecast::editor_integration::{
     transmission::deserialize,
 };
 use bevy_ui_text_input::TextInputContents;
+use thiserror::Error;
 
 use crate::{
     backend::{GlobalNavmeshSettings, NavmeshHandle, NavmeshObstacles},
+    toast::show_error_from_task,
     ui::ConnectionInput,
     visualization::{ObstacleGizmo, VisualMesh},
 };
 use bevy_malek_async::{WorldIdRes, async_access};
 
 pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ConnectionState>();
     app.add_observer(on_get_navmesh_input);
 }
 
 #[derive(Event)]
 pub(crate) struct GetNavmeshInput;
 
+/// Whether the BRP server of the game could be reached on the last scene load.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConnectionState {
+    /// No scene was loaded yet.
+    #[default]
+    Idle,
+    /// The last scene load reached the game, even if it failed for another reason.
+    Connected,
+    /// The last scene load could not reach the game, e.g. because it was closed.
+    Disconnected,
+}
+
+/// The BRP server of the game could not be reached.
+#[derive(Debug, Error)]
+#[error("Could not connect to {url}: {message}")]
+struct ConnectionError {
+    url: String,
+    message: String,
+}
+
 fn on_get_navmesh_input(
     _: On<GetNavmeshInput>,
     mut task: Local<Option<Task<()>>>,
@@ -43,8 +66,18 @@ fn on_get_navmesh_input(
     match task.as_ref() {
         None => {
             task.replace(IoTaskPool::get().spawn(async move {
-                if let Err(e) = navmesh_pipeline(world_id).await {
+                let result = navmesh_pipeline(world_id).await;
+                let state = match &result {
+                    Err(e) if e.is::<ConnectionError>() => ConnectionState::Disconnected,
+                    _ => ConnectionState::Connected,
+                };
+                async_access::<ResMut<ConnectionState>, _, _>(world_id, move |mut connection| {
+                    connection.set_if_neq(state);
+                })
+                .await;
+                if let Err(e) = result {
                     error!("navmesh pipeline failed: {e:?}");
+                    show_error_from_task(world_id, format!("Failed to load the scene: {e}")).await;
                 }
             }));
         }
@@ -80,17 +113,7 @@ async fn navmesh_pipeline(world_id: WorldId) -> Result<()> {
             id: None,
             params: Some(settings),
         };
-        let resp = ehttp::fetch_async(ehttp::Request::json(url, &req)?)
-            .await
-            .map_err(|s| anyhow!("{s}"))?;
-
-        let mut v: serde_json::Value = resp.json()?;
-        let val = v.get_mut("result").map(|r| r.take()).ok_or_else(|| {
-            anyhow!(
-                "BRP error: {}",
-                v.get("error").unwrap_or(&serde_json::Value::Null)
-            )
-        })?;
+        let val = brp_request(&url, &req).await?;
         let GenerateEditorInputResponse { id, .. } = serde_json::from_value(val)?;
         id
     };
@@ -103,17 +126,7 @@ async fn navmesh_pipeline(world_id: WorldId) -> Result<()> {
             id: None,
             params: Some(params),
         };
-        let resp = ehttp::fetch_async(ehttp::Request::json("http://127.0.0.1:15702/", &req)?)
-            .await
-            .map_err(|s| anyhow!("{s}"))?;
-
-        let mut v: serde_json::Value = resp.json()?;
-        let val = v.get_mut("result").map(|r| r.take()).ok_or_else(|| {
-            anyhow!(
-                "BRP error: {}",
-                v.get("error").unwrap_or(&serde_json::Value::Null)
-            )
-        })?;
+        let val = brp_request(&url, &req).await?;
         deserialize(&val)?
     };
 
@@ -196,6 +209,7 @@ async fn navmesh_pipeline(world_id: WorldId) -> Result<()> {
                 let mesh_handle = mesh_indices
                     .entry(visual.mesh)
                     .or_insert_with(|| {
+                        // Also decodes quantized positions
                         let mut m = response.meshes[visual.mesh as usize].clone().into_mesh();
                         // Avoid skinned attributes without SkinnedMesh
                         m.remove_attribute(Mesh::ATTRIBUTE_JOINT_INDEX);
@@ -240,3 +254,22 @@ async fn navmesh_pipeline(world_id: WorldId) -> Result<()> {
 
     Ok(())
 }
+
+/// Sends `req` to the BRP server at `url` and returns its result.
+/// Fails with a [`ConnectionError`] if the server cannot be reached.
+async fn brp_request(url: &str, req: &BrpRequest) -> Result<serde_json::Value> {
+    let resp = ehttp::fetch_async(ehttp::Request::json(url, req)?)
+        .await
+        .map_err(|message| ConnectionError {
+            url: url.to_string(),
+            message,
+        })?;
+
+    let mut v: serde_json::Value = resp.json()?;
+    v.get_mut("result").map(|r| r.take()).ok_or_else(|| {
+        anyhow!(
+            "BRP error: {}",
+            v.get("error").unwrap_or(&serde_json::Value::Null)
+        )
+    })
+}
@@ -1,14 +1,17 @@
-use std::{fs::File, io};
+use std::{fs, io, path::PathBuf};
 
 use bevy::{
     prelude::*,
     tasks::{AsyncComputeTaskPool, Task, futures_lite::future},
 };
-use bevy_rerecast::Navmesh;
+use bevy_rerecast::{Navmesh, asset_loader::NavmeshLoaderError};
 use rfd::FileHandle;
 use thiserror::Error;
 
-use crate::backend::{GlobalNavmeshSettings, NavmeshHandle};
+use crate::{
+    backend::{GlobalNavmeshSettings, NavmeshHandle},
+    config::RecentNavmesh,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<ReadTasks>();
@@ -20,6 +23,7 @@ pub(super) fn plugin(app: &mut App) {
         )
             .chain(),
     );
+    app.add_observer(load_navmesh_file);
 }
 
 #[derive(Resource, Deref, DerefMut)]
@@ -40,15 +44,22 @@ fn poll_load_task(
     };
 
     let thread_pool = AsyncComputeTaskPool::get();
+    read_tasks.push(thread_pool.spawn(read_navmesh(file.path().to_path_buf())));
+}
 
-    let future = async move {
-        let path = file.path();
-        let mut file = File::open(path)?;
-        let config = bincode::config::standard();
-        let content: Navmesh = bincode::serde::decode_from_std_read(&mut file, config)?;
-        Ok(content)
-    };
-    read_tasks.push(thread_pool.spawn(future));
+/// Loads the navmesh at the given path without asking the user for a file.
+#[derive(Event, Debug, Clone)]
+pub(crate) struct LoadNavmeshFile(pub(crate) PathBuf);
+
+fn load_navmesh_file(load: On<LoadNavmeshFile>, mut read_tasks: ResMut<ReadTasks>) {
+    let thread_pool = AsyncComputeTaskPool::get();
+    read_tasks.push(thread_pool.spawn(read_navmesh(load.event().0.clone())));
+}
+
+async fn read_navmesh(path: PathBuf) -> Result<(PathBuf, Navmesh), LoadError> {
+    let bytes = fs::read(&path)?;
+    let content = Navmesh::from_nav_bytes(&bytes)?;
+    Ok((path, content))
 }
 
 #[derive(Debug, Error)]
@@ -56,11 +67,11 @@ pub enum LoadError {
     #[error("Failed to open file: {0}")]
     OpenFile(#[from] io::Error),
     #[error("Failed to decode navmesh: {0}")]
-    ReadNavmesh(#[from] bincode::error::DecodeError),
+    ReadNavmesh(#[from] NavmeshLoaderError),
 }
 
 #[derive(Resource, Default, Deref, DerefMut)]
-struct ReadTasks(Vec<Task<Result<Navmesh, LoadError>>>);
+struct ReadTasks(Vec<Task<Result<(PathBuf, Navmesh), LoadError>>>);
 
 fn poll_read_tasks(
     mut read_tasks: ResMut<ReadTasks>,
@@ -73,9 +84,10 @@ fn poll_read_tasks(
             return true;
         };
         match result {
-            Ok(navmesh) => {
+            Ok((path, navmesh)) => {
                 settings.0 = navmesh.settings.clone();
                 commands.insert_resource(NavmeshHandle(navmeshes.add(navmesh)));
+                commands.trigger(RecentNavmesh(path));
                 false
             }
             Err(err) => {
@@ -14,10 +14,12 @@ extern crate alloc;
 
 mod backend;
 mod camera;
+mod config;
 mod get_navmesh_input;
 mod load;
 mod save;
 mod theme;
+mod toast;
 mod ui;
 mod visualization;
 
@@ -43,12 +45,14 @@ fn main() -> AppExit {
         .add_plugins((NavmeshPlugins::default(), TextInputPlugin, AsyncPlugin))
         .add_plugins((
             camera::plugin,
+            config::plugin,
             get_navmesh_input::plugin,
             ui::plugin,
             theme::plugin,
             visualization::plugin,
             backend::plugin,
             load::plugin,
+            toast::plugin,
         ))
         .run()
 }
@@ -1,8 +1,8 @@
-use std::{fs::File, io};
+use std::{fs, io};
 
-use crate::backend::NavmeshHandle;
+use crate::{backend::NavmeshHandle, config::RecentNavmesh};
 use bevy::ecs::world::WorldId;
-use bevy::{prelude::*};
+use bevy::prelude::*;
 use bevy_malek_async::async_access;
 use bevy_rerecast::Navmesh;
 use rfd::FileHandle;
@@ -10,7 +10,7 @@ use thiserror::Error;
 
 pub(crate) async fn save_navmesh(
     world_id: WorldId,
-    save: impl Future<Output=Option<FileHandle>>,
+    save: impl Future<Output = Option<FileHandle>>,
 ) -> core::result::Result<(), SaveError> {
     let Some(file_handle) = save.await else {
         return Err(SaveError::UserCanceled);
@@ -26,9 +26,33 @@ pub(crate) async fn save_navmesh(
     )
     .await?;
     let path = file_handle.path();
-    let mut file = File::create(path)?;
-    let config = bincode::config::standard();
-    bincode::serde::encode_into_std_write(navmesh, &mut file, config)?;
+    fs::write(path, navmesh.to_nav_bytes())?;
+    let path = path.to_path_buf();
+    async_access::<Commands, _, _>(world_id, |mut commands| {
+        commands.trigger(RecentNavmesh(path));
+    })
+    .await;
+    Ok(())
+}
+
+pub(crate) async fn export_obj(
+    world_id: WorldId,
+    save: impl Future<Output = Option<FileHandle>>,
+) -> core::result::Result<(), SaveError> {
+    let Some(file_handle) = save.await else {
+        return Err(SaveError::UserCanceled);
+    };
+    let obj = async_access::<(Res<NavmeshHandle>, Res<Assets<Navmesh>>), _, _>(
+        world_id,
+        |(navmesh, navmeshes)| {
+            navmeshes
+                .get(navmesh.id())
+                .ok_or(SaveError::NoNavmesh)
+                .map(Navmesh::to_obj)
+        },
+    )
+    .await?;
+    fs::write(file_handle.path(), obj)?;
     Ok(())
 }
 
@@ -40,6 +64,4 @@ pub enum SaveError {
     NoNavmesh,
     #[error("Failed to create file: {0}")]
     CreateFile(#[from] io::Error),
-    #[error("Failed to encode navmesh: {0}")]
-    WriteNavmesh(#[from] bincode::error::EncodeError),
 }
@@ -19,3 +19,6 @@ pub const BUTTON_DISABLED_BACKGROUND: Color = Color::srgb(0.6117647, 0.6392157,
 
 // #080202
 pub(crate) const BEVY_GRAY: Color = Color::srgb(0.035, 0.01, 0.01);
+
+/// #992929
+pub const ERROR_BACKGROUND: Color = Color::srgb(0.600, 0.161, 0.161);
@@ -0,0 +1,101 @@
+//! On-screen notifications for errors that would otherwise only show up in the log.
+
+use std::time::Duration;
+
+use bevy::{ecs::world::WorldId, prelude::*};
+use bevy_malek_async::async_access;
+use bevy_rerecast::prelude::*;
+
+use crate::theme::palette::{ERROR_BACKGROUND, LABEL_TEXT};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Startup, spawn_toast_container);
+    app.add_systems(Update, dismiss_toasts);
+    app.add_observer(show_error);
+    app.add_observer(show_build_error);
+}
+
+/// How long a toast stays on screen.
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+
+/// Shows an error message in a toast that is dismissed after [`TOAST_DURATION`].
+#[derive(Event, Debug, Clone)]
+pub(crate) struct ShowError(pub(crate) String);
+
+/// Triggers [`ShowError`] from an async task.
+pub(crate) async fn show_error_from_task(world_id: WorldId, message: String) {
+    async_access::<Commands, _, _>(world_id, |mut commands| {
+        commands.trigger(ShowError(message));
+    })
+    .await;
+}
+
+#[derive(Component)]
+struct ToastContainer;
+
+#[derive(Component, Deref, DerefMut)]
+struct Toast(Timer);
+
+fn spawn_toast_container(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Toasts"),
+        ToastContainer,
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            // Above the status bar
+            bottom: Val::Px(40.0),
+            max_width: Val::Px(400.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(5.0),
+            ..default()
+        },
+        GlobalZIndex(1),
+        Pickable::IGNORE,
+    ));
+}
+
+fn show_error(
+    error: On<ShowError>,
+    mut commands: Commands,
+    container: Single<Entity, With<ToastContainer>>,
+) {
+    commands.spawn((
+        Name::new("Error Toast"),
+        Toast(Timer::new(TOAST_DURATION, TimerMode::Once)),
+        Node {
+            padding: UiRect::axes(Val::Px(10.0), Val::Px(5.0)),
+            ..default()
+        },
+        BorderRadius::all(Val::Px(4.0)),
+        BackgroundColor(ERROR_BACKGROUND),
+        ChildOf(*container),
+        children![(
+            Text::new(error.event().0.clone()),
+            TextColor(LABEL_TEXT),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+        )],
+    ));
+}
+
+fn show_build_error(failed: On<NavmeshGenerationFailed>, mut commands: Commands) {
+    commands.trigger(ShowError(format!(
+        "Failed to build the navmesh: {}",
+        failed.event().error
+    )));
+}
+
+fn dismiss_toasts(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut toasts: Query<(Entity, &mut Toast)>,
+) {
+    for (entity, mut toast) in &mut toasts {
+        if toast.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -17,11 +17,11 @@ use bevy::{
     prelude::*,
     tasks::prelude::*,
     ui::{Checked, InteractionDisabled, Val::*},
-    ui_widgets::{Activate, ValueChange, observe},
+    ui_widgets::{Activate, RadioGroup, ValueChange, observe},
     window::{PrimaryWindow, RawHandleWrapper},
 };
 use bevy_malek_async::WorldIdRes;
-use bevy_rerecast::prelude::*;
+use bevy_rerecast::{PartitionMethod, prelude::*};
 use bevy_ui_text_input::{
     TextInputContents, TextInputFilter, TextInputMode, TextInputNode, TextInputQueue,
     actions::{TextInputAction, TextInputEdit},
@@ -30,9 +30,10 @@ use bevy_ui_text_input::{
 use rfd::AsyncFileDialog;
 
 use crate::{
-    backend::{BuildNavmesh, GlobalNavmeshSettings},
-    get_navmesh_input::GetNavmeshInput,
-    load::LoadTask,
+    backend::{BuildNavmesh, GlobalNavmeshSettings, RunningBuild},
+    config::EditorConfig,
+    get_navmesh_input::{ConnectionState, GetNavmeshInput},
+    load::{LoadNavmeshFile, LoadTask},
     save,
     visualization::{AvailableGizmos, GizmosToDraw, ObstacleGizmo},
 };
@@ -40,19 +41,28 @@ use crate::{
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(Startup, spawn_ui);
     app.add_systems(Update, read_config_inputs);
+    app.add_systems(
+        Update,
+        update_connection_status.run_if(resource_changed::<ConnectionState>),
+    );
+    app.add_systems(
+        Update,
+        update_recent_navmeshes_menu.run_if(resource_changed::<EditorConfig>),
+    );
     app.add_observer(update_primary_buttons_when_obstacle_added);
     app.add_observer(update_primary_buttons_when_obstacle_removed);
+    app.add_observer(update_status_text);
     app.add_observer(clear_focus);
     app.add_observer(set_ui_size);
     app.add_observer(set_font_size);
 }
 
-fn spawn_ui(mut commands: Commands) {
-    let ui = ui_bundle();
+fn spawn_ui(mut commands: Commands, config: Res<EditorConfig>) {
+    let ui = ui_bundle(config.connection_url.clone());
     commands.spawn(ui);
 }
 
-fn ui_bundle() -> impl Bundle {
+fn ui_bundle(connection_url: String) -> impl Bundle {
     (
         Name::new("Canvas"),
         Node {
@@ -98,7 +108,7 @@ fn ui_bundle() -> impl Bundle {
                             font_size: 16.0,
                             ..default()
                         },
-                        text_input_queue("http://127.0.0.1:15702"),
+                        text_input_queue(connection_url),
                         TextInputContents::default(),
                         ConnectionInput,
                     ),
@@ -113,6 +123,19 @@ fn ui_bundle() -> impl Bundle {
                         }),
                         LoadSceneButton
                     )),
+                    (
+                        menu_button((
+                            feathers::controls::button(
+                                ButtonProps::default(),
+                                (),
+                                Spawn((Text::new("Reconnect"), ThemedText))
+                            ),
+                            observe(|_: On<Activate>, mut commands: Commands| {
+                                commands.trigger(GetNavmeshInput);
+                            }),
+                        )),
+                        ReconnectButton
+                    ),
                     hspace(px(20)),
                     menu_button((
                         feathers::controls::button(
@@ -134,6 +157,15 @@ fn ui_bundle() -> impl Bundle {
                         observe(save_navmesh),
                         SaveNavmeshButton
                     )),
+                    menu_button((
+                        feathers::controls::button(
+                            ButtonProps::default(),
+                            InteractionDisabled,
+                            Spawn((Text::new("Export OBJ"), ThemedText))
+                        ),
+                        observe(export_obj),
+                        ExportObjButton
+                    )),
                     menu_button((
                         feathers::controls::button(
                             ButtonProps::default(),
@@ -143,6 +175,40 @@ fn ui_bundle() -> impl Bundle {
                         observe(load_navmesh),
                         LoadNavmeshButton
                     )),
+                    (
+                        Node {
+                            width: Val::Px(120.0),
+                            ..default()
+                        },
+                        children![
+                            (
+                                feathers::controls::button(
+                                    ButtonProps::default(),
+                                    (),
+                                    Spawn((Text::new("Recent"), ThemedText))
+                                ),
+                                observe(toggle_recent_navmeshes),
+                                ThemedText
+                            ),
+                            (
+                                Name::new("Recent Navmeshes"),
+                                Node {
+                                    display: Display::None,
+                                    position_type: PositionType::Absolute,
+                                    top: percent(100),
+                                    left: px(0),
+                                    min_width: px(300),
+                                    flex_direction: FlexDirection::Column,
+                                    row_gap: px(2),
+                                    padding: UiRect::all(px(5)),
+                                    ..default()
+                                },
+                                ThemeBackgroundColor(tokens::WINDOW_BG),
+                                GlobalZIndex(1),
+                                RecentNavmeshesMenu
+                            ),
+                        ],
+                    ),
                 ]
             ),
             (
@@ -199,15 +265,50 @@ fn ui_bundle() -> impl Bundle {
                                 WalkableClimbInput,
                                 GlobalNavmeshSettings::default().walkable_climb
                             ),
-                            decimal_option_label("Max Slope (degrees)"),
+                            decimal_option_label("Agent Max Slope (degrees)"),
                             decimal_option_input(
-                                MaxSlopeInput,
+                                WalkableSlopeInput,
                                 GlobalNavmeshSettings::default()
                                     .walkable_slope_angle
                                     .to_degrees()
                             ),
                         ],
                     ),
+                    vspace(px(20)),
+                    (
+                        Node {
+                            flex_direction: FlexDirection::Column,
+                            left: percent(10),
+                            row_gap: px(5),
+                            ..default()
+                        },
+                        RadioGroup,
+                        observe(set_partition_method),
+                        children![
+                            label("Partition Method"),
+                            (
+                                feathers::controls::radio(
+                                    Checked,
+                                    Spawn((Text::new("Watershed"), ThemedText))
+                                ),
+                                PartitionRadio(PartitionMethod::Watershed)
+                            ),
+                            (
+                                feathers::controls::radio(
+                                    (),
+                                    Spawn((Text::new("Monotone"), ThemedText))
+                                ),
+                                PartitionRadio(PartitionMethod::Monotone)
+                            ),
+                            (
+                                feathers::controls::radio(
+                                    (),
+                                    Spawn((Text::new("Layers"), ThemedText))
+                                ),
+                                PartitionRadio(PartitionMethod::Layers)
+                            ),
+                        ],
+                    ),
                     vspace(px(50)),
                     (
                         Node {
@@ -280,7 +381,10 @@ struct AgentRadiusInput;
 struct WalkableClimbInput;
 
 #[derive(Component)]
-struct MaxSlopeInput;
+struct WalkableSlopeInput;
+
+#[derive(Component)]
+struct PartitionRadio(PartitionMethod);
 
 fn read_config_inputs(
     mut settings: ResMut<GlobalNavmeshSettings>,
@@ -289,35 +393,33 @@ fn read_config_inputs(
     agent_height: Single<&TextInputContents, With<AgentHeightInput>>,
     agent_radius: Single<&TextInputContents, With<AgentRadiusInput>>,
     walkable_climb: Single<&TextInputContents, With<WalkableClimbInput>>,
-    max_slope: Single<&TextInputContents, With<MaxSlopeInput>>,
+    walkable_slope: Single<&TextInputContents, With<WalkableSlopeInput>>,
+    partition: Query<&PartitionRadio, With<Checked>>,
 ) {
     let d = NavmeshSettings::default();
-    settings.0 = NavmeshSettings {
-        cell_size_fraction: cell_size.get().parse().unwrap_or(d.cell_size_fraction),
-        cell_height_fraction: cell_height.get().parse().unwrap_or(d.cell_height_fraction),
-        walkable_slope_angle: max_slope
-            .get()
-            .parse()
-            .unwrap_or(d.walkable_slope_angle.to_degrees())
-            .to_radians(),
-        agent_height: agent_height.get().parse().unwrap_or(d.agent_height),
-        walkable_climb: walkable_climb.get().parse().unwrap_or(d.walkable_climb),
-        agent_radius: agent_radius.get().parse().unwrap_or(d.agent_radius),
-        min_region_size: d.min_region_size,
-        merge_region_size: d.merge_region_size,
-        detail_sample_max_error: d.detail_sample_max_error,
-        tile_size: d.tile_size,
-        aabb: d.aabb,
-        contour_flags: d.contour_flags,
-        tiling: d.tiling,
-        area_volumes: d.area_volumes.clone(),
-        edge_max_len_factor: d.edge_max_len_factor,
-        max_simplification_error: d.max_simplification_error,
-        max_vertices_per_polygon: d.max_vertices_per_polygon,
-        detail_sample_dist: d.detail_sample_dist,
-        up: d.up,
-        filter: None,
-    };
+    // Only mark the settings as changed when they actually differ, so that the navmesh is not rebuilt every frame.
+    let new_settings = NavmeshSettings::builder()
+        .cell_size_fraction(cell_size.get().parse().unwrap_or(d.cell_size_fraction))
+        .cell_height_fraction(cell_height.get().parse().unwrap_or(d.cell_height_fraction))
+        .walkable_slope_angle(
+            walkable_slope
+                .get()
+                .parse()
+                .unwrap_or(d.walkable_slope_angle.to_degrees())
+                .clamp(0.0, 90.0)
+                .to_radians(),
+        )
+        .agent_height(agent_height.get().parse().unwrap_or(d.agent_height))
+        .walkable_climb(walkable_climb.get().parse().unwrap_or(d.walkable_climb))
+        .agent_radius(agent_radius.get().parse().unwrap_or(d.agent_radius))
+        .partition(
+            partition
+                .single()
+                .map(|radio| radio.0)
+                .unwrap_or(d.partition),
+        )
+        .build();
+    settings.set_if_neq(GlobalNavmeshSettings(new_settings));
 }
 
 fn save_navmesh(
@@ -352,6 +454,37 @@ fn save_navmesh(
     }));
 }
 
+fn export_obj(
+    _: On<Activate>,
+    world_id: Res<WorldIdRes>,
+    mut task: Local<Option<Task<()>>>,
+    window_handle: Single<&RawHandleWrapper, With<PrimaryWindow>>,
+) {
+    let world_id = world_id.0.clone();
+    if task.as_ref().is_some_and(|task| task.is_finished()) {
+        task.take();
+    }
+    if task.is_some() {
+        info!("an OBJ export task is already running");
+        return;
+    }
+    // Safety: we're on the main thread, so this is fine??? I think??
+    let window_handle = unsafe { window_handle.get_handle() };
+    let save_file_dialog = AsyncFileDialog::new()
+        .add_filter("Wavefront OBJ", &["obj"])
+        .add_filter("All files", &["*"])
+        .set_title("Export Navmesh as OBJ")
+        .set_file_name("navmesh.obj")
+        .set_parent(&window_handle)
+        .set_can_create_directories(true)
+        .save_file();
+    task.replace(AsyncComputeTaskPool::get().spawn(async move {
+        if let Err(e) = save::export_obj(world_id, save_file_dialog).await {
+            error!("OBJ export failed: {e:?}");
+        }
+    }));
+}
+
 fn load_navmesh(
     _: On<Activate>,
     mut commands: Commands,
@@ -378,6 +511,49 @@ fn load_navmesh(
     commands.insert_resource(LoadTask(task));
 }
 
+fn toggle_recent_navmeshes(
+    _: On<Activate>,
+    mut menu: Single<&mut Node, With<RecentNavmeshesMenu>>,
+) {
+    menu.display = if menu.display == Display::None {
+        Display::Flex
+    } else {
+        Display::None
+    };
+}
+
+fn update_recent_navmeshes_menu(
+    mut commands: Commands,
+    config: Res<EditorConfig>,
+    menu: Single<Entity, With<RecentNavmeshesMenu>>,
+) {
+    let menu = *menu;
+    commands.entity(menu).despawn_related::<Children>();
+    if config.recent_navmeshes.is_empty() {
+        commands.spawn((label("No recent navmeshes"), ChildOf(menu)));
+        return;
+    }
+    for path in &config.recent_navmeshes {
+        let path_to_load = path.clone();
+        commands.spawn((
+            feathers::controls::button(
+                ButtonProps::default(),
+                (),
+                Spawn((Text::new(path.display().to_string()), ThemedText)),
+            ),
+            observe(
+                move |_: On<Activate>,
+                      mut commands: Commands,
+                      mut menu: Single<&mut Node, With<RecentNavmeshesMenu>>| {
+                    menu.display = Display::None;
+                    commands.trigger(LoadNavmeshFile(path_to_load.clone()));
+                },
+            ),
+            ChildOf(menu),
+        ));
+    }
+}
+
 fn menu_button(button: impl Bundle) -> impl Bundle {
     (
         Node {
@@ -426,17 +602,28 @@ struct BuildNavmeshButton;
 #[derive(Component)]
 struct SaveNavmeshButton;
 
+#[derive(Component)]
+struct ExportObjButton;
+
 #[derive(Component)]
 struct LoadNavmeshButton;
 
 #[derive(Component)]
 struct StatusText;
 
+#[derive(Component)]
+struct RecentNavmeshesMenu;
+
+/// Marks the container of the reconnect button, which is only shown while the game cannot be reached.
+#[derive(Component)]
+struct ReconnectButton;
+
 fn update_primary_buttons_when_obstacle_added(
     _obstacle_added: On<Add, ObstacleGizmo>,
     load_button: Single<Entity, With<LoadSceneButton>>,
     build_button: Single<Entity, With<BuildNavmeshButton>>,
     save_button: Single<Entity, With<SaveNavmeshButton>>,
+    export_obj_button: Single<Entity, With<ExportObjButton>>,
     load_navmesh_button: Single<Entity, With<LoadNavmeshButton>>,
     mut commands: Commands,
 ) {
@@ -448,6 +635,9 @@ fn update_primary_buttons_when_obstacle_added(
     commands
         .entity(*save_button)
         .remove::<InteractionDisabled>();
+    commands
+        .entity(*export_obj_button)
+        .remove::<InteractionDisabled>();
     commands
         .entity(*load_navmesh_button)
         .remove::<InteractionDisabled>();
@@ -458,6 +648,7 @@ fn update_primary_buttons_when_obstacle_removed(
     load_button: Single<Entity, With<LoadSceneButton>>,
     build_button: Single<Entity, With<BuildNavmeshButton>>,
     save_button: Single<Entity, With<SaveNavmeshButton>>,
+    export_obj_button: Single<Entity, With<ExportObjButton>>,
     load_navmesh_button: Single<Entity, With<LoadNavmeshButton>>,
     mut commands: Commands,
 ) {
@@ -466,11 +657,69 @@ fn update_primary_buttons_when_obstacle_removed(
         .entity(*build_button)
         .insert((ButtonVariant::Normal, InteractionDisabled));
     commands.entity(*save_button).insert(InteractionDisabled);
+    commands
+        .entity(*export_obj_button)
+        .insert(InteractionDisabled);
     commands
         .entity(*load_navmesh_button)
         .insert(InteractionDisabled);
 }
 
+fn update_status_text(
+    ready: On<NavmeshReady>,
+    navmeshes: Res<Assets<Navmesh>>,
+    running_build: Res<RunningBuild>,
+    status_text: Single<&Children, With<StatusText>>,
+    mut texts: Query<&mut Text>,
+) {
+    let Some(navmesh) = navmeshes.get(ready.event().0) else {
+        return;
+    };
+    let mut status = if navmesh.is_empty() {
+        "Empty navmesh: no walkable surfaces found".to_string()
+    } else {
+        format!(
+            "{} polygons, {} vertices, {} detail triangles",
+            navmesh.polygon_count(),
+            navmesh.vertex_count(),
+            navmesh.detail_triangle_count(),
+        )
+    };
+    if let Some(elapsed) = running_build.elapsed() {
+        status += &format!(", built in {:.2} s", elapsed.as_secs_f32());
+    }
+    set_status_text(&status_text, &mut texts, &status);
+}
+
+fn update_connection_status(
+    state: Res<ConnectionState>,
+    connection_input: Single<&TextInputContents, With<ConnectionInput>>,
+    mut reconnect_button: Single<&mut Node, With<ReconnectButton>>,
+    status_text: Single<&Children, With<StatusText>>,
+    mut texts: Query<&mut Text>,
+) {
+    reconnect_button.display = if *state == ConnectionState::Disconnected {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    let url = connection_input.get();
+    let status = match *state {
+        ConnectionState::Idle => return,
+        ConnectionState::Connected => format!("Connected to {url}"),
+        ConnectionState::Disconnected => format!("Disconnected from {url}"),
+    };
+    set_status_text(&status_text, &mut texts, &status);
+}
+
+fn set_status_text(status_text: &Children, texts: &mut Query<&mut Text>, status: &str) {
+    for child in status_text.iter() {
+        if let Ok(mut text) = texts.get_mut(child) {
+            status.clone_into(&mut text.0);
+        }
+    }
+}
+
 fn clear_focus(press: On<Pointer<Press>>, mut focus: ResMut<InputFocus>) {
     if Some(press.original_event_target()) != focus.0 {
         focus.0 = None;
@@ -527,6 +776,20 @@ fn set_gizmo(gizmo: AvailableGizmos) -> impl ObserverSystem<ValueChange<bool>, (
     )
 }
 
+fn set_partition_method(
+    val: On<ValueChange<Entity>>,
+    radios: Query<Entity, With<PartitionRadio>>,
+    mut commands: Commands,
+) {
+    for radio in &radios {
+        if radio == val.value {
+            commands.entity(radio).insert(Checked);
+        } else {
+            commands.entity(radio).remove::<Checked>();
+        }
+    }
+}
+
 fn set_ui_size(add: On<Add, InheritableFont>, mut font: Query<&mut InheritableFont>) {
     font.get_mut(add.entity).unwrap().font_size = FONT_SIZE;
 }
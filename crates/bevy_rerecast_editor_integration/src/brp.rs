@@ -8,11 +8,11 @@ use bevy_camera::visibility::InheritedVisibility;
 use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::prelude::*;
 use bevy_image::{Image, SerializedImage};
-use bevy_mesh::{Mesh, Mesh3d, SerializedMesh};
+use bevy_mesh::{Mesh, Mesh3d};
 use bevy_pbr::{MeshMaterial3d, StandardMaterial};
 use bevy_platform::collections::HashMap;
 use bevy_remote::{BrpError, BrpResult, RemoteMethodSystemId, RemoteMethods};
-use bevy_rerecast_core::{NavmeshBackend, NavmeshSettings};
+use bevy_rerecast_core::{NavmeshBackends, NavmeshSettings};
 use bevy_tasks::{AsyncComputeTaskPool, Task, futures_lite::future};
 use bevy_transform::prelude::*;
 use rerecast::TriMesh;
@@ -20,12 +20,15 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::{
-    EditorExluded,
-    transmission::{SerializedStandardMaterial, serialize},
+    EditorExcluded,
+    transmission::{
+        SerializedStandardMaterial, SerializedVisualMesh, TransmissionConfig, serialize,
+    },
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<NavmeshInputTasks>();
+    app.init_resource::<TransmissionConfig>();
     app.add_systems(
         Startup,
         setup_methods.run_if(resource_exists::<RemoteMethods>),
@@ -72,14 +75,15 @@ fn get_navmesh_input(In(params): In<Option<Value>>, world: &mut World) -> BrpRes
             });
         }
     };
-    let Some(backend_id) = world.get_resource::<NavmeshBackend>().cloned() else {
+    let Some(backends) = world.get_resource::<NavmeshBackends>().cloned() else {
         return Err(BrpError {
             code: bevy_remote::error_codes::RESOURCE_NOT_PRESENT,
             message: "No navmesh backend found. Did you forget to add one?".to_string(),
             data: None,
         });
     };
-    let obstacles = match world.run_system_with(*backend_id, params.backend_input) {
+    let config = *world.resource::<TransmissionConfig>();
+    let obstacles = match backends.run(world, &params.backend_input) {
         Ok(obstacles) => obstacles,
         Err(err) => {
             return Err(BrpError {
@@ -90,12 +94,42 @@ fn get_navmesh_input(In(params): In<Option<Value>>, world: &mut World) -> BrpRes
         }
     };
 
+    let response = collect_editor_input(world, obstacles, config.quantize_positions)?;
+    let future = async move {
+        serialize(&response, config.compression).map_err(|e| BrpError {
+            code: bevy_remote::error_codes::INTERNAL_ERROR,
+            message: format!("Failed to serialize navmesh input: {e}"),
+            data: None,
+        })
+    };
+    let id = Uuid::new_v4();
+    let mut tasks = world.resource_mut::<NavmeshInputTasks>();
+    let task = AsyncComputeTaskPool::get().spawn(future);
+    tasks.0.insert(id, task);
+
+    let response = GenerateEditorInputResponse {
+        id: EditorInputTaskId(id.to_string()),
+    };
+    serde_json::to_value(&response).map_err(|e| BrpError {
+        code: bevy_remote::error_codes::INTERNAL_ERROR,
+        message: format!("Failed to serialize editor task ID: {e}"),
+        data: None,
+    })
+}
+
+/// Collects the obstacles and all visual meshes for the editor.
+/// Every mesh, material, and image is only included once, no matter how many entities use it.
+fn collect_editor_input(
+    world: &mut World,
+    obstacles: TriMesh,
+    quantize_positions: bool,
+) -> Result<PollEditorInputResponse, BrpError> {
     let mut visuals = world.query_filtered::<(
         &GlobalTransform,
         &Mesh3d,
         &InheritedVisibility,
         Option<&MeshMaterial3d<StandardMaterial>>,
-    ), Without<EditorExluded>>();
+    ), Without<EditorExcluded>>();
     let Some(meshes) = world.get_resource::<Assets<Mesh>>() else {
         return Err(BrpError {
             code: bevy_remote::error_codes::RESOURCE_NOT_PRESENT,
@@ -118,12 +152,12 @@ fn get_navmesh_input(In(params): In<Option<Value>>, world: &mut World) -> BrpRes
         });
     };
 
-    let mut image_indices: HashMap<Handle<Image>, u32> = HashMap::new();
-    let mut material_indices: HashMap<Handle<StandardMaterial>, u32> = HashMap::new();
-    let mut mesh_indices: HashMap<Handle<Mesh>, u32> = HashMap::new();
+    let mut image_indices: HashMap<AssetId<Image>, u32> = HashMap::new();
+    let mut material_indices: HashMap<AssetId<StandardMaterial>, u32> = HashMap::new();
+    let mut mesh_indices: HashMap<AssetId<Mesh>, u32> = HashMap::new();
     let mut serialized_images: Vec<SerializedImage> = Vec::new();
     let mut serialized_materials: Vec<SerializedStandardMaterial> = Vec::new();
-    let mut serialized_meshes: Vec<SerializedMesh> = Vec::new();
+    let mut serialized_meshes: Vec<SerializedVisualMesh> = Vec::new();
 
     let visuals = visuals
         .iter(world)
@@ -132,17 +166,20 @@ fn get_navmesh_input(In(params): In<Option<Value>>, world: &mut World) -> BrpRes
                 return None;
             }
             let transform = *transform;
-            let mesh_index = if let Some(&index) = mesh_indices.get(&mesh_handle.0) {
+            let mesh_index = if let Some(&index) = mesh_indices.get(&mesh_handle.id()) {
                 index
             } else {
                 let mesh = meshes.get(mesh_handle)?;
                 let index = serialized_meshes.len() as u32;
-                serialized_meshes.push(SerializedMesh::from_mesh(mesh.clone()));
-                mesh_indices.insert(mesh_handle.0.clone(), index);
+                serialized_meshes.push(SerializedVisualMesh::from_mesh(
+                    mesh.clone(),
+                    quantize_positions,
+                ));
+                mesh_indices.insert(mesh_handle.id(), index);
                 index
             };
             let material_index = if let Some(material_handle) = material_handle {
-                if let Some(&index) = material_indices.get(&material_handle.0) {
+                if let Some(&index) = material_indices.get(&material_handle.id()) {
                     Some(index)
                 } else {
                     match materials.get(material_handle) {
@@ -156,7 +193,7 @@ fn get_navmesh_input(In(params): In<Option<Value>>, world: &mut World) -> BrpRes
                             ) {
                                 Ok(serialized_material) => {
                                     serialized_materials.push(serialized_material);
-                                    material_indices.insert(material_handle.0.clone(), index);
+                                    material_indices.insert(material_handle.id(), index);
                                     Some(index)
                                 }
                                 Err(_e) => None,
@@ -176,32 +213,12 @@ fn get_navmesh_input(In(params): In<Option<Value>>, world: &mut World) -> BrpRes
             })
         })
         .collect::<Vec<_>>();
-    let response = PollEditorInputResponse {
+    Ok(PollEditorInputResponse {
         obstacles,
         visual_meshes: visuals,
         materials: serialized_materials,
         meshes: serialized_meshes,
         images: serialized_images,
-    };
-    let future = async move {
-        serialize(&response).map_err(|e| BrpError {
-            code: bevy_remote::error_codes::INTERNAL_ERROR,
-            message: format!("Failed to serialize navmesh input: {e}"),
-            data: None,
-        })
-    };
-    let id = Uuid::new_v4();
-    let mut tasks = world.resource_mut::<NavmeshInputTasks>();
-    let task = AsyncComputeTaskPool::get().spawn(future);
-    tasks.0.insert(id, task);
-
-    let response = GenerateEditorInputResponse {
-        id: EditorInputTaskId(id.to_string()),
-    };
-    serde_json::to_value(&response).map_err(|e| BrpError {
-        code: bevy_remote::error_codes::INTERNAL_ERROR,
-        message: format!("Failed to serialize editor task ID: {e}"),
-        data: None,
     })
 }
 
@@ -301,7 +318,7 @@ pub struct PollEditorInputResponse {
     /// Materials indexed by [`Self::visual_meshes`].
     pub materials: Vec<SerializedStandardMaterial>,
     /// Meshes indexed by [`Self::visual_meshes`].
-    pub meshes: Vec<SerializedMesh>,
+    pub meshes: Vec<SerializedVisualMesh>,
     /// Images indexed by [`Self::materials`].
     pub images: Vec<SerializedImage>,
 }
@@ -316,3 +333,39 @@ pub struct VisualMesh {
     /// The index of the material in [`PollEditorInputResponse::materials`].
     pub material: Option<u32>,
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_math::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn shared_assets_are_transmitted_once() {
+        let mut world = World::new();
+        let mut meshes = Assets::<Mesh>::default();
+        let mesh = meshes.add(Cuboid::default());
+        world.insert_resource(meshes);
+        let mut materials = Assets::<StandardMaterial>::default();
+        let material = materials.add(StandardMaterial::default());
+        world.insert_resource(materials);
+        world.init_resource::<Assets<Image>>();
+        for x in [0.0, 2.0] {
+            world.spawn((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                GlobalTransform::from_xyz(x, 0.0, 0.0),
+                InheritedVisibility::VISIBLE,
+            ));
+        }
+
+        let response = collect_editor_input(&mut world, TriMesh::default(), true).unwrap();
+        assert_eq!(response.visual_meshes.len(), 2);
+        assert_eq!(response.meshes.len(), 1);
+        assert_eq!(response.materials.len(), 1);
+        for visual in &response.visual_meshes {
+            assert_eq!(visual.mesh, 0);
+            assert_eq!(visual.material, Some(0));
+        }
+    }
+}
@@ -26,21 +26,30 @@ impl Plugin for NavmeshEditorIntegrationPlugin {
             app.add_observer(exclude_polygon_gizmo)
                 .add_observer(exclude_detail_gizmo);
         }
-        app.register_type::<EditorExluded>();
+        app.register_type::<EditorExcluded>();
     }
 }
 
 #[cfg(feature = "debug_plugin")]
 fn exclude_polygon_gizmo(trigger: On<Add, PolygonNavmeshGizmo>, mut commands: Commands) {
-    commands.entity(trigger.entity).insert(EditorExluded);
+    commands.entity(trigger.entity).insert(EditorExcluded);
 }
 
 #[cfg(feature = "debug_plugin")]
 fn exclude_detail_gizmo(trigger: On<Add, DetailNavmeshGizmo>, mut commands: Commands) {
-    commands.entity(trigger.entity).insert(EditorExluded);
+    commands.entity(trigger.entity).insert(EditorExcluded);
 }
 
 /// Component used to mark [`Mesh3d`](bevy_mesh::Mesh3d)es so that they're not sent to the editor for previewing the level.
 #[derive(Debug, Component, Reflect, Serialize, Deserialize)]
 #[reflect(Component, Serialize, Deserialize)]
-pub struct EditorExluded;
+pub struct EditorExcluded;
+
+/// Misspelled name of [`EditorExcluded`].
+#[deprecated(note = "Renamed to `EditorExcluded`")]
+pub type EditorExluded = EditorExcluded;
+
+/// Misspelled name of [`EditorExcluded`], so that `insert(EditorExluded)` keeps compiling.
+#[deprecated(note = "Renamed to `EditorExcluded`")]
+#[allow(non_upper_case_globals)]
+pub const EditorExluded: EditorExcluded = EditorExcluded;
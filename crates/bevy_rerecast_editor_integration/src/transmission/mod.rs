@@ -2,6 +2,8 @@
 
 mod serialization;
 mod serialized_standard_material;
+mod serialized_visual_mesh;
 
 pub use serialization::*;
 pub use serialized_standard_material::*;
+pub use serialized_visual_mesh::*;
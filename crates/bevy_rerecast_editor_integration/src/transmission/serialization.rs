@@ -1,39 +1,166 @@
 //! Serialization and deserialization of data for the editor integration.
 
-use anyhow::Context as _;
+use std::io::{Read as _, Write as _};
+
+use anyhow::{Context as _, bail};
 use base64::prelude::*;
 use bevy_ecs::prelude::*;
+use flate2::{Compression, read::ZlibDecoder, write::ZlibEncoder};
 use serde::{Serialize, de::DeserializeOwned};
 use serde_json::Value;
 
+/// The maximum size of decompressed data accepted by [`deserialize`],
+/// so that a small malicious payload can't decompress into gigabytes.
+const MAX_DECOMPRESSED_BYTES: u64 = 1 << 30;
+
+/// Configures how data is transmitted between the editor and the running game.
+///
+/// Insert this resource into the running game to change the settings used for responses to the editor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Resource)]
+pub struct TransmissionConfig {
+    /// The compression applied to transmitted data.
+    pub compression: TransmissionCompression,
+    /// Whether to quantize the vertex positions of visual meshes to 16 bits per axis, see [`QuantizedPositions`](super::QuantizedPositions).
+    /// This greatly reduces the size of large scenes, and the precision is plenty for previewing them in the editor.
+    /// Disable this to transmit the exact positions.
+    pub quantize_positions: bool,
+}
+
+impl Default for TransmissionConfig {
+    fn default() -> Self {
+        Self {
+            compression: TransmissionCompression::default(),
+            quantize_positions: true,
+        }
+    }
+}
+
+/// The compression applied to transmitted data.
+///
+/// The compression is written as a one-byte tag in front of the data,
+/// so the receiving side doesn't need to know which compression was used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TransmissionCompression {
+    /// Don't compress the data. Cheaper to encode, but results in much larger payloads.
+    None = 0,
+    /// Compress the data with zlib.
+    #[default]
+    Zlib = 1,
+}
+
+impl TransmissionCompression {
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(Self::None),
+            1 => Some(Self::Zlib),
+            _ => None,
+        }
+    }
+}
+
 /// Serializes a value to a JSON value in the format expected by the editor integration.
-pub fn serialize<T: Serialize>(val: &T) -> Result<Value> {
-    let bytes = bincode::serde::encode_to_vec(val, bincode::config::standard())?;
+///
+/// The time spent in each phase is recorded in `debug` level [`tracing`] spans.
+pub fn serialize<T: Serialize>(val: &T, compression: TransmissionCompression) -> Result<Value> {
+    let encoded = {
+        let _span = tracing::debug_span!("bincode_encode").entered();
+        bincode::serde::encode_to_vec(val, bincode::config::standard())?
+    };
 
-    /*
-    let mut compression_encoder = ZlibEncoder::new(Vec::new(), Compression::fast());
-    compression_encoder.write_all(&bytes)?;
-    let bytes = compression_encoder.finish()?;
-    */
+    let mut bytes = vec![compression as u8];
+    match compression {
+        TransmissionCompression::None => bytes.extend(encoded),
+        TransmissionCompression::Zlib => {
+            let _span = tracing::debug_span!("zlib_compress", bytes = encoded.len()).entered();
+            let mut encoder = ZlibEncoder::new(bytes, Compression::fast());
+            encoder.write_all(&encoded)?;
+            bytes = encoder.finish()?;
+        }
+    }
 
-    let string = BASE64_STANDARD.encode(bytes);
+    let string = {
+        let _span = tracing::debug_span!("base64_encode", bytes = bytes.len()).entered();
+        BASE64_STANDARD.encode(bytes)
+    };
 
     Ok(Value::String(string))
 }
 
 /// Deserializes a JSON value in the format expected by the editor integration to a value.
+///
+/// The compression used by [`serialize`] is detected automatically.
+/// The time spent in each phase is recorded in `debug` level [`tracing`] spans.
 pub fn deserialize<T: DeserializeOwned>(value: &Value) -> anyhow::Result<T> {
     let string = value.as_str().context("Expected a string")?;
 
-    let bytes = BASE64_STANDARD.decode(string)?;
+    let bytes = {
+        let _span = tracing::debug_span!("base64_decode", chars = string.len()).entered();
+        BASE64_STANDARD.decode(string)?
+    };
 
-    /*
-    let mut compression_decoder = ZlibDecoder::new(&bytes[..]);
-    let mut bytes = Vec::new();
-    compression_decoder.read_to_end(&mut bytes)?;
-    */
+    let (&tag, bytes) = bytes.split_first().context("Expected a compression tag")?;
+    let Some(compression) = TransmissionCompression::from_tag(tag) else {
+        bail!("Unknown compression tag {tag}");
+    };
+    let bytes = match compression {
+        TransmissionCompression::None => bytes.to_vec(),
+        TransmissionCompression::Zlib => {
+            let _span = tracing::debug_span!("zlib_decompress", bytes = bytes.len()).entered();
+            let mut decoder = ZlibDecoder::new(bytes).take(MAX_DECOMPRESSED_BYTES + 1);
+            let mut decompressed = Vec::new();
+            decoder.read_to_end(&mut decompressed)?;
+            if decompressed.len() as u64 > MAX_DECOMPRESSED_BYTES {
+                bail!("Decompressed data is larger than {MAX_DECOMPRESSED_BYTES} bytes");
+            }
+            decompressed
+        }
+    };
 
+    let _span = tracing::debug_span!("bincode_decode", bytes = bytes.len()).entered();
     let (val, _len): (T, usize) =
         bincode::serde::decode_from_slice(&bytes, bincode::config::standard())?;
     Ok(val)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload() -> Vec<u32> {
+        (0..1000).map(|i| i % 7).collect()
+    }
+
+    #[test]
+    fn round_trip_uncompressed() {
+        let value = serialize(&payload(), TransmissionCompression::None).unwrap();
+        let decoded: Vec<u32> = deserialize(&value).unwrap();
+        assert_eq!(decoded, payload());
+    }
+
+    #[test]
+    fn round_trip_zlib() {
+        let value = serialize(&payload(), TransmissionCompression::Zlib).unwrap();
+        let decoded: Vec<u32> = deserialize(&value).unwrap();
+        assert_eq!(decoded, payload());
+
+        let uncompressed = serialize(&payload(), TransmissionCompression::None).unwrap();
+        assert!(value.as_str().unwrap().len() < uncompressed.as_str().unwrap().len());
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let value = serialize(&payload(), TransmissionCompression::None).unwrap();
+        let bytes = BASE64_STANDARD.decode(value.as_str().unwrap()).unwrap();
+        for len in 0..bytes.len() {
+            let value = Value::String(BASE64_STANDARD.encode(&bytes[..len]));
+            assert!(deserialize::<Vec<u32>>(&value).is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_tag() {
+        let value = Value::String(BASE64_STANDARD.encode([42, 0]));
+        assert!(deserialize::<Vec<u32>>(&value).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use bevy_asset::{Assets, Handle};
+use bevy_asset::{AssetId, Assets, Handle};
 use bevy_color::prelude::*;
 use bevy_image::{Image, SerializedImage};
 use bevy_math::Affine2;
@@ -122,13 +122,13 @@ impl SerializedStandardMaterial {
     /// Serialize a [`StandardMaterial`] into a [`SerializedStandardMaterial`]. Returns `None` if any of the images are not found in `images`.
     pub fn try_from_standard_material(
         material: StandardMaterial,
-        indices: &mut HashMap<Handle<Image>, u32>,
+        indices: &mut HashMap<AssetId<Image>, u32>,
         images: &Assets<Image>,
         cache: &mut Vec<SerializedImage>,
     ) -> Result<Self, SerializedStandardMaterialError> {
-        let mut serialize_image = |image_handle, name| {
+        let mut serialize_image = |image_handle: Option<Handle<Image>>, name| {
             if let Some(image_handle) = image_handle {
-                let index = match indices.get(&image_handle) {
+                let index = match indices.get(&image_handle.id()) {
                     Some(&index) => index,
                     None => {
                         let Some(image) = images.get(&image_handle) else {
@@ -136,7 +136,7 @@ impl SerializedStandardMaterial {
                         };
                         let index = cache.len() as u32;
                         cache.push(SerializedImage::from_image(image.clone()));
-                        indices.insert(image_handle, index);
+                        indices.insert(image_handle.id(), index);
                         index
                     }
                 };
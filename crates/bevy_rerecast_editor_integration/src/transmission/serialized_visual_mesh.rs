@@ -0,0 +1,154 @@
+use bevy_math::{U16Vec3, Vec3};
+use bevy_mesh::{Mesh, SerializedMesh, VertexAttributeValues};
+use serde::{Deserialize, Serialize};
+
+/// Serialized representation of a [`Mesh`] that is sent to the editor for visualizing the level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializedVisualMesh {
+    mesh: SerializedMesh,
+    quantized_positions: Option<QuantizedPositions>,
+}
+
+impl SerializedVisualMesh {
+    /// Serialize a [`Mesh`] into a [`SerializedVisualMesh`].
+    ///
+    /// If `quantize_positions` is `true`, the vertex positions are stored as [`QuantizedPositions`],
+    /// which is much smaller but only precise enough for previewing the mesh.
+    pub fn from_mesh(mut mesh: Mesh, quantize_positions: bool) -> Self {
+        let quantized_positions = if quantize_positions {
+            match mesh.remove_attribute(Mesh::ATTRIBUTE_POSITION) {
+                Some(VertexAttributeValues::Float32x3(positions)) => {
+                    let positions = positions.into_iter().map(Vec3::from_array);
+                    Some(QuantizedPositions::new(positions))
+                }
+                Some(positions) => {
+                    // Unusual format, send it as is.
+                    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+                    None
+                }
+                None => None,
+            }
+        } else {
+            None
+        };
+        Self {
+            mesh: SerializedMesh::from_mesh(mesh),
+            quantized_positions,
+        }
+    }
+
+    /// Deserialize a [`SerializedVisualMesh`] into a [`Mesh`].
+    pub fn into_mesh(self) -> Mesh {
+        let mut mesh = self.mesh.into_mesh();
+        if let Some(quantized_positions) = self.quantized_positions {
+            let positions = quantized_positions
+                .decode()
+                .map(Vec3::to_array)
+                .collect::<Vec<_>>();
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        }
+        mesh
+    }
+}
+
+/// Vertex positions quantized to 16 bits per axis within their AABB.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuantizedPositions {
+    /// The minimum corner of the AABB of the positions.
+    pub min: Vec3,
+    /// The maximum corner of the AABB of the positions.
+    pub max: Vec3,
+    /// The positions relative to [`Self::min`], scaled so that [`Self::max`] corresponds to [`u16::MAX`].
+    pub positions: Vec<U16Vec3>,
+}
+
+impl QuantizedPositions {
+    /// Quantize positions.
+    /// The error per axis is at most the extent of the AABB on that axis divided by `2 * u16::MAX`.
+    pub fn new(positions: impl IntoIterator<Item = Vec3>) -> Self {
+        let positions = positions.into_iter().collect::<Vec<_>>();
+        if positions.is_empty() {
+            return Self {
+                min: Vec3::ZERO,
+                max: Vec3::ZERO,
+                positions: Vec::new(),
+            };
+        }
+        let (min, max) = positions.iter().fold(
+            (Vec3::INFINITY, Vec3::NEG_INFINITY),
+            |(min, max), position| (min.min(*position), max.max(*position)),
+        );
+        let scale = Self::scale(min, max);
+        let positions = positions
+            .into_iter()
+            .map(|position| {
+                let quantized = ((position - min) / scale).round();
+                quantized
+                    .clamp(Vec3::ZERO, Vec3::splat(u16::MAX as f32))
+                    .as_u16vec3()
+            })
+            .collect();
+        Self {
+            min,
+            max,
+            positions,
+        }
+    }
+
+    /// Decode the quantized positions back into world positions.
+    pub fn decode(&self) -> impl Iterator<Item = Vec3> + '_ {
+        let scale = Self::scale(self.min, self.max);
+        self.positions
+            .iter()
+            .map(move |position| self.min + position.as_vec3() * scale)
+    }
+
+    /// The size of one quantization step per axis. Flat axes get a step of 1 to avoid dividing by zero.
+    fn scale(min: Vec3, max: Vec3) -> Vec3 {
+        let scale = (max - min) / u16::MAX as f32;
+        Vec3::select(scale.cmpgt(Vec3::ZERO), scale, Vec3::ONE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_math::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn quantized_positions_are_close() {
+        let positions = [
+            Vec3::new(-3.5, 0.0, 100.0),
+            Vec3::new(12.25, 0.0, -7.0),
+            Vec3::new(0.1, 0.0, 0.2),
+        ];
+        let quantized = QuantizedPositions::new(positions);
+        assert_eq!(quantized.min, Vec3::new(-3.5, 0.0, -7.0));
+        assert_eq!(quantized.max, Vec3::new(12.25, 0.0, 100.0));
+        let tolerance = (quantized.max - quantized.min) / (2.0 * u16::MAX as f32);
+        for (decoded, original) in quantized.decode().zip(positions) {
+            assert!(((decoded - original).abs() - tolerance).max_element() <= 1e-5);
+            // Flat axes are exact.
+            assert_eq!(decoded.y, 0.0);
+        }
+    }
+
+    #[test]
+    fn exact_positions_round_trip() {
+        let mesh = Mesh::from(Sphere::new(1.5));
+        let original = mesh.attribute(Mesh::ATTRIBUTE_POSITION).unwrap().clone();
+
+        let exact = SerializedVisualMesh::from_mesh(mesh.clone(), false).into_mesh();
+        let exact = exact.attribute(Mesh::ATTRIBUTE_POSITION).unwrap();
+        assert_eq!(exact.as_float3(), original.as_float3());
+
+        let quantized = SerializedVisualMesh::from_mesh(mesh, true).into_mesh();
+        let quantized = quantized.attribute(Mesh::ATTRIBUTE_POSITION).unwrap();
+        assert_eq!(quantized.len(), original.len());
+        let quantized = quantized.as_float3().unwrap();
+        for (a, b) in quantized.iter().zip(original.as_float3().unwrap()) {
+            assert!(Vec3::from_array(*a).distance(Vec3::from_array(*b)) < 1e-3);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use alloc::vec::Vec;
+use alloc::{vec, vec::Vec};
 
 #[cfg(feature = "bevy_reflect")]
 use bevy_reflect::prelude::*;
@@ -6,7 +6,10 @@ use glam::{U16Vec3, Vec3Swizzles};
 
 use crate::{
     Aabb3d, AreaType, CompactHeightfield, RegionId,
-    math::{dir_offset_x, dir_offset_z, distance_squared_between_point_and_line_u16vec2},
+    math::{
+        dir_offset_x, dir_offset_z, distance_squared_between_point_and_line_u16vec2, next, prev,
+    },
+    poly_mesh::{intersect, is_left, is_left_on, vequal},
 };
 
 impl CompactHeightfield {
@@ -121,7 +124,8 @@ impl CompactHeightfield {
                             #[cfg(feature = "tracing")]
                             let old_max = max_contours;
                             max_contours *= 2;
-                            cset.contours.truncate(max_contours as usize);
+                            cset.contours
+                                .resize_with(max_contours as usize, Contour::default);
 
                             #[cfg(feature = "tracing")]
                             tracing::warn!(
@@ -154,6 +158,9 @@ impl CompactHeightfield {
             }
         }
         cset.contours.resize_with(contour_count, Contour::default);
+
+        // Merge holes if needed.
+        cset.merge_holes(self.max_region);
         cset
     }
 
@@ -497,6 +504,199 @@ fn simplify_contour(
     }
 }
 
+impl ContourSet {
+    /// Merges the holes of each region into the region's outline, so that every region is a single simple polygon.
+    /// Holes are wound backwards and happen e.g. for layer regions around an obstacle.
+    fn merge_holes(&mut self, max_region: RegionId) {
+        // If the contour is wound backwards, it is a hole.
+        let is_hole = self
+            .contours
+            .iter()
+            .map(|contour| signed_area(&contour.vertices) < 0)
+            .collect::<Vec<_>>();
+        if !is_hole.contains(&true) {
+            return;
+        }
+
+        // Collect outline contour and holes contours per region.
+        // We assume that there is one outline and multiple holes.
+        let region_count = max_region.bits() as usize + 1;
+        let mut outlines = vec![None; region_count];
+        let mut holes = vec![Vec::new(); region_count];
+        for (i, contour) in self.contours.iter().enumerate() {
+            let region = contour.region.bits() as usize;
+            if is_hole[i] {
+                holes[region].push(i);
+            } else {
+                #[cfg(feature = "tracing")]
+                if outlines[region].is_some() {
+                    tracing::error!("Multiple outlines for region {region}.");
+                }
+                outlines[region] = Some(i);
+            }
+        }
+
+        // Finally merge each region's holes into the outline.
+        for (region, holes) in holes.into_iter().enumerate() {
+            if holes.is_empty() {
+                continue;
+            }
+            let Some(outline) = outlines[region] else {
+                // The region does not have an outline.
+                // This can happen if the contour becomes self-overlapping because of
+                // too aggressive simplification settings.
+                #[cfg(feature = "tracing")]
+                tracing::error!(
+                    "Bad outline for region {region}, contour simplification is likely too aggressive."
+                );
+                continue;
+            };
+            self.merge_region_holes(outline, &holes);
+        }
+    }
+
+    fn merge_region_holes(&mut self, outline: usize, holes: &[usize]) {
+        // Sort holes from left to right.
+        let mut holes = holes
+            .iter()
+            .map(|&hole| (hole, leftmost_vertex(&self.contours[hole].vertices)))
+            .collect::<Vec<_>>();
+        holes.sort_by_key(|&(hole, leftmost)| {
+            let vertex = self.contours[hole].vertices[leftmost].0;
+            (vertex.x, vertex.z)
+        });
+
+        let mut diagonals = Vec::new();
+        // Merge holes into the outline one by one.
+        for i in 0..holes.len() {
+            let (hole, leftmost) = holes[i];
+            let outline_vertices = &self.contours[outline].vertices;
+            let hole_vertices = &self.contours[hole].vertices;
+
+            let mut index = None;
+            let mut best_vertex = leftmost;
+            for _ in 0..hole_vertices.len() {
+                // Find potential diagonals.
+                // The 'best' vertex must be in the cone described by 3 consecutive vertices of the outline.
+                // ..o j-1
+                //   |
+                //   |   * best
+                //   |
+                // j o-----o j+1
+                //         :
+                let corner = hole_vertices[best_vertex].0;
+                diagonals.clear();
+                for j in 0..outline_vertices.len() {
+                    if in_cone(j, outline_vertices, corner) {
+                        let delta = outline_vertices[j].0.xz().as_ivec2() - corner.xz().as_ivec2();
+                        diagonals.push((j, delta.length_squared()));
+                    }
+                }
+                // Sort potential diagonals by distance, we want to make the connection as short as possible.
+                diagonals.sort_by_key(|&(_, distance)| distance);
+
+                // Find a diagonal that is not intersecting the outline nor the remaining holes.
+                index = diagonals.iter().map(|&(j, _)| j).find(|&j| {
+                    let point = outline_vertices[j].0;
+                    !intersect_segment_contour(point, corner, Some(j), outline_vertices)
+                        && holes[i..].iter().all(|&(other, _)| {
+                            !intersect_segment_contour(
+                                point,
+                                corner,
+                                None,
+                                &self.contours[other].vertices,
+                            )
+                        })
+                });
+                // If found non-intersecting diagonal, stop looking.
+                if index.is_some() {
+                    break;
+                }
+                // All the potential diagonals for the current vertex were intersecting, try next vertex.
+                best_vertex = next(best_vertex, hole_vertices.len());
+            }
+
+            let Some(index) = index else {
+                #[cfg(feature = "tracing")]
+                tracing::warn!("Failed to find merge points for contours {outline} and {hole}.");
+                continue;
+            };
+            let hole_vertices = core::mem::take(&mut self.contours[hole].vertices);
+            let outline_vertices = &mut self.contours[outline].vertices;
+            let mut merged = Vec::with_capacity(outline_vertices.len() + hole_vertices.len() + 2);
+            // Walk around the outline back to the merge point, then around the hole back to its merge point.
+            merged.extend(
+                (0..=outline_vertices.len())
+                    .map(|i| outline_vertices[(index + i) % outline_vertices.len()]),
+            );
+            merged.extend(
+                (0..=hole_vertices.len())
+                    .map(|i| hole_vertices[(best_vertex + i) % hole_vertices.len()]),
+            );
+            *outline_vertices = merged;
+        }
+    }
+}
+
+/// Twice the signed area of a contour on the xz-plane. Negative for holes.
+fn signed_area(vertices: &[(U16Vec3, u32)]) -> i64 {
+    let mut area = 0;
+    for i in 0..vertices.len() {
+        let vi = vertices[i].0.as_i64vec3();
+        let vj = vertices[prev(i, vertices.len())].0.as_i64vec3();
+        area += vi.x * vj.z - vj.x * vi.z;
+    }
+    area
+}
+
+/// Finds the lowest leftmost vertex of a contour.
+fn leftmost_vertex(vertices: &[(U16Vec3, u32)]) -> usize {
+    (0..vertices.len())
+        .min_by_key(|&i| (vertices[i].0.x, vertices[i].0.z))
+        .unwrap_or_default()
+}
+
+/// Returns true iff `point` is strictly inside the cone of the contour at vertex `i`.
+fn in_cone(i: usize, vertices: &[(U16Vec3, u32)], point: U16Vec3) -> bool {
+    let n = vertices.len();
+    let pi = vertices[i].0;
+    let pi1 = vertices[next(i, n)].0;
+    let pin1 = vertices[prev(i, n)].0;
+
+    // If P[i] is a convex vertex [ i+1 left or on (i-1,i) ].
+    if is_left_on(pin1, pi, pi1) {
+        is_left(pi, point, pin1) && is_left(point, pi, pi1)
+    } else {
+        // Assume (i-1,i,i+1) not collinear.
+        // else P[i] is reflex.
+        !(is_left_on(pi, point, pi1) && is_left_on(point, pi, pin1))
+    }
+}
+
+/// Returns true iff the segment `d0`-`d1` intersects any edge of the contour, ignoring the edges incident to `skip`.
+fn intersect_segment_contour(
+    d0: U16Vec3,
+    d1: U16Vec3,
+    skip: Option<usize>,
+    vertices: &[(U16Vec3, u32)],
+) -> bool {
+    // For each edge (k,k+1) of P
+    let n = vertices.len();
+    (0..n).any(|k| {
+        let k1 = next(k, n);
+        // Skip edges incident to i.
+        if skip == Some(k) || skip == Some(k1) {
+            return false;
+        }
+        let p0 = vertices[k].0;
+        let p1 = vertices[k1].0;
+        if vequal(d0, p0) || vequal(d1, p0) || vequal(d0, p1) || vequal(d1, p1) {
+            return false;
+        }
+        intersect(d0, d1, p0, p1)
+    })
+}
+
 fn remove_degenerate_segments(simplified: &mut Vec<(U16Vec3, u32)>) {
     // Remove adjacent vertices which are equal on xz-plane,
     // or else the triangulator will get confused.
@@ -143,6 +143,68 @@ impl DetailNavmesh {
     pub const MAX_TRIANGLES_PER_SUBMESH: usize = u8::MAX as usize;
     const MAX_VERTS_PER_EDGE: usize = 32;
 
+    /// Computes a normal for each vertex in [`DetailNavmesh::vertices`] by averaging the normals of the adjacent triangles,
+    /// weighted by their area.
+    ///
+    /// The triangle normals are flipped where necessary so that they point towards `up`, which is [`Vec3::Y`]
+    /// for meshes built by Recast. Vertices that are not part of any non-degenerate triangle get `up` as their normal.
+    pub fn compute_normals(&self, up: Vec3) -> Vec<Vec3> {
+        let mut normals = vec![Vec3::ZERO; self.vertices.len()];
+        for mesh in &self.meshes {
+            let base_vertex = mesh.base_vertex_index as usize;
+            let tris = &self.triangles[mesh.base_triangle_index as usize..]
+                [..mesh.triangle_count as usize];
+            for tri in tris {
+                let [a, b, c] = tri.map(|i| base_vertex + i as usize);
+                // The length of the cross product is twice the area of the triangle.
+                let mut normal = (self.vertices[b] - self.vertices[a])
+                    .cross(self.vertices[c] - self.vertices[a]);
+                if normal.dot(up) < 0.0 {
+                    normal = -normal;
+                }
+                normals[a] += normal;
+                normals[b] += normal;
+                normals[c] += normal;
+            }
+        }
+        for normal in &mut normals {
+            *normal = normal.try_normalize().unwrap_or(up);
+        }
+        normals
+    }
+
+    /// Concatenates the detail meshes of the tiles whose polygon meshes were combined with [`PolygonNavmesh::merge`].
+    ///
+    /// `meshes` must be in the same order as the polygon meshes passed there, so that the sub-meshes stay associated
+    /// with their polygons.
+    pub fn merge(meshes: &[DetailNavmesh]) -> DetailNavmesh {
+        let mut merged = DetailNavmesh {
+            meshes: Vec::with_capacity(meshes.iter().map(|mesh| mesh.meshes.len()).sum()),
+            vertices: Vec::with_capacity(meshes.iter().map(|mesh| mesh.vertices.len()).sum()),
+            triangles: Vec::with_capacity(meshes.iter().map(|mesh| mesh.triangles.len()).sum()),
+            triangle_flags: Vec::with_capacity(
+                meshes.iter().map(|mesh| mesh.triangle_flags.len()).sum(),
+            ),
+        };
+        for mesh in meshes {
+            let base_vertex = merged.vertices.len() as u32;
+            let base_triangle = merged.triangles.len() as u32;
+            merged
+                .meshes
+                .extend(mesh.meshes.iter().map(|submesh| SubMesh {
+                    base_vertex_index: submesh.base_vertex_index + base_vertex,
+                    base_triangle_index: submesh.base_triangle_index + base_triangle,
+                    ..submesh.clone()
+                }));
+            merged.vertices.extend_from_slice(&mesh.vertices);
+            merged.triangles.extend_from_slice(&mesh.triangles);
+            merged
+                .triangle_flags
+                .extend_from_slice(&mesh.triangle_flags);
+        }
+        merged
+    }
+
     /// Builds a detail mesh from the provided polygon mesh.
     pub fn new(
         mesh: &PolygonNavmesh,
@@ -1226,6 +1288,8 @@ fn poly_min_extent_squared(verts: &[Vec3A], nverts: usize) -> f32 {
     min_dist
 }
 
+/// Errors that can occur when building a [`DetailNavmesh`] with [`DetailNavmesh::new`].
+/// Building a detail mesh currently cannot fail.
 #[derive(Error, Debug)]
 pub enum DetailNavmeshError {}
 
@@ -1533,3 +1597,55 @@ impl Bounds {
         self.zmax - self.zmin
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A unit ramp rising by 1 along the Z axis, split into two triangles with opposing winding.
+    fn ramp() -> DetailNavmesh {
+        DetailNavmesh {
+            meshes: vec![SubMesh {
+                base_vertex_index: 0,
+                vertex_count: 4,
+                base_triangle_index: 0,
+                triangle_count: 2,
+            }],
+            vertices: vec![
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(1.0, 0.0, 0.0),
+                Vec3::new(0.0, 1.0, 1.0),
+                Vec3::new(1.0, 1.0, 1.0),
+            ],
+            triangles: vec![[0, 2, 1], [1, 2, 3]],
+            triangle_flags: vec![0; 2],
+        }
+    }
+
+    #[test]
+    fn ramp_normals_point_up() {
+        let normals = ramp().compute_normals(Vec3::Y);
+        let expected = Vec3::new(0.0, 1.0, -1.0).normalize();
+        assert_eq!(normals.len(), 4);
+        for normal in normals {
+            assert!(normal.abs_diff_eq(expected, 1e-6), "{normal}");
+        }
+    }
+
+    #[test]
+    fn ramp_normals_follow_up() {
+        let normals = ramp().compute_normals(Vec3::NEG_Y);
+        let expected = Vec3::new(0.0, -1.0, 1.0).normalize();
+        for normal in normals {
+            assert!(normal.abs_diff_eq(expected, 1e-6), "{normal}");
+        }
+    }
+
+    #[test]
+    fn unused_vertices_get_up_normal() {
+        let mut mesh = ramp();
+        mesh.vertices.push(Vec3::new(5.0, 0.0, 5.0));
+        let normals = mesh.compute_normals(Vec3::Y);
+        assert_eq!(normals[4], Vec3::Y);
+    }
+}
@@ -0,0 +1,228 @@
+use alloc::vec::Vec;
+
+use crate::{
+    CompactHeightfield, RegionId,
+    math::{dir_offset_x, dir_offset_z},
+    watershed_build_regions::{BuildRegionsError, Region},
+};
+
+impl CompactHeightfield {
+    /// Builds region data for the heightfield by partitioning it into non-overlapping 2D layers.
+    ///
+    /// The heightfield is first partitioned into monotone regions just like in [`CompactHeightfield::build_regions_monotone`].
+    /// Neighbouring regions are then merged into layers as long as they do not overlap each other.
+    ///
+    /// If a layer is smaller than `min_region_area` and does not touch the tile border,
+    /// then all of its spans will be re-assigned to [`AreaType::NOT_WALKABLE`](crate::AreaType::NOT_WALKABLE).
+    ///
+    /// This partitioning method produces better triangles than monotone partitioning and does not need a distance field.
+    /// It is a good choice for tiled navmeshes with small tiles.
+    ///
+    /// The region data will be available via the [`CompactHeightfield::max_region`]
+    /// and [`CompactSpan::region`](crate::CompactSpan::region) fields.
+    pub fn build_layer_regions(
+        &mut self,
+        border_size: u16,
+        min_region_area: u16,
+    ) -> Result<(), BuildRegionsError> {
+        let (mut src_reg, region_id) = self.sweep_monotone_regions(border_size)?;
+
+        // Merge monotone regions to layers and remove small regions.
+        self.max_region = region_id;
+        self.merge_and_filter_layer_regions(min_region_area, &mut src_reg);
+
+        // Store the result out.
+        #[expect(clippy::needless_range_loop)]
+        for i in 0..self.spans.len() {
+            self.spans[i].region = src_reg[i];
+        }
+
+        Ok(())
+    }
+
+    fn merge_and_filter_layer_regions(&mut self, min_region_area: u16, src_reg: &mut [RegionId]) {
+        let w = self.width;
+        let h = self.height;
+
+        let nreg = self.max_region.bits() + 1;
+
+        // Construct regions
+        let mut regions = (0..nreg)
+            .map(|i| Region::new(RegionId::from(i)))
+            .collect::<Vec<_>>();
+
+        // Find region neighbours and overlapping regions.
+        let mut layer_regions = Vec::with_capacity(32);
+        for z in 0..h {
+            for x in 0..w {
+                let cell = self.cell_at(x, z);
+                let max_index = cell.index() as usize + cell.count() as usize;
+                layer_regions.clear();
+
+                for i in cell.index() as usize..max_index {
+                    let span = &self.spans[i];
+                    let r = src_reg[i];
+                    if r == RegionId::NONE || r >= RegionId::from(nreg) {
+                        continue;
+                    }
+                    let reg = &mut regions[r.bits() as usize];
+                    reg.span_count += 1;
+                    reg.area = self.areas[i];
+                    reg.y_min = reg.y_min.min(span.y);
+                    reg.y_max = reg.y_max.max(span.y);
+
+                    // Collect all region layers.
+                    layer_regions.push(r);
+
+                    // Update neighbours
+                    for dir in 0..4 {
+                        let Some(con) = span.con(dir) else {
+                            continue;
+                        };
+                        let a_x = (x as i32 + dir_offset_x(dir) as i32) as u16;
+                        let a_z = (z as i32 + dir_offset_z(dir) as i32) as u16;
+                        let a_index = self.cell_at(a_x, a_z).index() as usize + con as usize;
+                        let a_r = src_reg[a_index];
+                        if a_r != RegionId::NONE
+                            && a_r < RegionId::from(nreg)
+                            && a_r != r
+                            && !reg.connections.contains(&a_r)
+                        {
+                            reg.connections.push(a_r);
+                        }
+                        if a_r.intersects(RegionId::BORDER_REGION) {
+                            reg.connects_to_border = true;
+                        }
+                    }
+                }
+
+                // Update overlapping regions.
+                for i in 0..layer_regions.len().saturating_sub(1) {
+                    for j in i + 1..layer_regions.len() {
+                        let r_i = layer_regions[i];
+                        let r_j = layer_regions[j];
+                        if r_i != r_j {
+                            regions[r_i.bits() as usize].add_unique_floor_region(r_j);
+                            regions[r_j.bits() as usize].add_unique_floor_region(r_i);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Create 2D layers from regions.
+        let mut layer_id = RegionId::from(1);
+        for reg in regions.iter_mut() {
+            reg.id = RegionId::NONE;
+        }
+
+        // Merge monotone regions to create non-overlapping areas.
+        let mut stack = Vec::with_capacity(32);
+        for i in 1..nreg as usize {
+            // Skip already visited.
+            if regions[i].id != RegionId::NONE {
+                continue;
+            }
+
+            // Start search.
+            regions[i].id = layer_id;
+            stack.clear();
+            stack.push(i);
+
+            while !stack.is_empty() {
+                // Pop front
+                let reg_index = stack.remove(0);
+                let area = regions[reg_index].area;
+                let connections = regions[reg_index].connections.clone();
+
+                for connection in connections {
+                    let nei = connection.bits() as usize;
+                    let nei_reg = &regions[nei];
+                    // Skip already visited.
+                    if nei_reg.id != RegionId::NONE {
+                        continue;
+                    }
+                    // Skip if different area type, do not connect regions with different area type.
+                    if nei_reg.area != area {
+                        continue;
+                    }
+                    // Skip if the neighbour is overlapping root region.
+                    if regions[i].floors.contains(&connection) {
+                        continue;
+                    }
+
+                    // Deepen
+                    stack.push(nei);
+
+                    // Mark layer id
+                    let nei_reg = &mut regions[nei];
+                    nei_reg.id = layer_id;
+                    let floors = nei_reg.floors.clone();
+                    let y_min = nei_reg.y_min;
+                    let y_max = nei_reg.y_max;
+                    let span_count = core::mem::take(&mut nei_reg.span_count);
+                    let connects_to_border = nei_reg.connects_to_border;
+
+                    // Merge current layers to root.
+                    let root = &mut regions[i];
+                    for floor in floors {
+                        root.add_unique_floor_region(floor);
+                    }
+                    root.y_min = root.y_min.min(y_min);
+                    root.y_max = root.y_max.max(y_max);
+                    root.span_count += span_count;
+                    root.connects_to_border |= connects_to_border;
+                }
+            }
+
+            layer_id += 1;
+        }
+
+        // Remove small regions
+        for i in 0..nreg as usize {
+            let reg = &regions[i];
+            if reg.span_count > 0
+                && reg.span_count < min_region_area as usize
+                && !reg.connects_to_border
+            {
+                let reg_id = reg.id;
+                for reg in regions.iter_mut() {
+                    if reg.id == reg_id {
+                        reg.id = RegionId::NONE;
+                    }
+                }
+            }
+        }
+
+        // Compress region IDs
+        for reg in regions.iter_mut() {
+            // Skip nil regions and external regions.
+            reg.remap = !(reg.id == RegionId::NONE || reg.id.intersects(RegionId::BORDER_REGION));
+        }
+
+        let mut reg_id_gen = 0;
+        for i in 0..nreg as usize {
+            if !regions[i].remap {
+                continue;
+            }
+            let old_id = regions[i].id;
+            reg_id_gen += 1;
+            let new_id = RegionId::from(reg_id_gen);
+            #[expect(clippy::needless_range_loop)]
+            for j in i..nreg as usize {
+                if regions[j].id == old_id {
+                    regions[j].id = new_id;
+                    regions[j].remap = false;
+                }
+            }
+        }
+        self.max_region = RegionId::from(reg_id_gen);
+
+        // Remap regions
+        for reg in src_reg.iter_mut() {
+            if !reg.intersects(RegionId::BORDER_REGION) {
+                *reg = regions[reg.bits() as usize].id;
+            }
+        }
+    }
+}
@@ -3,6 +3,9 @@
 //!
 //! This crate contains low-level API.
 //! See the [`rerecast` repo](https://github.com/janhohenheim/rerecast) for instructions for high-level engine integrations
+//!
+//! All build steps are deterministic: the same input [`TriMesh`] and [`Config`] always produce bit-identical output on the same platform.
+//! Enable the `libm` feature to extend this guarantee across platforms.
 
 #![no_std]
 #[macro_use]
@@ -18,28 +21,40 @@ mod contours;
 mod detail_mesh;
 mod erosion;
 mod heightfield;
+mod layer_build_regions;
 mod mark_convex_poly_area;
 pub(crate) mod math;
+mod monotone_build_regions;
 pub(crate) mod ops;
 mod poly_mesh;
 mod pre_filter;
 mod rasterize;
+mod recast_obj;
 mod region;
+#[cfg(feature = "serialize")]
+mod run_length;
 mod span;
+mod terrain;
 mod trimesh;
 mod watershed_build_regions;
 mod watershed_distance_field;
 
 pub use compact_cell::CompactCell;
-pub use compact_heightfield::CompactHeightfield;
+pub use compact_heightfield::{CompactHeightfield, CompactHeightfieldError};
 pub use compact_span::CompactSpan;
 pub use config::{Config, ConfigBuilder};
 pub use contours::{BuildContoursFlags, Contour, ContourSet, RegionVertexId};
-pub use detail_mesh::{DetailNavmesh, SubMesh};
-pub use heightfield::{Heightfield, HeightfieldBuilder, HeightfieldBuilderError};
+pub use detail_mesh::{DetailNavmesh, DetailNavmeshError, SubMesh};
+pub use heightfield::{
+    Heightfield, HeightfieldBuilder, HeightfieldBuilderError, SpanInsertionError,
+};
 pub use mark_convex_poly_area::ConvexVolume;
 pub use math::{Aabb2d, Aabb3d};
-pub use poly_mesh::PolygonNavmesh;
+pub use poly_mesh::{FlowField, PolygonNavmesh, PolygonNavmeshError, RepairReport};
+pub use rasterize::{PolygonDivisionError, RasterizationError};
+pub use recast_obj::{RecastObjError, import_recast_obj};
 pub use region::RegionId;
 pub use span::{AreaType, Span, SpanKey, Spans};
+pub use terrain::HeightSampler;
 pub use trimesh::TriMesh;
+pub use watershed_build_regions::BuildRegionsError;
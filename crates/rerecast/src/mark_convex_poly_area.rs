@@ -7,6 +7,9 @@ use crate::{Aabb2d, AreaType, CompactHeightfield};
 
 impl CompactHeightfield {
     /// Sets the [`AreaType`] of the spans within the given convex volume.
+    ///
+    /// Marking a volume with [`AreaType::NOT_WALKABLE`] removes the spans from the walkable area,
+    /// which carves a hole into the resulting navmesh.
     pub fn mark_convex_poly_area(&mut self, volume: &ConvexVolume) {
         // Compute the bounding box of the polygon
         let Some(aabb) = Aabb2d::from_verts(&volume.vertices) else {
@@ -106,5 +109,6 @@ pub struct ConvexVolume {
     /// The upper Y coordinate of the convex volume.
     pub max_y: f32,
     /// The area type of the convex volume.
+    /// Use [`AreaType::NOT_WALKABLE`] to carve a hole into the navmesh, e.g. for pits or no-go zones.
     pub area: AreaType,
 }
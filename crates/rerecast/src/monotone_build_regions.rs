@@ -0,0 +1,238 @@
+use alloc::vec::Vec;
+
+use crate::{
+    CompactHeightfield, RegionId,
+    math::{dir_offset_x, dir_offset_z},
+    watershed_build_regions::BuildRegionsError,
+};
+
+impl CompactHeightfield {
+    /// Builds region data for the heightfield using simple monotone partitioning.
+    ///
+    /// Non-null regions will consist of connected, non-overlapping walkable spans that form a single contour.
+    /// Contours will form simple polygons.
+    ///
+    /// If multiple regions form an area that is smaller than `min_region_area`, then all spans will be
+    /// re-assigned to [`AreaType::NOT_WALKABLE`](crate::AreaType::NOT_WALKABLE).
+    ///
+    /// Partitioning can result in smaller than necessary regions. `merge_region_area` helps
+    /// reduce unnecessarily small regions.
+    ///
+    /// Compared to [`CompactHeightfield::build_regions`], this is the fastest partitioning method and does not need
+    /// a distance field, but it tends to create long, thin polygons, which can result in detours along paths.
+    ///
+    /// The region data will be available via the [`CompactHeightfield::max_region`]
+    /// and [`CompactSpan::region`](crate::CompactSpan::region) fields.
+    pub fn build_regions_monotone(
+        &mut self,
+        border_size: u16,
+        min_region_area: u16,
+        merge_region_area: u16,
+    ) -> Result<(), BuildRegionsError> {
+        let (mut src_reg, region_id) = self.sweep_monotone_regions(border_size)?;
+
+        // Merge regions and filter out small regions.
+        self.max_region = region_id;
+        // Monotone partitioning does not generate overlapping regions.
+        let _overlaps =
+            self.merge_and_filter_regions(min_region_area, merge_region_area, &mut src_reg);
+
+        // Store the result out.
+        #[expect(clippy::needless_range_loop)]
+        for i in 0..self.spans.len() {
+            self.spans[i].region = src_reg[i];
+        }
+
+        Ok(())
+    }
+
+    /// Partitions the walkable spans into monotone regions by sweeping the heightfield one row at a time.
+    /// Returns the region of each span and the next free region ID.
+    ///
+    /// Shared by [`CompactHeightfield::build_regions_monotone`] and [`CompactHeightfield::build_layer_regions`].
+    pub(crate) fn sweep_monotone_regions(
+        &mut self,
+        border_size: u16,
+    ) -> Result<(Vec<RegionId>, RegionId), BuildRegionsError> {
+        let w = self.width;
+        let h = self.height;
+        let mut region_id = RegionId::from(1);
+
+        let mut src_reg = vec![RegionId::NONE; self.spans.len()];
+        // Jan: The original preallocates `max(width, height)` sweeps, which is not enough
+        // when a row contains multiple spans per cell, so we grow the vector as needed instead.
+        let mut sweeps: Vec<SweepSpan> = Vec::with_capacity(w.max(h) as usize);
+
+        // Mark border regions.
+        if border_size > 0 {
+            // Make sure border will not overflow.
+            let border_width = border_size.min(w);
+            let border_height = border_size.min(h);
+
+            self.paint_rect_region(
+                0,
+                border_width,
+                0,
+                h,
+                region_id | RegionId::BORDER_REGION,
+                &mut src_reg,
+            );
+            region_id += 1;
+            self.paint_rect_region(
+                w - border_width,
+                w,
+                0,
+                h,
+                region_id | RegionId::BORDER_REGION,
+                &mut src_reg,
+            );
+            region_id += 1;
+            self.paint_rect_region(
+                0,
+                w,
+                0,
+                border_height,
+                region_id | RegionId::BORDER_REGION,
+                &mut src_reg,
+            );
+            region_id += 1;
+            self.paint_rect_region(
+                0,
+                w,
+                h - border_height,
+                h,
+                region_id | RegionId::BORDER_REGION,
+                &mut src_reg,
+            );
+            region_id += 1;
+        }
+        self.border_size = border_size;
+
+        let mut prev: Vec<u16> = Vec::with_capacity(256);
+
+        // Sweep one line at a time.
+        for z in border_size..h.saturating_sub(border_size) {
+            // Collect spans from this row.
+            prev.clear();
+            prev.resize(region_id.bits() as usize + 1, 0);
+            sweeps.clear();
+            // Index 0 is the null sweep
+            sweeps.push(SweepSpan::default());
+
+            for x in border_size..w.saturating_sub(border_size) {
+                let cell = self.cell_at(x, z);
+                let max_index = cell.index() as usize + cell.count() as usize;
+                for i in cell.index() as usize..max_index {
+                    let span = &self.spans[i];
+                    let area = self.areas[i];
+                    if !area.is_walkable() {
+                        continue;
+                    }
+
+                    // -x
+                    let mut prev_id = 0;
+                    if let Some(con) = span.con(0) {
+                        let a_x = (x as i32 + dir_offset_x(0) as i32) as u16;
+                        let a_z = (z as i32 + dir_offset_z(0) as i32) as u16;
+                        let a_index = self.cell_at(a_x, a_z).index() as usize + con as usize;
+                        if !src_reg[a_index].intersects(RegionId::BORDER_REGION)
+                            && self.areas[a_index] == area
+                        {
+                            prev_id = src_reg[a_index].bits() as usize;
+                        }
+                    }
+
+                    if prev_id == 0 {
+                        prev_id = sweeps.len();
+                        sweeps.push(SweepSpan::default());
+                    }
+
+                    // -z
+                    if let Some(con) = span.con(3) {
+                        let a_x = (x as i32 + dir_offset_x(3) as i32) as u16;
+                        let a_z = (z as i32 + dir_offset_z(3) as i32) as u16;
+                        let a_index = self.cell_at(a_x, a_z).index() as usize + con as usize;
+                        let neighbour = src_reg[a_index];
+                        if neighbour != RegionId::NONE
+                            && !neighbour.intersects(RegionId::BORDER_REGION)
+                            && self.areas[a_index] == area
+                        {
+                            let sweep = &mut sweeps[prev_id];
+                            match sweep.neighbour {
+                                SweepNeighbour::None => {
+                                    sweep.neighbour = SweepNeighbour::Region(neighbour);
+                                    sweep.sample_count += 1;
+                                    prev[neighbour.bits() as usize] += 1;
+                                }
+                                SweepNeighbour::Region(region) if region == neighbour => {
+                                    sweep.sample_count += 1;
+                                    prev[neighbour.bits() as usize] += 1;
+                                }
+                                _ => {
+                                    sweep.neighbour = SweepNeighbour::Multiple;
+                                }
+                            }
+                        }
+                    }
+
+                    src_reg[i] = RegionId::from(prev_id as u16);
+                }
+            }
+
+            // Create unique ID.
+            for sweep in sweeps.iter_mut().skip(1) {
+                match sweep.neighbour {
+                    SweepNeighbour::Region(region)
+                        if prev[region.bits() as usize] == sweep.sample_count =>
+                    {
+                        sweep.id = region;
+                    }
+                    _ => {
+                        if region_id == RegionId::MAX {
+                            return Err(BuildRegionsError::RegionIdOverflow);
+                        }
+                        sweep.id = region_id;
+                        region_id += 1;
+                    }
+                }
+            }
+
+            // Remap IDs
+            for x in border_size..w.saturating_sub(border_size) {
+                let cell = self.cell_at(x, z);
+                let max_index = cell.index() as usize + cell.count() as usize;
+                #[expect(clippy::needless_range_loop)]
+                for i in cell.index() as usize..max_index {
+                    let sweep_id = src_reg[i].bits() as usize;
+                    if sweep_id > 0 && sweep_id < sweeps.len() {
+                        src_reg[i] = sweeps[sweep_id].id;
+                    }
+                }
+            }
+        }
+
+        Ok((src_reg, region_id))
+    }
+}
+
+/// A run of spans in a single row of the heightfield that share the same region.
+#[derive(Debug, Clone, Default)]
+struct SweepSpan {
+    /// The final region ID of the sweep.
+    id: RegionId,
+    /// The number of samples connected to the neighbouring region in the previous row.
+    sample_count: u16,
+    /// The neighbouring region in the previous row.
+    neighbour: SweepNeighbour,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum SweepNeighbour {
+    /// No neighbour found yet.
+    #[default]
+    None,
+    /// Exactly one neighbouring region.
+    Region(RegionId),
+    /// The sweep touches multiple regions in the previous row, so it cannot be merged with any of them.
+    Multiple,
+}
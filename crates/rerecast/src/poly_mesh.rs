@@ -1,12 +1,18 @@
 use crate::{
-    Aabb3d, AreaType, RegionId,
+    Aabb3d, AreaType, CompactHeightfield, RegionId,
     contours::{ContourSet, RegionVertexId},
-    math::{next, prev},
+    math::{distance_squared_between_point_and_line_vec3, next, prev},
+    ops,
+};
+use alloc::{
+    collections::{BTreeMap, BinaryHeap, VecDeque},
+    vec::Vec,
 };
-use alloc::vec::Vec;
 #[cfg(feature = "bevy_reflect")]
 use bevy_reflect::prelude::*;
-use glam::{U16Vec2, U16Vec3, Vec3Swizzles as _, u16vec3, uvec3};
+use core::{cmp::Reverse, hash::BuildHasher};
+use glam::{U16Vec2, U16Vec3, Vec2, Vec3, Vec3A, Vec3Swizzles as _, u16vec3, uvec3};
+use hashbrown::HashSet;
 use thiserror::Error;
 
 #[derive(Debug, Default, Clone, PartialEq)]
@@ -123,6 +129,9 @@ pub struct PolygonNavmesh {
     /// (i.e. The edge is a solid border.)
     pub polygon_neighbors: Vec<u16>,
     /// The user-defined flags assigned to each polygon.
+    ///
+    /// Serialized with run-length encoding, as most polygons usually share the same flags.
+    #[cfg_attr(feature = "serialize", serde(with = "crate::run_length"))]
     pub flags: Vec<u16>,
     /// The region id assigned to each polygon.
     pub regions: Vec<RegionId>,
@@ -130,6 +139,9 @@ pub struct PolygonNavmesh {
     ///
     /// The standard build process assigns the value of [`AreaType::DEFAULT_WALKABLE`] to all walkable polygons.
     /// This value can then be changed to meet user requirements.
+    ///
+    /// Serialized with run-length encoding, as most polygons usually share the same area.
+    #[cfg_attr(feature = "serialize", serde(with = "crate::run_length"))]
     pub areas: Vec<AreaType>,
     /// The maximum number of vertices per polygon
     pub max_vertices_per_polygon: u16,
@@ -165,6 +177,1019 @@ impl PolygonNavmesh {
             .chunks_exact(self.max_vertices_per_polygon as usize)
             .map(|chunk| chunk.iter().take_while(|i| **i != Self::NO_INDEX).copied())
     }
+
+    /// Returns the shared "portal" edges between consecutive polygons in `poly_path`, in world space.
+    ///
+    /// Each portal is returned as `(left, right)`, as seen when walking from one polygon into the next.
+    /// This is the geometric input to the funnel algorithm used to smooth a polygon corridor into a path.
+    ///
+    /// Consecutive polygons that do not share an edge are skipped,
+    /// so for a valid corridor the number of portals is `poly_path.len() - 1`.
+    pub fn portals(&self, poly_path: &[u32]) -> Vec<(Vec3, Vec3)> {
+        let nvp = self.max_vertices_per_polygon as usize;
+        let mut portals = Vec::with_capacity(poly_path.len().saturating_sub(1));
+        for pair in poly_path.windows(2) {
+            let (from, to) = (pair[0] as usize, pair[1] as usize);
+            let vertices = &self.polygons[from * nvp..][..nvp];
+            let neighbors = &self.polygon_neighbors[from * nvp..][..nvp];
+            let vertex_count = count_poly_verts(vertices, nvp);
+            let Some(edge) = (0..vertex_count).find(|&j| neighbors[j] as usize == to) else {
+                continue;
+            };
+            let left = self.world_vertex(vertices[edge]);
+            let right = self.world_vertex(vertices[next(edge, vertex_count)]);
+            portals.push((left, right));
+        }
+        portals
+    }
+
+    /// Returns `true` if the polygons `a` and `b` share an edge, so that an agent can walk directly from one into the other.
+    ///
+    /// Off-mesh connections are not part of a [`PolygonNavmesh`] and are therefore not considered.
+    /// Neither are portals to other tiles.
+    /// Returns `false` if `a` and `b` are the same polygon or if either of them does not exist.
+    pub fn are_adjacent(&self, a: u32, b: u32) -> bool {
+        let polygon_count = self.polygon_count();
+        if a == b || a as usize >= polygon_count || b as usize >= polygon_count {
+            return false;
+        }
+        self.neighbor_indices(a as usize)
+            .any(|neighbor| neighbor == b as usize)
+    }
+
+    /// Iterates over the polygons that share an edge with the polygon `poly`, in the order of its edges.
+    ///
+    /// Polygons are identified by their index, i.e. polygon `i` consists of the `i`-th chunk of
+    /// [`Self::max_vertices_per_polygon`] entries in [`Self::polygons`] and [`Self::polygon_neighbors`],
+    /// and is described by the `i`-th entry of [`Self::flags`], [`Self::regions`] and [`Self::areas`].
+    /// Portals to other tiles are not followed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `poly` is not smaller than [`Self::polygon_count`].
+    pub fn neighbors(&self, poly: u32) -> impl Iterator<Item = u32> + '_ {
+        self.neighbor_indices(poly as usize)
+            .map(|neighbor| neighbor as u32)
+    }
+
+    /// Returns the edge shared by the polygons `a` and `b` in world space, as `(left, right)` when walking from `a` into `b`.
+    /// See [`Self::portals`].
+    ///
+    /// Returns `None` if the polygons are not adjacent, see [`Self::are_adjacent`].
+    pub fn portal(&self, a: u32, b: u32) -> Option<(Vec3, Vec3)> {
+        if !self.are_adjacent(a, b) {
+            return None;
+        }
+        self.portals(&[a, b]).pop()
+    }
+
+    /// Returns the index of the polygon containing `point` when projected onto the XZ plane.
+    ///
+    /// If multiple polygons contain the point, e.g. on different floors of a building,
+    /// the one whose vertices are vertically closest to the point on average is returned.
+    pub fn find_polygon(&self, point: Vec3) -> Option<u32> {
+        let nvp = self.max_vertices_per_polygon as usize;
+        let mut closest = None;
+        let mut closest_distance = f32::INFINITY;
+        for poly in 0..self.polygon_count() {
+            let vertices = &self.polygons[poly * nvp..][..nvp];
+            let vertex_count = count_poly_verts(vertices, nvp);
+            let mut sign = 0.0_f32;
+            let mut height = 0.0;
+            let mut inside = true;
+            for (j, &vertex) in vertices[..vertex_count].iter().enumerate() {
+                let a = self.world_vertex(vertex);
+                let b = self.world_vertex(vertices[next(j, vertex_count)]);
+                height += a.y;
+                // Polygons are convex, so the point is inside if it is on the same side of every edge.
+                let cross = (b.xz() - a.xz()).perp_dot(point.xz() - a.xz());
+                if cross * sign < 0.0 {
+                    inside = false;
+                    break;
+                }
+                if cross != 0.0 {
+                    sign = cross;
+                }
+            }
+            if !inside {
+                continue;
+            }
+            let distance = ops::abs(height / vertex_count as f32 - point.y);
+            if distance < closest_distance {
+                closest = Some(poly as u32);
+                closest_distance = distance;
+            }
+        }
+        closest
+    }
+
+    /// Finds the shortest corridor of polygons from the polygon `start` to the polygon `end` with A*,
+    /// using the distances between the polygon centers as costs.
+    ///
+    /// The polygons in `blocked` are treated as impassable without modifying the navmesh,
+    /// e.g. to route around polygons occupied by other agents. Pass an empty set to consider all polygons.
+    ///
+    /// Returns `None` if there is no connection between the polygons, if either of them does not exist,
+    /// or if either of them is blocked.
+    /// Use [`Self::find_polygon`] to find the polygons containing a point.
+    pub fn find_path<S: BuildHasher>(
+        &self,
+        start: u32,
+        end: u32,
+        blocked: &HashSet<u32, S>,
+    ) -> Option<Vec<u32>> {
+        self.find_path_where(start, end, |poly| !blocked.contains(&poly))
+    }
+
+    /// Like [`Self::find_path`], but only walks through polygons for which `passable` returns `true`.
+    ///
+    /// Returns `None` if `start` or `end` is not passable.
+    pub fn find_path_where(
+        &self,
+        start: u32,
+        end: u32,
+        passable: impl Fn(u32) -> bool,
+    ) -> Option<Vec<u32>> {
+        let polygon_count = self.polygon_count();
+        if start as usize >= polygon_count || end as usize >= polygon_count {
+            return None;
+        }
+        if !passable(start) || !passable(end) {
+            return None;
+        }
+        let (start, end) = (start as usize, end as usize);
+        let centers = (0..polygon_count)
+            .map(|poly| self.polygon_center(poly))
+            .collect::<Vec<_>>();
+        let mut costs = vec![f32::INFINITY; polygon_count];
+        let mut parents = vec![usize::MAX; polygon_count];
+        let mut open = BinaryHeap::new();
+        costs[start] = 0.0;
+        // Non-negative floats compare the same as their bit patterns, so they can be used as keys directly.
+        open.push(Reverse((
+            centers[start].distance(centers[end]).to_bits(),
+            start,
+        )));
+        while let Some(Reverse((_, poly))) = open.pop() {
+            if poly == end {
+                let mut path = vec![end as u32];
+                let mut poly = end;
+                while poly != start {
+                    poly = parents[poly];
+                    path.push(poly as u32);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for neighbor in self.neighbor_indices(poly) {
+                if !passable(neighbor as u32) {
+                    continue;
+                }
+                let cost = costs[poly] + centers[poly].distance(centers[neighbor]);
+                if cost >= costs[neighbor] {
+                    continue;
+                }
+                costs[neighbor] = cost;
+                parents[neighbor] = poly;
+                let estimate = cost + centers[neighbor].distance(centers[end]);
+                open.push(Reverse((estimate.to_bits(), neighbor)));
+            }
+        }
+        None
+    }
+
+    /// Builds a [`FlowField`] towards the polygon `goal` by expanding from it with Dijkstra's algorithm,
+    /// using the distances between the polygon centers as costs, like [`Self::find_path`].
+    ///
+    /// Many agents sharing a destination can follow the flow field instead of each searching for a path.
+    /// Portals to other tiles are not followed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `goal` is not smaller than [`Self::polygon_count`].
+    pub fn build_flow_field(&self, goal: u32) -> FlowField {
+        let polygon_count = self.polygon_count();
+        assert!(
+            (goal as usize) < polygon_count,
+            "Polygon {goal} does not exist"
+        );
+        let centers = (0..polygon_count)
+            .map(|poly| self.polygon_center(poly))
+            .collect::<Vec<_>>();
+        let mut costs = vec![f32::INFINITY; polygon_count];
+        let mut next = vec![None; polygon_count];
+        let mut open = BinaryHeap::new();
+        costs[goal as usize] = 0.0;
+        // Non-negative floats compare the same as their bit patterns, so they can be used as keys directly.
+        open.push(Reverse((0.0_f32.to_bits(), goal as usize)));
+        while let Some(Reverse((cost, poly))) = open.pop() {
+            if f32::from_bits(cost) > costs[poly] {
+                continue;
+            }
+            for neighbor in self.neighbor_indices(poly) {
+                let cost = costs[poly] + centers[poly].distance(centers[neighbor]);
+                if cost >= costs[neighbor] {
+                    continue;
+                }
+                costs[neighbor] = cost;
+                next[neighbor] = Some(poly as u32);
+                open.push(Reverse((cost.to_bits(), neighbor)));
+            }
+        }
+        FlowField { goal, next, costs }
+    }
+
+    /// Groups the polygons into islands that are connected through shared edges.
+    ///
+    /// Agents can walk between any two polygons of the same island, but never between polygons of different islands.
+    /// More than one island usually means that some part of the level is unreachable, e.g. a platform without a ramp.
+    /// Each island is sorted in ascending order, and the islands are ordered by their first polygon.
+    /// Portals to other tiles are not followed.
+    pub fn connected_components(&self) -> Vec<Vec<u32>> {
+        let polygon_count = self.polygon_count();
+        let mut visited = vec![false; polygon_count];
+        let mut islands = Vec::new();
+        let mut stack = Vec::new();
+        for seed in 0..polygon_count {
+            if visited[seed] {
+                continue;
+            }
+            visited[seed] = true;
+            stack.push(seed);
+            let mut island = Vec::new();
+            while let Some(poly) = stack.pop() {
+                island.push(poly as u32);
+                for neighbor in self.neighbor_indices(poly) {
+                    if !visited[neighbor] {
+                        visited[neighbor] = true;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+            island.sort_unstable();
+            islands.push(island);
+        }
+        islands
+    }
+
+    /// Returns the area-weighted centroid of the walkable surface in world space,
+    /// e.g. to frame the navmesh with a camera.
+    ///
+    /// Note that the barycenter of a non-convex surface does not need to lie on the surface itself.
+    /// Returns the center of [`Self::aabb`] if the navmesh has no area.
+    pub fn barycenter(&self) -> Vec3 {
+        let (weighted_sum, area) = (0..self.polygon_count() as u32)
+            .flat_map(|poly| self.poly_triangles(poly))
+            .fold((Vec3::ZERO, 0.0), |(weighted_sum, area), [a, b, c]| {
+                let triangle_area = (b - a).cross(c - a).length() / 2.0;
+                let centroid = (a + b + c) / 3.0;
+                (
+                    weighted_sum + centroid * triangle_area,
+                    area + triangle_area,
+                )
+            });
+        if area > 0.0 {
+            weighted_sum / area
+        } else {
+            (self.aabb.min + self.aabb.max) / 2.0
+        }
+    }
+
+    /// Removes the islands of [`Self::connected_components`] that consist of fewer than `min_polygons` polygons,
+    /// e.g. isolated fragments on rooftops that no agent can reach.
+    ///
+    /// Returns the removed islands, with the polygon indices from before the removal.
+    pub fn prune_islands(&mut self, min_polygons: usize) -> Vec<Vec<u32>> {
+        let (kept, removed): (Vec<_>, Vec<_>) = self
+            .connected_components()
+            .into_iter()
+            .partition(|island| island.len() >= min_polygons);
+        if removed.is_empty() {
+            return removed;
+        }
+        let mut keep = vec![false; self.polygon_count()];
+        for &poly in kept.iter().flatten() {
+            keep[poly as usize] = true;
+        }
+        self.retain_polygons(|poly| keep[poly as usize]);
+        removed
+    }
+
+    /// Removes the polygons for which `keep` returns `false`, together with the links to them.
+    /// The remaining polygons keep their order. Vertices are left untouched, even if they are no longer used.
+    ///
+    /// Returns the number of removed polygons.
+    pub fn retain_polygons(&mut self, keep: impl Fn(u32) -> bool) -> usize {
+        let nvp = self.max_vertices_per_polygon as usize;
+        let polygon_count = self.polygon_count();
+        let kept = (0..polygon_count)
+            .map(|poly| keep(poly as u32))
+            .collect::<Vec<_>>();
+        let mut new_indices = vec![Self::NO_CONNECTION; polygon_count];
+        let mut kept_count = 0;
+        for poly in (0..polygon_count).filter(|&poly| kept[poly]) {
+            new_indices[poly] = kept_count;
+            kept_count += 1;
+        }
+        if kept_count as usize == polygon_count {
+            return 0;
+        }
+
+        let remap_neighbor = |neighbor: u16| {
+            if RegionId::from(neighbor).intersects(RegionId::BORDER_REGION)
+                || neighbor as usize >= polygon_count
+            {
+                // No connection or a portal to another tile.
+                neighbor
+            } else {
+                new_indices[neighbor as usize]
+            }
+        };
+        self.polygons = self
+            .polygons
+            .chunks(nvp)
+            .zip(&kept)
+            .filter(|(_vertices, kept)| **kept)
+            .flat_map(|(vertices, _kept)| vertices.iter().copied())
+            .collect();
+        self.polygon_neighbors = self
+            .polygon_neighbors
+            .chunks(nvp)
+            .zip(&kept)
+            .filter(|(_neighbors, kept)| **kept)
+            .flat_map(|(neighbors, _kept)| neighbors.iter().copied())
+            .map(remap_neighbor)
+            .collect();
+        retain_kept(&mut self.flags, &kept);
+        retain_kept(&mut self.regions, &kept);
+        retain_kept(&mut self.areas, &kept);
+        polygon_count - kept_count as usize
+    }
+
+    /// Merges the meshes of adjacent tiles that were built on the same cell grid,
+    /// e.g. with [`ConfigBuilder::tiling`](crate::ConfigBuilder::tiling), into a single mesh.
+    ///
+    /// Vertices shared by the tiles on their borders are welded together. Tile border portals,
+    /// i.e. links with [`RegionId::BORDER_REGION`] set, are connected to the portal of the neighboring tile
+    /// that runs along the same two vertices. Portals without such a counterpart become solid borders.
+    ///
+    /// The polygons keep the order of `meshes`, so per-polygon data of the tiles can be concatenated in the same order.
+    pub fn merge(meshes: &[PolygonNavmesh]) -> Result<PolygonNavmesh, PolygonNavmeshError> {
+        let Some(first) = meshes.first() else {
+            return Ok(PolygonNavmesh::default());
+        };
+        let nvp = first.max_vertices_per_polygon as usize;
+        let vertex_count = meshes.iter().map(|mesh| mesh.vertices.len()).sum::<usize>();
+        let max_vertices = Self::NO_INDEX as usize;
+        if vertex_count > max_vertices {
+            return Err(PolygonNavmeshError::TooManyVertices {
+                actual: vertex_count,
+                max: max_vertices,
+            });
+        }
+        let polygon_count = meshes.iter().map(Self::polygon_count).sum::<usize>();
+        // Larger indices would be mistaken for tile border portals.
+        let max_polygons = RegionId::BORDER_REGION.bits() as usize;
+        if polygon_count > max_polygons {
+            return Err(PolygonNavmeshError::TooManyPolygons {
+                actual: polygon_count,
+                max: max_polygons,
+            });
+        }
+
+        let mut merged = PolygonNavmesh {
+            max_vertices_per_polygon: first.max_vertices_per_polygon,
+            aabb: first.aabb,
+            cell_size: first.cell_size,
+            cell_height: first.cell_height,
+            max_edge_error: first.max_edge_error,
+            ..Default::default()
+        };
+        for mesh in &meshes[1..] {
+            merged.aabb.min = merged.aabb.min.min(mesh.aabb.min);
+            merged.aabb.max = merged.aabb.max.max(mesh.aabb.max);
+            merged.max_edge_error = merged.max_edge_error.max(mesh.max_edge_error);
+        }
+
+        let mut vertices = vec![U16Vec3::ZERO; vertex_count];
+        let mut nvertices = 0;
+        let mut first_vert = vec![None; VERTEX_BUCKET_COUNT];
+        let mut next_vert = vec![None; vertex_count];
+        let cell = Vec3::new(merged.cell_size, merged.cell_height, merged.cell_size);
+        for mesh in meshes {
+            // The tiles are never below the merged AABB, so truncating rounds to the nearest cell.
+            let offset = ((mesh.aabb.min - merged.aabb.min) / cell + 0.5).as_u16vec3();
+            let vertex_remap = mesh
+                .vertices
+                .iter()
+                .map(|&vertex| {
+                    add_vertex(
+                        vertex + offset,
+                        &mut vertices,
+                        &mut first_vert,
+                        &mut next_vert,
+                        &mut nvertices,
+                    )
+                })
+                .collect::<Vec<_>>();
+            let poly_offset = merged.polygon_count() as u16;
+            merged.polygons.extend(mesh.polygons.iter().map(|&index| {
+                if index == Self::NO_INDEX {
+                    index
+                } else {
+                    vertex_remap[index as usize]
+                }
+            }));
+            merged
+                .polygon_neighbors
+                .extend(mesh.polygon_neighbors.iter().map(|&neighbor| {
+                    if RegionId::from(neighbor).intersects(RegionId::BORDER_REGION) {
+                        // No connection or a portal, which is resolved below.
+                        neighbor
+                    } else {
+                        neighbor + poly_offset
+                    }
+                }));
+            merged.flags.extend_from_slice(&mesh.flags);
+            merged.regions.extend_from_slice(&mesh.regions);
+            merged.areas.extend_from_slice(&mesh.areas);
+        }
+        vertices.truncate(nvertices as usize);
+        merged.vertices = vertices;
+
+        // Connect the portals of neighboring tiles. The shared edge runs in opposite directions in the two polygons.
+        let mut portals = BTreeMap::new();
+        for poly in 0..merged.polygon_count() {
+            let base = poly * nvp;
+            let count = count_poly_verts(&merged.polygons[base..], nvp);
+            for edge in 0..count {
+                let neighbor = merged.polygon_neighbors[base + edge];
+                if neighbor != Self::NO_CONNECTION
+                    && RegionId::from(neighbor).intersects(RegionId::BORDER_REGION)
+                {
+                    let a = merged.polygons[base + edge];
+                    let b = merged.polygons[base + next(edge, count)];
+                    portals.insert((a, b), base + edge);
+                }
+            }
+        }
+        for (&(a, b), &slot) in &portals {
+            merged.polygon_neighbors[slot] = portals
+                .get(&(b, a))
+                .map_or(Self::NO_CONNECTION, |&other| (other / nvp) as u16);
+        }
+
+        Ok(merged)
+    }
+
+    /// Finds a smooth path from `start` to `end` in world space.
+    ///
+    /// This finds the polygons containing the points with [`Self::find_polygon`], connects them with [`Self::find_path`],
+    /// and pulls the resulting corridor taut along its [`Self::portals`] with the funnel algorithm.
+    /// The returned path starts at `start`, ends at `end`, and in between only contains the corners the agent needs to walk around.
+    ///
+    /// The heights of the corners are those of the polygon vertices. Use the [`DetailNavmesh`](crate::DetailNavmesh)
+    /// if you need the exact height of the ground along the path.
+    ///
+    /// Returns `None` if either point is not on the navmesh or if there is no connection between them.
+    pub fn find_smooth_path(&self, start: Vec3, end: Vec3) -> Option<Vec<Vec3>> {
+        let start_poly = self.find_polygon(start)?;
+        let end_poly = self.find_polygon(end)?;
+        self.smooth_path((start_poly, start), (end_poly, end), |_poly| true)
+    }
+
+    /// Returns the polygon nearest to `point` together with the closest point on it, in world space.
+    ///
+    /// Only polygons overlapping the box of `half_extents` around `point` are considered. Use this to snap positions
+    /// that are slightly off the navmesh, such as the origin of an agent standing on the ground.
+    ///
+    /// Returns `None` if no polygon overlaps the box.
+    pub fn find_nearest_polygon(&self, point: Vec3, half_extents: Vec3) -> Option<(u32, Vec3)> {
+        self.find_nearest_polygon_where(point, half_extents, |_poly| true)
+    }
+
+    /// Like [`Self::find_nearest_polygon`], but only considers polygons for which `passable` returns `true`.
+    pub fn find_nearest_polygon_where(
+        &self,
+        point: Vec3,
+        half_extents: Vec3,
+        passable: impl Fn(u32) -> bool,
+    ) -> Option<(u32, Vec3)> {
+        let nvp = self.max_vertices_per_polygon as usize;
+        let (min, max) = (point - half_extents, point + half_extents);
+        let mut nearest = None;
+        let mut nearest_distance = f32::INFINITY;
+        for poly in 0..self.polygon_count() {
+            if !passable(poly as u32) {
+                continue;
+            }
+            let vertices = &self.polygons[poly * nvp..][..nvp];
+            let vertex_count = count_poly_verts(vertices, nvp);
+            let (poly_min, poly_max) = vertices[..vertex_count]
+                .iter()
+                .map(|&vertex| self.world_vertex(vertex))
+                .fold(
+                    (Vec3::INFINITY, Vec3::NEG_INFINITY),
+                    |(min, max), vertex| (min.min(vertex), max.max(vertex)),
+                );
+            if poly_min.cmpgt(max).any() || poly_max.cmplt(min).any() {
+                continue;
+            }
+            let closest = self.closest_point_on_polygon(poly, point);
+            let distance = closest.distance_squared(point);
+            if distance < nearest_distance {
+                nearest = Some((poly as u32, closest));
+                nearest_distance = distance;
+            }
+        }
+        nearest
+    }
+
+    /// Like [`Self::find_smooth_path`], but first snaps `start` and `end` to the navmesh with [`Self::find_nearest_polygon`].
+    ///
+    /// The returned path starts and ends at the snapped points.
+    /// Returns `None` if either point is not within `half_extents` of the navmesh or if there is no connection between them.
+    pub fn find_nearest_smooth_path(
+        &self,
+        start: Vec3,
+        end: Vec3,
+        half_extents: Vec3,
+    ) -> Option<Vec<Vec3>> {
+        self.find_nearest_smooth_path_where(start, end, half_extents, |_poly| true)
+    }
+
+    /// Like [`Self::find_nearest_smooth_path`], but only snaps to and walks through polygons
+    /// for which `passable` returns `true`.
+    pub fn find_nearest_smooth_path_where(
+        &self,
+        start: Vec3,
+        end: Vec3,
+        half_extents: Vec3,
+        passable: impl Fn(u32) -> bool,
+    ) -> Option<Vec<Vec3>> {
+        let start = self.find_nearest_polygon_where(start, half_extents, &passable)?;
+        let end = self.find_nearest_polygon_where(end, half_extents, &passable)?;
+        self.smooth_path(start, end, passable)
+    }
+
+    fn smooth_path(
+        &self,
+        (start_poly, start): (u32, Vec3),
+        (end_poly, end): (u32, Vec3),
+        passable: impl Fn(u32) -> bool,
+    ) -> Option<Vec<Vec3>> {
+        let corridor = self.find_path_where(start_poly, end_poly, passable)?;
+        let mut portals = vec![(start, start)];
+        portals.extend(self.portals(&corridor));
+        portals.push((end, end));
+        Some(string_pull(&portals))
+    }
+
+    /// Returns the vertical clearance above each polygon in world units, i.e. the free space between the floor and
+    /// the ceiling, as sampled from the `heightfield` this mesh was built from.
+    ///
+    /// The clearance of a polygon is the smallest clearance of the spans of its region whose cells have their center
+    /// inside the polygon. Clearances beyond what a [`CompactSpan`](crate::CompactSpan) can store, including those of
+    /// spans without any ceiling, are [`f32::INFINITY`]. So are those of polygons that contain no cell center.
+    ///
+    /// Use this to keep tall agents out of polygons below low ceilings without building a navmesh per agent height.
+    pub fn clearances(&self, heightfield: &CompactHeightfield) -> Vec<f32> {
+        let nvp = self.max_vertices_per_polygon as usize;
+        let border_size = heightfield.border_size as usize;
+        (0..self.polygon_count())
+            .map(|poly| {
+                let vertices = &self.polygons[poly * nvp..][..nvp];
+                let vertex_count = count_poly_verts(vertices, nvp);
+                let corners = vertices[..vertex_count]
+                    .iter()
+                    .map(|&vertex| self.vertices[vertex as usize].xz())
+                    .collect::<Vec<_>>();
+                let min = corners
+                    .iter()
+                    .fold(U16Vec2::MAX, |min, &corner| min.min(corner));
+                let max = corners
+                    .iter()
+                    .fold(U16Vec2::ZERO, |max, &corner| max.max(corner));
+                // Polygons are convex, so a point is inside if it is on the same side of every edge.
+                let is_inside = |point: Vec2| {
+                    let (lowest, highest) = (0..vertex_count)
+                        .map(|j| {
+                            let a = corners[j].as_vec2();
+                            let b = corners[next(j, vertex_count)].as_vec2();
+                            (b - a).perp_dot(point - a)
+                        })
+                        .fold(
+                            (f32::INFINITY, f32::NEG_INFINITY),
+                            |(lowest, highest), cross| (lowest.min(cross), highest.max(cross)),
+                        );
+                    lowest >= 0.0 || highest <= 0.0
+                };
+                let mut clearance = u8::MAX;
+                for z in min.y..max.y {
+                    for x in min.x..max.x {
+                        if !is_inside(Vec2::new(x as f32 + 0.5, z as f32 + 0.5)) {
+                            continue;
+                        }
+                        let column = x as usize
+                            + border_size
+                            + (z as usize + border_size) * heightfield.width as usize;
+                        let cell = &heightfield.cells[column];
+                        for span in &heightfield.spans[cell.index_range()] {
+                            if span.region == self.regions[poly] {
+                                clearance = clearance.min(span.height());
+                            }
+                        }
+                    }
+                }
+                if clearance == u8::MAX {
+                    f32::INFINITY
+                } else {
+                    clearance as f32 * heightfield.cell_height
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the polygons whose whole area is at least `min_wall_distance` away from any wall, in world units.
+    /// Walls are the polygon edges without a neighbor. Portals to other tiles are not considered walls.
+    ///
+    /// Agents spawned on these polygons don't clip into walls as long as their radius is at most `min_wall_distance`.
+    /// Polygons touching a wall are only included if `min_wall_distance` is 0.
+    pub fn interior_polys(&self, min_wall_distance: f32) -> Vec<u32> {
+        let nvp = self.max_vertices_per_polygon as usize;
+        let edges = |poly: usize| {
+            let vertices = &self.polygons[poly * nvp..][..nvp];
+            let neighbors = &self.polygon_neighbors[poly * nvp..][..nvp];
+            let vertex_count = count_poly_verts(vertices, nvp);
+            (0..vertex_count).map(move |j| {
+                let a = Vec3A::from(self.world_vertex(vertices[j]));
+                let b = Vec3A::from(self.world_vertex(vertices[next(j, vertex_count)]));
+                (a, b, neighbors[j])
+            })
+        };
+        let walls = (0..self.polygon_count())
+            .flat_map(edges)
+            .filter(|(_a, _b, neighbor)| *neighbor == Self::NO_CONNECTION)
+            .map(|(a, b, _neighbor)| (a, b))
+            .collect::<Vec<_>>();
+
+        let min_wall_distance = min_wall_distance.max(0.0);
+        let min_distance_squared = min_wall_distance * min_wall_distance;
+        (0..self.polygon_count())
+            .filter(|&poly| {
+                // The mesh edges never cross, so the closest points between an edge and a wall
+                // always include an endpoint of either of them.
+                edges(poly).all(|(a, b, _neighbor)| {
+                    walls.iter().all(|&wall| {
+                        distance_squared_between_point_and_line_vec3(a, wall)
+                            >= min_distance_squared
+                            && distance_squared_between_point_and_line_vec3(wall.0, (a, b))
+                                >= min_distance_squared
+                            && distance_squared_between_point_and_line_vec3(wall.1, (a, b))
+                                >= min_distance_squared
+                    })
+                })
+            })
+            .map(|poly| poly as u32)
+            .collect()
+    }
+
+    /// Returns the distance from `point` to the nearest wall within `max_radius` and the nearest point on that wall.
+    /// Walls are the polygon edges without a neighbor. Portals to other tiles are not considered walls.
+    ///
+    /// Like Detour's `findDistanceToWall`, the search starts at the polygon containing `point`, see [`Self::find_polygon`],
+    /// and only spreads into neighboring polygons that are closer than the nearest wall found so far.
+    /// So walls of other floors are ignored. Distances are measured on the XZ plane, while the height of
+    /// the returned point is interpolated along the wall.
+    ///
+    /// Returns `None` if `point` is not on the navmesh or if there is no wall within `max_radius`.
+    pub fn distance_to_wall(&self, point: Vec3, max_radius: f32) -> Option<(f32, Vec3)> {
+        let start = self.find_polygon(point)?;
+        let nvp = self.max_vertices_per_polygon as usize;
+        let mut radius_squared = max_radius * max_radius;
+        let mut nearest = None;
+        let mut visited = vec![false; self.polygon_count()];
+        visited[start as usize] = true;
+        let mut open = vec![start as usize];
+        while let Some(poly) = open.pop() {
+            let vertices = &self.polygons[poly * nvp..][..nvp];
+            let neighbors = &self.polygon_neighbors[poly * nvp..][..nvp];
+            let vertex_count = count_poly_verts(vertices, nvp);
+            for j in 0..vertex_count {
+                let a = self.world_vertex(vertices[j]);
+                let b = self.world_vertex(vertices[next(j, vertex_count)]);
+                let ab = b.xz() - a.xz();
+                let t = (point.xz() - a.xz()).dot(ab) / ab.length_squared().max(f32::EPSILON);
+                let closest = a.lerp(b, t.clamp(0.0, 1.0));
+                let distance_squared = closest.xz().distance_squared(point.xz());
+                let neighbor = neighbors[j];
+                if neighbor == Self::NO_CONNECTION {
+                    if distance_squared < radius_squared {
+                        radius_squared = distance_squared;
+                        nearest = Some(closest);
+                    }
+                } else if !RegionId::from(neighbor).intersects(RegionId::BORDER_REGION)
+                    && (neighbor as usize) < visited.len()
+                    && !visited[neighbor as usize]
+                    && distance_squared < radius_squared
+                {
+                    visited[neighbor as usize] = true;
+                    open.push(neighbor as usize);
+                }
+            }
+        }
+        nearest.map(|wall| (ops::sqrt(radius_squared), wall))
+    }
+
+    /// Moves from `start` in the polygon `start_poly` towards `target`, sliding along the walls it runs into,
+    /// and returns the reached position together with the polygons walked through, starting with `start_poly`.
+    ///
+    /// This is a port of Detour's `moveAlongSurface`. Only the polygons within the circle spanned by `start` and `target`
+    /// on the XZ plane are searched. If none of them contains `target`, the point on their walls closest to `target`
+    /// is returned instead, so the result is always on the navmesh, no matter how far outside of it `target` lies.
+    /// Edges without a neighbor and portals to other tiles are treated as walls.
+    /// The height of the returned position lies on the polygon it ends in.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start_poly` is not smaller than [`Self::polygon_count`].
+    pub fn move_along_surface(
+        &self,
+        start_poly: u32,
+        start: Vec3,
+        target: Vec3,
+    ) -> (Vec3, Vec<u32>) {
+        let nvp = self.max_vertices_per_polygon as usize;
+        let polygon_count = self.polygon_count();
+        assert!(
+            (start_poly as usize) < polygon_count,
+            "Polygon {start_poly} does not exist"
+        );
+        let search_center = (start.xz() + target.xz()) / 2.0;
+        let search_radius = start.xz().distance(target.xz()) / 2.0 + 0.001;
+        let search_radius_squared = search_radius * search_radius;
+        // Returns the point on the segment closest to `point` on the XZ plane and their squared distance.
+        let closest_on_segment = |a: Vec3, b: Vec3, point: Vec2| {
+            let ab = b.xz() - a.xz();
+            let t = (point - a.xz()).dot(ab) / ab.length_squared().max(f32::EPSILON);
+            let closest = a.lerp(b, t.clamp(0.0, 1.0));
+            (closest, closest.xz().distance_squared(point))
+        };
+
+        let mut parents = vec![None; polygon_count];
+        let mut visited = vec![false; polygon_count];
+        visited[start_poly as usize] = true;
+        let mut open = VecDeque::from([start_poly as usize]);
+        let mut best = (start_poly as usize, start);
+        let mut best_distance_squared = f32::INFINITY;
+        while let Some(poly) = open.pop_front() {
+            if let Some(height) = self
+                .poly_triangles(poly as u32)
+                .find_map(|triangle| height_on_triangle(target, triangle))
+            {
+                best = (poly, target.with_y(height));
+                break;
+            }
+            let vertices = &self.polygons[poly * nvp..][..nvp];
+            let neighbors = &self.polygon_neighbors[poly * nvp..][..nvp];
+            let vertex_count = count_poly_verts(vertices, nvp);
+            for j in 0..vertex_count {
+                let a = self.world_vertex(vertices[j]);
+                let b = self.world_vertex(vertices[next(j, vertex_count)]);
+                let neighbor = neighbors[j];
+                let is_wall = neighbor == Self::NO_CONNECTION
+                    || RegionId::from(neighbor).intersects(RegionId::BORDER_REGION)
+                    || neighbor as usize >= polygon_count;
+                if is_wall {
+                    let (closest, distance_squared) = closest_on_segment(a, b, target.xz());
+                    if distance_squared < best_distance_squared {
+                        best = (poly, closest);
+                        best_distance_squared = distance_squared;
+                    }
+                    continue;
+                }
+                let neighbor = neighbor as usize;
+                if visited[neighbor] {
+                    continue;
+                }
+                let (_closest, distance_squared) = closest_on_segment(a, b, search_center);
+                if distance_squared > search_radius_squared {
+                    continue;
+                }
+                visited[neighbor] = true;
+                parents[neighbor] = Some(poly);
+                open.push_back(neighbor);
+            }
+        }
+
+        let (mut poly, position) = best;
+        let mut visited_polys = vec![poly as u32];
+        while let Some(parent) = parents[poly] {
+            visited_polys.push(parent as u32);
+            poly = parent;
+        }
+        visited_polys.reverse();
+        (position, visited_polys)
+    }
+
+    /// Triangulates the polygon at index `poly` as a fan around its first vertex, in world space.
+    ///
+    /// Since polygons are convex, this yields `n - 2` triangles for a polygon with `n` vertices.
+    /// This is a cheap way to get the coarse shape of a polygon, e.g. for rendering or point-in-polygon tests.
+    /// Use the [`DetailNavmesh`](crate::DetailNavmesh) instead if you need the height detail.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `poly` is not smaller than [`Self::polygon_count`].
+    pub fn poly_triangles(&self, poly: u32) -> impl Iterator<Item = [Vec3; 3]> + '_ {
+        let nvp = self.max_vertices_per_polygon as usize;
+        let vertices = &self.polygons[poly as usize * nvp..][..nvp];
+        let vertex_count = count_poly_verts(vertices, nvp);
+        (2..vertex_count).map(move |i| {
+            [vertices[0], vertices[i - 1], vertices[i]].map(|index| self.world_vertex(index))
+        })
+    }
+
+    /// Finds and fixes inconsistent adjacency information in [`Self::polygon_neighbors`].
+    ///
+    /// A link from a polygon to a neighbor is only kept if the neighbor exists and shares the edge.
+    /// Links that are only present on one side of a shared edge are added to the other side.
+    /// Tile border portals, i.e. links with [`RegionId::BORDER_REGION`] set, are left untouched.
+    ///
+    /// Freshly generated meshes are always consistent. This is meant to be run after editing the mesh by hand.
+    pub fn repair(&mut self) -> RepairReport {
+        let nvp = self.max_vertices_per_polygon as usize;
+        let polygon_count = self.polygon_count();
+        let mut report = RepairReport::default();
+
+        // Remove links to polygons that do not exist or do not share the edge.
+        let mut links = Vec::new();
+        for polygon in 0..polygon_count {
+            let vertices = &self.polygons[polygon * nvp..][..nvp];
+            let vertex_count = count_poly_verts(vertices, nvp);
+            for edge in 0..vertex_count {
+                let neighbor = self.polygon_neighbors[polygon * nvp + edge];
+                if RegionId::from(neighbor).intersects(RegionId::BORDER_REGION) {
+                    // No connection or a portal to another tile.
+                    continue;
+                }
+                let neighbor = neighbor as usize;
+                let neighbor_edge = if neighbor != polygon && neighbor < polygon_count {
+                    self.find_shared_edge(polygon, edge, neighbor)
+                } else {
+                    None
+                };
+                if let Some(neighbor_edge) = neighbor_edge {
+                    links.push((polygon, neighbor, neighbor_edge));
+                } else {
+                    self.polygon_neighbors[polygon * nvp + edge] = Self::NO_CONNECTION;
+                    report.removed.push((polygon as u16, neighbor as u16));
+                }
+            }
+        }
+
+        // Add the missing back links.
+        for (polygon, neighbor, neighbor_edge) in links {
+            let back_link = &mut self.polygon_neighbors[neighbor * nvp + neighbor_edge];
+            if *back_link != polygon as u16 {
+                *back_link = polygon as u16;
+                report.symmetrized.push((polygon as u16, neighbor as u16));
+            }
+        }
+
+        report
+    }
+
+    /// Returns the index of the edge of `neighbor` that runs along `edge` of `polygon` in the opposite direction.
+    fn find_shared_edge(&self, polygon: usize, edge: usize, neighbor: usize) -> Option<usize> {
+        let nvp = self.max_vertices_per_polygon as usize;
+        let vertices = &self.polygons[polygon * nvp..][..nvp];
+        let vertex_count = count_poly_verts(vertices, nvp);
+        let (a, b) = (vertices[edge], vertices[next(edge, vertex_count)]);
+
+        let neighbor_vertices = &self.polygons[neighbor * nvp..][..nvp];
+        let neighbor_vertex_count = count_poly_verts(neighbor_vertices, nvp);
+        (0..neighbor_vertex_count).find(|&j| {
+            neighbor_vertices[j] == b && neighbor_vertices[next(j, neighbor_vertex_count)] == a
+        })
+    }
+
+    /// Like [`Self::neighbors`], but with `usize` indices for internal bookkeeping.
+    fn neighbor_indices(&self, poly: usize) -> impl Iterator<Item = usize> + '_ {
+        let nvp = self.max_vertices_per_polygon as usize;
+        let polygon_count = self.polygon_count();
+        self.polygon_neighbors[poly * nvp..][..nvp]
+            .iter()
+            .filter(|&&neighbor| !RegionId::from(neighbor).intersects(RegionId::BORDER_REGION))
+            .map(|&neighbor| neighbor as usize)
+            .filter(move |&neighbor| neighbor < polygon_count)
+    }
+
+    /// Returns the point on the polygon `poly` closest to `point`.
+    /// Points above or below the polygon are projected onto it vertically.
+    fn closest_point_on_polygon(&self, poly: usize, point: Vec3) -> Vec3 {
+        if let Some(height) = self
+            .poly_triangles(poly as u32)
+            .find_map(|triangle| height_on_triangle(point, triangle))
+        {
+            return point.with_y(height);
+        }
+        let nvp = self.max_vertices_per_polygon as usize;
+        let vertices = &self.polygons[poly * nvp..][..nvp];
+        let vertex_count = count_poly_verts(vertices, nvp);
+        (0..vertex_count)
+            .map(|j| {
+                let a = self.world_vertex(vertices[j]);
+                let b = self.world_vertex(vertices[next(j, vertex_count)]);
+                let ab = b - a;
+                let t = (point - a).dot(ab) / ab.length_squared().max(f32::EPSILON);
+                a + ab * t.clamp(0.0, 1.0)
+            })
+            .min_by(|a, b| {
+                a.distance_squared(point)
+                    .total_cmp(&b.distance_squared(point))
+            })
+            .unwrap_or(point)
+    }
+
+    fn polygon_center(&self, poly: usize) -> Vec3 {
+        let nvp = self.max_vertices_per_polygon as usize;
+        let vertices = &self.polygons[poly * nvp..][..nvp];
+        let vertex_count = count_poly_verts(vertices, nvp);
+        let sum = vertices[..vertex_count]
+            .iter()
+            .map(|&vertex| self.world_vertex(vertex))
+            .sum::<Vec3>();
+        sum / vertex_count as f32
+    }
+
+    fn world_vertex(&self, index: u16) -> Vec3 {
+        let vertex = self.vertices[index as usize].as_vec3();
+        self.aabb.min + vertex * Vec3::new(self.cell_size, self.cell_height, self.cell_size)
+    }
+}
+
+/// Keeps the per-polygon `values` whose entry in `kept` is `true`.
+fn retain_kept<T>(values: &mut Vec<T>, kept: &[bool]) {
+    let mut kept = kept.iter();
+    values.retain(|_value| kept.next().copied().unwrap_or(true));
+}
+
+/// The directions towards a goal polygon for every polygon of a [`PolygonNavmesh`], see [`PolygonNavmesh::build_flow_field`].
+///
+/// Agents look up the polygon they are on and walk into [`Self::next`] until they reach [`Self::goal`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlowField {
+    /// The polygon the flow field leads to.
+    pub goal: u32,
+    /// The next polygon on the shortest way from each polygon to the goal,
+    /// or `None` for the goal itself and for polygons that can't reach it.
+    pub next: Vec<Option<u32>>,
+    /// The cost of the shortest way from each polygon to the goal,
+    /// or [`f32::INFINITY`] for polygons that can't reach it.
+    pub costs: Vec<f32>,
+}
+
+impl FlowField {
+    /// Returns `true` if the goal can be reached from the polygon `poly`.
+    pub fn is_reachable(&self, poly: u32) -> bool {
+        self.costs
+            .get(poly as usize)
+            .is_some_and(|cost| cost.is_finite())
+    }
+
+    /// Follows the flow field from the polygon `poly` and returns the polygons on the way, from `poly` to the goal.
+    ///
+    /// Returns `None` if the goal can't be reached from `poly`.
+    pub fn path(&self, poly: u32) -> Option<Vec<u32>> {
+        if !self.is_reachable(poly) {
+            return None;
+        }
+        let mut path = vec![poly];
+        let mut poly = poly;
+        while let Some(next) = self.next[poly as usize] {
+            path.push(next);
+            poly = next;
+        }
+        Some(path)
+    }
+}
+
+/// The fixes applied by [`PolygonNavmesh::repair`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepairReport {
+    /// Links `(polygon, neighbor)` that were only stored on `polygon` and have been added to `neighbor`.
+    pub symmetrized: Vec<(u16, u16)>,
+    /// Links `(polygon, neighbor)` that have been removed from `polygon`,
+    /// because `neighbor` does not exist or does not share the edge.
+    pub removed: Vec<(u16, u16)>,
+}
+
+impl RepairReport {
+    /// Returns `true` if the mesh was already consistent and nothing had to be fixed.
+    pub fn is_empty(&self) -> bool {
+        self.symmetrized.is_empty() && self.removed.is_empty()
+    }
 }
 
 impl From<InternalPolygonNavmesh> for PolygonNavmesh {
@@ -179,6 +1204,7 @@ impl From<InternalPolygonNavmesh> for PolygonNavmesh {
             polygon_neighbors.extend_from_slice(neighbors);
         }
         value.vertices.truncate(value.nvertices as usize);
+        value.regions.truncate(value.npolys);
         value.areas.truncate(value.npolys);
         PolygonNavmesh {
             vertices: value.vertices,
@@ -977,6 +2003,91 @@ fn uleft(a: U16Vec3, b: U16Vec3, c: U16Vec3) -> bool {
     cross < 0
 }
 
+/// Returns the height of the triangle at `point` when projected onto the XZ plane,
+/// or `None` if the point is outside of the triangle.
+fn height_on_triangle(point: Vec3, [a, b, c]: [Vec3; 3]) -> Option<f32> {
+    let v0 = c - a;
+    let v1 = b - a;
+    let v2 = point - a;
+    let mut denom = v0.x * v1.z - v0.z * v1.x;
+    if ops::abs(denom) < f32::EPSILON {
+        return None;
+    }
+    let mut u = v1.z * v2.x - v1.x * v2.z;
+    let mut v = v0.x * v2.z - v0.z * v2.x;
+    if denom < 0.0 {
+        denom = -denom;
+        u = -u;
+        v = -v;
+    }
+    (u >= 0.0 && v >= 0.0 && u + v <= denom).then(|| a.y + (v0.y * u + v1.y * v) / denom)
+}
+
+/// The funnel algorithm, also known as string pulling.
+/// Returns the shortest path through the `(left, right)` portals, which start and end with degenerate portals at the start and end points.
+///
+/// Port of Mikko Mononen's [Simple Stupid Funnel Algorithm](https://digestingduck.blogspot.com/2010/03/simple-stupid-funnel-algorithm.html).
+fn string_pull(portals: &[(Vec3, Vec3)]) -> Vec<Vec3> {
+    /// Twice the signed area of the triangle on the XZ plane.
+    fn area(a: Vec3, b: Vec3, c: Vec3) -> f32 {
+        (c.x - a.x) * (b.z - a.z) - (b.x - a.x) * (c.z - a.z)
+    }
+    fn equal(a: Vec3, b: Vec3) -> bool {
+        a.distance_squared(b) < 1e-6
+    }
+
+    let (mut apex, mut left, mut right) = (portals[0].0, portals[0].0, portals[0].1);
+    let (mut left_index, mut right_index) = (0, 0);
+    let mut path = vec![apex];
+    let mut i = 1;
+    while i < portals.len() {
+        let (portal_left, portal_right) = portals[i];
+        i += 1;
+
+        // Update the right side of the funnel.
+        if area(apex, right, portal_right) <= 0.0 {
+            if equal(apex, right) || area(apex, left, portal_right) > 0.0 {
+                // Tighten the funnel.
+                right = portal_right;
+                right_index = i - 1;
+            } else {
+                // Right crossed over left, so the left side is a corner of the path.
+                apex = left;
+                path.push(apex);
+                right = apex;
+                right_index = left_index;
+                // Restart the scan from the new apex.
+                i = left_index + 1;
+                continue;
+            }
+        }
+
+        // Update the left side of the funnel.
+        if area(apex, left, portal_left) >= 0.0 {
+            if equal(apex, left) || area(apex, right, portal_left) < 0.0 {
+                // Tighten the funnel.
+                left = portal_left;
+                left_index = i - 1;
+            } else {
+                // Left crossed over right, so the right side is a corner of the path.
+                apex = right;
+                path.push(apex);
+                left = apex;
+                left_index = right_index;
+                // Restart the scan from the new apex.
+                i = right_index + 1;
+                continue;
+            }
+        }
+    }
+
+    let end = portals[portals.len() - 1].0;
+    if !path.last().is_some_and(|&last| equal(last, end)) {
+        path.push(end);
+    }
+    path
+}
+
 fn count_poly_verts(p: &[u16], nvp: usize) -> usize {
     p.iter()
         .take(nvp)
@@ -1167,13 +2278,13 @@ fn in_cone(i: usize, j: usize, n: usize, verts: &[(U16Vec3, u32)], indices: &[us
 }
 
 #[inline]
-fn is_left_on(a: U16Vec3, b: U16Vec3, c: U16Vec3) -> bool {
+pub(crate) fn is_left_on(a: U16Vec3, b: U16Vec3, c: U16Vec3) -> bool {
     area2(a, b, c) <= 0
 }
 
 /// Returns true iff c is strictly to the left of the directed line through a to b.
 #[inline]
-fn is_left(a: U16Vec3, b: U16Vec3, c: U16Vec3) -> bool {
+pub(crate) fn is_left(a: U16Vec3, b: U16Vec3, c: U16Vec3) -> bool {
     area2(a, b, c) < 0
 }
 
@@ -1218,13 +2329,13 @@ fn is_diagonal_internal_or_external(
 const INDEX_MASK: usize = 0x0fffffff;
 
 #[inline]
-fn vequal(a: U16Vec3, b: U16Vec3) -> bool {
+pub(crate) fn vequal(a: U16Vec3, b: U16Vec3) -> bool {
     a.xz() == b.xz()
 }
 
 /// Returns true iff segments ab and cd intersect, properly or improperly.
 #[inline]
-fn intersect(a: U16Vec3, b: U16Vec3, c: U16Vec3, d: U16Vec3) -> bool {
+pub(crate) fn intersect(a: U16Vec3, b: U16Vec3, c: U16Vec3, d: U16Vec3) -> bool {
     if intersect_prop(a, b, c, d) {
         return true;
     }
@@ -1334,14 +2445,537 @@ fn is_diagonal_internal_or_external_loose(
     true
 }
 
+/// Errors that can occur when building or merging [`PolygonNavmesh`]es.
 #[derive(Error, Debug)]
 pub enum PolygonNavmeshError {
+    /// The mesh would have more vertices than can be indexed.
     #[error("Too many vertices: {actual} > {max}")]
-    TooManyVertices { actual: usize, max: usize },
+    TooManyVertices {
+        /// The number of vertices the mesh would have.
+        actual: usize,
+        /// The maximum number of vertices.
+        max: usize,
+    },
+    /// The mesh would have more polygons than can be indexed.
     #[error("Too many polygons: {actual} > {max}")]
-    TooManyPolygons { actual: usize, max: usize },
+    TooManyPolygons {
+        /// The number of polygons the mesh would have.
+        actual: usize,
+        /// The maximum number of polygons.
+        max: usize,
+    },
+    /// A contour could not be triangulated.
     #[error(
         "Invalid contour. This sometimes happens if the contour simplification is too aggressive."
     )]
     InvalidContour,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NC: u16 = PolygonNavmesh::NO_CONNECTION;
+
+    /// Three unit quads forming an L: 0 -> 1 along x, then 1 -> 2 along z.
+    fn l_corridor() -> PolygonNavmesh {
+        PolygonNavmesh {
+            vertices: vec![
+                u16vec3(0, 0, 0),
+                u16vec3(1, 0, 0),
+                u16vec3(2, 0, 0),
+                u16vec3(0, 0, 1),
+                u16vec3(1, 0, 1),
+                u16vec3(2, 0, 1),
+                u16vec3(1, 0, 2),
+                u16vec3(2, 0, 2),
+            ],
+            polygons: vec![0, 3, 4, 1, 1, 4, 5, 2, 4, 6, 7, 5],
+            polygon_neighbors: vec![NC, NC, 1, NC, 0, 2, NC, NC, NC, NC, NC, 1],
+            flags: vec![0; 3],
+            regions: vec![RegionId::from(1); 3],
+            areas: vec![AreaType::DEFAULT_WALKABLE; 3],
+            max_vertices_per_polygon: 4,
+            aabb: Aabb3d::default(),
+            cell_size: 1.0,
+            cell_height: 1.0,
+            border_size: 0,
+            max_edge_error: 0.0,
+        }
+    }
+
+    #[test]
+    fn portals_of_l_corridor() {
+        let mesh = l_corridor();
+        let path = [0, 1, 2];
+        let portals = mesh.portals(&path);
+        assert_eq!(portals.len(), path.len() - 1);
+        assert_eq!(
+            portals[0],
+            (Vec3::new(1.0, 0.0, 1.0), Vec3::new(1.0, 0.0, 0.0))
+        );
+        assert_eq!(
+            portals[1],
+            (Vec3::new(1.0, 0.0, 1.0), Vec3::new(2.0, 0.0, 1.0))
+        );
+    }
+
+    #[test]
+    fn portals_skip_unconnected_polygons() {
+        let mesh = l_corridor();
+        assert!(mesh.portals(&[0, 2]).is_empty());
+    }
+
+    #[test]
+    fn adjacency_of_l_corridor() {
+        let mesh = l_corridor();
+        assert!(mesh.are_adjacent(0, 1));
+        assert!(mesh.are_adjacent(1, 0));
+        assert!(mesh.are_adjacent(1, 2));
+        assert!(!mesh.are_adjacent(0, 2));
+        assert!(!mesh.are_adjacent(0, 0));
+        assert!(!mesh.are_adjacent(0, 3));
+    }
+
+    #[test]
+    fn quad_fans_into_two_triangles() {
+        let mesh = l_corridor();
+        let triangles = mesh.poly_triangles(0).collect::<Vec<_>>();
+        assert_eq!(
+            triangles,
+            [
+                [
+                    Vec3::ZERO,
+                    Vec3::new(0.0, 0.0, 1.0),
+                    Vec3::new(1.0, 0.0, 1.0)
+                ],
+                [
+                    Vec3::ZERO,
+                    Vec3::new(1.0, 0.0, 1.0),
+                    Vec3::new(1.0, 0.0, 0.0)
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn smooth_path_around_l_corner() {
+        let mesh = l_corridor();
+        let start = Vec3::new(0.2, 0.0, 0.1);
+        let end = Vec3::new(1.5, 0.0, 1.8);
+        assert_eq!(mesh.find_path(0, 2, &HashSet::new()), Some(vec![0, 1, 2]));
+        assert_eq!(
+            mesh.find_smooth_path(start, end),
+            Some(vec![start, Vec3::new(1.0, 0.0, 1.0), end])
+        );
+        assert_eq!(
+            mesh.find_smooth_path(end, start),
+            Some(vec![end, Vec3::new(1.0, 0.0, 1.0), start])
+        );
+    }
+
+    #[test]
+    fn find_path_avoids_blocked_polygons() {
+        let mesh = l_corridor();
+        assert_eq!(mesh.find_path(0, 2, &HashSet::new()), Some(vec![0, 1, 2]));
+        assert_eq!(mesh.find_path(0, 2, &HashSet::from([1])), None);
+        assert_eq!(mesh.find_path(0, 2, &HashSet::from([2])), None);
+
+        // A 2x2 grid of unit quads, which offers two ways from the polygon 0 to the opposite polygon 3.
+        let vertices = (0..3)
+            .flat_map(|z| (0..3).map(move |x| u16vec3(x, 0, z)))
+            .collect::<Vec<_>>();
+        let mesh = PolygonNavmesh {
+            vertices,
+            polygons: vec![0, 3, 4, 1, 1, 4, 5, 2, 3, 6, 7, 4, 4, 7, 8, 5],
+            polygon_neighbors: vec![NC, 2, 1, NC, 0, 3, NC, NC, NC, NC, 3, 0, 2, NC, NC, 1],
+            flags: vec![0; 4],
+            regions: vec![RegionId::from(1); 4],
+            areas: vec![AreaType::DEFAULT_WALKABLE; 4],
+            ..l_corridor()
+        };
+        assert_eq!(
+            mesh.find_path(0, 3, &HashSet::from([1])),
+            Some(vec![0, 2, 3])
+        );
+        assert_eq!(
+            mesh.find_path(0, 3, &HashSet::from([2])),
+            Some(vec![0, 1, 3])
+        );
+        assert_eq!(mesh.find_path(0, 3, &HashSet::from([1, 2])), None);
+    }
+
+    #[test]
+    fn find_path_where_skips_impassable_polygons() {
+        let mesh = l_corridor();
+        assert_eq!(mesh.find_path_where(0, 2, |poly| poly != 1), None);
+        assert_eq!(mesh.find_path_where(0, 1, |poly| poly != 1), None);
+        assert_eq!(
+            mesh.find_path_where(0, 1, |poly| poly != 2),
+            Some(vec![0, 1])
+        );
+
+        let start = Vec3::new(0.5, 0.0, 0.5);
+        let half_extents = Vec3::new(0.4, 1.0, 0.4);
+        assert_eq!(
+            mesh.find_nearest_polygon_where(start, half_extents, |poly| poly != 0),
+            None
+        );
+    }
+
+    #[test]
+    fn separated_floors_are_two_islands() {
+        let mut mesh = l_corridor();
+        assert_eq!(mesh.connected_components(), [vec![0, 1, 2]]);
+
+        // Stack a copy of the corridor above the original, without any connection between them.
+        let vertex_offset = mesh.vertices.len() as u16;
+        let poly_offset = mesh.polygon_count() as u16;
+        let upper_vertices = mesh
+            .vertices
+            .iter()
+            .map(|&v| v + u16vec3(0, 10, 0))
+            .collect::<Vec<_>>();
+        let upper_polygons = mesh
+            .polygons
+            .iter()
+            .map(|&i| i + vertex_offset)
+            .collect::<Vec<_>>();
+        let upper_neighbors = mesh
+            .polygon_neighbors
+            .iter()
+            .map(|&n| if n == NC { NC } else { n + poly_offset })
+            .collect::<Vec<_>>();
+        mesh.vertices.extend(upper_vertices);
+        mesh.polygons.extend(upper_polygons);
+        mesh.polygon_neighbors.extend(upper_neighbors);
+        mesh.flags.extend([0; 3]);
+        mesh.regions.extend([RegionId::from(2); 3]);
+        mesh.areas.extend([AreaType::DEFAULT_WALKABLE; 3]);
+
+        assert_eq!(mesh.connected_components(), [vec![0, 1, 2], vec![3, 4, 5]]);
+        assert_eq!(mesh.find_path(0, 5, &HashSet::new()), None);
+    }
+
+    #[test]
+    fn prune_islands_removes_small_islands() {
+        let mut mesh = l_corridor();
+        // Cut off the last polygon of the corridor.
+        mesh.polygon_neighbors[5] = NC;
+        mesh.polygon_neighbors[11] = NC;
+        mesh.flags = vec![1, 2, 3];
+
+        assert_eq!(mesh.prune_islands(1), Vec::<Vec<u32>>::new());
+        assert_eq!(mesh.polygon_count(), 3);
+
+        assert_eq!(mesh.prune_islands(2), [vec![2]]);
+        assert_eq!(mesh.polygon_count(), 2);
+        assert_eq!(mesh.polygons, [0, 3, 4, 1, 1, 4, 5, 2]);
+        assert_eq!(mesh.polygon_neighbors, [NC, NC, 1, NC, 0, NC, NC, NC]);
+        assert_eq!(mesh.flags, [1, 2]);
+        assert_eq!(mesh.connected_components(), [vec![0, 1]]);
+    }
+
+    #[test]
+    fn barycenter_of_symmetric_grid_is_its_center() {
+        let center = grid(3).barycenter();
+        assert!(center.distance(Vec3::new(1.5, 0.0, 1.5)) < 1e-5, "{center}");
+
+        let center = l_corridor().barycenter();
+        assert!(
+            center.distance(Vec3::new(3.5, 0.0, 2.5) / 3.0) < 1e-5,
+            "{center}"
+        );
+
+        let empty = PolygonNavmesh {
+            max_vertices_per_polygon: 4,
+            aabb: Aabb3d::new(Vec3::ONE, Vec3::ONE),
+            ..Default::default()
+        };
+        assert_eq!(empty.barycenter(), Vec3::ONE);
+    }
+
+    /// A tile consisting of a single unit quad at `x`, with portals on its -x and +x edges.
+    fn quad_tile(x: f32) -> PolygonNavmesh {
+        let portal = RegionId::BORDER_REGION.bits();
+        PolygonNavmesh {
+            vertices: vec![
+                u16vec3(0, 0, 0),
+                u16vec3(1, 0, 0),
+                u16vec3(0, 0, 1),
+                u16vec3(1, 0, 1),
+            ],
+            polygons: vec![0, 2, 3, 1],
+            polygon_neighbors: vec![portal, NC, portal | 2, NC],
+            flags: vec![0],
+            regions: vec![RegionId::from(1)],
+            areas: vec![AreaType::DEFAULT_WALKABLE],
+            max_vertices_per_polygon: 4,
+            aabb: Aabb3d {
+                min: Vec3::new(x, 0.0, 0.0),
+                max: Vec3::new(x + 1.0, 1.0, 1.0),
+            },
+            cell_size: 1.0,
+            cell_height: 1.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn merge_connects_tile_portals() {
+        let tiles = [quad_tile(0.0), quad_tile(1.0), quad_tile(2.0)];
+        let merged = PolygonNavmesh::merge(&tiles).unwrap();
+        assert_eq!(merged.polygon_count(), 3);
+        // The vertices on the borders between the tiles are welded.
+        assert_eq!(merged.vertices.len(), 8);
+        assert_eq!(merged.aabb.max, Vec3::new(3.0, 1.0, 1.0));
+        // The portals on the outer borders have no counterpart.
+        assert_eq!(
+            merged.polygon_neighbors,
+            [NC, NC, 1, NC, 0, NC, 2, NC, 1, NC, NC, NC]
+        );
+        assert_eq!(merged.find_path(0, 2, &HashSet::new()), Some(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn smooth_path_without_corners() {
+        let mesh = l_corridor();
+        let start = Vec3::new(0.5, 0.0, 0.5);
+        let end = Vec3::new(1.5, 0.0, 0.5);
+        assert_eq!(mesh.find_smooth_path(start, end), Some(vec![start, end]));
+        assert_eq!(mesh.find_smooth_path(start, start), Some(vec![start]));
+        assert_eq!(mesh.find_smooth_path(start, Vec3::new(0.5, 0.0, 1.5)), None);
+    }
+
+    /// A `size` x `size` grid of unit quads. For a 3x3 grid, only the center quad does not touch a wall.
+    fn grid(size: u16) -> PolygonNavmesh {
+        let vertex = |x: u16, z: u16| z * (size + 1) + x;
+        let poly = |x: u16, z: u16| z * size + x;
+        let mut mesh = PolygonNavmesh {
+            vertices: (0..=size)
+                .flat_map(|z| (0..=size).map(move |x| u16vec3(x, 0, z)))
+                .collect(),
+            max_vertices_per_polygon: 4,
+            cell_size: 1.0,
+            cell_height: 1.0,
+            ..Default::default()
+        };
+        for z in 0..size {
+            for x in 0..size {
+                mesh.polygons.extend([
+                    vertex(x, z),
+                    vertex(x, z + 1),
+                    vertex(x + 1, z + 1),
+                    vertex(x + 1, z),
+                ]);
+                mesh.polygon_neighbors.extend([
+                    if x > 0 { poly(x - 1, z) } else { NC },
+                    if z < size - 1 { poly(x, z + 1) } else { NC },
+                    if x < size - 1 { poly(x + 1, z) } else { NC },
+                    if z > 0 { poly(x, z - 1) } else { NC },
+                ]);
+            }
+        }
+        let polygon_count = usize::from(size * size);
+        mesh.flags = vec![0; polygon_count];
+        mesh.regions = vec![RegionId::from(1); polygon_count];
+        mesh.areas = vec![AreaType::DEFAULT_WALKABLE; polygon_count];
+        mesh
+    }
+
+    #[test]
+    fn adjacency_and_portals_of_2x2_grid() {
+        let mesh = grid(2);
+        // Polygons 0 and 1 are the lower row, 2 and 3 the upper row.
+        let neighbors = |poly: u32| mesh.neighbors(poly).collect::<Vec<_>>();
+        assert_eq!(neighbors(0), [2, 1]);
+        assert_eq!(neighbors(1), [0, 3]);
+        assert_eq!(neighbors(2), [3, 0]);
+        assert_eq!(neighbors(3), [2, 1]);
+
+        assert_eq!(
+            mesh.portal(0, 1),
+            Some((Vec3::new(1.0, 0.0, 1.0), Vec3::new(1.0, 0.0, 0.0)))
+        );
+        assert_eq!(
+            mesh.portal(1, 0),
+            Some((Vec3::new(1.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 1.0)))
+        );
+        assert_eq!(
+            mesh.portal(0, 2),
+            Some((Vec3::new(0.0, 0.0, 1.0), Vec3::new(1.0, 0.0, 1.0)))
+        );
+        assert_eq!(mesh.portal(0, 3), None);
+        assert_eq!(mesh.portal(0, 0), None);
+        assert_eq!(mesh.portal(0, 4), None);
+    }
+
+    #[test]
+    fn flow_field_leads_to_goal() {
+        let mut mesh = grid(3);
+        // Add an unreachable quad far away from the grid.
+        let base = mesh.vertices.len() as u16;
+        mesh.vertices.extend([
+            u16vec3(10, 0, 10),
+            u16vec3(10, 0, 11),
+            u16vec3(11, 0, 11),
+            u16vec3(11, 0, 10),
+        ]);
+        mesh.polygons.extend([base, base + 1, base + 2, base + 3]);
+        mesh.polygon_neighbors.extend([NC; 4]);
+        mesh.flags.push(0);
+        mesh.regions.push(RegionId::from(2));
+        mesh.areas.push(AreaType::DEFAULT_WALKABLE);
+
+        let goal = 8;
+        let field = mesh.build_flow_field(goal);
+        assert_eq!(field.goal, goal);
+        assert_eq!(field.next[goal as usize], None);
+        assert_eq!(field.costs[goal as usize], 0.0);
+        for poly in 0..9 {
+            let path = field.path(poly).unwrap();
+            assert_eq!(path.first(), Some(&poly));
+            assert_eq!(path.last(), Some(&goal));
+            assert!(
+                path.windows(2)
+                    .all(|pair| mesh.are_adjacent(pair[0], pair[1]))
+            );
+            // Along the grid, every step goes one quad closer to the goal in either direction.
+            let (x, z) = (poly % 3, poly / 3);
+            assert_eq!(path.len() as u32 - 1, (2 - x) + (2 - z));
+        }
+        assert!(!field.is_reachable(9));
+        assert_eq!(field.next[9], None);
+        assert_eq!(field.path(9), None);
+    }
+
+    #[test]
+    fn interior_polys_exclude_edge_polygons() {
+        let mesh = grid(3);
+        assert_eq!(mesh.interior_polys(0.0), (0..9).collect::<Vec<_>>());
+        assert_eq!(mesh.interior_polys(0.5), [4]);
+        assert_eq!(mesh.interior_polys(1.0), [4]);
+        assert!(mesh.interior_polys(1.5).is_empty());
+        assert!(l_corridor().interior_polys(0.1).is_empty());
+    }
+
+    #[test]
+    fn distance_to_wall_of_square_room() {
+        let mesh = grid(3);
+        let (distance, wall) = mesh
+            .distance_to_wall(Vec3::new(1.5, 0.0, 1.5), 10.0)
+            .unwrap();
+        assert_eq!(distance, 1.5);
+        assert_eq!(wall.distance(Vec3::new(1.5, 0.0, 1.5)), 1.5);
+        assert!(wall.x == 0.0 || wall.x == 3.0 || wall.z == 0.0 || wall.z == 3.0);
+
+        assert_eq!(
+            mesh.distance_to_wall(Vec3::new(2.5, 0.0, 1.5), 10.0),
+            Some((0.5, Vec3::new(3.0, 0.0, 1.5)))
+        );
+        assert_eq!(mesh.distance_to_wall(Vec3::new(1.5, 0.0, 1.5), 1.0), None);
+        assert_eq!(mesh.distance_to_wall(Vec3::new(5.0, 0.0, 5.0), 10.0), None);
+    }
+
+    #[test]
+    fn move_along_surface_slides_along_walls() {
+        let mesh = l_corridor();
+        let start = Vec3::new(0.5, 0.0, 0.5);
+        assert_eq!(
+            mesh.move_along_surface(0, start, Vec3::new(1.5, 0.0, 1.5)),
+            (Vec3::new(1.5, 0.0, 1.5), vec![0, 1, 2])
+        );
+        // Straight into the wall.
+        assert_eq!(
+            mesh.move_along_surface(0, start, Vec3::new(0.5, 0.0, -3.0)),
+            (Vec3::new(0.5, 0.0, 0.0), vec![0])
+        );
+        // Diagonally into the wall, sliding along it.
+        assert_eq!(
+            mesh.move_along_surface(0, start, Vec3::new(-1.0, 0.0, 0.75)),
+            (Vec3::new(0.0, 0.0, 0.75), vec![0])
+        );
+        // Far outside of the mesh, ending at the corner of the L closest to the target.
+        assert_eq!(
+            mesh.move_along_surface(0, start, Vec3::new(0.5, 0.0, 5.0)),
+            (Vec3::new(1.0, 0.0, 2.0), vec![0, 1, 2])
+        );
+    }
+
+    #[test]
+    fn nearest_polygon_snaps_points_off_the_mesh() {
+        let mesh = l_corridor();
+        let half_extents = Vec3::new(1.0, 2.0, 1.0);
+        assert_eq!(
+            mesh.find_nearest_polygon(Vec3::new(0.5, 1.5, 0.5), half_extents),
+            Some((0, Vec3::new(0.5, 0.0, 0.5)))
+        );
+        // Outside of the L, next to the corner of polygon 2.
+        assert_eq!(
+            mesh.find_nearest_polygon(Vec3::new(0.5, 0.0, 1.8), half_extents),
+            Some((2, Vec3::new(1.0, 0.0, 1.8)))
+        );
+        assert_eq!(
+            mesh.find_nearest_polygon(Vec3::new(5.0, 0.0, 5.0), half_extents),
+            None
+        );
+        assert_eq!(
+            mesh.find_nearest_polygon(Vec3::new(0.5, 3.0, 0.5), half_extents),
+            None
+        );
+    }
+
+    #[test]
+    fn nearest_smooth_path_starts_and_ends_on_the_mesh() {
+        let mesh = l_corridor();
+        let path = mesh
+            .find_nearest_smooth_path(
+                Vec3::new(0.2, 1.0, 0.1),
+                Vec3::new(1.5, 1.0, 2.5),
+                Vec3::new(1.0, 2.0, 1.0),
+            )
+            .unwrap();
+        assert_eq!(
+            path,
+            [
+                Vec3::new(0.2, 0.0, 0.1),
+                Vec3::new(1.0, 0.0, 1.0),
+                Vec3::new(1.5, 0.0, 2.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn repair_consistent_mesh_is_noop() {
+        let mut mesh = l_corridor();
+        let report = mesh.repair();
+        assert!(report.is_empty());
+        assert_eq!(mesh, l_corridor());
+    }
+
+    #[test]
+    fn repair_symmetrizes_one_directional_link() {
+        let mut mesh = l_corridor();
+        // Polygon 1 forgets about polygon 2, but polygon 2 still links to polygon 1.
+        mesh.polygon_neighbors[4 + 1] = NC;
+
+        let report = mesh.repair();
+        assert_eq!(report.symmetrized, vec![(2, 1)]);
+        assert!(report.removed.is_empty());
+        assert_eq!(mesh, l_corridor());
+    }
+
+    #[test]
+    fn repair_removes_dangling_links() {
+        let mut mesh = l_corridor();
+        // Link to a deleted polygon.
+        mesh.polygon_neighbors[0] = 7;
+        // Link to a polygon that does not share the edge.
+        mesh.polygon_neighbors[8] = 0;
+
+        let report = mesh.repair();
+        assert_eq!(report.removed, vec![(0, 7), (2, 0)]);
+        assert!(report.symmetrized.is_empty());
+        assert_eq!(mesh, l_corridor());
+    }
+}
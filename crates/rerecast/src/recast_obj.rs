@@ -0,0 +1,224 @@
+//! Importing the Wavefront OBJ dumps written by the original Recast debug utilities,
+//! i.e. `duDumpPolyMeshToObj` and `duDumpPolyMeshDetailToObj`, for comparing against navmeshes built by this crate.
+
+use alloc::vec::Vec;
+use glam::{UVec3, Vec3A};
+use thiserror::Error;
+
+use crate::{AreaType, DetailNavmesh, TriMesh};
+
+/// Parses a Wavefront OBJ written by Recast's debug utilities into a [`TriMesh`], e.g. to A/B test
+/// navmeshes built by the C++ tools against ones built by this crate.
+///
+/// Recast's OBJ dumps are lossy: `duDumpPolyMeshToObj` fan-triangulates the polygons and offsets their height,
+/// and `duDumpPolyMeshDetailToObj` drops the boundaries between sub-meshes. So instead of a
+/// [`PolygonNavmesh`](crate::PolygonNavmesh) or [`DetailNavmesh`], the geometry is returned as a plain [`TriMesh`].
+/// Compare a detail dump against [`DetailNavmesh::to_trimesh`], which lists vertices and triangles in the same order.
+///
+/// Only `v` and `f` statements are read. Faces with more than three vertices are fan-triangulated,
+/// and texture and normal indices are ignored. All triangles are [`AreaType::DEFAULT_WALKABLE`].
+pub fn import_recast_obj(obj: &str) -> Result<TriMesh, RecastObjError> {
+    let mut trimesh = TriMesh::default();
+    for (line_index, line) in obj.lines().enumerate() {
+        let line_number = line_index + 1;
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("v") => {
+                let mut coordinates = tokens.map(str::parse::<f32>);
+                let mut coordinate = || match coordinates.next() {
+                    Some(Ok(coordinate)) => Ok(coordinate),
+                    _ => Err(RecastObjError::InvalidVertex { line: line_number }),
+                };
+                let vertex = Vec3A::new(coordinate()?, coordinate()?, coordinate()?);
+                trimesh.vertices.push(vertex);
+            }
+            Some("f") => {
+                let indices = tokens
+                    .map(|token| parse_face_index(token, line_number))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if indices.len() < 3 {
+                    return Err(RecastObjError::InvalidFace { line: line_number });
+                }
+                for window in 1..indices.len() - 1 {
+                    trimesh.indices.push(UVec3::new(
+                        indices[0],
+                        indices[window],
+                        indices[window + 1],
+                    ));
+                    trimesh.area_types.push(AreaType::DEFAULT_WALKABLE);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let vertex_count = trimesh.vertices.len();
+    if let Some(index) = trimesh
+        .indices
+        .iter()
+        .flat_map(|triangle| triangle.to_array())
+        .find(|&index| index as usize >= vertex_count)
+    {
+        return Err(RecastObjError::IndexOutOfBounds {
+            index: index as usize + 1,
+            vertex_count,
+        });
+    }
+    Ok(trimesh)
+}
+
+/// Parses a 1-based OBJ face index such as `3`, `3/1` or `3//2` into a 0-based vertex index.
+fn parse_face_index(token: &str, line: usize) -> Result<u32, RecastObjError> {
+    let vertex = token.split('/').next().unwrap_or_default();
+    match vertex.parse::<u32>() {
+        Ok(index) if index > 0 => Ok(index - 1),
+        _ => Err(RecastObjError::InvalidFace { line }),
+    }
+}
+
+impl DetailNavmesh {
+    /// Returns the vertices and triangles of all sub-meshes as a single [`TriMesh`] with global indices,
+    /// in the same order as Recast's `duDumpPolyMeshDetailToObj` writes them. See [`import_recast_obj`].
+    pub fn to_trimesh(&self) -> TriMesh {
+        let indices: Vec<UVec3> = self
+            .meshes
+            .iter()
+            .flat_map(|mesh| {
+                self.triangles[mesh.base_triangle_index as usize..][..mesh.triangle_count as usize]
+                    .iter()
+                    .map(|triangle| {
+                        UVec3::from_array(triangle.map(u32::from)) + mesh.base_vertex_index
+                    })
+            })
+            .collect();
+        TriMesh {
+            vertices: self.vertices.iter().copied().map(Vec3A::from).collect(),
+            area_types: vec![AreaType::DEFAULT_WALKABLE; indices.len()],
+            indices,
+        }
+    }
+}
+
+/// Errors that can occur when importing a Recast OBJ with [`import_recast_obj`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RecastObjError {
+    /// A `v` statement does not consist of three numbers.
+    #[error("Invalid vertex on line {line}")]
+    InvalidVertex {
+        /// The 1-based line number of the statement.
+        line: usize,
+    },
+    /// An `f` statement has fewer than three vertices or an index that is not a positive number.
+    #[error("Invalid face on line {line}")]
+    InvalidFace {
+        /// The 1-based line number of the statement.
+        line: usize,
+    },
+    /// A face refers to a vertex that does not exist.
+    #[error("Face refers to vertex {index}, but there are only {vertex_count} vertices")]
+    IndexOutOfBounds {
+        /// The 1-based index as written in the OBJ.
+        index: usize,
+        /// The number of vertices in the OBJ.
+        vertex_count: usize,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SubMesh;
+    use alloc::string::String;
+    use core::fmt::Write as _;
+    use glam::Vec3;
+
+    /// Writes `detail` the way `duDumpPolyMeshDetailToObj` does.
+    fn dump_detail(detail: &DetailNavmesh) -> String {
+        let mut obj = String::from("# Recast Navmesh\no NavMesh\n\n");
+        for v in &detail.vertices {
+            writeln!(obj, "v {:.6} {:.6} {:.6}", v.x, v.y, v.z).unwrap();
+        }
+        obj.push('\n');
+        for mesh in &detail.meshes {
+            let triangles = &detail.triangles[mesh.base_triangle_index as usize..]
+                [..mesh.triangle_count as usize];
+            for [a, b, c] in triangles {
+                let [a, b, c] = [a, b, c].map(|&i| mesh.base_vertex_index + u32::from(i) + 1);
+                writeln!(obj, "f {a} {b} {c}").unwrap();
+            }
+        }
+        obj
+    }
+
+    fn two_quads() -> DetailNavmesh {
+        let quad = |x: f32| {
+            [
+                Vec3::new(x, 0.0, 0.0),
+                Vec3::new(x, 0.0, 1.0),
+                Vec3::new(x + 1.0, 0.5, 1.0),
+                Vec3::new(x + 1.0, 0.5, 0.0),
+            ]
+        };
+        DetailNavmesh {
+            meshes: vec![
+                SubMesh {
+                    base_vertex_index: 0,
+                    vertex_count: 4,
+                    base_triangle_index: 0,
+                    triangle_count: 2,
+                },
+                SubMesh {
+                    base_vertex_index: 4,
+                    vertex_count: 4,
+                    base_triangle_index: 2,
+                    triangle_count: 2,
+                },
+            ],
+            vertices: [quad(0.0), quad(1.0)].concat(),
+            triangles: vec![[0, 1, 2], [0, 2, 3], [0, 1, 2], [0, 2, 3]],
+            triangle_flags: vec![0; 4],
+        }
+    }
+
+    #[test]
+    fn detail_dump_matches_trimesh() {
+        let detail = two_quads();
+        let imported = import_recast_obj(&dump_detail(&detail)).unwrap();
+        assert_eq!(imported, detail.to_trimesh());
+        assert_eq!(imported.indices[2], UVec3::new(4, 5, 6));
+    }
+
+    #[test]
+    fn polygons_are_fan_triangulated() {
+        let obj = "v 0 0 0\nv 0 0 1\nv 1 0 1\nv 1 0 0\nf 1/1 2/2 3//3 4\n";
+        let imported = import_recast_obj(obj).unwrap();
+        assert_eq!(
+            imported.indices,
+            vec![UVec3::new(0, 1, 2), UVec3::new(0, 2, 3)]
+        );
+        assert_eq!(imported.area_types.len(), 2);
+    }
+
+    #[test]
+    fn rejects_malformed_statements() {
+        assert_eq!(
+            import_recast_obj("v 0 0\n"),
+            Err(RecastObjError::InvalidVertex { line: 1 })
+        );
+        assert_eq!(
+            import_recast_obj("v 0 0 0\nf 1 0 1\n"),
+            Err(RecastObjError::InvalidFace { line: 2 })
+        );
+        assert_eq!(
+            import_recast_obj("v 0 0 0\nf 1 1\n"),
+            Err(RecastObjError::InvalidFace { line: 2 })
+        );
+        assert_eq!(
+            import_recast_obj("v 0 0 0\nf 1 1 2\n"),
+            Err(RecastObjError::IndexOutOfBounds {
+                index: 2,
+                vertex_count: 1
+            })
+        );
+    }
+}
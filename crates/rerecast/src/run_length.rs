@@ -0,0 +1,98 @@
+//! Run-length encoding for serializing arrays that mostly contain the same value,
+//! such as [`PolygonNavmesh::flags`](crate::PolygonNavmesh::flags) and [`PolygonNavmesh::areas`](crate::PolygonNavmesh::areas).
+//!
+//! Use with `#[serde(with = "crate::run_length")]`.
+
+use alloc::vec::Vec;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as _};
+
+/// The maximum number of values [`deserialize`] expands the runs to.
+/// Polygons are indexed by `u16`, so no per-polygon array can be longer than this.
+/// Bounding the length keeps a corrupted count from allocating gigabytes.
+const MAX_LEN: usize = u16::MAX as usize;
+
+/// Serializes `values` as a sequence of `(value, count)` runs.
+pub(crate) fn serialize<T, S>(values: &[T], serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize + PartialEq,
+    S: Serializer,
+{
+    let mut runs: Vec<(&T, u32)> = Vec::new();
+    for value in values {
+        match runs.last_mut() {
+            Some((last, count)) if *last == value && *count < u32::MAX => *count += 1,
+            _ => runs.push((value, 1)),
+        }
+    }
+    runs.serialize(serializer)
+}
+
+/// Deserializes a sequence of `(value, count)` runs written by [`serialize`].
+pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    T: Deserialize<'de> + Clone,
+    D: Deserializer<'de>,
+{
+    let runs = Vec::<(T, u32)>::deserialize(deserializer)?;
+    let mut values = Vec::new();
+    for (value, count) in runs {
+        let len = values.len().saturating_add(count as usize);
+        if len > MAX_LEN {
+            return Err(D::Error::custom(format_args!(
+                "run-length encoded array is longer than {MAX_LEN} values"
+            )));
+        }
+        values.resize(len, value);
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AreaType;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Runs {
+        #[serde(with = "crate::run_length")]
+        areas: Vec<AreaType>,
+    }
+
+    #[test]
+    fn round_trip() {
+        let runs = Runs {
+            areas: vec![
+                AreaType::DEFAULT_WALKABLE,
+                AreaType::DEFAULT_WALKABLE,
+                AreaType(3),
+                AreaType::DEFAULT_WALKABLE,
+                AreaType(3),
+                AreaType(3),
+            ],
+        };
+        let json = serde_json::to_string(&runs).unwrap();
+        assert_eq!(serde_json::from_str::<Runs>(&json).unwrap(), runs);
+
+        let empty = Runs { areas: Vec::new() };
+        let json = serde_json::to_string(&empty).unwrap();
+        assert_eq!(serde_json::from_str::<Runs>(&json).unwrap(), empty);
+    }
+
+    #[test]
+    fn uniform_values_are_stored_once() {
+        let runs = Runs {
+            areas: vec![AreaType::DEFAULT_WALKABLE; 1000],
+        };
+        let json = serde_json::to_string(&runs).unwrap();
+        assert_eq!(json, r#"{"areas":[[255,1000]]}"#);
+        assert_eq!(serde_json::from_str::<Runs>(&json).unwrap(), runs);
+    }
+
+    #[test]
+    fn rejects_huge_counts() {
+        let json = r#"{"areas":[[255,4294967295]]}"#;
+        assert!(serde_json::from_str::<Runs>(json).is_err());
+        let json = r#"{"areas":[[255,60000],[3,60000]]}"#;
+        assert!(serde_json::from_str::<Runs>(json).is_err());
+    }
+}
@@ -128,8 +128,10 @@ impl From<u8> for AreaType {
 }
 
 impl AreaType {
-    /// The area type 0. Triangles with this area type are not walkable.
+    /// The area type 0, also known as the null area. Triangles with this area type are not walkable.
     /// All other area types are walkable.
+    ///
+    /// A [`ConvexVolume`](crate::ConvexVolume) with this area type carves a hole into the navmesh.
     pub const NOT_WALKABLE: Self = Self(0);
     /// Default area type for walkable triangles. The highest possible area type.
     /// Other area types that are not [`AreaType::NOT_WALKABLE`] are also walkable.
@@ -0,0 +1,151 @@
+//! Support for turning terrain that can be sampled by height, e.g. heightfield colliders, into a [`TriMesh`].
+
+use alloc::vec::Vec;
+use glam::{UVec3, Vec2, Vec3A};
+
+use crate::{Aabb2d, AreaType, TriMesh, ops::ceil};
+
+/// A terrain whose height can be queried at arbitrary points on the horizontal plane.
+///
+/// Implemented for all closures of the form `Fn(f32, f32) -> Option<f32>`.
+pub trait HeightSampler {
+    /// Returns the height of the terrain's surface at the given X and Z coordinates,
+    /// or `None` if there is no terrain at that point.
+    fn sample_height(&self, x: f32, z: f32) -> Option<f32>;
+}
+
+impl<F: Fn(f32, f32) -> Option<f32>> HeightSampler for F {
+    #[inline]
+    fn sample_height(&self, x: f32, z: f32) -> Option<f32> {
+        self(x, z)
+    }
+}
+
+impl TriMesh {
+    /// Samples the height of a terrain on a regular grid inside `bounds` and triangulates the samples.
+    ///
+    /// This only creates geometry for the part of the terrain that is actually relevant for the navmesh,
+    /// which is much cheaper than converting a whole large terrain into a trimesh.
+    /// Grid cells with at least one corner without a height are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `sampler` - The terrain to sample.
+    /// * `bounds` - The area to sample on the XZ plane.
+    /// * `spacing` - The distance between two samples. Should be at most the cell size used for rasterization
+    ///   to not lose detail. Must be greater than 0.
+    ///
+    /// All triangles are marked as [`AreaType::NOT_WALKABLE`], so that [`TriMesh::mark_walkable_triangles`]
+    /// can be used on them like on any other trimesh.
+    pub fn from_height_sampler(sampler: &impl HeightSampler, bounds: Aabb2d, spacing: f32) -> Self {
+        assert!(spacing > 0.0, "Sample spacing must be greater than 0");
+        let size = (bounds.max - bounds.min).max(Vec2::ZERO);
+        // Include both borders of the bounds.
+        let columns = ceil(size.x / spacing) as u32 + 1;
+        let rows = ceil(size.y / spacing) as u32 + 1;
+
+        let mut trimesh = TriMesh::default();
+        let mut sample_indices = Vec::with_capacity((columns * rows) as usize);
+        for row in 0..rows {
+            for column in 0..columns {
+                let x = (bounds.min.x + column as f32 * spacing).min(bounds.max.x);
+                let z = (bounds.min.y + row as f32 * spacing).min(bounds.max.y);
+                let index = sampler.sample_height(x, z).map(|y| {
+                    trimesh.vertices.push(Vec3A::new(x, y, z));
+                    trimesh.vertices.len() as u32 - 1
+                });
+                sample_indices.push(index);
+            }
+        }
+
+        let sample = |column: u32, row: u32| sample_indices[(row * columns + column) as usize];
+        for row in 0..rows.saturating_sub(1) {
+            for column in 0..columns.saturating_sub(1) {
+                let (Some(v00), Some(v10), Some(v01), Some(v11)) = (
+                    sample(column, row),
+                    sample(column + 1, row),
+                    sample(column, row + 1),
+                    sample(column + 1, row + 1),
+                ) else {
+                    continue;
+                };
+                // Wind counter-clockwise when seen from above so that the normals point up.
+                trimesh.indices.push(UVec3::new(v00, v01, v10));
+                trimesh.indices.push(UVec3::new(v10, v01, v11));
+            }
+        }
+        trimesh.area_types = vec![AreaType::NOT_WALKABLE; trimesh.indices.len()];
+        trimesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        HeightfieldBuilder,
+        math::TriangleIndices as _,
+        ops::{cos, sin},
+    };
+
+    fn bounds() -> Aabb2d {
+        Aabb2d {
+            min: Vec2::new(-4.0, -4.0),
+            max: Vec2::new(4.0, 4.0),
+        }
+    }
+
+    #[test]
+    fn samples_analytic_terrain() {
+        let slope = |x: f32, z: f32| Some(0.25 * x + 0.5 * z);
+        let trimesh = TriMesh::from_height_sampler(&slope, bounds(), 1.0);
+
+        // 9x9 samples and 8x8 quads of two triangles each
+        assert_eq!(trimesh.vertices.len(), 81);
+        assert_eq!(trimesh.indices.len(), 128);
+        assert_eq!(trimesh.area_types.len(), 128);
+        for vertex in &trimesh.vertices {
+            assert_eq!(vertex.y, 0.25 * vertex.x + 0.5 * vertex.z);
+        }
+        for indices in &trimesh.indices {
+            assert!(indices.normal(&trimesh.vertices).y > 0.0);
+        }
+    }
+
+    #[test]
+    fn skips_cells_without_terrain() {
+        let half = |x: f32, _z: f32| (x <= 0.0).then_some(0.0);
+        let trimesh = TriMesh::from_height_sampler(&half, bounds(), 1.0);
+
+        // 5x9 samples and 4x8 quads
+        assert_eq!(trimesh.vertices.len(), 45);
+        assert_eq!(trimesh.indices.len(), 64);
+    }
+
+    #[test]
+    fn bakes_walkable_terrain() {
+        let hills = |x: f32, z: f32| Some(0.5 * sin(x * 0.5) + 0.5 * cos(z * 0.5));
+        let mut trimesh = TriMesh::from_height_sampler(&hills, bounds(), 0.5);
+        trimesh.mark_walkable_triangles(45.0_f32.to_radians());
+        assert!(
+            trimesh
+                .area_types
+                .iter()
+                .all(|area| *area == AreaType::DEFAULT_WALKABLE)
+        );
+
+        let mut heightfield = HeightfieldBuilder {
+            aabb: trimesh.compute_aabb().unwrap(),
+            cell_size: 0.5,
+            cell_height: 0.1,
+        }
+        .build()
+        .unwrap();
+        heightfield.rasterize_triangles(&trimesh, 1).unwrap();
+        let compact = heightfield.into_compact(2, 1).unwrap();
+        // The terrain is a single walkable surface without overhangs.
+        assert!(!compact.spans.is_empty());
+        assert!(compact.cells.iter().all(|cell| cell.count() <= 1));
+        assert!(compact.areas.iter().all(|area| area.is_walkable()));
+    }
+}
@@ -24,6 +24,23 @@ pub struct TriMesh {
     pub area_types: Vec<AreaType>,
 }
 
+impl FromIterator<TriMesh> for TriMesh {
+    /// Combines the trimeshes with [`TriMesh::extend`].
+    /// The capacity for all of them is reserved up front, so that the combined trimesh is allocated only once.
+    fn from_iter<I: IntoIterator<Item = TriMesh>>(iter: I) -> Self {
+        let trimeshes = iter.into_iter().collect::<Vec<_>>();
+        let mut combined = TriMesh::default();
+        combined.reserve(
+            trimeshes.iter().map(|trimesh| trimesh.vertices.len()).sum(),
+            trimeshes.iter().map(|trimesh| trimesh.indices.len()).sum(),
+        );
+        for trimesh in trimeshes {
+            combined.extend(trimesh);
+        }
+        combined
+    }
+}
+
 impl TriMesh {
     /// Extends the trimesh with the vertices and indices of another trimesh.
     /// The indices of `other` will be offset by the number of vertices in `self`.
@@ -38,6 +55,37 @@ impl TriMesh {
         self.area_types.extend(other.area_types);
     }
 
+    /// Extends the trimesh with the triangles of another trimesh, moving each of its vertices with `transform_vertex`.
+    /// Unlike [`TriMesh::extend`], `other` is only borrowed, so a single trimesh can be appended once per instance
+    /// without being cloned.
+    pub fn extend_transformed(
+        &mut self,
+        other: &TriMesh,
+        transform_vertex: impl Fn(Vec3A) -> Vec3A,
+    ) {
+        if self.vertices.len() > u32::MAX as usize {
+            panic!("Cannot extend a trimesh with more than 2^32 vertices");
+        }
+        let next_vertex_index = self.vertices.len() as u32;
+        self.vertices.extend(
+            other
+                .vertices
+                .iter()
+                .map(|&vertex| transform_vertex(vertex)),
+        );
+        self.indices
+            .extend(other.indices.iter().map(|i| i + next_vertex_index));
+        self.area_types.extend_from_slice(&other.area_types);
+    }
+
+    /// Reserves capacity for at least `additional_vertices` more vertices and `additional_triangles` more triangles,
+    /// so that extending the trimesh by that much does not reallocate.
+    pub fn reserve(&mut self, additional_vertices: usize, additional_triangles: usize) {
+        self.vertices.reserve(additional_vertices);
+        self.indices.reserve(additional_triangles);
+        self.area_types.reserve(additional_triangles);
+    }
+
     /// Computes the AABB of the trimesh.
     /// Returns `None` if the trimesh is empty.
     pub fn compute_aabb(&self) -> Option<Aabb3d> {
@@ -53,12 +101,24 @@ impl TriMesh {
     /// * `threshold_rad` - The threshold angle in radians.
     ///
     pub fn mark_walkable_triangles(&mut self, threshold_rad: f32) {
+        self.mark_walkable_triangles_as(threshold_rad, AreaType::DEFAULT_WALKABLE);
+    }
+
+    /// Same as [`TriMesh::mark_walkable_triangles`], but marks the walkable triangles with the given `area`
+    /// instead of [`AreaType::DEFAULT_WALKABLE`].
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold_rad` - The threshold angle in radians.
+    /// * `area` - The area type to assign to walkable triangles. Should not be [`AreaType::NOT_WALKABLE`].
+    ///
+    pub fn mark_walkable_triangles_as(&mut self, threshold_rad: f32, area: AreaType) {
         let threshold_cos = cos(threshold_rad);
         for (i, indices) in self.indices.iter().enumerate() {
             let normal = indices.normal(&self.vertices);
 
             if normal.y > threshold_cos {
-                self.area_types[i] = AreaType::DEFAULT_WALKABLE;
+                self.area_types[i] = area;
             }
         }
     }
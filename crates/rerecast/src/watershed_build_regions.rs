@@ -174,7 +174,7 @@ impl CompactHeightfield {
         Ok(())
     }
 
-    fn merge_and_filter_regions(
+    pub(crate) fn merge_and_filter_regions(
         &mut self,
         min_region_area: u16,
         merge_region_size: u16,
@@ -624,7 +624,7 @@ impl CompactHeightfield {
         count > 0
     }
 
-    fn paint_rect_region(
+    pub(crate) fn paint_rect_region(
         &self,
         min_x: u16,
         max_x: u16,
@@ -833,24 +833,21 @@ struct DirtyEntry {
 }
 
 #[derive(Debug, Clone)]
-struct Region {
-    span_count: usize,
-    id: RegionId,
-    area: AreaType,
-    remap: bool,
+pub(crate) struct Region {
+    pub(crate) span_count: usize,
+    pub(crate) id: RegionId,
+    pub(crate) area: AreaType,
+    pub(crate) remap: bool,
     visited: bool,
     overlap: bool,
-    #[expect(dead_code, reason = "Used by the layer API")]
-    connects_to_border: bool,
-    #[expect(dead_code, reason = "Used by the layer API")]
-    y_min: u16,
-    #[expect(dead_code, reason = "Used by the layer API")]
-    y_max: u16,
-    connections: Vec<RegionId>,
-    floors: Vec<RegionId>,
+    pub(crate) connects_to_border: bool,
+    pub(crate) y_min: u16,
+    pub(crate) y_max: u16,
+    pub(crate) connections: Vec<RegionId>,
+    pub(crate) floors: Vec<RegionId>,
 }
 impl Region {
-    fn new(id: RegionId) -> Self {
+    pub(crate) fn new(id: RegionId) -> Self {
         Self {
             id,
             span_count: 0,
@@ -866,7 +863,7 @@ impl Region {
         }
     }
 
-    fn add_unique_floor_region(&mut self, floor_id: RegionId) {
+    pub(crate) fn add_unique_floor_region(&mut self, floor_id: RegionId) {
         if self.floors.contains(&floor_id) {
             return;
         }
@@ -985,7 +982,8 @@ impl Region {
         true
     }
 }
-/// Error type for [`CompactHeightfield::build_regions`].
+/// Error type for [`CompactHeightfield::build_regions`], [`CompactHeightfield::build_regions_monotone`],
+/// and [`CompactHeightfield::build_layer_regions`].
 #[derive(Debug, thiserror::Error)]
 pub enum BuildRegionsError {
     /// The region ID overflowed.
@@ -0,0 +1,132 @@
+//! A test scene that computes a path through a navmesh and draws it with a [`PathGizmo`].
+//!
+//! The polygon corridor is found with [`PolygonNavmesh::find_path`](bevy_rerecast::rerecast::PolygonNavmesh::find_path)
+//! and smoothed into waypoints with [`PolygonNavmesh::find_smooth_path`](bevy_rerecast::rerecast::PolygonNavmesh::find_smooth_path).
+
+use bevy::{
+    color::palettes::tailwind,
+    input::common_conditions::input_just_pressed,
+    platform::collections::HashSet,
+    prelude::*,
+    remote::{RemotePlugin, http::RemoteHttpPlugin},
+};
+use bevy_rerecast::{
+    Mesh3dBackendPlugin,
+    debug::{PathGizmo, PolygonNavmeshGizmo},
+    prelude::*,
+};
+
+const START: Vec3 = Vec3::new(-8.0, 0.0, -8.0);
+const GOAL: Vec3 = Vec3::new(8.0, 0.0, 8.0);
+
+fn main() -> AppExit {
+    App::new()
+        .add_plugins(DefaultPlugins.set(AssetPlugin {
+            file_path: "../assets".to_string(),
+            ..default()
+        }))
+        .add_plugins((RemotePlugin::default(), RemoteHttpPlugin::default()))
+        .add_plugins((NavmeshPlugins::default(), Mesh3dBackendPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            generate_navmesh.run_if(input_just_pressed(KeyCode::Space)),
+        )
+        .add_observer(configure_camera)
+        .add_observer(draw_path)
+        .run()
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material_ground = materials.add(Color::from(tailwind::SKY_500));
+    let material_wall = materials.add(Color::from(tailwind::RED_500));
+    commands.spawn((
+        Name::new("Ground"),
+        Mesh3d(meshes.add(Cuboid::new(20.0, 0.2, 20.0))),
+        Transform::from_xyz(0.0, -0.1, 0.0),
+        MeshMaterial3d(material_ground),
+    ));
+    let wall = meshes.add(Cuboid::new(12.0, 2.0, 1.0));
+    for (name, translation) in [
+        ("Left Wall", Vec3::new(-4.0, 1.0, -3.0)),
+        ("Right Wall", Vec3::new(4.0, 1.0, 3.0)),
+    ] {
+        commands.spawn((
+            Name::new(name),
+            Mesh3d(wall.clone()),
+            Transform::from_translation(translation),
+            MeshMaterial3d(material_wall.clone()),
+        ));
+    }
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::default().looking_to(Vec3::new(0.5, -1.0, 0.3), Vec3::Y),
+    ));
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 25.0, 15.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Text::new("Press space to generate navmesh and find a path"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+#[derive(Resource)]
+#[allow(dead_code)]
+struct NavmeshHandle(Handle<Navmesh>);
+
+fn generate_navmesh(mut generator: NavmeshGenerator, mut commands: Commands) {
+    let navmesh = generator.generate(NavmeshSettings::default());
+    commands.spawn(PolygonNavmeshGizmo::new(&navmesh));
+    commands.insert_resource(NavmeshHandle(navmesh));
+}
+
+fn draw_path(
+    trigger: On<NavmeshReady>,
+    mut commands: Commands,
+    navmeshes: Res<Assets<Navmesh>>,
+    paths: Query<Entity, With<PathGizmo>>,
+) {
+    let id = trigger.event().0;
+    let Some(navmesh) = navmeshes.get(id) else {
+        return;
+    };
+    for entity in paths.iter() {
+        commands.entity(entity).despawn();
+    }
+    let mesh = &navmesh.polygon;
+    let corridor = mesh
+        .find_polygon(START)
+        .zip(mesh.find_polygon(GOAL))
+        .and_then(|(start, goal)| mesh.find_path(start, goal, &HashSet::new()));
+    let (Some(corridor), Some(waypoints)) = (corridor, mesh.find_smooth_path(START, GOAL)) else {
+        warn!("No path found from {START} to {GOAL}");
+        return;
+    };
+    commands.spawn(PathGizmo::new(id, corridor, waypoints));
+}
+
+fn configure_camera(
+    trigger: On<Add, Camera>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands.entity(trigger.entity).insert(EnvironmentMapLight {
+        diffuse_map: asset_server.load("environment_maps/voortrekker_interior_1k_diffuse.ktx2"),
+        specular_map: asset_server.load("environment_maps/voortrekker_interior_1k_specular.ktx2"),
+        intensity: 2000.0,
+        ..default()
+    });
+}